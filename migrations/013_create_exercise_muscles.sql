@@ -0,0 +1,23 @@
+-- Primary/secondary muscle mapping per exercise. `exercises.category` stays
+-- the coarse grouping used by the pickers; this table is what the weekly
+-- muscle volume page counts against. `muscle` is one of the names in
+-- `models::muscle::MUSCLE_GROUPS` (validated in Rust, not by a CHECK, so the
+-- list can grow without a table rebuild).
+CREATE TABLE IF NOT EXISTS exercise_muscles (
+    exercise_id TEXT NOT NULL REFERENCES exercises(id) ON DELETE CASCADE,
+    muscle TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('primary', 'secondary')),
+    PRIMARY KEY (exercise_id, muscle)
+);
+
+CREATE INDEX IF NOT EXISTS idx_exercise_muscles_muscle ON exercise_muscles(muscle);
+
+-- Per-user weekly hard-set targets. A muscle with no row uses the defaults in
+-- `models::muscle` (10-20 sets), so nothing needs backfilling.
+CREATE TABLE IF NOT EXISTS muscle_volume_targets (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    muscle TEXT NOT NULL,
+    min_sets INTEGER NOT NULL,
+    max_sets INTEGER NOT NULL,
+    PRIMARY KEY (user_id, muscle)
+);
@@ -0,0 +1,54 @@
+-- Per-user exercise categories, replacing the fixed six-entry list that used
+-- to live in `models::exercise::CATEGORIES`.
+--
+-- `name` is the stable key stored in `exercises.category`; renaming a
+-- category only changes `display_name`, so no exercise rows are touched.
+-- `position` orders the pickers and the exercise list.
+CREATE TABLE IF NOT EXISTS exercise_categories (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (user_id, name)
+);
+
+-- Every new account starts with the old default set. A trigger rather than
+-- application code so that every path that creates a user (signup, the admin
+-- panel, the first-run bootstrap, tests inserting rows directly) gets it.
+CREATE TRIGGER IF NOT EXISTS seed_exercise_categories
+AFTER INSERT ON users
+BEGIN
+    INSERT INTO exercise_categories (user_id, name, display_name, position) VALUES
+        (NEW.id, 'chest', 'Chest', 0),
+        (NEW.id, 'back', 'Back', 1),
+        (NEW.id, 'legs', 'Legs', 2),
+        (NEW.id, 'shoulders', 'Shoulders', 3),
+        (NEW.id, 'arms', 'Arms', 4),
+        (NEW.id, 'core', 'Core', 5);
+END;
+
+-- Backfill existing users with the same defaults.
+INSERT OR IGNORE INTO exercise_categories (user_id, name, display_name, position)
+SELECT u.id, d.name, d.display_name, d.position
+FROM users u
+CROSS JOIN (
+    SELECT 'chest' AS name, 'Chest' AS display_name, 0 AS position
+    UNION ALL SELECT 'back', 'Back', 1
+    UNION ALL SELECT 'legs', 'Legs', 2
+    UNION ALL SELECT 'shoulders', 'Shoulders', 3
+    UNION ALL SELECT 'arms', 'Arms', 4
+    UNION ALL SELECT 'core', 'Core', 5
+) d;
+
+-- The old handlers never validated the submitted category, so an exercise
+-- may carry a value outside the defaults. Give each such value a category of
+-- its own, after the defaults, rather than leaving the exercise ungrouped.
+-- A blank category has no usable key, so those exercises go under 'other'.
+UPDATE exercises SET category = 'other' WHERE trim(category) = '';
+
+INSERT OR IGNORE INTO exercise_categories (user_id, name, display_name, position)
+SELECT DISTINCT e.user_id, e.category,
+       upper(substr(e.category, 1, 1)) || substr(e.category, 2),
+       6
+FROM exercises e
+JOIN users u ON u.id = e.user_id;
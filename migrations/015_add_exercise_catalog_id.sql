@@ -0,0 +1,10 @@
+-- Links an exercise to its built-in catalog entry (see src/catalog.rs). NULL
+-- for exercises the user created by hand. The catalog lives in the binary,
+-- not in a table, so there is no foreign key to point at.
+ALTER TABLE exercises ADD COLUMN catalog_id TEXT;
+
+-- One copy of each catalog entry per user: re-importing skips entries the
+-- user already has, and cross-user matching can rely on (user, catalog_id)
+-- naming exactly one exercise.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_exercises_user_catalog
+    ON exercises(user_id, catalog_id) WHERE catalog_id IS NOT NULL;
@@ -0,0 +1,35 @@
+-- Instance-wide exercises. A NULL user_id marks an exercise as global: owned
+-- by the instance, edited by admins, and listed for every user alongside
+-- their own. SQLite cannot drop a NOT NULL constraint in place, so this is
+-- the same rebuild as 010. workout_logs and exercise_muscles reference this
+-- table, so foreign keys must be off while the old table is dropped; see
+-- 010's trailing comment for why this migration does not turn them back on.
+PRAGMA foreign_keys = OFF;
+
+CREATE TABLE exercises_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    category TEXT NOT NULL,
+    user_id TEXT REFERENCES users(id) ON DELETE CASCADE,
+    catalog_id TEXT
+);
+
+INSERT INTO exercises_new (id, name, category, user_id, catalog_id)
+    SELECT id, name, category, user_id, catalog_id FROM exercises;
+
+DROP TABLE exercises;
+ALTER TABLE exercises_new RENAME TO exercises;
+
+CREATE INDEX IF NOT EXISTS idx_exercises_category ON exercises(category);
+CREATE INDEX IF NOT EXISTS idx_exercises_user_id ON exercises(user_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_exercises_user_catalog
+    ON exercises(user_id, catalog_id) WHERE catalog_id IS NOT NULL;
+
+-- Global exercises a user has chosen not to see in their lists and pickers.
+-- Hiding is purely presentational: logs already recorded against the
+-- exercise keep it, and the stats pages still show it.
+CREATE TABLE IF NOT EXISTS hidden_exercises (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    exercise_id TEXT NOT NULL REFERENCES exercises(id) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, exercise_id)
+);
@@ -0,0 +1,5 @@
+-- Archived exercises drop out of the exercise list's main groups and the
+-- workout pickers but keep their logs, so history and stats still show them.
+-- NULL means active. Archiving is the way out for an exercise that can no
+-- longer be deleted because logs reference it.
+ALTER TABLE exercises ADD COLUMN archived_at DATETIME;
@@ -0,0 +1,8 @@
+-- Unilateral exercises (single-arm rows, split squats) train each side
+-- separately. Sets of those exercises record the left side in the existing
+-- reps/weight columns and the right side in right_reps/right_weight; both
+-- stay NULL for ordinary sets. A set's volume is the sum over its sides.
+ALTER TABLE exercises ADD COLUMN unilateral INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE workout_logs ADD COLUMN right_reps INTEGER;
+ALTER TABLE workout_logs ADD COLUMN right_weight REAL;
@@ -0,0 +1,6 @@
+-- Per-set annotations. notes is free text ("belt", "paused"); tempo is the
+-- normalised four-phase notation such as 3-1-X-0; rir (reps in reserve) is
+-- an alternative effort measure to rpe. All optional.
+ALTER TABLE workout_logs ADD COLUMN notes TEXT;
+ALTER TABLE workout_logs ADD COLUMN tempo TEXT;
+ALTER TABLE workout_logs ADD COLUMN rir INTEGER;
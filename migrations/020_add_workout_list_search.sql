@@ -0,0 +1,36 @@
+-- Full-text index over session notes for the workout list's search box.
+-- A standalone FTS5 table keyed by session id rather than an external-content
+-- table: workout_sessions has a TEXT primary key, so its implicit rowid is not
+-- stable across a VACUUM and cannot be relied on as the content key.
+CREATE VIRTUAL TABLE IF NOT EXISTS workout_sessions_fts USING fts5(
+    session_id UNINDEXED,
+    notes
+);
+
+INSERT INTO workout_sessions_fts (session_id, notes)
+SELECT id, notes FROM workout_sessions WHERE notes IS NOT NULL;
+
+CREATE TRIGGER IF NOT EXISTS workout_sessions_fts_insert
+AFTER INSERT ON workout_sessions
+WHEN new.notes IS NOT NULL
+BEGIN
+    INSERT INTO workout_sessions_fts (session_id, notes) VALUES (new.id, new.notes);
+END;
+
+CREATE TRIGGER IF NOT EXISTS workout_sessions_fts_delete
+AFTER DELETE ON workout_sessions
+BEGIN
+    DELETE FROM workout_sessions_fts WHERE session_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS workout_sessions_fts_update
+AFTER UPDATE OF notes ON workout_sessions
+BEGIN
+    DELETE FROM workout_sessions_fts WHERE session_id = old.id;
+    INSERT INTO workout_sessions_fts (session_id, notes)
+    SELECT new.id, new.notes WHERE new.notes IS NOT NULL;
+END;
+
+-- Keyset pagination walks (date, id) per user.
+CREATE INDEX IF NOT EXISTS idx_workout_sessions_user_date_id
+    ON workout_sessions(user_id, date, id);
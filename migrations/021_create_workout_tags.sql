@@ -0,0 +1,18 @@
+-- User-defined labels for workout sessions ("push", "deload", "competition").
+-- Tags are rows of their own so renaming one relabels every session at once;
+-- names are unique per user regardless of case.
+CREATE TABLE IF NOT EXISTS workout_tags (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL COLLATE NOCASE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS workout_session_tags (
+    session_id TEXT NOT NULL REFERENCES workout_sessions(id) ON DELETE CASCADE,
+    tag_id TEXT NOT NULL REFERENCES workout_tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (session_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_workout_session_tags_tag_id ON workout_session_tags(tag_id);
@@ -0,0 +1,6 @@
+-- Per-user training preferences. A user with no row uses the defaults in
+-- `models::preferences`, so nothing needs backfilling.
+CREATE TABLE IF NOT EXISTS user_preferences (
+    user_id TEXT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    weekly_session_target INTEGER NOT NULL DEFAULT 3
+);
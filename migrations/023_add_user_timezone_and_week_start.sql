@@ -0,0 +1,5 @@
+-- The user's IANA timezone and first day of the week, which decide what
+-- "today", "this week" and "this month" mean for them. `week_start` holds a
+-- three-letter day name as written by chrono ("Mon", "Sun", "Sat").
+ALTER TABLE user_preferences ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
+ALTER TABLE user_preferences ADD COLUMN week_start TEXT NOT NULL DEFAULT 'Mon';
@@ -0,0 +1,11 @@
+-- Public links to a user's year-in-review page, with tokens and expiry as
+-- for workout share links (009, 012). One link per user and year; sharing
+-- again replaces it.
+CREATE TABLE IF NOT EXISTS year_review_shares (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    year INTEGER NOT NULL,
+    token TEXT NOT NULL UNIQUE,
+    expires_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, year)
+);
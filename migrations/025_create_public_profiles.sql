@@ -0,0 +1,18 @@
+-- Opt-in public profile pages, linked as workouts are (009, 012). Revoking
+-- clears the token but keeps the section choices, so sharing again shows
+-- the same profile. Every section starts hidden.
+CREATE TABLE IF NOT EXISTS public_profiles (
+    user_id TEXT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    token TEXT UNIQUE,
+    expires_at DATETIME,
+    show_prs INTEGER NOT NULL DEFAULT 0,
+    show_sessions INTEGER NOT NULL DEFAULT 0,
+    show_consistency INTEGER NOT NULL DEFAULT 0
+);
+
+-- The exercises whose PRs the profile lists.
+CREATE TABLE IF NOT EXISTS public_profile_exercises (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    exercise_id TEXT NOT NULL REFERENCES exercises(id) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, exercise_id)
+);
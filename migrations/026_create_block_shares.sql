@@ -0,0 +1,17 @@
+-- Public links to several workouts at once: every session in a date range,
+-- every session with a tag, or the tagged sessions within a range (a
+-- training block). Either end of the range may be open when a tag is set.
+-- Tokens and expiry work as in 009 and 012. Deleting the tag deletes the
+-- links that follow it.
+CREATE TABLE IF NOT EXISTS block_shares (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token TEXT NOT NULL UNIQUE,
+    from_date DATE,
+    to_date DATE,
+    tag_id TEXT REFERENCES workout_tags(id) ON DELETE CASCADE,
+    expires_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_block_shares_user_id ON block_shares(user_id);
@@ -0,0 +1,27 @@
+-- Coaching. An athlete grants another user read access to their workouts
+-- and stats; commenting and programming workouts into the athlete's plan
+-- are separate opt-ins. Revoking deletes the row.
+CREATE TABLE IF NOT EXISTS coach_grants (
+    athlete_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    coach_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    can_comment INTEGER NOT NULL DEFAULT 0,
+    can_program INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (athlete_id, coach_id),
+    CHECK (athlete_id <> coach_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_coach_grants_coach_id ON coach_grants(coach_id);
+
+-- Comments on a workout, or on one of its sets when log_id is set. Written
+-- by the athlete or a coach allowed to comment; they outlive the grant.
+CREATE TABLE IF NOT EXISTS workout_comments (
+    id TEXT PRIMARY KEY NOT NULL,
+    session_id TEXT NOT NULL REFERENCES workout_sessions(id) ON DELETE CASCADE,
+    log_id TEXT REFERENCES workout_logs(id) ON DELETE CASCADE,
+    author_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_workout_comments_session_id ON workout_comments(session_id);
@@ -0,0 +1,4 @@
+-- Whether a workout's share link also serves progress charts of its
+-- exercises, which cover the owner's earlier, unshared sessions. Off by
+-- default, so existing links keep showing only their own sets.
+ALTER TABLE workout_sessions ADD COLUMN share_history INTEGER NOT NULL DEFAULT 0;
@@ -18,6 +18,10 @@
 //! correlate across restarts — that would require persisting the salt,
 //! which is unnecessary complexity for what OWASP actually requires (no
 //! raw-token disclosure, not cross-restart correlation).
+//!
+//! The `coach.*` events cover delegated access: an athlete granting,
+//! changing or revoking a coach's access, and everything a coach then does
+//! with the athlete's data, reads included.
 
 use axum::extract::FromRequestParts;
 use axum::http::request::Parts;
@@ -340,6 +344,64 @@ pub fn session_rejected(ctx: &AuditContext, session_fp: &str) {
     );
 }
 
+/// An athlete granted a coach access, changed what they may do, or revoked
+/// it (`change` is `granted` or `revoked`). The permissions are what the
+/// coach holds afterwards, so a revocation reports both as false.
+pub fn coach_access_changed(
+    ctx: &AuditContext,
+    actor_session_fp: &str,
+    athlete_id: &str,
+    coach_id: &str,
+    change: &str,
+    can_comment: bool,
+    can_program: bool,
+) {
+    let user_agent = ctx.user_agent.as_deref();
+    tracing::info!(
+        target: "liftlog::audit",
+        event = "coach.access",
+        actor_session_fp,
+        athlete_id,
+        coach_id,
+        change,
+        can_comment,
+        can_program,
+        client_ip = %ctx.client_ip,
+        user_agent,
+        path = %ctx.path,
+        "coach access changed"
+    );
+}
+
+/// Something a coach did with an athlete's data: viewing the overview or a
+/// workout, commenting, or programming a workout. `target` is the workout
+/// or comment acted on, empty for the overview. Reads are logged too, as
+/// an athlete deciding whether to keep a coach will want to know what they
+/// looked at.
+pub fn coach_action(
+    ctx: &AuditContext,
+    actor_session_fp: &str,
+    coach_id: &str,
+    athlete_id: &str,
+    action: &str,
+    target: &str,
+) {
+    let user_agent = ctx.user_agent.as_deref();
+    tracing::info!(
+        target: "liftlog::audit",
+        event = "coach.action",
+        actor_session_fp,
+        coach_id,
+        athlete_id,
+        action,
+        target,
+        client_ip = %ctx.client_ip,
+        user_agent,
+        path = %ctx.path,
+        "coach action"
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -373,7 +435,11 @@ mod tests {
         let state = AppState {
             user_repo: crate::repositories::UserRepository::new(pool.clone()),
             exercise_repo: crate::repositories::ExerciseRepository::new(pool.clone()),
+            category_repo: crate::repositories::CategoryRepository::new(pool.clone()),
+            tag_repo: crate::repositories::TagRepository::new(pool.clone()),
             workout_repo: crate::repositories::WorkoutRepository::new(pool.clone()),
+            share_repo: crate::repositories::ShareRepository::new(pool.clone()),
+            comment_repo: crate::repositories::CommentRepository::new(pool.clone()),
             session_repo: crate::repositories::SessionRepository::new(pool),
             login_rate_limiter: std::sync::Arc::new(crate::rate_limit::RateLimiter::new(
                 5,
@@ -393,7 +459,9 @@ mod tests {
             cookie_secure: false,
             hsts_max_age: 0,
             hsts_include_subdomains: false,
+            public_url: None,
             log_salt: std::sync::Arc::new([0u8; 32]),
+            share_card_cache: std::sync::Arc::new(crate::chart_image::PngCache::new(1)),
         };
 
         let stored = AuditContext {
@@ -0,0 +1,431 @@
+//! Month calendar and year heatmap of training days
+//!
+//! Both shade each day by its volume relative to the heaviest day on show,
+//! in four steps like a contribution graph, and link a day to its workout
+//! (or to the workout list filtered to that date when there were several).
+//! There is no separate program model: a workout created ahead of time with
+//! no sets logged yet is the plan, and is drawn as an outline until it is
+//! trained.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+
+use crate::models::DailyVolume;
+
+/// Shading steps for days with training; 0 is a rest day.
+pub const LEVELS: u8 = 4;
+
+/// Years a month or week may be asked for. Dates near chrono's limits have
+/// no neighbouring weeks to draw, so links to them are refused up front.
+pub const YEARS: RangeInclusive<i32> = 1900..=9999;
+
+/// Weeks (columns) in the heatmap, including the current one.
+pub const HEATMAP_WEEKS: i64 = 53;
+
+const CELL: f64 = 11.0;
+const GAP: f64 = 2.0;
+const PAD_L: f64 = 28.0;
+const PAD_T: f64 = 16.0;
+
+#[derive(Debug, Clone)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    /// False for the leading and trailing days of neighbouring months that
+    /// fill out the month grid.
+    pub in_month: bool,
+    pub is_today: bool,
+    /// 0 for no training, otherwise 1..=`LEVELS`.
+    pub level: u8,
+    /// A workout is scheduled but nothing is logged yet.
+    pub planned: bool,
+    pub sessions: i64,
+    pub sets: i64,
+    pub volume: f64,
+    pub link: Option<String>,
+}
+
+impl CalendarDay {
+    pub fn css_class(&self) -> String {
+        if self.planned {
+            "heat-planned".to_string()
+        } else {
+            format!("heat-{}", self.level)
+        }
+    }
+
+    /// Tooltip text, e.g. "2024-01-15: 1 workout, 18 sets, 9450 kg".
+    pub fn summary(&self) -> String {
+        if self.sessions == 0 {
+            return format!("{}: rest", self.date);
+        }
+        let workouts = plural(self.sessions, "workout");
+        if self.planned {
+            return format!("{}: {workouts} planned", self.date);
+        }
+        format!(
+            "{}: {workouts}, {}, {:.0} kg",
+            self.date,
+            plural(self.sets, "set"),
+            self.volume
+        )
+    }
+}
+
+fn plural(count: i64, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+/// The `first_day` on or before `date`: the start of its week.
+pub fn week_start(date: NaiveDate, first_day: Weekday) -> NaiveDate {
+    date - Duration::days(i64::from(date.weekday().days_since(first_day)))
+}
+
+/// Short day names in week order, starting from `first_day`.
+pub fn weekday_labels(first_day: Weekday) -> Vec<String> {
+    (0..7)
+        .scan(first_day, |day, _| {
+            let label = day.to_string();
+            *day = day.succ();
+            Some(label)
+        })
+        .collect()
+}
+
+/// Shading step for `volume` against the heaviest day, `max`. Any day with a
+/// session gets at least the first step, even one of bodyweight work only.
+fn level(volume: f64, max: f64) -> u8 {
+    if max <= 0.0 || volume <= 0.0 {
+        return 1;
+    }
+    let steps = f64::from(LEVELS);
+    #[allow(
+        clippy::cast_sign_loss,
+        reason = "clamped to 1..=LEVELS before the cast"
+    )]
+    let level = (volume / max * steps).ceil().clamp(1.0, steps) as u8;
+    level
+}
+
+struct Days<'a> {
+    by_date: HashMap<NaiveDate, &'a DailyVolume>,
+    max_volume: f64,
+    today: NaiveDate,
+}
+
+impl<'a> Days<'a> {
+    fn new(days: &'a [DailyVolume], today: NaiveDate) -> Self {
+        Self {
+            by_date: days.iter().map(|d| (d.date, d)).collect(),
+            max_volume: days.iter().map(|d| d.volume).fold(0.0, f64::max),
+            today,
+        }
+    }
+
+    fn day(&self, date: NaiveDate, in_month: bool) -> CalendarDay {
+        let mut day = CalendarDay {
+            date,
+            in_month,
+            is_today: date == self.today,
+            level: 0,
+            planned: false,
+            sessions: 0,
+            sets: 0,
+            volume: 0.0,
+            link: None,
+        };
+        if let Some(d) = self.by_date.get(&date) {
+            day.sessions = d.sessions;
+            day.sets = d.sets;
+            day.volume = d.volume;
+            day.planned = d.sets == 0 && date >= self.today;
+            day.level = if day.planned {
+                0
+            } else {
+                level(d.volume, self.max_volume)
+            };
+            day.link = Some(if d.sessions == 1 {
+                format!("/workouts/{}", d.session_id)
+            } else {
+                format!("/workouts?from={date}&to={date}")
+            });
+        }
+        day
+    }
+}
+
+/// One month laid out in whole weeks.
+#[derive(Debug)]
+pub struct MonthCalendar {
+    /// First day of the month.
+    pub month: NaiveDate,
+    /// Column headings, from the user's first day of the week.
+    pub weekdays: Vec<String>,
+    pub weeks: Vec<Vec<CalendarDay>>,
+    pub prev: NaiveDate,
+    pub next: NaiveDate,
+    pub sessions: i64,
+    pub volume: f64,
+}
+
+/// The first day of the month containing `date`.
+pub fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// The last day of the month containing `date`.
+pub fn month_end(date: NaiveDate) -> NaiveDate {
+    let first = month_start(date);
+    first
+        .checked_add_months(Months::new(1))
+        .map_or(first, |next| next - Duration::days(1))
+}
+
+/// The first day of a `YYYY-MM` month, if it is one in `YEARS`.
+pub fn parse_month(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{value}-01"), "%Y-%m-%d")
+        .ok()
+        .filter(|month| YEARS.contains(&month.year()))
+}
+
+/// First and last day of the grid drawn for `month`: whole weeks covering it.
+pub fn month_grid_range(month: NaiveDate, first_day: Weekday) -> (NaiveDate, NaiveDate) {
+    (
+        week_start(month_start(month), first_day),
+        week_start(month_end(month), first_day) + Duration::days(6),
+    )
+}
+
+/// Lay out `month` from the per-day totals in its grid range.
+pub fn month_calendar(
+    month: NaiveDate,
+    days: &[DailyVolume],
+    today: NaiveDate,
+    first_day: Weekday,
+) -> MonthCalendar {
+    let month = month_start(month);
+    let (start, end) = month_grid_range(month, first_day);
+    let in_month: Vec<DailyVolume> = days
+        .iter()
+        .filter(|d| month_start(d.date) == month)
+        .cloned()
+        .collect();
+    // Shade against this month's heaviest day, not a neighbour's.
+    let lookup = Days::new(&in_month, today);
+
+    let mut weeks = Vec::new();
+    let mut date = start;
+    while date <= end {
+        let week = (0..7)
+            .map(|offset| {
+                let day = date + Duration::days(offset);
+                lookup.day(day, month_start(day) == month)
+            })
+            .collect();
+        weeks.push(week);
+        date += Duration::days(7);
+    }
+
+    MonthCalendar {
+        month,
+        weekdays: weekday_labels(first_day),
+        weeks,
+        prev: month.checked_sub_months(Months::new(1)).unwrap_or(month),
+        next: month.checked_add_months(Months::new(1)).unwrap_or(month),
+        sessions: in_month.iter().map(|d| d.sessions).sum(),
+        volume: in_month.iter().map(|d| d.volume).sum(),
+    }
+}
+
+pub struct HeatmapCell {
+    pub x: f64,
+    pub y: f64,
+    pub day: CalendarDay,
+}
+
+/// Geometry for the year heatmap SVG: one column per week, the first day
+/// of the week at the top. Computed here so the template stays declarative, as with
+/// `RenderedChart`.
+pub struct Heatmap {
+    pub width: f64,
+    pub height: f64,
+    pub cell: f64,
+    pub cells: Vec<HeatmapCell>,
+    /// (`x_pixel`, label) at the first week of each month.
+    pub month_labels: Vec<(f64, String)>,
+    /// (`y_pixel`, label) for the first, third and fifth rows.
+    pub day_labels: Vec<(f64, String)>,
+    pub active_days: usize,
+}
+
+/// First and last day shown by the heatmap ending in the week of `today`.
+pub fn heatmap_range(today: NaiveDate, first_day: Weekday) -> (NaiveDate, NaiveDate) {
+    let last_week = week_start(today, first_day);
+    (
+        last_week - Duration::weeks(HEATMAP_WEEKS - 1),
+        last_week + Duration::days(6),
+    )
+}
+
+pub fn heatmap(days: &[DailyVolume], today: NaiveDate, first_day: Weekday) -> Heatmap {
+    let (start, end) = heatmap_range(today, first_day);
+    let lookup = Days::new(days, today);
+    let step = CELL + GAP;
+
+    let mut cells = Vec::new();
+    let mut month_labels = Vec::new();
+    let mut column = 0.0;
+    let mut week = start;
+    while week <= end {
+        let x = PAD_L + column * step;
+        // Label the first column that holds a 1st of the month.
+        if let Some(first) = (0..7)
+            .map(|offset| week + Duration::days(offset))
+            .find(|d| d.day() == 1)
+        {
+            month_labels.push((x, first.format("%b").to_string()));
+        }
+        for row in 0..7_u8 {
+            let date = week + Duration::days(i64::from(row));
+            cells.push(HeatmapCell {
+                x,
+                y: PAD_T + f64::from(row) * step,
+                day: lookup.day(date, true),
+            });
+        }
+        column += 1.0;
+        week += Duration::days(7);
+    }
+
+    let day_labels = weekday_labels(first_day)
+        .into_iter()
+        .zip(0_u8..)
+        .filter(|(_, row)| row % 2 == 0 && *row < 6)
+        .map(|(label, row)| (PAD_T + f64::from(row) * step + CELL - 1.0, label))
+        .collect();
+
+    Heatmap {
+        width: PAD_L + column * step,
+        height: PAD_T + 7.0 * step,
+        cell: CELL,
+        cells,
+        month_labels,
+        day_labels,
+        active_days: days.iter().filter(|d| d.sets > 0).count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn day(date: NaiveDate, sessions: i64, sets: i64, volume: f64) -> DailyVolume {
+        DailyVolume {
+            date,
+            sessions,
+            session_id: format!("s-{date}"),
+            sets,
+            volume,
+        }
+    }
+
+    #[test]
+    fn month_grid_covers_whole_weeks() {
+        // February 2024 starts on a Thursday and ends on a Thursday.
+        assert_eq!(
+            month_grid_range(date(2024, 2, 14), Weekday::Mon),
+            (date(2024, 1, 29), date(2024, 3, 3))
+        );
+        assert_eq!(
+            month_grid_range(date(2024, 2, 14), Weekday::Sun),
+            (date(2024, 1, 28), date(2024, 3, 2))
+        );
+        assert_eq!(
+            weekday_labels(Weekday::Sun),
+            vec!["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"]
+        );
+    }
+
+    #[test]
+    fn parse_month_refuses_years_out_of_range() {
+        assert_eq!(parse_month("2024-03"), Some(date(2024, 3, 1)));
+        assert_eq!(parse_month("2024-13"), None);
+        assert_eq!(parse_month("+262142-12"), None);
+        assert_eq!(parse_month("-262143-01"), None);
+        assert_eq!(parse_month("0999-01"), None);
+    }
+
+    #[test]
+    fn month_calendar_shades_links_and_outlines_planned_days() {
+        let days = vec![
+            day(date(2024, 2, 5), 1, 20, 10_000.0),
+            day(date(2024, 2, 7), 1, 5, 1_000.0),
+            day(date(2024, 2, 9), 2, 10, 5_000.0),
+            day(date(2024, 2, 20), 1, 0, 0.0),
+            // Outside the month: ignored for shading and totals.
+            day(date(2024, 1, 30), 1, 50, 90_000.0),
+        ];
+        let calendar = month_calendar(date(2024, 2, 1), &days, date(2024, 2, 15), Weekday::Mon);
+        assert_eq!(calendar.weeks.len(), 5);
+        assert_eq!(calendar.sessions, 5);
+        assert_eq!(calendar.prev, date(2024, 1, 1));
+        assert_eq!(calendar.next, date(2024, 3, 1));
+
+        let find = |d: NaiveDate| {
+            calendar
+                .weeks
+                .iter()
+                .flatten()
+                .find(|c| c.date == d)
+                .unwrap()
+        };
+        assert_eq!(find(date(2024, 2, 5)).level, 4);
+        assert_eq!(find(date(2024, 2, 7)).level, 1);
+        assert_eq!(find(date(2024, 2, 9)).level, 2);
+        assert_eq!(
+            find(date(2024, 2, 5)).link.as_deref(),
+            Some("/workouts/s-2024-02-05")
+        );
+        assert_eq!(
+            find(date(2024, 2, 9)).link.as_deref(),
+            Some("/workouts?from=2024-02-09&to=2024-02-09")
+        );
+        let planned = find(date(2024, 2, 20));
+        assert!(planned.planned);
+        assert_eq!(planned.css_class(), "heat-planned");
+        assert!(!find(date(2024, 1, 30)).in_month);
+        assert_eq!(find(date(2024, 1, 30)).level, 0);
+        assert!(find(date(2024, 2, 15)).is_today);
+    }
+
+    #[test]
+    fn heatmap_ends_with_the_current_week() {
+        let today = date(2024, 6, 12);
+        let (start, end) = heatmap_range(today, Weekday::Mon);
+        assert_eq!(start.weekday(), Weekday::Mon);
+        assert_eq!(end, date(2024, 6, 16));
+
+        let map = heatmap(
+            &[day(date(2024, 6, 10), 1, 12, 4_000.0)],
+            today,
+            Weekday::Mon,
+        );
+        assert_eq!(map.cells.len(), 53 * 7);
+        assert_eq!(map.active_days, 1);
+        let last_monday = &map.cells[52 * 7];
+        assert_eq!(last_monday.day.date, date(2024, 6, 10));
+        assert_eq!(last_monday.day.level, 4);
+        assert_eq!(map.month_labels.len(), 12);
+        let labels: Vec<&str> = map.day_labels.iter().map(|(_, l)| l.as_str()).collect();
+        assert_eq!(labels, vec!["Mon", "Wed", "Fri"]);
+    }
+}
@@ -0,0 +1,159 @@
+//! Built-in exercise catalog
+//!
+//! The catalog ships inside the binary the same way migrations do: the JSON
+//! at `catalog/exercises.json` is embedded with `include_str!` and parsed once
+//! on first use. Users copy entries into their own `exercises` table from the
+//! catalog page; each copy keeps the entry's `id` in `exercises.catalog_id`,
+//! so the same lift can be matched across users and across imports regardless
+//! of what anyone renamed it to.
+//!
+//! Catalog ids are permanent. Entries may be renamed or gain aliases, but an
+//! id that has shipped must never be reused for a different exercise.
+
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+
+use crate::models::ExerciseMuscle;
+use crate::models::muscle::MuscleRole;
+
+const CATALOG_JSON: &str = include_str!("../catalog/exercises.json");
+
+/// How sets of an exercise are naturally measured. Everything is still logged
+/// as weight × reps today; this tells the UI which numbers matter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Measurement {
+    WeightReps,
+    Reps,
+    Time,
+    Distance,
+}
+
+impl Measurement {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Measurement::WeightReps => "Weight × Reps",
+            Measurement::Reps => "Reps",
+            Measurement::Time => "Time",
+            Measurement::Distance => "Distance",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CatalogExercise {
+    pub id: String,
+    pub name: String,
+    pub aliases: Vec<String>,
+    /// One of the default category keys seeded by migration 014.
+    pub category: String,
+    pub equipment: String,
+    pub primary_muscles: Vec<String>,
+    pub secondary_muscles: Vec<String>,
+    pub measurement: Measurement,
+    pub unilateral: bool,
+}
+
+impl CatalogExercise {
+    /// The entry's muscle mapping in the form `ExerciseRepository` stores.
+    pub fn muscles(&self) -> Vec<ExerciseMuscle> {
+        let primary = self.primary_muscles.iter().map(|m| ExerciseMuscle {
+            muscle: m.clone(),
+            role: MuscleRole::Primary,
+        });
+        let secondary = self.secondary_muscles.iter().map(|m| ExerciseMuscle {
+            muscle: m.clone(),
+            role: MuscleRole::Secondary,
+        });
+        primary.chain(secondary).collect()
+    }
+
+    /// Whether `name` refers to this entry, by name or alias, ignoring case and
+    /// surrounding whitespace. Used to link exercises a user typed in by hand
+    /// before the catalog existed rather than importing a duplicate.
+    pub fn matches_name(&self, name: &str) -> bool {
+        let name = name.trim();
+        self.name.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Outcome of `ExerciseRepository::import_from_catalog`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// New exercises created from catalog entries.
+    pub created: usize,
+    /// Existing hand-made exercises that matched an entry by name or alias and
+    /// were linked to it instead of being duplicated.
+    pub linked: usize,
+    /// Entries the user already had a linked exercise for.
+    pub skipped: usize,
+}
+
+static CATALOG: LazyLock<Vec<CatalogExercise>> = LazyLock::new(|| {
+    // The file is compiled in and covered by the tests below, so a parse
+    // failure here is a build defect, not a runtime condition to handle.
+    serde_json::from_str(CATALOG_JSON).expect("catalog/exercises.json is valid")
+});
+
+/// Every catalog entry, in file order (grouped by category).
+pub fn entries() -> &'static [CatalogExercise] {
+    &CATALOG
+}
+
+#[cfg(test)]
+pub fn find(id: &str) -> Option<&'static CatalogExercise> {
+    CATALOG.iter().find(|e| e.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::models::muscle::MUSCLE_GROUPS;
+
+    const DEFAULT_CATEGORIES: &[&str] = &["chest", "back", "legs", "shoulders", "arms", "core"];
+
+    #[test]
+    fn catalog_parses_and_ids_are_unique() {
+        let mut seen = HashSet::new();
+        for entry in entries() {
+            assert!(seen.insert(&entry.id), "duplicate catalog id {}", entry.id);
+        }
+        assert!(!entries().is_empty());
+    }
+
+    #[test]
+    fn catalog_entries_reference_known_muscles_and_categories() {
+        for entry in entries() {
+            assert!(
+                DEFAULT_CATEGORIES.contains(&entry.category.as_str()),
+                "{} has unknown category {}",
+                entry.id,
+                entry.category
+            );
+            assert!(
+                !entry.primary_muscles.is_empty(),
+                "{} has no primary muscle",
+                entry.id
+            );
+            for muscle in entry.primary_muscles.iter().chain(&entry.secondary_muscles) {
+                assert!(
+                    MUSCLE_GROUPS.iter().any(|m| m.name == muscle),
+                    "{} references unknown muscle {muscle}",
+                    entry.id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matches_name_checks_aliases_case_insensitively() {
+        let ohp = find("overhead-press").unwrap();
+        assert!(ohp.matches_name("overhead press"));
+        assert!(ohp.matches_name(" OHP "));
+        assert!(!ohp.matches_name("Bench Press"));
+    }
+}
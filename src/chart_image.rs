@@ -0,0 +1,132 @@
+//! PNG rendering of chart SVGs
+//!
+//! Charts are drawn as SVG and rasterised here with resvg, which also
+//! renders the apple-touch icon at build time. Text is set in the system's
+//! fonts, loaded once; a host without fonts gets charts without labels.
+//! `PngCache` keeps recent renderings for images fetched again and again,
+//! such as a shared workout's preview card.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use resvg::{tiny_skia, usvg};
+use sha2::{Digest, Sha256};
+
+use crate::error::{AppError, Result};
+
+fn fonts() -> Arc<usvg::fontdb::Database> {
+    static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = usvg::fontdb::Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+/// Rasterise `svg` at the size it declares. CPU-bound, so call it from
+/// `spawn_blocking`.
+pub fn svg_to_png(svg: &str) -> Result<Vec<u8>> {
+    let options = usvg::Options {
+        fontdb: fonts(),
+        ..usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &options)
+        .map_err(|e| AppError::Internal(format!("Invalid chart SVG: {e}")))?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| AppError::Internal("Chart has no area".to_string()))?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap
+        .encode_png()
+        .map_err(|e| AppError::Internal(format!("PNG encoding failed: {e}")))
+}
+
+/// SHA-256 of an SVG source and its PNG.
+type CacheEntries = VecDeque<([u8; 32], Arc<[u8]>)>;
+
+/// Rasterised SVGs keyed by a digest of the source, so a changed image is
+/// a new entry and a stale one is never served. Holds at most `capacity`
+/// images, dropping the oldest first.
+pub struct PngCache {
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+}
+
+impl PngCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// A poisoned lock only means a panic mid-insert; the entries are
+    /// still whole images, so carry on with them.
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheEntries> {
+        self.entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// `svg` as a PNG, from the cache when it was rendered before.
+    pub async fn render(&self, svg: String) -> Result<Arc<[u8]>> {
+        let key: [u8; 32] = Sha256::digest(svg.as_bytes()).into();
+        if let Some((_, png)) = self.lock().iter().find(|(k, _)| *k == key) {
+            return Ok(png.clone());
+        }
+        let png: Arc<[u8]> = tokio::task::spawn_blocking(move || svg_to_png(&svg))
+            .await??
+            .into();
+        let mut entries = self.lock();
+        if !entries.iter().any(|(k, _)| *k == key) {
+            if entries.len() >= self.capacity {
+                entries.pop_front();
+            }
+            entries.push_back((key, png.clone()));
+        }
+        Ok(png)
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.lock().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rasterises_at_the_declared_size() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20"><rect width="40" height="20" fill="#FF5A10"/></svg>"##;
+        let png = svg_to_png(svg).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        // IHDR width and height, big-endian.
+        assert_eq!(&png[16..24], &[0, 0, 0, 40, 0, 0, 0, 20]);
+        assert!(svg_to_png("not svg").is_err());
+    }
+
+    #[tokio::test]
+    async fn cache_reuses_renderings_and_drops_the_oldest() {
+        let svg = |fill: &str| {
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><rect width="4" height="4" fill="{fill}"/></svg>"#
+            )
+        };
+        let cache = PngCache::new(2);
+        let first = cache.render(svg("red")).await.unwrap();
+        let again = cache.render(svg("red")).await.unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+        assert_eq!(cache.len(), 1);
+
+        cache.render(svg("green")).await.unwrap();
+        cache.render(svg("blue")).await.unwrap();
+        assert_eq!(cache.len(), 2);
+        let rerendered = cache.render(svg("red")).await.unwrap();
+        assert!(!Arc::ptr_eq(&first, &rerendered));
+        assert_eq!(first, rerendered);
+    }
+}
@@ -21,6 +21,7 @@ pub struct Config {
     pub cookie_secure: bool,
     pub hsts_max_age: u64,
     pub hsts_include_subdomains: bool,
+    pub public_url: Option<String>,
 }
 
 /// Env vars that were renamed under the `LIFTLOG_` prefix, paired with their
@@ -80,6 +81,8 @@ impl Config {
                 false,
             )
             .map_err(anyhow::Error::msg)?,
+            public_url: parse_public_url(read_env_var("LIFTLOG_PUBLIC_URL")?.as_deref())
+                .map_err(anyhow::Error::msg)?,
         })
     }
 }
@@ -238,6 +241,30 @@ pub fn parse_hsts_max_age(raw: Option<&str>) -> Result<u64, String> {
         .map_err(|e| format!("invalid LIFTLOG_HSTS_MAX_AGE '{raw}': {e}"))
 }
 
+/// Resolve `LIFTLOG_PUBLIC_URL`, the `scheme://host` the site is served at.
+/// Unset or empty means `None`; anything else must be an `http` or `https`
+/// URL, and a trailing slash is dropped so paths can be appended to it.
+pub fn parse_public_url(raw: Option<&str>) -> Result<Option<String>, String> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+    let trimmed = raw.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    let host = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"));
+    match host {
+        Some(host) if !host.is_empty() && !host.contains(char::is_whitespace) => {
+            Ok(Some(trimmed.to_string()))
+        }
+        _ => Err(format!(
+            "invalid LIFTLOG_PUBLIC_URL '{raw}': expected http://host or https://host"
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -516,4 +543,35 @@ mod tests {
         let err = parse_hsts_max_age(Some("-1")).unwrap_err();
         assert!(err.contains("invalid LIFTLOG_HSTS_MAX_AGE"), "got: {err}");
     }
+
+    #[test]
+    fn parse_public_url_defaults_to_none() {
+        assert_eq!(parse_public_url(None).unwrap(), None);
+        assert_eq!(parse_public_url(Some("  ")).unwrap(), None);
+    }
+
+    #[test]
+    fn parse_public_url_drops_the_trailing_slash() {
+        assert_eq!(
+            parse_public_url(Some("https://lifts.example/")).unwrap(),
+            Some("https://lifts.example".to_string())
+        );
+        assert_eq!(
+            parse_public_url(Some("http://10.0.0.2:8080")).unwrap(),
+            Some("http://10.0.0.2:8080".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_public_url_rejects_other_values() {
+        for raw in [
+            "lifts.example",
+            "ftp://lifts.example",
+            "https://",
+            "https://a b",
+        ] {
+            let err = parse_public_url(Some(raw)).unwrap_err();
+            assert!(err.contains("invalid LIFTLOG_PUBLIC_URL"), "got: {err}");
+        }
+    }
 }
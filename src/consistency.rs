@@ -0,0 +1,255 @@
+//! Training consistency: weekly streaks and adherence to a target
+//!
+//! A week "meets the target" when it has at least the user's weekly session
+//! target of trained sessions. Weeks start on the user's chosen day. The week in
+//! progress never breaks a streak: it only extends one once the target is
+//! reached, and it is left out of adherence until it is over. The current
+//! target is applied to the whole history.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate, Weekday};
+
+use crate::calendar::week_start;
+use crate::models::WeeklySessionCount;
+
+/// Weeks shown in the sessions-per-week chart and counted for adherence.
+pub const CONSISTENCY_WEEKS: i64 = 52;
+
+const CHART_W: f64 = 600.0;
+const CHART_H: f64 = 140.0;
+const PAD_L: f64 = 24.0;
+const PAD_R: f64 = 4.0;
+const PAD_T: f64 = 8.0;
+const PAD_B: f64 = 18.0;
+
+#[derive(Debug, Clone)]
+pub struct WeekBar {
+    pub week_start: NaiveDate,
+    pub sessions: i64,
+    pub met: bool,
+    pub x: f64,
+    pub y: f64,
+    pub height: f64,
+}
+
+/// Geometry for the sessions-per-week bar chart, oldest week first.
+#[derive(Debug, Clone)]
+pub struct WeeklyChart {
+    pub width: f64,
+    pub height: f64,
+    pub bar_width: f64,
+    pub bars: Vec<WeekBar>,
+    /// Y pixel of the dashed target line.
+    pub target_y: f64,
+    pub padding_left: f64,
+    pub padding_right: f64,
+    /// Y-axis labels: (`y_pixel`, `label_text`).
+    pub y_ticks: Vec<(f64, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Consistency {
+    pub target: i64,
+    /// Sessions so far in the current week.
+    pub this_week: i64,
+    /// Consecutive weeks meeting the target, up to this week.
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    /// Percentage of completed weeks in the last year that met the target,
+    /// counted from the first week trained. `None` before any week has
+    /// finished.
+    pub adherence: Option<i64>,
+    pub chart: WeeklyChart,
+}
+
+/// Work out streaks, adherence and the chart from the per-week counts
+/// (weeks without training may be missing) as of `today`. The counts must be
+/// grouped into weeks starting on `first_day`.
+pub fn consistency(
+    weeks: &[WeeklySessionCount],
+    target: i64,
+    today: NaiveDate,
+    first_day: Weekday,
+) -> Consistency {
+    let counts: HashMap<NaiveDate, i64> =
+        weeks.iter().map(|w| (w.week_start, w.sessions)).collect();
+    let sessions = |week: NaiveDate| counts.get(&week).copied().unwrap_or(0);
+    let current_week = week_start(today, first_day);
+    let this_week = sessions(current_week);
+
+    let mut current_streak = 0;
+    let mut week = current_week - Duration::weeks(1);
+    while sessions(week) >= target {
+        current_streak += 1;
+        week -= Duration::weeks(1);
+    }
+    if this_week >= target {
+        current_streak += 1;
+    }
+
+    let window_start = current_week - Duration::weeks(CONSISTENCY_WEEKS);
+    let first_trained = weeks
+        .iter()
+        .map(|w| w.week_start)
+        .min()
+        .unwrap_or(current_week);
+    let mut completed = 0;
+    let mut met = 0;
+    let mut week = window_start.max(first_trained);
+    while week < current_week {
+        completed += 1;
+        if sessions(week) >= target {
+            met += 1;
+        }
+        week += Duration::weeks(1);
+    }
+    let adherence = (completed > 0).then(|| (met * 100 + completed / 2) / completed);
+
+    Consistency {
+        target,
+        this_week,
+        current_streak,
+        longest_streak: longest_streak(weeks, target),
+        adherence,
+        chart: weekly_chart(&sessions, current_week, target),
+    }
+}
+
+/// Most consecutive weeks meeting `target` in `weeks`, which must be in
+/// order.
+pub fn longest_streak(weeks: &[WeeklySessionCount], target: i64) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for w in weeks.iter().filter(|w| w.sessions >= target) {
+        run = match previous {
+            Some(p) if w.week_start - p == Duration::weeks(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(w.week_start);
+    }
+    longest
+}
+
+fn weekly_chart(
+    sessions: &dyn Fn(NaiveDate) -> i64,
+    current_week: NaiveDate,
+    target: i64,
+) -> WeeklyChart {
+    let first = current_week - Duration::weeks(CONSISTENCY_WEEKS - 1);
+    let counts: Vec<(NaiveDate, i64)> = (0..CONSISTENCY_WEEKS)
+        .map(|i| {
+            let week = first + Duration::weeks(i);
+            (week, sessions(week))
+        })
+        .collect();
+    let max = counts
+        .iter()
+        .map(|(_, n)| *n)
+        .max()
+        .unwrap_or(0)
+        .max(target)
+        .max(1);
+
+    let (max, weeks) = (max as f64, CONSISTENCY_WEEKS as f64);
+    let plot_h = CHART_H - PAD_T - PAD_B;
+    let step = (CHART_W - PAD_L - PAD_R) / weeks;
+    let y_for = |n: f64| PAD_T + plot_h - n / max * plot_h;
+
+    let bars = counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, (week, n))| {
+            let (i, count) = (i as f64, n as f64);
+            let y = y_for(count);
+            WeekBar {
+                week_start: week,
+                sessions: n,
+                met: n >= target,
+                x: PAD_L + i * step + 1.0,
+                y,
+                height: PAD_T + plot_h - y,
+            }
+        })
+        .collect();
+
+    let target_y = y_for(target as f64);
+    WeeklyChart {
+        width: CHART_W,
+        height: CHART_H,
+        bar_width: (step - 2.0).max(1.0),
+        bars,
+        target_y,
+        padding_left: PAD_L,
+        padding_right: PAD_R,
+        y_ticks: vec![
+            (y_for(0.0), "0".to_string()),
+            (y_for(max), format!("{max}")),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn week(y: i32, m: u32, d: u32, sessions: i64) -> WeeklySessionCount {
+        WeeklySessionCount {
+            week_start: NaiveDate::from_ymd_opt(y, m, d).unwrap(),
+            sessions,
+        }
+    }
+
+    #[test]
+    fn current_streak_does_not_break_on_the_week_in_progress() {
+        let weeks = vec![
+            week(2024, 1, 1, 3),
+            week(2024, 1, 8, 2),
+            week(2024, 1, 15, 3),
+            week(2024, 1, 22, 4),
+            week(2024, 1, 29, 3),
+            week(2024, 2, 5, 1),
+        ];
+        // Wednesday of the week of 5 February.
+        let today = NaiveDate::from_ymd_opt(2024, 2, 7).unwrap();
+        let c = consistency(&weeks, 3, today, Weekday::Mon);
+        assert_eq!(c.this_week, 1);
+        assert_eq!(c.current_streak, 3);
+        assert_eq!(c.longest_streak, 3);
+        // Four of the five finished weeks met the target.
+        assert_eq!(c.adherence, Some(80));
+
+        let c = consistency(&weeks, 1, today, Weekday::Mon);
+        assert_eq!(c.current_streak, 6);
+        assert_eq!(c.longest_streak, 6);
+        assert_eq!(c.adherence, Some(100));
+    }
+
+    #[test]
+    fn a_missed_week_ends_the_streak() {
+        let weeks = vec![
+            week(2024, 1, 1, 3),
+            week(2024, 1, 8, 3),
+            week(2024, 1, 15, 3),
+            week(2024, 1, 29, 3),
+        ];
+        let today = NaiveDate::from_ymd_opt(2024, 2, 12).unwrap();
+        let c = consistency(&weeks, 3, today, Weekday::Mon);
+        assert_eq!(c.current_streak, 0);
+        assert_eq!(c.longest_streak, 3);
+        assert_eq!(c.adherence, Some(67));
+    }
+
+    #[test]
+    fn no_history_has_no_adherence() {
+        let today = NaiveDate::from_ymd_opt(2024, 2, 12).unwrap();
+        let c = consistency(&[], 3, today, Weekday::Mon);
+        assert_eq!(c.current_streak, 0);
+        assert_eq!(c.longest_streak, 0);
+        assert_eq!(c.adherence, None);
+        assert_eq!(c.chart.bars.len(), 52);
+        assert_eq!(c.chart.bars[51].week_start, today);
+    }
+}
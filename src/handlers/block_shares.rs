@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use askama::Template;
+use axum::{
+    Form,
+    extract::{Path, State},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use serde::Deserialize;
+
+use crate::error::{AppError, Result};
+use crate::handlers::workouts::ShareForm;
+use crate::middleware::AuthUser;
+use crate::models::{
+    BlockScope, BlockShare, ExercisePeriodTotals, MAX_BLOCK_SESSIONS, PeriodSummary,
+    WorkoutLogWithExercise, WorkoutSession, WorkoutTag,
+};
+use crate::report::format_rpe;
+use crate::state::AppState;
+
+#[derive(Template)]
+#[template(path = "workouts/shares.html")]
+struct BlockSharesTemplate {
+    user: AuthUser,
+    shares: Vec<BlockShare>,
+    tags: Vec<WorkoutTag>,
+    error: Option<String>,
+}
+
+/// A session on the public page, with its sets.
+struct BlockSession {
+    workout: WorkoutSession,
+    logs: Vec<WorkoutLogWithExercise>,
+}
+
+#[derive(Template)]
+#[template(path = "workouts/block_shared.html")]
+struct SharedBlockTemplate {
+    title: String,
+    owner_username: String,
+    summary: PeriodSummary,
+    average_rpe: String,
+    exercises: Vec<ExercisePeriodTotals>,
+    /// The first `MAX_BLOCK_SESSIONS` workouts of the block.
+    sessions: Vec<BlockSession>,
+    /// Every workout in the block, with sets or not.
+    total: i64,
+    /// Workouts in the block beyond those listed.
+    more: i64,
+}
+
+#[derive(Deserialize)]
+pub struct BlockShareForm {
+    #[serde(default)]
+    from: String,
+    #[serde(default)]
+    to: String,
+    #[serde(default)]
+    tag_id: String,
+    #[serde(flatten)]
+    share: ShareForm,
+}
+
+async fn render_page(
+    state: &AppState,
+    auth_user: AuthUser,
+    error: Option<String>,
+) -> Result<Response> {
+    let shares = state.share_repo.find_block_shares(&auth_user.id).await?;
+    let tags = state.tag_repo.find_by_user(&auth_user.id).await?;
+    let template = BlockSharesTemplate {
+        user: auth_user,
+        shares,
+        tags,
+        error,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// The owner's share links covering several workouts: a date range, a tag,
+/// or a tag within a range (a training block).
+pub async fn index(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    render_page(&state, auth_user, None).await
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Form(form): Form<BlockShareForm>,
+) -> Result<Response> {
+    let ttl = form.share.ttl()?;
+    let scope = match BlockScope::parse(&form.from, &form.to, &form.tag_id) {
+        Ok(scope) => scope,
+        Err(message) => return render_page(&state, auth_user, Some(message)).await,
+    };
+    if let Some(tag_id) = &scope.tag_id {
+        let tags = state.tag_repo.find_by_user(&auth_user.id).await?;
+        if !tags.iter().any(|tag| tag.id == *tag_id) {
+            return Err(AppError::NotFound("Tag not found".to_string()));
+        }
+    }
+    state
+        .share_repo
+        .create_block_share(&auth_user.id, &scope, ttl)
+        .await?;
+    Ok(Redirect::to("/workouts/shares").into_response())
+}
+
+pub async fn revoke(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    if !state
+        .share_repo
+        .revoke_block_share(&auth_user.id, &id)
+        .await?
+    {
+        return Err(AppError::NotFound("Share link not found".to_string()));
+    }
+    Ok(Redirect::to("/workouts/shares").into_response())
+}
+
+/// The public page of a block. Expired and unknown tokens are both 404, as
+/// for shared workouts. Sessions are listed oldest first, at most
+/// `MAX_BLOCK_SESSIONS` of them.
+pub async fn view_shared(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Response> {
+    let not_found = || AppError::NotFound("Shared workouts not found".to_string());
+    let share = state
+        .share_repo
+        .find_block_share_by_token(&token)
+        .await?
+        .ok_or_else(not_found)?;
+    let owner = state
+        .user_repo
+        .find_by_id(&share.user_id)
+        .await?
+        .ok_or_else(not_found)?;
+
+    let repo = &state.workout_repo;
+    let workouts = repo
+        .find_block_sessions(&owner.id, &share.scope, MAX_BLOCK_SESSIONS)
+        .await?;
+    let mut logs: HashMap<String, Vec<WorkoutLogWithExercise>> = HashMap::new();
+    for log in repo
+        .find_block_logs(&owner.id, &share.scope, MAX_BLOCK_SESSIONS)
+        .await?
+    {
+        logs.entry(log.session_id.clone()).or_default().push(log);
+    }
+    let sessions: Vec<BlockSession> = workouts
+        .into_iter()
+        .map(|workout| BlockSession {
+            logs: logs.remove(&workout.id).unwrap_or_default(),
+            workout,
+        })
+        .collect();
+    let summary = repo.get_block_summary(&owner.id, &share.scope).await?;
+    let total = repo.count_block_sessions(&owner.id, &share.scope).await?;
+    let more = total.saturating_sub(i64::try_from(sessions.len()).unwrap_or(i64::MAX));
+
+    let template = SharedBlockTemplate {
+        title: share.label(),
+        owner_username: owner.username,
+        average_rpe: format_rpe(summary.average_rpe),
+        summary,
+        exercises: repo
+            .get_block_exercise_totals(&owner.id, &share.scope)
+            .await?,
+        total,
+        more,
+        sessions,
+    };
+    Ok(Html(template.render()?).into_response())
+}
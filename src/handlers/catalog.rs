@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use askama::Template;
+use axum::{
+    Form,
+    extract::State,
+    response::{Html, IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::catalog::{self, CatalogExercise, ImportSummary};
+use crate::error::Result;
+use crate::middleware::AuthUser;
+use crate::models::muscle::muscle_display_name;
+use crate::state::AppState;
+
+pub(crate) struct CatalogRow {
+    pub(crate) id: &'static str,
+    pub(crate) name: &'static str,
+    pub(crate) aliases: String,
+    pub(crate) equipment: &'static str,
+    pub(crate) muscles: String,
+    pub(crate) measurement: &'static str,
+    pub(crate) unilateral: bool,
+    /// The user already has an exercise linked to this entry.
+    pub(crate) imported: bool,
+}
+
+pub(crate) struct CatalogGroup {
+    pub(crate) display_name: String,
+    pub(crate) rows: Vec<CatalogRow>,
+}
+
+#[derive(Template)]
+#[template(path = "exercises/catalog.html")]
+struct CatalogTemplate {
+    user: AuthUser,
+    groups: Vec<CatalogGroup>,
+    error: Option<String>,
+    success: Option<String>,
+}
+
+/// `all=1` imports everything; otherwise one `entry.<catalog_id>` checkbox per
+/// selected row. Flattened for the same reason as the muscle picker: the
+/// urlencoded extractor cannot collect repeated keys into a `Vec`.
+#[derive(Deserialize)]
+pub struct ImportCatalogForm {
+    #[serde(flatten)]
+    fields: HashMap<String, String>,
+}
+
+fn catalog_row(entry: &'static CatalogExercise, imported: bool) -> CatalogRow {
+    let muscles = entry
+        .primary_muscles
+        .iter()
+        .map(|m| muscle_display_name(m).to_string())
+        .chain(
+            entry
+                .secondary_muscles
+                .iter()
+                .map(|m| format!("{} (secondary)", muscle_display_name(m))),
+        )
+        .collect::<Vec<_>>()
+        .join(", ");
+    CatalogRow {
+        id: &entry.id,
+        name: &entry.name,
+        aliases: entry.aliases.join(", "),
+        equipment: &entry.equipment,
+        muscles,
+        measurement: entry.measurement.display_name(),
+        unilateral: entry.unilateral,
+        imported,
+    }
+}
+
+async fn render_page(
+    state: &AppState,
+    auth_user: AuthUser,
+    error: Option<String>,
+    success: Option<String>,
+) -> Result<Response> {
+    // Archived exercises still count as imported; importing skips them too.
+    let mut exercises = state
+        .exercise_repo
+        .find_available_for_user(&auth_user.id)
+        .await?;
+    exercises.extend(
+        state
+            .exercise_repo
+            .find_archived_for_user(&auth_user.id)
+            .await?,
+    );
+    let categories = state.category_repo.find_by_user(&auth_user.id).await?;
+
+    // Catalog entries are grouped by category in file order; headings use the
+    // user's own name for the category when they still have it.
+    let mut groups: Vec<(&str, CatalogGroup)> = Vec::new();
+    for entry in catalog::entries() {
+        let imported = exercises
+            .iter()
+            .any(|e| e.catalog_id.as_deref() == Some(entry.id.as_str()));
+        let row = catalog_row(entry, imported);
+        if let Some((_, group)) = groups.iter_mut().find(|(key, _)| *key == entry.category) {
+            group.rows.push(row);
+        } else {
+            let display_name = categories
+                .iter()
+                .find(|c| c.name == entry.category)
+                .map_or_else(|| entry.category.clone(), |c| c.display_name.clone());
+            groups.push((
+                &entry.category,
+                CatalogGroup {
+                    display_name,
+                    rows: vec![row],
+                },
+            ));
+        }
+    }
+
+    let template = CatalogTemplate {
+        user: auth_user,
+        groups: groups.into_iter().map(|(_, group)| group).collect(),
+        error,
+        success,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+fn summary_message(summary: ImportSummary) -> String {
+    let plural = if summary.created == 1 { "" } else { "s" };
+    if summary.linked > 0 {
+        format!(
+            "Imported {} exercise{plural}; linked {} you already had",
+            summary.created, summary.linked
+        )
+    } else {
+        format!("Imported {} exercise{plural}", summary.created)
+    }
+}
+
+pub async fn index(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    render_page(&state, auth_user, None, None).await
+}
+
+pub async fn import(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Form(form): Form<ImportCatalogForm>,
+) -> Result<Response> {
+    let import_all = form.fields.contains_key("all");
+    let entries: Vec<&'static CatalogExercise> = catalog::entries()
+        .iter()
+        .filter(|e| import_all || form.fields.contains_key(&format!("entry.{}", e.id)))
+        .collect();
+
+    if entries.is_empty() {
+        return render_page(
+            &state,
+            auth_user,
+            Some("Select at least one exercise to import".to_string()),
+            None,
+        )
+        .await;
+    }
+
+    let summary = state
+        .exercise_repo
+        .import_from_catalog(&auth_user.id, entries)
+        .await?;
+    render_page(&state, auth_user, None, Some(summary_message(summary))).await
+}
@@ -0,0 +1,135 @@
+use askama::Template;
+use axum::{
+    Form,
+    extract::{Path, State},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+
+use crate::error::{AppError, Result};
+use crate::middleware::AuthUser;
+use crate::models::exercise::category_key;
+use crate::models::{CategoryForm, ExerciseCategory, MoveCategoryForm};
+use crate::state::AppState;
+
+/// Longest display name accepted. Category names end up as picker headings,
+/// so anything past this is almost certainly a paste accident.
+const MAX_CATEGORY_NAME_LEN: usize = 40;
+
+#[derive(Template)]
+#[template(path = "exercises/categories.html")]
+struct CategoriesTemplate {
+    user: AuthUser,
+    categories: Vec<ExerciseCategory>,
+    error: Option<String>,
+}
+
+async fn render_page(
+    state: &AppState,
+    auth_user: AuthUser,
+    error: Option<String>,
+) -> Result<Response> {
+    let categories = state.category_repo.find_by_user(&auth_user.id).await?;
+    let template = CategoriesTemplate {
+        user: auth_user,
+        categories,
+        error,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+fn display_name_error(display_name: &str) -> Option<String> {
+    if display_name.is_empty() {
+        Some("Category name is required".to_string())
+    } else if display_name.chars().count() > MAX_CATEGORY_NAME_LEN {
+        Some(format!(
+            "Category name must be at most {MAX_CATEGORY_NAME_LEN} characters"
+        ))
+    } else if category_key(display_name).is_empty() {
+        Some("Category name must contain a letter or digit".to_string())
+    } else {
+        None
+    }
+}
+
+pub async fn list(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    render_page(&state, auth_user, None).await
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Form(form): Form<CategoryForm>,
+) -> Result<Response> {
+    let display_name = form.display_name.trim();
+    if let Some(error) = display_name_error(display_name) {
+        return render_page(&state, auth_user, Some(error)).await;
+    }
+    match state
+        .category_repo
+        .create(&auth_user.id, &category_key(display_name), display_name)
+        .await
+    {
+        Ok(_) => Ok(Redirect::to("/exercises/categories").into_response()),
+        Err(AppError::BadRequest(message)) => render_page(&state, auth_user, Some(message)).await,
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn rename(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(name): Path<String>,
+    Form(form): Form<CategoryForm>,
+) -> Result<Response> {
+    let display_name = form.display_name.trim();
+    if let Some(error) = display_name_error(display_name) {
+        return render_page(&state, auth_user, Some(error)).await;
+    }
+
+    match state
+        .category_repo
+        .rename(&auth_user.id, &name, display_name)
+        .await
+    {
+        Ok(true) => Ok(Redirect::to("/exercises/categories").into_response()),
+        Ok(false) => Err(AppError::NotFound("Category not found".to_string())),
+        Err(AppError::BadRequest(message)) => render_page(&state, auth_user, Some(message)).await,
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn move_category(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(name): Path<String>,
+    Form(form): Form<MoveCategoryForm>,
+) -> Result<Response> {
+    let up = match form.direction.as_str() {
+        "up" => true,
+        "down" => false,
+        _ => return Err(AppError::BadRequest("Invalid direction".to_string())),
+    };
+
+    if !state
+        .category_repo
+        .move_category(&auth_user.id, &name, up)
+        .await?
+    {
+        return Err(AppError::NotFound("Category not found".to_string()));
+    }
+
+    Ok(Redirect::to("/exercises/categories").into_response())
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(name): Path<String>,
+) -> Result<Response> {
+    match state.category_repo.delete(&auth_user.id, &name).await {
+        Ok(true) => Ok(Redirect::to("/exercises/categories").into_response()),
+        Ok(false) => Err(AppError::NotFound("Category not found".to_string())),
+        Err(AppError::BadRequest(message)) => render_page(&state, auth_user, Some(message)).await,
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,231 @@
+use askama::Template;
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use chrono::NaiveDate;
+
+use crate::chart_image::{PngCache, svg_to_png};
+use crate::error::{AppError, Result};
+use crate::handlers::stats::ProgressQuery;
+use crate::middleware::AuthUser;
+use crate::models::exercise::category_key;
+use crate::progress_chart::{
+    ChartOptions, ChartRange, ChartSize, ProgressChart, render_chart, series,
+};
+use crate::state::AppState;
+
+/// Height of the title band above the plot.
+const HEADER_H: f64 = 28.0;
+
+#[derive(Template)]
+#[template(path = "stats/chart.svg")]
+struct ChartImageTemplate {
+    title: String,
+    width: f64,
+    height: f64,
+    header: f64,
+    /// `None` with nothing logged in range.
+    chart: Option<ProgressChart>,
+}
+
+#[derive(Clone, Copy)]
+enum ImageFormat {
+    Svg,
+    Png,
+}
+
+impl ImageFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Svg => "image/svg+xml",
+            Self::Png => "image/png",
+        }
+    }
+}
+
+/// The chart of `user_id`'s `exercise_id` between `from` and `to` as an
+/// image. PNGs are rasterised through `png_cache` when given.
+async fn chart_image(
+    state: &AppState,
+    user_id: &str,
+    (exercise_id, exercise_name): (String, String),
+    options: &ChartOptions,
+    (from, to): (Option<NaiveDate>, Option<NaiveDate>),
+    format: ImageFormat,
+    png_cache: Option<&PngCache>,
+) -> Result<Response> {
+    let sets = state
+        .workout_repo
+        .get_sets_for_exercises(user_id, std::slice::from_ref(&exercise_id), from, to)
+        .await?;
+    let chart = render_chart(
+        &series(
+            &sets,
+            &[(exercise_id, exercise_name.clone())],
+            options.metric,
+            options.rep_range,
+        ),
+        options,
+    );
+
+    let template = ChartImageTemplate {
+        title: format!("{exercise_name} \u{b7} {}", options.metric.label()),
+        width: f64::from(options.size.width),
+        height: f64::from(options.size.height) + HEADER_H,
+        header: HEADER_H,
+        chart,
+    };
+    let svg = template.render()?;
+    let body = match format {
+        ImageFormat::Svg => svg.into_bytes(),
+        ImageFormat::Png => match png_cache {
+            Some(cache) => cache.render(svg).await?.to_vec(),
+            None => tokio::task::spawn_blocking(move || svg_to_png(&svg)).await??,
+        },
+    };
+
+    let slug: String = category_key(&exercise_name)
+        .chars()
+        .filter(char::is_ascii)
+        .collect();
+    let disposition = format!(
+        "inline; filename=\"{}-{}.{}\"",
+        if slug.is_empty() { "exercise" } else { &slug },
+        options.metric.param(),
+        format.extension()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// The owner's chart, with the progress page's query options plus `width`
+/// and `height`.
+async fn owner_chart(
+    state: AppState,
+    auth_user: AuthUser,
+    exercise_id: String,
+    query: ProgressQuery,
+    format: ImageFormat,
+) -> Result<Response> {
+    let options = ChartOptions::parse(&query.fields).map_err(AppError::BadRequest)?;
+    let exercise = state
+        .exercise_repo
+        .find_usable(&exercise_id, &auth_user.id)
+        .await?;
+    let today = state
+        .user_repo
+        .get_preferences(&auth_user.id)
+        .await?
+        .today();
+    chart_image(
+        &state,
+        &auth_user.id,
+        (exercise.id, exercise.name),
+        &options,
+        options.dates(today),
+        format,
+        None,
+    )
+    .await
+}
+
+/// The chart of an exercise in a shared workout: the owner's progress over
+/// a preset range ending on that workout's date, at the default size. It
+/// shows sessions the owner never shared, so it is not found unless they
+/// opted in when sharing, and all-time and custom ranges are refused.
+/// Anything else under the token is not found, and the image is
+/// revalidated on every fetch so it stops with the link. PNGs come from
+/// the share card cache, since anyone with the link can fetch them.
+async fn shared_chart(
+    state: AppState,
+    token: String,
+    exercise_id: String,
+    query: ProgressQuery,
+    format: ImageFormat,
+) -> Result<Response> {
+    let mut options = ChartOptions::parse(&query.fields).map_err(AppError::BadRequest)?;
+    if options.range == ChartRange::All || options.from.is_some() || options.to.is_some() {
+        return Err(AppError::BadRequest(
+            "Shared charts only cover 3 months, 6 months or a year up to the workout".to_string(),
+        ));
+    }
+    options.size = ChartSize::DEFAULT;
+    let not_found = || AppError::NotFound("Shared chart not found".to_string());
+    let workout = state
+        .workout_repo
+        .find_session_by_share_token(&token)
+        .await?
+        .filter(|workout| workout.share_history)
+        .ok_or_else(not_found)?;
+    let exercise_name = state
+        .workout_repo
+        .find_logs_by_session_for_share(&workout.id)
+        .await?
+        .into_iter()
+        .find(|log| log.exercise_id == exercise_id)
+        .ok_or_else(not_found)?
+        .exercise_name;
+    let mut response = chart_image(
+        &state,
+        &workout.user_id,
+        (exercise_id, exercise_name),
+        &options,
+        (options.range.start(workout.date), Some(workout.date)),
+        format,
+        Some(&state.share_card_cache),
+    )
+    .await?;
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    Ok(response)
+}
+
+pub async fn exercise_svg(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<ProgressQuery>,
+) -> Result<Response> {
+    owner_chart(state, auth_user, id, query, ImageFormat::Svg).await
+}
+
+pub async fn exercise_png(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<ProgressQuery>,
+) -> Result<Response> {
+    owner_chart(state, auth_user, id, query, ImageFormat::Png).await
+}
+
+pub async fn shared_svg(
+    State(state): State<AppState>,
+    Path((token, exercise_id)): Path<(String, String)>,
+    Query(query): Query<ProgressQuery>,
+) -> Result<Response> {
+    shared_chart(state, token, exercise_id, query, ImageFormat::Svg).await
+}
+
+pub async fn shared_png(
+    State(state): State<AppState>,
+    Path((token, exercise_id)): Path<(String, String)>,
+    Query(query): Query<ProgressQuery>,
+) -> Result<Response> {
+    shared_chart(state, token, exercise_id, query, ImageFormat::Png).await
+}
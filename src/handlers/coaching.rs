@@ -0,0 +1,481 @@
+use askama::Template;
+use axum::{
+    Form,
+    extract::{Path, Query, State},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::audit::{self, AuditContext};
+use crate::consistency::Consistency;
+use crate::error::{AppError, Result};
+use crate::handlers::stats::load_consistency;
+use crate::middleware::AuthUser;
+use crate::models::{
+    Access, CoachGrant, PageCursor, PersonalRecordSummary, WorkoutComment, WorkoutCursor,
+    WorkoutFilter, WorkoutLogWithExercise, WorkoutSession, WorkoutSort, WorkoutTag, comment_error,
+    encode_query_value,
+};
+use crate::session::token_fingerprint;
+use crate::state::AppState;
+
+/// Workouts per page of a coach's view of an athlete.
+const SESSIONS_PAGE_SIZE: i64 = 20;
+
+#[derive(Template)]
+#[template(path = "settings/coaches.html")]
+struct CoachesTemplate {
+    user: AuthUser,
+    coaches: Vec<CoachGrant>,
+    error: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "coaching/index.html")]
+struct AthletesTemplate {
+    user: AuthUser,
+    athletes: Vec<CoachGrant>,
+}
+
+#[derive(Template)]
+#[template(path = "coaching/athlete.html")]
+struct AthleteTemplate {
+    user: AuthUser,
+    grant: CoachGrant,
+    consistency: Consistency,
+    prs: Vec<PersonalRecordSummary>,
+    /// Each with whether it is planned: no sets yet, and not in the past.
+    sessions: Vec<(WorkoutSession, bool)>,
+    /// The earliest date a workout can be programmed for: the athlete's
+    /// today.
+    today: NaiveDate,
+    /// Encoded cursors of the neighbouring pages of workouts.
+    prev: Option<String>,
+    next: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "coaching/workout.html")]
+struct CoachWorkoutTemplate {
+    user: AuthUser,
+    grant: CoachGrant,
+    workout: WorkoutSession,
+    tags: Vec<WorkoutTag>,
+    logs: Vec<WorkoutLogWithExercise>,
+    comments: Vec<WorkoutComment>,
+    can_comment: bool,
+}
+
+#[derive(Deserialize)]
+pub struct GrantForm {
+    username: String,
+    can_comment: Option<String>,
+    can_program: Option<String>,
+}
+
+/// Where the athlete's workouts are read from, as on the workout list.
+#[derive(Deserialize)]
+pub struct AthleteQuery {
+    #[serde(default)]
+    after: String,
+    #[serde(default)]
+    before: String,
+}
+
+impl AthleteQuery {
+    fn cursor(&self) -> Result<Option<PageCursor>> {
+        let parse = |value: &str| {
+            WorkoutCursor::parse(value, WorkoutSort::Newest)
+                .ok_or_else(|| AppError::BadRequest("Invalid page position".to_string()))
+        };
+        if !self.after.is_empty() {
+            Ok(Some(PageCursor::After(parse(&self.after)?)))
+        } else if !self.before.is_empty() {
+            Ok(Some(PageCursor::Before(parse(&self.before)?)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ProgramForm {
+    date: NaiveDate,
+    notes: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CommentForm {
+    body: String,
+    /// The set commented on; empty for the whole workout.
+    #[serde(default)]
+    log_id: String,
+}
+
+async fn render_coaches(
+    state: &AppState,
+    auth_user: AuthUser,
+    error: Option<String>,
+) -> Result<Response> {
+    let coaches = state
+        .user_repo
+        .find_coach_grants(&auth_user.id, true)
+        .await?;
+    let template = CoachesTemplate {
+        user: auth_user,
+        coaches,
+        error,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// The athlete's coaches. A coach may read their workouts and stats, and
+/// optionally comment on them or program workouts into their plan.
+pub async fn coaches(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    render_coaches(&state, auth_user, None).await
+}
+
+/// Add a coach by username, or change what an existing one may do.
+pub async fn grant(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    audit_ctx: AuditContext,
+    Form(form): Form<GrantForm>,
+) -> Result<Response> {
+    let Some(coach) = state
+        .user_repo
+        .find_by_username(form.username.trim())
+        .await?
+    else {
+        return render_coaches(
+            &state,
+            auth_user,
+            Some("No user with that name".to_string()),
+        )
+        .await;
+    };
+    let can_comment = form.can_comment.is_some();
+    let can_program = form.can_program.is_some();
+    match state
+        .user_repo
+        .grant_coach(&auth_user.id, &coach.id, can_comment, can_program)
+        .await
+    {
+        Ok(()) => {}
+        Err(AppError::BadRequest(message)) => {
+            return render_coaches(&state, auth_user, Some(message)).await;
+        }
+        Err(e) => return Err(e),
+    }
+    audit::coach_access_changed(
+        &audit_ctx,
+        &token_fingerprint(&auth_user.session_token, state.log_salt.as_ref()),
+        &auth_user.id,
+        &coach.id,
+        "granted",
+        can_comment,
+        can_program,
+    );
+    Ok(Redirect::to("/settings/coaches").into_response())
+}
+
+pub async fn revoke(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    audit_ctx: AuditContext,
+    Path(coach_id): Path<String>,
+) -> Result<Response> {
+    if !state
+        .user_repo
+        .revoke_coach(&auth_user.id, &coach_id)
+        .await?
+    {
+        return Err(AppError::NotFound("Coach not found".to_string()));
+    }
+    audit::coach_access_changed(
+        &audit_ctx,
+        &token_fingerprint(&auth_user.session_token, state.log_salt.as_ref()),
+        &auth_user.id,
+        &coach_id,
+        "revoked",
+        false,
+        false,
+    );
+    Ok(Redirect::to("/settings/coaches").into_response())
+}
+
+pub async fn athletes(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    let athletes = state
+        .user_repo
+        .find_coach_grants(&auth_user.id, false)
+        .await?;
+    let template = AthletesTemplate {
+        user: auth_user,
+        athletes,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// The grant letting `auth_user` coach `athlete_id`. Users who are not
+/// their coach get the same `NotFound` as for an unknown athlete. Every
+/// coach request goes through it, or through
+/// `WorkoutRepository::find_accessible_session`, and is audited.
+async fn find_grant(
+    state: &AppState,
+    athlete_id: &str,
+    auth_user: &AuthUser,
+) -> Result<CoachGrant> {
+    state
+        .user_repo
+        .find_coach_grant(athlete_id, &auth_user.id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Athlete not found".to_string()))
+}
+
+fn audit_coach(
+    state: &AppState,
+    audit_ctx: &AuditContext,
+    auth_user: &AuthUser,
+    athlete_id: &str,
+    action: &str,
+    target: &str,
+) {
+    audit::coach_action(
+        audit_ctx,
+        &token_fingerprint(&auth_user.session_token, state.log_salt.as_ref()),
+        &auth_user.id,
+        athlete_id,
+        action,
+        target,
+    );
+}
+
+/// An athlete's stats and workouts, newest first and paged by cursor,
+/// read-only.
+pub async fn athlete(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    audit_ctx: AuditContext,
+    Path(athlete_id): Path<String>,
+    Query(query): Query<AthleteQuery>,
+) -> Result<Response> {
+    let grant = find_grant(&state, &athlete_id, &auth_user).await?;
+    let cursor = query.cursor()?;
+    audit_coach(
+        &state,
+        &audit_ctx,
+        &auth_user,
+        &athlete_id,
+        "view_athlete",
+        "",
+    );
+
+    let preferences = state.user_repo.get_preferences(&athlete_id).await?;
+    let consistency = load_consistency(&state, &athlete_id, &preferences).await?;
+    let prs = state
+        .workout_repo
+        .get_pr_summaries_by_user(&athlete_id, preferences.pr_window_start())
+        .await?;
+    let today = preferences.today();
+    let page = state
+        .workout_repo
+        .find_sessions_page(
+            &athlete_id,
+            WorkoutFilter::default(),
+            WorkoutSort::Newest,
+            cursor,
+            SESSIONS_PAGE_SIZE,
+        )
+        .await?;
+    let sessions = page
+        .workouts
+        .into_iter()
+        .map(|w| {
+            let planned = w.set_count == 0 && w.session.date >= today;
+            (w.session, planned)
+        })
+        .collect();
+
+    let template = AthleteTemplate {
+        user: auth_user,
+        grant,
+        consistency,
+        prs,
+        sessions,
+        today,
+        prev: page.prev.map(|c| encode_query_value(&c.to_param())),
+        next: page.next.map(|c| encode_query_value(&c.to_param())),
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Add a planned workout to the athlete's log, for today or later in their
+/// timezone. It shows on their calendar as planned until they log sets.
+pub async fn program(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    audit_ctx: AuditContext,
+    Path(athlete_id): Path<String>,
+    Form(form): Form<ProgramForm>,
+) -> Result<Response> {
+    let grant = find_grant(&state, &athlete_id, &auth_user).await?;
+    if !grant.access().can_program() {
+        return Err(AppError::Forbidden(
+            "You may not program workouts for this athlete".to_string(),
+        ));
+    }
+    let today = state.user_repo.get_preferences(&athlete_id).await?.today();
+    if form.date < today {
+        return Err(AppError::BadRequest(
+            "Programmed workouts must be for today or later".to_string(),
+        ));
+    }
+    let notes = form
+        .notes
+        .as_deref()
+        .map(str::trim)
+        .filter(|notes| !notes.is_empty());
+    let workout = state
+        .workout_repo
+        .create_session(&athlete_id, form.date, notes)
+        .await?;
+    audit_coach(
+        &state,
+        &audit_ctx,
+        &auth_user,
+        &athlete_id,
+        "program_workout",
+        &workout.id,
+    );
+    Ok(Redirect::to(&format!("/coaching/{athlete_id}")).into_response())
+}
+
+/// One of an athlete's workouts, read-only, with its comments. Owners are
+/// sent to their own page.
+pub async fn workout(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    audit_ctx: AuditContext,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    let (workout, access) = state
+        .workout_repo
+        .find_accessible_session(&id, &auth_user.id)
+        .await?;
+    if access.is_owner() {
+        return Ok(Redirect::to(&format!("/workouts/{id}")).into_response());
+    }
+    let grant = find_grant(&state, &workout.user_id, &auth_user).await?;
+    audit_coach(
+        &state,
+        &audit_ctx,
+        &auth_user,
+        &workout.user_id,
+        "view_workout",
+        &workout.id,
+    );
+
+    let template = CoachWorkoutTemplate {
+        user: auth_user,
+        grant,
+        tags: state.tag_repo.find_for_session(&workout.id).await?,
+        logs: state
+            .workout_repo
+            .find_logs_by_session_for_share(&workout.id)
+            .await?,
+        comments: state.comment_repo.find_comments(&workout.id).await?,
+        can_comment: access.can_comment(),
+        workout,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Where a comment form returns to: the owner's workout page, or the
+/// coach's view of it.
+fn workout_page(id: &str, access: Access) -> String {
+    if access.is_owner() {
+        format!("/workouts/{id}")
+    } else {
+        format!("/coaching/workouts/{id}")
+    }
+}
+
+/// Comment on a workout or one of its sets, as its owner or as a coach
+/// allowed to comment.
+pub async fn add_comment(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    audit_ctx: AuditContext,
+    Path(id): Path<String>,
+    Form(form): Form<CommentForm>,
+) -> Result<Response> {
+    let (workout, access) = state
+        .workout_repo
+        .find_accessible_session(&id, &auth_user.id)
+        .await?;
+    if !access.can_comment() {
+        return Err(AppError::Forbidden(
+            "You may not comment on this workout".to_string(),
+        ));
+    }
+    if let Some(error) = comment_error(&form.body) {
+        return Err(AppError::BadRequest(error));
+    }
+    let log_id = Some(form.log_id.as_str()).filter(|id| !id.is_empty());
+    let comment_id = state
+        .comment_repo
+        .add_comment(&workout.id, log_id, &auth_user.id, &form.body)
+        .await?;
+    if !access.is_owner() {
+        audit_coach(
+            &state,
+            &audit_ctx,
+            &auth_user,
+            &workout.user_id,
+            "comment",
+            &comment_id,
+        );
+    }
+    Ok(Redirect::to(&workout_page(&id, access)).into_response())
+}
+
+/// Delete a comment: the workout's owner may delete any of them, a coach
+/// only their own. A coach can still delete theirs after losing comment
+/// access, but not once the grant is gone.
+pub async fn delete_comment(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    audit_ctx: AuditContext,
+    Path((id, comment_id)): Path<(String, String)>,
+) -> Result<Response> {
+    let (workout, access) = state
+        .workout_repo
+        .find_accessible_session(&id, &auth_user.id)
+        .await?;
+    let deleted = if access.is_owner() {
+        state
+            .comment_repo
+            .delete_comment_as_owner(&workout.id, &comment_id, &auth_user.id)
+            .await?
+    } else {
+        state
+            .comment_repo
+            .delete_comment(&workout.id, &comment_id, &auth_user.id)
+            .await?
+    };
+    if !deleted {
+        return Err(AppError::NotFound("Comment not found".to_string()));
+    }
+    if !access.is_owner() {
+        audit_coach(
+            &state,
+            &audit_ctx,
+            &auth_user,
+            &workout.user_id,
+            "delete_comment",
+            &comment_id,
+        );
+    }
+    Ok(Redirect::to(&workout_page(&id, access)).into_response())
+}
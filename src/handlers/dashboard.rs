@@ -4,7 +4,9 @@ use axum::{
     response::{Html, IntoResponse, Response},
 };
 
+use crate::consistency::Consistency;
 use crate::error::Result;
+use crate::handlers::stats::{load_consistency, load_period_totals};
 use crate::middleware::AuthUser;
 use crate::models::WorkoutSession;
 use crate::state::AppState;
@@ -17,25 +19,18 @@ struct DashboardTemplate {
     workouts_this_month: i64,
     total_volume: f64,
     recent_workouts: Vec<WorkoutSession>,
+    consistency: Consistency,
 }
 
 pub async fn index(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
-    let workouts_this_week = state
-        .workout_repo
-        .count_workouts_this_week(&auth_user.id)
-        .await?;
-    let workouts_this_month = state
-        .workout_repo
-        .count_workouts_this_month(&auth_user.id)
-        .await?;
-    let total_volume = state
-        .workout_repo
-        .get_total_volume_this_week(&auth_user.id)
-        .await?;
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
+    let (workouts_this_week, workouts_this_month, total_volume) =
+        load_period_totals(&state, &auth_user.id, &preferences).await?;
     let recent_workouts = state
         .workout_repo
         .find_sessions_by_user_paginated(&auth_user.id, 5, 0)
         .await?;
+    let consistency = load_consistency(&state, &auth_user.id, &preferences).await?;
 
     let template = DashboardTemplate {
         user: auth_user,
@@ -43,6 +38,7 @@ pub async fn index(State(state): State<AppState>, auth_user: AuthUser) -> Result
         workouts_this_month,
         total_volume,
         recent_workouts,
+        consistency,
     };
 
     Ok(Html(template.render()?).into_response())
@@ -5,25 +5,71 @@ use axum::{
     response::{Html, IntoResponse, Redirect, Response},
 };
 
-use crate::error::Result;
+use crate::error::{AppError, Result};
 use crate::middleware::AuthUser;
-use crate::models::exercise::{CATEGORIES, ExerciseCategory};
-use crate::models::{CreateExercise, Exercise, UpdateExercise};
+use crate::models::exercise::with_missing_categories;
+use crate::models::muscle::{MUSCLE_GROUPS, muscles_from_form};
+use crate::models::{
+    CreateExercise, Exercise, ExerciseCategory, ExerciseMuscle, MergeExerciseForm, UpdateExercise,
+};
 use crate::state::AppState;
 
+/// One row of the muscle picker on the exercise forms: the muscle and the role
+/// currently selected for it (`""` when the exercise does not work it).
+pub(crate) struct MuscleChoice {
+    pub(crate) name: &'static str,
+    pub(crate) display_name: &'static str,
+    pub(crate) role: &'static str,
+}
+
+pub(crate) fn muscle_choices(selected: &[ExerciseMuscle]) -> Vec<MuscleChoice> {
+    MUSCLE_GROUPS
+        .iter()
+        .map(|m| MuscleChoice {
+            name: m.name,
+            display_name: m.display_name,
+            role: selected
+                .iter()
+                .find(|s| s.muscle == m.name)
+                .map_or("", |s| s.role.as_str()),
+        })
+        .collect()
+}
+
+/// Shared validation for the create and edit forms. The category must be one
+/// of the user's own; the select only offers those, so anything else is a
+/// stale page (the category was deleted in another tab) or a hand-built form.
+fn exercise_form_error(
+    name: &str,
+    category: &str,
+    categories: &[ExerciseCategory],
+) -> Option<String> {
+    if name.trim().is_empty() {
+        Some("Exercise name is required".to_string())
+    } else if !categories.iter().any(|c| c.name == category) {
+        Some("Please choose one of your categories".to_string())
+    } else {
+        None
+    }
+}
+
 #[derive(Template)]
 #[template(path = "exercises/list.html")]
 struct ExercisesListTemplate {
     user: AuthUser,
     exercises: Vec<Exercise>,
-    categories: &'static [ExerciseCategory],
+    /// Global exercises the user has hidden, listed so they can be restored.
+    hidden: Vec<Exercise>,
+    archived: Vec<Exercise>,
+    categories: Vec<ExerciseCategory>,
 }
 
 #[derive(Template)]
 #[template(path = "exercises/new.html")]
 struct NewExerciseTemplate {
     user: AuthUser,
-    categories: &'static [ExerciseCategory],
+    categories: Vec<ExerciseCategory>,
+    muscles: Vec<MuscleChoice>,
     error: Option<String>,
 }
 
@@ -32,29 +78,61 @@ struct NewExerciseTemplate {
 struct EditExerciseTemplate {
     user: AuthUser,
     exercise: Exercise,
-    categories: &'static [ExerciseCategory],
+    categories: Vec<ExerciseCategory>,
+    muscles: Vec<MuscleChoice>,
+    /// Exercises this one can be merged into.
+    merge_targets: Vec<Exercise>,
     error: Option<String>,
 }
 
+/// Exercises `exercise` may be merged into: anything else the user can pick,
+/// except that a shared exercise only merges into another shared one, since
+/// its logs belong to every user.
+async fn merge_targets(
+    state: &AppState,
+    user_id: &str,
+    exercise: &Exercise,
+) -> Result<Vec<Exercise>> {
+    let mut targets = state.exercise_repo.find_available_for_user(user_id).await?;
+    targets.retain(|t| t.id != exercise.id && (t.is_global() || !exercise.is_global()));
+    Ok(targets)
+}
+
 pub async fn list(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
     let exercises = state
         .exercise_repo
         .find_available_for_user(&auth_user.id)
         .await?;
+    let hidden = state
+        .exercise_repo
+        .find_hidden_for_user(&auth_user.id)
+        .await?;
+    let archived = state
+        .exercise_repo
+        .find_archived_for_user(&auth_user.id)
+        .await?;
+    let categories = with_missing_categories(
+        state.category_repo.find_by_user(&auth_user.id).await?,
+        &exercises,
+    );
 
     let template = ExercisesListTemplate {
         user: auth_user,
         exercises,
-        categories: CATEGORIES,
+        hidden,
+        archived,
+        categories,
     };
 
     Ok(Html(template.render()?).into_response())
 }
 
-pub async fn new_page(auth_user: AuthUser) -> Result<Response> {
+pub async fn new_page(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    let categories = state.category_repo.find_by_user(&auth_user.id).await?;
     let template = NewExerciseTemplate {
         user: auth_user,
-        categories: CATEGORIES,
+        categories,
+        muscles: muscle_choices(&[]),
         error: None,
     };
 
@@ -66,18 +144,33 @@ pub async fn create(
     auth_user: AuthUser,
     Form(form): Form<CreateExercise>,
 ) -> Result<Response> {
-    if form.name.trim().is_empty() {
+    let muscles = muscles_from_form(&form.muscles);
+    let categories = state.category_repo.find_by_user(&auth_user.id).await?;
+
+    if let Some(error) = exercise_form_error(&form.name, &form.category, &categories) {
         let template = NewExerciseTemplate {
             user: auth_user,
-            categories: CATEGORIES,
-            error: Some("Exercise name is required".to_string()),
+            categories,
+            muscles: muscle_choices(&muscles),
+            error: Some(error),
         };
         return Ok(Html(template.render()?).into_response());
     }
 
+    let owner = if form.global.is_some() && auth_user.is_admin() {
+        None
+    } else {
+        Some(auth_user.id.as_str())
+    };
     state
         .exercise_repo
-        .create(&form.name, &form.category, &auth_user.id)
+        .create_with_muscles(
+            &form.name,
+            &form.category,
+            owner,
+            form.unilateral.is_some(),
+            muscles,
+        )
         .await?;
 
     Ok(Redirect::to("/exercises").into_response())
@@ -88,12 +181,23 @@ pub async fn edit_page(
     auth_user: AuthUser,
     Path(id): Path<String>,
 ) -> Result<Response> {
-    let exercise = state.exercise_repo.find_owned(&id, &auth_user.id).await?;
+    let exercise = state
+        .exercise_repo
+        .find_editable(&id, &auth_user.id, auth_user.is_admin())
+        .await?;
+    let muscles = state.exercise_repo.find_muscles(&id).await?;
+    let categories = with_missing_categories(
+        state.category_repo.find_by_user(&auth_user.id).await?,
+        std::slice::from_ref(&exercise),
+    );
+    let merge_targets = merge_targets(&state, &auth_user.id, &exercise).await?;
 
     let template = EditExerciseTemplate {
         user: auth_user,
         exercise,
-        categories: CATEGORIES,
+        categories,
+        muscles: muscle_choices(&muscles),
+        merge_targets,
         error: None,
     };
 
@@ -106,21 +210,44 @@ pub async fn update(
     Path(id): Path<String>,
     Form(form): Form<UpdateExercise>,
 ) -> Result<Response> {
-    let exercise = state.exercise_repo.find_owned(&id, &auth_user.id).await?;
+    let exercise = state
+        .exercise_repo
+        .find_editable(&id, &auth_user.id, auth_user.is_admin())
+        .await?;
+    let muscles = muscles_from_form(&form.muscles);
+    let categories = with_missing_categories(
+        state.category_repo.find_by_user(&auth_user.id).await?,
+        std::slice::from_ref(&exercise),
+    );
 
-    if form.name.trim().is_empty() {
+    if let Some(error) = exercise_form_error(&form.name, &form.category, &categories) {
+        let merge_targets = merge_targets(&state, &auth_user.id, &exercise).await?;
         let template = EditExerciseTemplate {
             user: auth_user,
             exercise,
-            categories: CATEGORIES,
-            error: Some("Exercise name is required".to_string()),
+            categories,
+            muscles: muscle_choices(&muscles),
+            merge_targets,
+            error: Some(error),
         };
         return Ok(Html(template.render()?).into_response());
     }
 
+    let owner = if exercise.is_global() {
+        None
+    } else {
+        Some(auth_user.id.as_str())
+    };
     state
         .exercise_repo
-        .update(&id, &auth_user.id, &form.name, &form.category)
+        .update_with_muscles(
+            &id,
+            owner,
+            &form.name,
+            &form.category,
+            form.unilateral.is_some(),
+            muscles,
+        )
         .await?;
 
     Ok(Redirect::to("/exercises").into_response())
@@ -131,9 +258,124 @@ pub async fn delete(
     auth_user: AuthUser,
     Path(id): Path<String>,
 ) -> Result<Response> {
-    state.exercise_repo.find_owned(&id, &auth_user.id).await?;
+    let exercise = state
+        .exercise_repo
+        .find_editable(&id, &auth_user.id, auth_user.is_admin())
+        .await?;
+
+    if exercise.is_global() {
+        state.exercise_repo.delete_global(&id).await?;
+    } else {
+        state.exercise_repo.delete(&id, &auth_user.id).await?;
+    }
+
+    Ok(Redirect::to("/exercises").into_response())
+}
 
-    state.exercise_repo.delete(&id, &auth_user.id).await?;
+pub async fn archive(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    state
+        .exercise_repo
+        .find_editable(&id, &auth_user.id, auth_user.is_admin())
+        .await?;
+    state.exercise_repo.set_archived(&id, true).await?;
 
     Ok(Redirect::to("/exercises").into_response())
 }
+
+pub async fn unarchive(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    state
+        .exercise_repo
+        .find_editable(&id, &auth_user.id, auth_user.is_admin())
+        .await?;
+    state.exercise_repo.set_archived(&id, false).await?;
+
+    Ok(Redirect::to("/exercises").into_response())
+}
+
+/// Move every log of this exercise onto `target_id` and delete it. Used to
+/// clean up duplicates ("Bench" vs "Bench Press") that can no longer be
+/// deleted because logs reference them.
+pub async fn merge(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Form(form): Form<MergeExerciseForm>,
+) -> Result<Response> {
+    let source = state
+        .exercise_repo
+        .find_editable(&id, &auth_user.id, auth_user.is_admin())
+        .await?;
+    let target = state
+        .exercise_repo
+        .find_usable(&form.target_id, &auth_user.id)
+        .await?;
+    if source.is_global() && !target.is_global() {
+        return Err(AppError::BadRequest(
+            "A shared exercise can only be merged into another shared exercise".to_string(),
+        ));
+    }
+    if target.is_archived() {
+        return Err(AppError::BadRequest(
+            "Restore the archived exercise before merging into it".to_string(),
+        ));
+    }
+
+    state.exercise_repo.merge(&source.id, &target.id).await?;
+
+    Ok(Redirect::to("/exercises").into_response())
+}
+
+/// Look up a global exercise the user can see. Hiding and forking only make
+/// sense for shared exercises; a personal one is edited or deleted instead.
+async fn find_global(state: &AppState, id: &str, user_id: &str) -> Result<Exercise> {
+    let exercise = state.exercise_repo.find_usable(id, user_id).await?;
+    if !exercise.is_global() {
+        return Err(AppError::BadRequest(
+            "Only shared exercises can be hidden or copied".to_string(),
+        ));
+    }
+    Ok(exercise)
+}
+
+pub async fn hide(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    find_global(&state, &id, &auth_user.id).await?;
+    state.exercise_repo.hide(&id, &auth_user.id).await?;
+
+    Ok(Redirect::to("/exercises").into_response())
+}
+
+pub async fn unhide(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    find_global(&state, &id, &auth_user.id).await?;
+    state.exercise_repo.unhide(&id, &auth_user.id).await?;
+
+    Ok(Redirect::to("/exercises").into_response())
+}
+
+/// Replace a shared exercise with a personal copy the user can edit. Their
+/// existing logs move to the copy and the shared one is hidden for them.
+pub async fn fork(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    find_global(&state, &id, &auth_user.id).await?;
+    let exercise = state.exercise_repo.fork(&id, &auth_user.id).await?;
+
+    Ok(Redirect::to(&format!("/exercises/{}/edit", exercise.id)).into_response())
+}
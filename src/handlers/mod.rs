@@ -1,8 +1,19 @@
 pub mod auth;
+pub mod block_shares;
+pub mod catalog;
+pub mod categories;
+pub mod chart_images;
+pub mod coaching;
 pub mod dashboard;
 pub mod exercises;
 pub mod favicon;
 pub mod health;
+pub mod profile;
+pub mod reports;
+pub mod set_editor;
 pub mod settings;
 pub mod stats;
+pub mod tags;
+pub mod text_import;
 pub mod workouts;
+pub mod year_review;
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use askama::Template;
+use axum::{
+    Form,
+    extract::{Path, State},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::consistency::Consistency;
+use crate::error::{AppError, Result};
+use crate::handlers::stats::load_consistency;
+use crate::handlers::workouts::ShareForm;
+use crate::middleware::AuthUser;
+use crate::models::{PersonalRecordSummary, ProfileSections, WorkoutSession};
+use crate::state::AppState;
+
+/// Shared workouts listed on the profile.
+const RECENT_SESSIONS: i64 = 10;
+
+#[derive(Template)]
+#[template(path = "settings/profile.html")]
+struct ProfileSettingsTemplate {
+    user: AuthUser,
+    sections: ProfileSections,
+    /// Every exercise with a PR, to pick from.
+    prs: Vec<PersonalRecordSummary>,
+    share_url: Option<String>,
+    share_expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Template)]
+#[template(path = "settings/profile_shared.html")]
+struct SharedProfileTemplate {
+    owner_username: String,
+    sections: ProfileSections,
+    /// The chosen exercises' PRs, when that section is on.
+    prs: Vec<PersonalRecordSummary>,
+    sessions: Vec<WorkoutSession>,
+    consistency: Option<Consistency>,
+}
+
+#[derive(Deserialize)]
+pub struct ProfileForm {
+    #[serde(flatten)]
+    fields: HashMap<String, String>,
+}
+
+async fn pr_summaries(state: &AppState, user_id: &str) -> Result<Vec<PersonalRecordSummary>> {
+    let since = state
+        .user_repo
+        .get_preferences(user_id)
+        .await?
+        .pr_window_start();
+    state
+        .workout_repo
+        .get_pr_summaries_by_user(user_id, since)
+        .await
+}
+
+/// The owner's settings for their public profile. Each section is off
+/// until they turn it on, and the link follows the workout share rules.
+pub async fn index(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    let profile = state.share_repo.get_public_profile(&auth_user.id).await?;
+    let prs = pr_summaries(&state, &auth_user.id).await?;
+    let template = ProfileSettingsTemplate {
+        user: auth_user,
+        sections: profile.sections,
+        prs,
+        share_url: profile
+            .token
+            .as_ref()
+            .map(|token| format!("/shared/profile/{token}")),
+        share_expires_at: profile.expires_at,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Save the sections. Only exercises the user has a PR in can be listed.
+pub async fn update(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Form(form): Form<ProfileForm>,
+) -> Result<Response> {
+    let sections = ProfileSections::from_form(&form.fields);
+    let prs = pr_summaries(&state, &auth_user.id).await?;
+    if let Some(unknown) = sections
+        .exercise_ids
+        .iter()
+        .find(|id| !prs.iter().any(|pr| pr.exercise_id == **id))
+    {
+        return Err(AppError::BadRequest(format!(
+            "No personal record to show for exercise {unknown}"
+        )));
+    }
+    state
+        .share_repo
+        .set_profile_sections(&auth_user.id, sections)
+        .await?;
+    Ok(Redirect::to("/settings/profile").into_response())
+}
+
+pub async fn share(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Form(form): Form<ShareForm>,
+) -> Result<Response> {
+    let ttl = form.ttl()?;
+    state
+        .share_repo
+        .set_profile_share_token(&auth_user.id, ttl)
+        .await?;
+    Ok(Redirect::to("/settings/profile").into_response())
+}
+
+pub async fn revoke_share(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    state
+        .share_repo
+        .revoke_profile_share_token(&auth_user.id)
+        .await?;
+    Ok(Redirect::to("/settings/profile").into_response())
+}
+
+/// The public profile. Expired and unknown tokens are both 404, as for
+/// shared workouts, and only the sections the owner turned on are loaded.
+pub async fn view_shared(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Response> {
+    let not_found = || AppError::NotFound("Shared profile not found".to_string());
+    let profile = state
+        .share_repo
+        .find_public_profile_by_token(&token)
+        .await?
+        .ok_or_else(not_found)?;
+    let owner = state
+        .user_repo
+        .find_by_id(&profile.user_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let sections = profile.sections;
+
+    let prs = if sections.prs {
+        let mut prs = pr_summaries(&state, &owner.id).await?;
+        prs.retain(|pr| sections.exercise_ids.contains(&pr.exercise_id));
+        prs
+    } else {
+        Vec::new()
+    };
+    let sessions = if sections.sessions {
+        state
+            .workout_repo
+            .find_shared_sessions_by_user(&owner.id, RECENT_SESSIONS)
+            .await?
+    } else {
+        Vec::new()
+    };
+    let consistency = if sections.consistency {
+        let preferences = state.user_repo.get_preferences(&owner.id).await?;
+        Some(load_consistency(&state, &owner.id, &preferences).await?)
+    } else {
+        None
+    };
+
+    let template = SharedProfileTemplate {
+        owner_username: owner.username,
+        sections,
+        prs,
+        sessions,
+        consistency,
+    };
+    Ok(Html(template.render()?).into_response())
+}
@@ -0,0 +1,121 @@
+use askama::Template;
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{Html, IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::error::{AppError, Result};
+use crate::middleware::AuthUser;
+use crate::report::{Report, ReportData, ReportKind, ReportPeriod, report};
+use crate::state::AppState;
+
+#[derive(Template)]
+#[template(path = "reports/show.html")]
+struct ReportTemplate {
+    user: AuthUser,
+    report: Report,
+    /// Link exercises and PRs to their pages; off in the downloaded copy.
+    links: bool,
+}
+
+/// The standalone copy offered for download: no navigation, no external
+/// assets, styled for paper.
+#[derive(Template)]
+#[template(path = "reports/printable.html")]
+struct PrintableReportTemplate {
+    username: String,
+    report: Report,
+    links: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ReportQuery {
+    /// Any day of the week, `YYYY-MM-DD`; the current week when blank.
+    #[serde(default)]
+    week: String,
+    /// `YYYY-MM`; the current month when blank.
+    #[serde(default)]
+    month: String,
+}
+
+/// Build the report `kind` (`week` or `month`) selected by `query`.
+async fn load_report(
+    state: &AppState,
+    user_id: &str,
+    kind: &str,
+    query: &ReportQuery,
+) -> Result<Report> {
+    let kind = ReportKind::from_param(kind)
+        .ok_or_else(|| AppError::NotFound("Report not found".to_string()))?;
+    let preferences = state.user_repo.get_preferences(user_id).await?;
+    let today = preferences.today();
+    let value = match kind {
+        ReportKind::Week => &query.week,
+        ReportKind::Month => &query.month,
+    };
+    let period = ReportPeriod::parse(kind, value, today, preferences.week_start)
+        .map_err(AppError::BadRequest)?;
+    let previous = period.previous();
+
+    let repo = &state.workout_repo;
+    let data = ReportData {
+        summary: repo
+            .get_period_summary(user_id, period.start, period.end)
+            .await?,
+        previous_summary: repo
+            .get_period_summary(user_id, previous.start, previous.end)
+            .await?,
+        exercises: repo
+            .get_exercise_totals_between(user_id, period.start, period.end)
+            .await?,
+        previous_exercises: repo
+            .get_exercise_totals_between(user_id, previous.start, previous.end)
+            .await?,
+        prs: repo
+            .get_prs_between(user_id, period.start, period.end)
+            .await?,
+    };
+    Ok(report(period, data, today))
+}
+
+/// A week's or month's training compared with the one before.
+pub async fn show(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kind): Path<String>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Response> {
+    let report = load_report(&state, &auth_user.id, &kind, &query).await?;
+    let template = ReportTemplate {
+        user: auth_user,
+        report,
+        links: true,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// The same report as a self-contained HTML file, for printing or keeping.
+pub async fn download(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kind): Path<String>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Response> {
+    let report = load_report(&state, &auth_user.id, &kind, &query).await?;
+    let disposition = format!("attachment; filename=\"{}\"", report.period.filename());
+    let template = PrintableReportTemplate {
+        username: auth_user.username,
+        report,
+        links: false,
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        template.render()?,
+    )
+        .into_response())
+}
@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+
+use askama::Template;
+use axum::{
+    Form,
+    extract::{Path, State},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use serde::Deserialize;
+
+use crate::error::{AppError, Result};
+use crate::middleware::AuthUser;
+use crate::models::{AddSetsForm, Exercise, SetValues, WorkoutLog, WorkoutSession};
+use crate::quick_entry::MAX_SETS_PER_GROUP;
+use crate::state::AppState;
+
+/// Sessions offered as move targets, newest first.
+const MOVE_TARGET_SESSIONS: i64 = 20;
+
+#[derive(Template)]
+#[template(path = "workouts/sets.html")]
+struct SetEditorTemplate {
+    user: AuthUser,
+    workout: WorkoutSession,
+    exercise: Exercise,
+    logs: Vec<WorkoutLog>,
+    /// Comma-separated ids in set order, posted back by the reorder form.
+    order: String,
+    exercises: Vec<Exercise>,
+    sessions: Vec<WorkoutSession>,
+    max_sets: usize,
+}
+
+#[derive(Deserialize)]
+pub struct ReorderForm {
+    /// Set ids in their new order, comma-separated.
+    order: String,
+}
+
+#[derive(Deserialize)]
+pub struct ShiftForm {
+    direction: String,
+}
+
+/// One `log.<id>` checkbox per selected set, flattened for the same reason
+/// as the catalog import: the urlencoded extractor cannot collect repeated
+/// keys into a `Vec`.
+#[derive(Deserialize)]
+pub struct MoveSetsForm {
+    target_session_id: String,
+    target_exercise_id: String,
+    #[serde(flatten)]
+    fields: HashMap<String, String>,
+}
+
+fn editor_url(session_id: &str, exercise_id: &str) -> String {
+    format!("/workouts/{session_id}/exercises/{exercise_id}/sets")
+}
+
+pub async fn page(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((session_id, exercise_id)): Path<(String, String)>,
+) -> Result<Response> {
+    let workout = state
+        .workout_repo
+        .find_owned_session(&session_id, &auth_user.id)
+        .await?;
+    let exercise = state
+        .exercise_repo
+        .find_usable(&exercise_id, &auth_user.id)
+        .await?;
+    let logs = state
+        .workout_repo
+        .find_logs_for_exercise(&session_id, &exercise_id)
+        .await?;
+    let exercises = state
+        .exercise_repo
+        .find_available_for_user(&auth_user.id)
+        .await?;
+    let mut sessions = state
+        .workout_repo
+        .find_sessions_by_user_paginated(&auth_user.id, MOVE_TARGET_SESSIONS, 0)
+        .await?;
+    // Older workouts fall outside the recent list but must stay selectable.
+    if !sessions.iter().any(|s| s.id == workout.id) {
+        sessions.insert(0, workout.clone());
+    }
+
+    let order = logs
+        .iter()
+        .map(|log| log.id.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let template = SetEditorTemplate {
+        user: auth_user,
+        workout,
+        exercise,
+        logs,
+        order,
+        exercises,
+        sessions,
+        max_sets: MAX_SETS_PER_GROUP,
+    };
+
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Add `count` identical sets after the exercise's existing ones.
+pub async fn add_sets(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((session_id, exercise_id)): Path<(String, String)>,
+    Form(form): Form<AddSetsForm>,
+) -> Result<Response> {
+    state
+        .workout_repo
+        .find_owned_session(&session_id, &auth_user.id)
+        .await?;
+    let exercise = state
+        .exercise_repo
+        .find_usable(&exercise_id, &auth_user.id)
+        .await?;
+    if !(1..=MAX_SETS_PER_GROUP).contains(&usize::try_from(form.count).unwrap_or(0)) {
+        return Err(AppError::BadRequest(format!(
+            "You can add between 1 and {MAX_SETS_PER_GROUP} sets at once"
+        )));
+    }
+
+    let values = SetValues::from_form(
+        exercise.unilateral,
+        form.reps,
+        form.weight,
+        form.rpe,
+        form.right_reps,
+        form.right_weight,
+    );
+    let sets = (0..form.count)
+        .map(|_| (exercise.id.clone(), values.clone()))
+        .collect();
+    state.workout_repo.create_logs(&session_id, sets).await?;
+
+    Ok(Redirect::to(&editor_url(&session_id, &exercise_id)).into_response())
+}
+
+/// Apply an order from drag and drop.
+pub async fn reorder(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((session_id, exercise_id)): Path<(String, String)>,
+    Form(form): Form<ReorderForm>,
+) -> Result<Response> {
+    state
+        .workout_repo
+        .find_owned_session(&session_id, &auth_user.id)
+        .await?;
+    let order = form
+        .order
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect();
+    state
+        .workout_repo
+        .reorder_logs(&session_id, &exercise_id, order)
+        .await?;
+
+    Ok(Redirect::to(&editor_url(&session_id, &exercise_id)).into_response())
+}
+
+/// Swap a set with its neighbour (`direction` is `up` or `down`). Moving the
+/// first set up or the last set down does nothing.
+pub async fn shift(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((session_id, exercise_id, log_id)): Path<(String, String, String)>,
+    Form(form): Form<ShiftForm>,
+) -> Result<Response> {
+    state
+        .workout_repo
+        .find_owned_session(&session_id, &auth_user.id)
+        .await?;
+    let mut order: Vec<String> = state
+        .workout_repo
+        .find_logs_for_exercise(&session_id, &exercise_id)
+        .await?
+        .into_iter()
+        .map(|log| log.id)
+        .collect();
+    let index = order
+        .iter()
+        .position(|id| *id == log_id)
+        .ok_or_else(|| AppError::NotFound("Log not found".to_string()))?;
+    let other = match form.direction.as_str() {
+        "up" => index.checked_sub(1),
+        "down" => Some(index + 1).filter(|&i| i < order.len()),
+        _ => {
+            return Err(AppError::BadRequest(
+                "Direction must be up or down".to_string(),
+            ));
+        }
+    };
+    if let Some(other) = other {
+        order.swap(index, other);
+        state
+            .workout_repo
+            .reorder_logs(&session_id, &exercise_id, order)
+            .await?;
+    }
+
+    Ok(Redirect::to(&editor_url(&session_id, &exercise_id)).into_response())
+}
+
+/// Move the selected sets to another exercise and/or session.
+pub async fn move_sets(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((session_id, exercise_id)): Path<(String, String)>,
+    Form(form): Form<MoveSetsForm>,
+) -> Result<Response> {
+    state
+        .workout_repo
+        .find_owned_session(&session_id, &auth_user.id)
+        .await?;
+    state
+        .workout_repo
+        .find_owned_session(&form.target_session_id, &auth_user.id)
+        .await?;
+    let target = state
+        .exercise_repo
+        .find_usable(&form.target_exercise_id, &auth_user.id)
+        .await?;
+
+    let log_ids: Vec<String> = form
+        .fields
+        .keys()
+        .filter_map(|key| key.strip_prefix("log."))
+        .map(str::to_string)
+        .collect();
+    if log_ids.is_empty() {
+        return Err(AppError::BadRequest(
+            "Select at least one set to move".to_string(),
+        ));
+    }
+    if form.target_session_id == session_id && target.id == exercise_id {
+        return Ok(Redirect::to(&editor_url(&session_id, &exercise_id)).into_response());
+    }
+
+    state
+        .workout_repo
+        .move_logs(
+            &session_id,
+            log_ids,
+            &form.target_session_id,
+            &target.id,
+            target.unilateral,
+        )
+        .await?;
+
+    Ok(Redirect::to(&editor_url(&form.target_session_id, &target.id)).into_response())
+}
@@ -10,7 +10,10 @@ use serde::Deserialize;
 use crate::audit::{self, AuditContext};
 use crate::error::Result;
 use crate::middleware::{AuthUser, SuppressSessionRefresh};
-use crate::models::password_policy_error;
+use crate::models::preferences::{
+    MAX_WEEKLY_SESSION_TARGET, WEEK_START_DAYS, timezone_names, weekday_name,
+};
+use crate::models::{TrainingPreferencesForm, UserPreferences, password_policy_error};
 use crate::repositories::SessionListRow;
 use crate::session::{create_session_cookie, token_fingerprint};
 use crate::state::AppState;
@@ -31,6 +34,11 @@ struct SettingsTemplate {
     error: Option<String>,
     success: Option<String>,
     sessions: Vec<SessionListRow>,
+    preferences: UserPreferences,
+    max_weekly_target: i64,
+    timezones: Vec<&'static str>,
+    /// (form value, label) for each day a week may start on.
+    week_start_days: Vec<(String, &'static str)>,
 }
 
 async fn render_page(
@@ -54,12 +62,20 @@ async fn render_page_with_status(
     status: StatusCode,
 ) -> Result<Response> {
     let sessions = state.session_repo.list_for_user(&auth_user.id).await?;
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
     let template = SettingsTemplate {
         user: auth_user,
         git_version: GIT_VERSION,
         error,
         success,
         sessions,
+        preferences,
+        max_weekly_target: MAX_WEEKLY_SESSION_TARGET,
+        timezones: timezone_names().collect(),
+        week_start_days: WEEK_START_DAYS
+            .iter()
+            .map(|day| (day.to_string(), weekday_name(*day)))
+            .collect(),
     };
     Ok((status, Html(template.render()?)).into_response())
 }
@@ -68,6 +84,31 @@ pub async fn index(State(state): State<AppState>, auth_user: AuthUser) -> Result
     render_page(&state, auth_user, None, None).await
 }
 
+/// Save the weekly target, timezone and first day of the week that streaks
+/// and every "today" / "this week" figure are worked out with.
+pub async fn update_training(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Form(form): Form<TrainingPreferencesForm>,
+) -> Result<Response> {
+    match form.parse() {
+        Ok(preferences) => {
+            state
+                .user_repo
+                .set_preferences(&auth_user.id, preferences)
+                .await?;
+            render_page(
+                &state,
+                auth_user,
+                None,
+                Some("Training preferences saved.".to_string()),
+            )
+            .await
+        }
+        Err(message) => render_page(&state, auth_user, Some(message), None).await,
+    }
+}
+
 pub async fn change_password(
     State(state): State<AppState>,
     auth_user: AuthUser,
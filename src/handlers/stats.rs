@@ -1,14 +1,29 @@
+use std::collections::HashMap;
+
 use askama::Template;
 use axum::{
-    extract::{Path, State},
-    response::{Html, IntoResponse, Response},
+    Form,
+    extract::{Path, Query, State},
+    response::{Html, IntoResponse, Redirect, Response},
 };
+use chrono::{Duration, Months, NaiveDate};
+use serde::Deserialize;
 
+use crate::calendar::{Heatmap, heatmap, heatmap_range, month_end, month_start, week_start};
+use crate::consistency::{Consistency, consistency};
 use crate::error::{AppError, Result};
 use crate::middleware::AuthUser;
+use crate::models::muscle::{
+    DEFAULT_TARGET_MAX_SETS, DEFAULT_TARGET_MIN_SETS, HARD_SET_MAX_RIR, HARD_SET_MIN_RPE,
+    MUSCLE_GROUPS, TargetStatus, muscle_display_name,
+};
 use crate::models::{
-    ChartPoint, DynamicPR, Exercise, PersonalRecordSummary, WorkoutLogWithExercise,
-    recent_pr_window_start,
+    ChartPoint, DynamicPR, Exercise, MuscleTarget, PersonalRecordSummary, SideBalance,
+    UserPreferences, WorkoutLogWithExercise,
+};
+use crate::progress_chart::{
+    ChartMetric, ChartOptions, ChartRange, MAX_SERIES, ProgressChart, RepRange, SMOOTHING_CHOICES,
+    render_chart, series,
 };
 use crate::state::AppState;
 
@@ -21,28 +36,9 @@ struct StatsTemplate {
     total_volume: f64,
     total_workouts: i64,
     prs: Vec<PersonalRecordSummary>,
-}
-
-/// Geometry + flags used to draw the *default* server-rendered SVG.
-/// Computed in the handler so the template stays declarative.
-pub(crate) struct RenderedChart {
-    pub(crate) width: f64,
-    pub(crate) height: f64,
-    pub(crate) padding_left: f64,
-    pub(crate) padding_right: f64,
-    pub(crate) points: Vec<RenderedPoint>,
-    /// Polyline `points` attribute, e.g. "10,20 50,60 ..."
-    pub(crate) polyline: String,
-    /// Y-axis tick labels: (`y_pixel`, `label_text`)
-    pub(crate) y_ticks: Vec<(f64, String)>,
-    /// X-axis tick labels: (`x_pixel`, `label_text`). Only every Nth point is labeled.
-    pub(crate) x_ticks: Vec<(f64, String)>,
-}
-
-pub(crate) struct RenderedPoint {
-    pub(crate) x: f64,
-    pub(crate) y: f64,
-    pub(crate) is_pr: bool,
+    /// Training days over the last year.
+    heatmap: Heatmap,
+    consistency: Consistency,
 }
 
 #[derive(Template)]
@@ -54,11 +50,20 @@ struct ExerciseStatsTemplate {
     pr: Option<DynamicPR>,
     /// Total session count for this exercise (for the empty/sparse copy).
     session_count: usize,
-    /// Default-state rendered chart. `None` when fewer than 2 sessions.
-    chart: Option<RenderedChart>,
-    /// JSON-encoded full `Vec<ChartPoint>` for the client switcher.
-    /// Already escaped: `</` → `<\/` so it cannot break out of `<script>`.
-    chart_data_json: String,
+    /// The metric and range picked by the chart's tabs.
+    options: ChartOptions,
+    metrics: [ChartMetric; 3],
+    ranges: [ChartRange; 4],
+    /// `None` with fewer than 2 sessions, or nothing logged in range.
+    chart: Option<ProgressChart>,
+    /// The most recent session, whatever the range.
+    latest: Option<ChartPoint>,
+    /// The category's current display name; falls back to the stored key.
+    category_name: String,
+    /// (display name, role) for each muscle the exercise works.
+    muscles: Vec<(String, &'static str)>,
+    /// Left/right totals; `None` until a unilateral set is logged.
+    side_balance: Option<SideBalance>,
 }
 
 #[derive(Template)]
@@ -68,117 +73,75 @@ struct PrsTemplate {
     prs: Vec<PersonalRecordSummary>,
 }
 
-const CHART_W: f64 = 600.0;
-const CHART_H: f64 = 220.0;
-const PAD_L: f64 = 44.0;
-const PAD_R: f64 = 12.0;
-const PAD_T: f64 = 14.0;
-const PAD_B: f64 = 28.0;
-
-fn render_default_chart(points: &[ChartPoint]) -> Option<RenderedChart> {
-    if points.len() < 2 {
-        return None;
-    }
-
-    // Default state: top set weight, last 20 sessions.
-    let slice: Vec<&ChartPoint> = points.iter().rev().take(20).rev().collect();
-    if slice.len() < 2 {
-        return None;
-    }
-
-    let values: Vec<f64> = slice.iter().map(|p| p.top_weight).collect();
-    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
-    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
-    // Pad y range a bit so the line isn't flush against the top.
-    let (y_min, y_max) = if (max - min).abs() < 1e-9 {
-        (min - 1.0, max + 1.0)
-    } else {
-        let pad = (max - min) * 0.1;
-        (min - pad, max + pad)
-    };
-
-    let plot_w = CHART_W - PAD_L - PAD_R;
-    let plot_h = CHART_H - PAD_T - PAD_B;
-    let n = slice.len();
-
-    // Running max for PR detection.
-    let mut running_max = f64::NEG_INFINITY;
-    let mut rendered_points = Vec::with_capacity(n);
-    let mut polyline_parts = Vec::with_capacity(n);
-
-    for (i, p) in slice.iter().enumerate() {
-        let x = PAD_L + (i as f64 / (n as f64 - 1.0)) * plot_w;
-        let y = PAD_T + (1.0 - (p.top_weight - y_min) / (y_max - y_min)) * plot_h;
-        let is_pr = p.top_weight > running_max;
-        if is_pr {
-            running_max = p.top_weight;
-        }
-        polyline_parts.push(format!("{x:.2},{y:.2}"));
-        rendered_points.push(RenderedPoint { x, y, is_pr });
-    }
-
-    // 4 evenly spaced y ticks.
-    let mut y_ticks = Vec::with_capacity(4);
-    for i in 0..4 {
-        let frac = i as f64 / 3.0;
-        let y = PAD_T + frac * plot_h;
-        let value = y_max - frac * (y_max - y_min);
-        y_ticks.push((y, format!("{value:.0}")));
-    }
+/// Metrics offered as tabs on an exercise's page; the progress page has
+/// the rest.
+const EXERCISE_CHART_METRICS: [ChartMetric; 3] =
+    [ChartMetric::TopSet, ChartMetric::E1rm, ChartMetric::Volume];
 
-    // Up to 5 x-axis date labels, evenly sampled.
-    let label_count = n.min(5);
-    let mut x_ticks = Vec::with_capacity(label_count);
-    if label_count >= 2 {
-        for i in 0..label_count {
-            let idx = i * (n - 1) / (label_count - 1);
-            let p = &rendered_points[idx];
-            let label = slice[idx].date.format("%m-%d").to_string();
-            x_ticks.push((p.x, label));
-        }
-    } else if let Some(p) = rendered_points.first() {
-        x_ticks.push((p.x, slice[0].date.format("%m-%d").to_string()));
-    }
-
-    Some(RenderedChart {
-        width: CHART_W,
-        height: CHART_H,
-        padding_left: PAD_L,
-        padding_right: PAD_R,
-        points: rendered_points,
-        polyline: polyline_parts.join(" "),
-        y_ticks,
-        x_ticks,
-    })
-}
-
-fn encode_chart_data(points: &[ChartPoint]) -> Result<String> {
-    let json = serde_json::to_string(points).map_err(|e| AppError::Internal(e.to_string()))?;
-    // Prevent `</script>` injection inside <script type="application/json">.
-    Ok(json.replace("</", "<\\/"))
+/// Streaks and adherence against the user's weekly target, as of today in
+/// their timezone.
+pub(crate) async fn load_consistency(
+    state: &AppState,
+    user_id: &str,
+    preferences: &UserPreferences,
+) -> Result<Consistency> {
+    let today = preferences.today();
+    let weeks = state
+        .workout_repo
+        .get_weekly_session_counts(user_id, today, preferences.week_start)
+        .await?;
+    Ok(consistency(
+        &weeks,
+        preferences.weekly_session_target,
+        today,
+        preferences.week_start,
+    ))
 }
 
-pub async fn index(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+/// Sessions this calendar week, sessions this calendar month, and volume this
+/// calendar week, in the user's timezone and week.
+pub(crate) async fn load_period_totals(
+    state: &AppState,
+    user_id: &str,
+    preferences: &UserPreferences,
+) -> Result<(i64, i64, f64)> {
+    let today = preferences.today();
+    let week = week_start(today, preferences.week_start);
+    let week_end = week + Duration::days(6);
     let workouts_this_week = state
         .workout_repo
-        .count_workouts_this_week(&auth_user.id)
+        .count_sessions_between(user_id, week, week_end)
         .await?;
     let workouts_this_month = state
         .workout_repo
-        .count_workouts_this_month(&auth_user.id)
+        .count_sessions_between(user_id, month_start(today), month_end(today))
         .await?;
-    let total_volume = state
+    let volume_this_week = state
         .workout_repo
-        .get_total_volume_this_week(&auth_user.id)
+        .get_volume_between(user_id, week, week_end)
         .await?;
+    Ok((workouts_this_week, workouts_this_month, volume_this_week))
+}
+
+pub async fn index(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
+    let (workouts_this_week, workouts_this_month, total_volume) =
+        load_period_totals(&state, &auth_user.id, &preferences).await?;
     let total_workouts = state
         .workout_repo
         .count_sessions_by_user(&auth_user.id)
         .await?;
     let prs = state
         .workout_repo
-        .get_pr_summaries_by_user(&auth_user.id, recent_pr_window_start())
+        .get_pr_summaries_by_user(&auth_user.id, preferences.pr_window_start())
+        .await?;
+    let today = preferences.today();
+    let (from, to) = heatmap_range(today, preferences.week_start);
+    let days = state
+        .workout_repo
+        .get_daily_volume(&auth_user.id, from, to)
         .await?;
+    let consistency = load_consistency(&state, &auth_user.id, &preferences).await?;
 
     let template = StatsTemplate {
         user: auth_user,
@@ -187,27 +150,38 @@ pub async fn index(State(state): State<AppState>, auth_user: AuthUser) -> Result
         total_volume,
         total_workouts,
         prs,
+        heatmap: heatmap(&days, today, preferences.week_start),
+        consistency,
     };
 
     Ok(Html(template.render()?).into_response())
 }
 
+/// An exercise's record, history and progress chart. The chart takes the
+/// progress page's `metric` and `range` options, over all time by default.
 pub async fn exercise_stats(
     State(state): State<AppState>,
     auth_user: AuthUser,
     Path(exercise_id): Path<String>,
+    Query(mut query): Query<ProgressQuery>,
 ) -> Result<Response> {
     // The history/PR/metrics queries below are all scoped by `auth_user.id`, but
     // the exercise record itself is rendered, so fetching it unscoped disclosed
     // another user's exercise name and category.
     let exercise = state
         .exercise_repo
-        .find_owned(&exercise_id, &auth_user.id)
+        .find_usable(&exercise_id, &auth_user.id)
         .await?;
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
 
     let history = state
         .workout_repo
-        .get_exercise_history_with_pr(&auth_user.id, &exercise_id, 50, recent_pr_window_start())
+        .get_exercise_history_with_pr(
+            &auth_user.id,
+            &exercise_id,
+            50,
+            preferences.pr_window_start(),
+        )
         .await?;
 
     let pr = state
@@ -219,11 +193,55 @@ pub async fn exercise_stats(
         .workout_repo
         .get_session_metrics_for_exercise(&auth_user.id, &exercise_id)
         .await?;
+    let session_count = metrics.len();
+    let latest = metrics.last().map(ChartPoint::from_metric);
+
+    query
+        .fields
+        .retain(|key, _| key == "metric" || key == "range");
+    query
+        .fields
+        .entry("range".to_string())
+        .or_insert_with(|| ChartRange::All.param().to_string());
+    let mut options = ChartOptions::parse(&query.fields).map_err(AppError::BadRequest)?;
+    options.exercise_ids = vec![exercise.id.clone()];
+    let chart = if session_count < 2 {
+        None
+    } else {
+        let (from, to) = options.dates(preferences.today());
+        let sets = state
+            .workout_repo
+            .get_sets_for_exercises(&auth_user.id, &options.exercise_ids, from, to)
+            .await?;
+        render_chart(
+            &series(
+                &sets,
+                &[(exercise.id.clone(), exercise.name.clone())],
+                options.metric,
+                options.rep_range,
+            ),
+            &options,
+        )
+    };
+
+    let muscles = state
+        .exercise_repo
+        .find_muscles(&exercise.id)
+        .await?
+        .into_iter()
+        .map(|m| (muscle_display_name(&m.muscle).to_string(), m.role.as_str()))
+        .collect();
+
+    let side_balance = state
+        .workout_repo
+        .get_side_balance(&auth_user.id, &exercise_id)
+        .await?;
 
-    let chart_points: Vec<ChartPoint> = metrics.iter().map(ChartPoint::from_metric).collect();
-    let session_count = chart_points.len();
-    let chart = render_default_chart(&chart_points);
-    let chart_data_json = encode_chart_data(&chart_points)?;
+    let category_name = state
+        .category_repo
+        .find_by_name(&auth_user.id, &exercise.category)
+        .await?
+        .map_or_else(|| exercise.category.clone(), |c| c.display_name);
 
     let template = ExerciseStatsTemplate {
         user: auth_user,
@@ -231,17 +249,103 @@ pub async fn exercise_stats(
         history,
         pr,
         session_count,
+        options,
+        metrics: EXERCISE_CHART_METRICS,
+        ranges: ChartRange::ALL,
+        chart,
+        latest,
+        category_name,
+        muscles,
+        side_balance,
+    };
+
+    Ok(Html(template.render()?).into_response())
+}
+
+#[derive(Template)]
+#[template(path = "stats/progress.html")]
+struct ProgressTemplate {
+    user: AuthUser,
+    /// The exercises offered, plus any archived ones already picked.
+    exercises: Vec<Exercise>,
+    options: ChartOptions,
+    metrics: [ChartMetric; 5],
+    rep_ranges: [RepRange; 3],
+    ranges: [ChartRange; 4],
+    smoothing_choices: [usize; 4],
+    max_series: usize,
+    /// `None` until an exercise is picked, or with nothing logged in range.
+    chart: Option<ProgressChart>,
+}
+
+/// `ex.<id>` checkboxes plus the chart options; see `ChartOptions`.
+#[derive(Deserialize)]
+pub struct ProgressQuery {
+    #[serde(flatten)]
+    pub(crate) fields: HashMap<String, String>,
+}
+
+/// Progress of up to `MAX_SERIES` exercises on one chart, by any metric.
+pub async fn progress(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<ProgressQuery>,
+) -> Result<Response> {
+    let options = ChartOptions::parse(&query.fields).map_err(AppError::BadRequest)?;
+    let mut exercises = state
+        .exercise_repo
+        .find_available_for_user(&auth_user.id)
+        .await?;
+    for id in &options.exercise_ids {
+        if !exercises.iter().any(|e| e.id == *id) {
+            exercises.push(state.exercise_repo.find_usable(id, &auth_user.id).await?);
+        }
+    }
+
+    let chart = if options.exercise_ids.is_empty() {
+        None
+    } else {
+        let picked: Vec<(String, String)> = exercises
+            .iter()
+            .filter(|e| options.exercise_ids.contains(&e.id))
+            .map(|e| (e.id.clone(), e.name.clone()))
+            .collect();
+        let today = state
+            .user_repo
+            .get_preferences(&auth_user.id)
+            .await?
+            .today();
+        let (from, to) = options.dates(today);
+        let sets = state
+            .workout_repo
+            .get_sets_for_exercises(&auth_user.id, &options.exercise_ids, from, to)
+            .await?;
+        render_chart(
+            &series(&sets, &picked, options.metric, options.rep_range),
+            &options,
+        )
+    };
+
+    let template = ProgressTemplate {
+        user: auth_user,
+        exercises,
+        options,
+        metrics: ChartMetric::ALL,
+        rep_ranges: RepRange::ALL,
+        ranges: ChartRange::ALL,
+        smoothing_choices: SMOOTHING_CHOICES,
+        max_series: MAX_SERIES,
         chart,
-        chart_data_json,
     };
 
     Ok(Html(template.render()?).into_response())
 }
 
 pub async fn prs_list(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
     let prs = state
         .workout_repo
-        .get_pr_summaries_by_user(&auth_user.id, recent_pr_window_start())
+        .get_pr_summaries_by_user(&auth_user.id, preferences.pr_window_start())
         .await?;
 
     let template = PrsTemplate {
@@ -251,3 +355,318 @@ pub async fn prs_list(State(state): State<AppState>, auth_user: AuthUser) -> Res
 
     Ok(Html(template.render()?).into_response())
 }
+
+/// Longest window the muscle volume page will render. Each week is a table
+/// column, so this is a layout limit as much as a query one.
+const MAX_MUSCLE_WEEKS: u32 = 12;
+const DEFAULT_MUSCLE_WEEKS: u32 = 4;
+
+#[derive(Deserialize)]
+pub struct MuscleVolumeQuery {
+    weeks: Option<u32>,
+}
+
+/// `min.<muscle>` / `max.<muscle>` pairs, one per row of the volume table.
+#[derive(Deserialize)]
+pub struct MuscleTargetsForm {
+    #[serde(flatten)]
+    fields: HashMap<String, String>,
+}
+
+pub(crate) struct MuscleVolumeCell {
+    pub(crate) hard_sets: String,
+    pub(crate) volume: f64,
+    pub(crate) css_class: &'static str,
+}
+
+pub(crate) struct MuscleVolumeRow {
+    pub(crate) name: &'static str,
+    pub(crate) display_name: &'static str,
+    pub(crate) min_sets: i32,
+    pub(crate) max_sets: i32,
+    /// One cell per entry in `MuscleVolumeTemplate::weeks`, oldest first.
+    pub(crate) cells: Vec<MuscleVolumeCell>,
+}
+
+#[derive(Template)]
+#[template(path = "stats/muscles.html")]
+struct MuscleVolumeTemplate {
+    user: AuthUser,
+    weeks: Vec<NaiveDate>,
+    week_count: u32,
+    rows: Vec<MuscleVolumeRow>,
+    hard_set_min_rpe: i32,
+    hard_set_max_rir: i32,
+    error: Option<String>,
+}
+
+/// Hard sets are whole numbers unless secondary credit is involved; avoid
+/// printing "12.0" for the common case.
+fn format_sets(sets: f64) -> String {
+    if sets.fract() == 0.0 {
+        format!("{sets:.0}")
+    } else {
+        format!("{sets:.1}")
+    }
+}
+
+async fn render_muscle_volume(
+    state: &AppState,
+    auth_user: AuthUser,
+    week_count: u32,
+    error: Option<String>,
+) -> Result<Response> {
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
+    let current = week_start(preferences.today(), preferences.week_start);
+    let weeks: Vec<NaiveDate> = (0..week_count)
+        .rev()
+        .map(|i| current - Duration::weeks(i64::from(i)))
+        .collect();
+
+    let volume = state
+        .workout_repo
+        .get_weekly_muscle_volume(&auth_user.id, weeks[0], preferences.week_start)
+        .await?;
+    let targets = state.user_repo.get_muscle_targets(&auth_user.id).await?;
+
+    let rows = MUSCLE_GROUPS
+        .iter()
+        .map(|m| {
+            let (min_sets, max_sets) = targets
+                .iter()
+                .find(|t| t.muscle == m.name)
+                .map_or((DEFAULT_TARGET_MIN_SETS, DEFAULT_TARGET_MAX_SETS), |t| {
+                    (t.min_sets, t.max_sets)
+                });
+            let cells = weeks
+                .iter()
+                .map(|week| {
+                    let (hard_sets, volume) = volume
+                        .iter()
+                        .find(|v| v.week_start == *week && v.muscle == m.name)
+                        .map_or((0.0, 0.0), |v| (v.hard_sets, v.volume));
+                    MuscleVolumeCell {
+                        hard_sets: format_sets(hard_sets),
+                        volume,
+                        css_class: TargetStatus::classify(hard_sets, min_sets, max_sets)
+                            .css_class(),
+                    }
+                })
+                .collect();
+            MuscleVolumeRow {
+                name: m.name,
+                display_name: m.display_name,
+                min_sets,
+                max_sets,
+                cells,
+            }
+        })
+        .collect();
+
+    let status = if error.is_some() {
+        axum::http::StatusCode::BAD_REQUEST
+    } else {
+        axum::http::StatusCode::OK
+    };
+    let template = MuscleVolumeTemplate {
+        user: auth_user,
+        weeks,
+        week_count,
+        rows,
+        hard_set_min_rpe: HARD_SET_MIN_RPE,
+        hard_set_max_rir: HARD_SET_MAX_RIR,
+        error,
+    };
+    Ok((status, Html(template.render()?)).into_response())
+}
+
+pub async fn muscle_volume(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<MuscleVolumeQuery>,
+) -> Result<Response> {
+    let week_count = query
+        .weeks
+        .unwrap_or(DEFAULT_MUSCLE_WEEKS)
+        .clamp(1, MAX_MUSCLE_WEEKS);
+    render_muscle_volume(&state, auth_user, week_count, None).await
+}
+
+/// Parse the `min.<muscle>` / `max.<muscle>` fields of the targets form.
+/// Muscles left at the defaults are not stored, so changing a default later
+/// reaches everyone who never customised it.
+fn targets_from_form(
+    fields: &HashMap<String, String>,
+) -> std::result::Result<Vec<MuscleTarget>, String> {
+    let mut targets = Vec::new();
+    for m in MUSCLE_GROUPS {
+        let parse = |key: String, default: i32| -> std::result::Result<i32, String> {
+            match fields.get(&key).map(|v| v.trim()) {
+                None | Some("") => Ok(default),
+                Some(v) => v
+                    .parse::<i32>()
+                    .ok()
+                    .filter(|n| (0..=100).contains(n))
+                    .ok_or_else(|| {
+                        format!(
+                            "{}: targets must be whole numbers from 0 to 100",
+                            m.display_name
+                        )
+                    }),
+            }
+        };
+        let min_sets = parse(format!("min.{}", m.name), DEFAULT_TARGET_MIN_SETS)?;
+        let max_sets = parse(format!("max.{}", m.name), DEFAULT_TARGET_MAX_SETS)?;
+        if min_sets > max_sets {
+            return Err(format!(
+                "{}: minimum cannot be above maximum",
+                m.display_name
+            ));
+        }
+        if (min_sets, max_sets) != (DEFAULT_TARGET_MIN_SETS, DEFAULT_TARGET_MAX_SETS) {
+            targets.push(MuscleTarget {
+                muscle: m.name.to_string(),
+                min_sets,
+                max_sets,
+            });
+        }
+    }
+    Ok(targets)
+}
+
+pub async fn update_muscle_targets(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Form(form): Form<MuscleTargetsForm>,
+) -> Result<Response> {
+    match targets_from_form(&form.fields) {
+        Ok(targets) => {
+            state
+                .user_repo
+                .set_muscle_targets(&auth_user.id, targets)
+                .await?;
+            Ok(Redirect::to("/stats/muscles").into_response())
+        }
+        Err(message) => {
+            render_muscle_volume(&state, auth_user, DEFAULT_MUSCLE_WEEKS, Some(message)).await
+        }
+    }
+}
+
+/// Longest window the tag volume page will render, one column per month.
+const MAX_TAG_MONTHS: u32 = 12;
+const DEFAULT_TAG_MONTHS: u32 = 6;
+
+#[derive(Deserialize)]
+pub struct TagVolumeQuery {
+    months: Option<u32>,
+}
+
+pub(crate) struct TagVolumeCell {
+    pub(crate) sessions: i64,
+    /// Average volume per session; `None` for a month without sessions.
+    pub(crate) average: Option<f64>,
+}
+
+pub(crate) struct TagVolumeRow {
+    pub(crate) name: String,
+    /// One cell per entry in `TagVolumeTemplate::months`, oldest first.
+    pub(crate) cells: Vec<TagVolumeCell>,
+    pub(crate) sessions: i64,
+    pub(crate) average: Option<f64>,
+}
+
+#[derive(Template)]
+#[template(path = "stats/tags.html")]
+struct TagVolumeTemplate {
+    user: AuthUser,
+    months: Vec<NaiveDate>,
+    month_count: u32,
+    rows: Vec<TagVolumeRow>,
+}
+
+/// Average session volume per tag per month, e.g. how "push" days trend.
+pub async fn tag_volume(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<TagVolumeQuery>,
+) -> Result<Response> {
+    let month_count = query
+        .months
+        .unwrap_or(DEFAULT_TAG_MONTHS)
+        .clamp(1, MAX_TAG_MONTHS);
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
+    let current = month_start(preferences.today());
+    let months: Vec<NaiveDate> = (0..month_count)
+        .rev()
+        .filter_map(|i| current.checked_sub_months(Months::new(i)))
+        .collect();
+
+    let volume = state
+        .workout_repo
+        .get_monthly_tag_volume(&auth_user.id, months[0])
+        .await?;
+    let tags = state.tag_repo.find_by_user(&auth_user.id).await?;
+
+    let rows = tags
+        .into_iter()
+        .map(|tag| {
+            let entries: Vec<_> = volume.iter().filter(|v| v.tag_name == tag.name).collect();
+            let cells = months
+                .iter()
+                .map(|month| {
+                    let entry = entries.iter().find(|v| v.month == *month);
+                    TagVolumeCell {
+                        sessions: entry.map_or(0, |v| v.sessions),
+                        average: entry.map(|v| v.volume / v.sessions as f64),
+                    }
+                })
+                .collect();
+            let sessions: i64 = entries.iter().map(|v| v.sessions).sum();
+            let total: f64 = entries.iter().map(|v| v.volume).sum();
+            TagVolumeRow {
+                name: tag.name,
+                cells,
+                sessions,
+                average: (sessions > 0).then(|| total / sessions as f64),
+            }
+        })
+        .collect();
+
+    let template = TagVolumeTemplate {
+        user: auth_user,
+        months,
+        month_count,
+        rows,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn week_start_is_monday() {
+        let sunday = NaiveDate::from_ymd_opt(2024, 1, 14).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+        assert_eq!(week_start(sunday, chrono::Weekday::Mon), monday);
+        assert_eq!(week_start(monday, chrono::Weekday::Mon), monday);
+    }
+
+    #[test]
+    fn targets_from_form_skips_defaults_and_rejects_inverted_ranges() {
+        let mut fields = HashMap::new();
+        fields.insert("min.chest".to_string(), "12".to_string());
+        fields.insert("max.chest".to_string(), "18".to_string());
+        fields.insert("min.lats".to_string(), "10".to_string());
+        fields.insert("max.lats".to_string(), "20".to_string());
+        let targets = targets_from_form(&fields).unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].muscle, "chest");
+        assert_eq!((targets[0].min_sets, targets[0].max_sets), (12, 18));
+
+        fields.insert("min.lats".to_string(), "25".to_string());
+        assert!(targets_from_form(&fields).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use askama::Template;
+use axum::{
+    Form,
+    extract::{Path, State},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+
+use crate::error::{AppError, Result};
+use crate::middleware::AuthUser;
+use crate::models::workout_tag::MAX_TAG_NAME_LEN;
+use crate::models::{TagForm, WorkoutTag, tag_name_error};
+use crate::state::AppState;
+
+#[derive(Template)]
+#[template(path = "workouts/tags.html")]
+struct TagsTemplate {
+    user: AuthUser,
+    tags: Vec<WorkoutTag>,
+    max_len: usize,
+    error: Option<String>,
+}
+
+async fn render_page(
+    state: &AppState,
+    auth_user: AuthUser,
+    error: Option<String>,
+) -> Result<Response> {
+    let tags = state.tag_repo.find_by_user(&auth_user.id).await?;
+    let template = TagsTemplate {
+        user: auth_user,
+        tags,
+        max_len: MAX_TAG_NAME_LEN,
+        error,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+pub async fn list(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    render_page(&state, auth_user, None).await
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Form(form): Form<TagForm>,
+) -> Result<Response> {
+    let name = form.name.trim();
+    if let Some(error) = tag_name_error(name) {
+        return render_page(&state, auth_user, Some(error)).await;
+    }
+
+    match state.tag_repo.create(&auth_user.id, name).await {
+        Ok(_) => Ok(Redirect::to("/workouts/tags").into_response()),
+        Err(AppError::BadRequest(message)) => render_page(&state, auth_user, Some(message)).await,
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn rename(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Form(form): Form<TagForm>,
+) -> Result<Response> {
+    let name = form.name.trim();
+    if let Some(error) = tag_name_error(name) {
+        return render_page(&state, auth_user, Some(error)).await;
+    }
+
+    match state.tag_repo.rename(&auth_user.id, &id, name).await {
+        Ok(true) => Ok(Redirect::to("/workouts/tags").into_response()),
+        Ok(false) => Err(AppError::NotFound("Tag not found".to_string())),
+        Err(AppError::BadRequest(message)) => render_page(&state, auth_user, Some(message)).await,
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    if !state.tag_repo.delete(&auth_user.id, &id).await? {
+        return Err(AppError::NotFound("Tag not found".to_string()));
+    }
+    Ok(Redirect::to("/workouts/tags").into_response())
+}
@@ -0,0 +1,80 @@
+use askama::Template;
+use axum::{
+    Form,
+    extract::State,
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use serde::Deserialize;
+
+use crate::error::{AppError, Result};
+use crate::middleware::AuthUser;
+use crate::state::AppState;
+use crate::text_import::{self, MAX_TEXT_BYTES, TextImport};
+
+#[derive(Template)]
+#[template(path = "workouts/import.html")]
+struct ImportTemplate {
+    user: AuthUser,
+    text: String,
+    /// `None` until the text has been submitted once.
+    preview: Option<TextImport>,
+}
+
+/// The preview button submits the text alone; the import button on the
+/// preview page adds `confirm=1`.
+#[derive(Deserialize)]
+pub struct ImportTextForm {
+    #[serde(default)]
+    text: String,
+    confirm: Option<String>,
+}
+
+pub async fn page(auth_user: AuthUser) -> Result<Response> {
+    let template = ImportTemplate {
+        user: auth_user,
+        text: String::new(),
+        preview: None,
+    };
+
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Preview the sessions found in the text or, once confirmed, create them.
+/// Confirming parses the text again and skips the lines the preview flagged.
+/// Text over the size, session or set limits is refused outright.
+pub async fn import(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Form(form): Form<ImportTextForm>,
+) -> Result<Response> {
+    if form.text.len() > MAX_TEXT_BYTES {
+        return Err(AppError::BadRequest(format!(
+            "Text to import must be at most {} KB",
+            MAX_TEXT_BYTES / 1024
+        )));
+    }
+    let exercises = state
+        .exercise_repo
+        .find_available_for_user(&auth_user.id)
+        .await?;
+    let preview = text_import::parse(&form.text, &exercises);
+    if let Some(error) = preview.limit_error() {
+        return Err(AppError::BadRequest(error));
+    }
+
+    if form.confirm.is_some() && !preview.sessions.is_empty() {
+        state
+            .workout_repo
+            .import_sessions(&auth_user.id, preview.sessions)
+            .await?;
+        return Ok(Redirect::to("/workouts").into_response());
+    }
+
+    let template = ImportTemplate {
+        user: auth_user,
+        text: form.text,
+        preview: Some(preview),
+    };
+
+    Ok(Html(template.render()?).into_response())
+}
@@ -1,28 +1,46 @@
+use std::collections::HashMap;
+
 use askama::Template;
 use axum::{
     Form,
     extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, header},
     response::{Html, IntoResponse, Redirect, Response},
 };
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Deserializer};
 
+use crate::calendar::{MonthCalendar, month_calendar, month_grid_range, month_start, parse_month};
 use crate::error::{AppError, Result};
 use crate::middleware::AuthUser;
-use crate::models::exercise::{CATEGORIES, ExerciseCategory};
+use crate::models::exercise::with_missing_categories;
 use crate::models::{
-    CreateWorkoutLog, CreateWorkoutSession, Exercise, LastExerciseWeight, UpdateWorkoutLog,
-    WorkoutLog, WorkoutLogWithExercise, WorkoutSession, recent_pr_window_start,
+    CreateWorkoutLog, CreateWorkoutSession, Exercise, ExerciseCategory, LastExerciseWeight,
+    PageCursor, SetNoteMatch, SetValues, UpdateWorkoutLog, WorkoutComment, WorkoutCursor,
+    WorkoutFilter, WorkoutLog, WorkoutLogWithExercise, WorkoutSession, WorkoutSort, WorkoutSummary,
+    WorkoutTag, encode_query_value, set_annotation_error, tag_name_error, tag_selection,
 };
+use crate::quick_entry::{self, QuickEntry};
+use crate::share_card::{self, CARD_HEIGHT, CARD_WIDTH, CardLine, MAX_LINES, card_lines};
 use crate::state::AppState;
+use crate::workout_compare::{ExerciseComparison, compare_workouts};
+use crate::workout_text::{self, TextFormat};
 
 #[derive(Template)]
 #[template(path = "workouts/list.html")]
 struct WorkoutsListTemplate {
     user: AuthUser,
-    workouts: Vec<WorkoutSession>,
-    page: i64,
-    total_pages: i64,
+    workouts: Vec<WorkoutSummary>,
+    total: i64,
+    filtered: bool,
+    exercises: Vec<Exercise>,
+    tags: Vec<WorkoutTag>,
+    query: ListQuery,
+    sorts: [WorkoutSort; 3],
+    /// The filters and sort as a query string, for the paging links.
+    filter_params: String,
+    prev: Option<String>,
+    next: Option<String>,
 }
 
 #[derive(Template)]
@@ -30,6 +48,9 @@ struct WorkoutsListTemplate {
 struct NewWorkoutTemplate {
     user: AuthUser,
     today: NaiveDate,
+    tags: Vec<WorkoutTag>,
+    /// Ids of the ticked tags (none on a new workout).
+    selected_tags: Vec<String>,
     error: Option<String>,
 }
 
@@ -40,10 +61,17 @@ struct ShowWorkoutTemplate {
     workout: WorkoutSession,
     logs: Vec<WorkoutLogWithExercise>,
     exercises: Vec<Exercise>,
-    categories: &'static [ExerciseCategory],
+    categories: Vec<ExerciseCategory>,
     exercise_last_weights: Vec<LastExerciseWeight>,
     share_url: Option<String>,
     share_expires_at: Option<DateTime<Utc>>,
+    tags: Vec<WorkoutTag>,
+    /// The last earlier workout with the same exercises, to compare with.
+    previous_comparable: Option<WorkoutSession>,
+    /// The owner's and their coaches' comments.
+    comments: Vec<WorkoutComment>,
+    /// Always true for the owner; read by the shared comments partial.
+    can_comment: bool,
     error: Option<String>,
 }
 
@@ -53,6 +81,27 @@ struct SharedWorkoutTemplate {
     workout: WorkoutSession,
     logs: Vec<WorkoutLogWithExercise>,
     owner_username: String,
+    token: String,
+    /// (id, name) of each exercise in the workout, for its chart links;
+    /// empty unless the owner shared their progress history.
+    chart_exercises: Vec<(String, String)>,
+    /// Absolute URLs of the page and its preview card, for the `og:` and
+    /// `twitter:` tags.
+    page_url: String,
+    card_url: String,
+    summary: String,
+}
+
+#[derive(Template)]
+#[template(path = "workouts/card.svg")]
+struct ShareCardTemplate {
+    width: u32,
+    height: u32,
+    date: String,
+    owner: String,
+    lines: Vec<CardLine>,
+    /// Exercises left off the card.
+    more: usize,
 }
 
 #[derive(Template)]
@@ -60,6 +109,8 @@ struct SharedWorkoutTemplate {
 struct EditWorkoutTemplate {
     user: AuthUser,
     workout: WorkoutSession,
+    tags: Vec<WorkoutTag>,
+    selected_tags: Vec<String>,
     error: Option<String>,
 }
 
@@ -70,64 +121,277 @@ struct EditLogTemplate {
     workout: WorkoutSession,
     log: WorkoutLog,
     exercise_name: String,
+    unilateral: bool,
     error: Option<String>,
 }
 
-#[derive(Deserialize)]
+/// Workout list query string. Every filter is kept as entered so the form
+/// can be redrawn with it; blank values mean "not filtered".
+#[derive(Deserialize, Default)]
+#[serde(default)]
 pub struct ListQuery {
-    page: Option<i64>,
+    from: String,
+    to: String,
+    exercise: String,
+    q: String,
+    tag: String,
+    sort: String,
+    after: String,
+    before: String,
 }
 
+impl ListQuery {
+    fn filter(&self) -> Result<WorkoutFilter> {
+        let date = |value: &str| -> Result<Option<NaiveDate>> {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(None);
+            }
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_err| AppError::BadRequest(format!("Invalid date: {value}")))
+        };
+        let text = |value: &str| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        let filter = WorkoutFilter {
+            from: date(&self.from)?,
+            to: date(&self.to)?,
+            exercise_id: text(&self.exercise),
+            text: text(&self.q),
+            tag: text(&self.tag),
+        };
+        if let (Some(from), Some(to)) = (filter.from, filter.to)
+            && from > to
+        {
+            return Err(AppError::BadRequest(
+                "The start date must be on or before the end date".to_string(),
+            ));
+        }
+        Ok(filter)
+    }
+
+    fn sort(&self) -> Result<WorkoutSort> {
+        if self.sort.is_empty() {
+            return Ok(WorkoutSort::default());
+        }
+        WorkoutSort::from_param(&self.sort)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown sort order: {}", self.sort)))
+    }
+
+    fn cursor(&self, sort: WorkoutSort) -> Result<Option<PageCursor>> {
+        let parse = |value: &str| {
+            WorkoutCursor::parse(value, sort)
+                .ok_or_else(|| AppError::BadRequest("Invalid page position".to_string()))
+        };
+        if !self.after.is_empty() {
+            Ok(Some(PageCursor::After(parse(&self.after)?)))
+        } else if !self.before.is_empty() {
+            Ok(Some(PageCursor::Before(parse(&self.before)?)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// The non-blank filters and sort, encoded for a link.
+    fn filter_params(&self) -> String {
+        [
+            ("from", &self.from),
+            ("to", &self.to),
+            ("exercise", &self.exercise),
+            ("q", &self.q),
+            ("tag", &self.tag),
+            ("sort", &self.sort),
+        ]
+        .iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(name, value)| format!("{name}={}", encode_query_value(value.trim())))
+        .collect::<Vec<_>>()
+        .join("&")
+    }
+}
+
+/// Workouts per page of the list.
+const LIST_PAGE_SIZE: i64 = 10;
+
+/// The workout list: filterable by date range, exercise and notes, sortable,
+/// and paged by cursor. All of it lives in the query string so a filtered
+/// view can be bookmarked.
 pub async fn list(
     State(state): State<AppState>,
     auth_user: AuthUser,
     Query(query): Query<ListQuery>,
 ) -> Result<Response> {
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = 10;
-    let offset = (page - 1) * per_page;
+    let filter = query.filter()?;
+    let sort = query.sort()?;
+    let cursor = query.cursor(sort)?;
 
-    let workouts = state
+    let mut page = state
         .workout_repo
-        .find_sessions_by_user_paginated(&auth_user.id, per_page, offset)
+        .find_sessions_page(&auth_user.id, filter.clone(), sort, cursor, LIST_PAGE_SIZE)
         .await?;
-
+    let mut tag_names = state
+        .tag_repo
+        .find_names_for_sessions(page.workouts.iter().map(|w| w.session.id.clone()).collect())
+        .await?;
+    for workout in &mut page.workouts {
+        workout.tags = tag_names.remove(&workout.session.id).unwrap_or_default();
+    }
+    let filtered = !filter.is_empty();
     let total = state
         .workout_repo
-        .count_sessions_by_user(&auth_user.id)
+        .count_sessions_filtered(&auth_user.id, filter)
         .await?;
-    let total_pages = (total + per_page - 1) / per_page;
+    let exercises = state
+        .exercise_repo
+        .find_available_for_user(&auth_user.id)
+        .await?;
+    let tags = state.tag_repo.find_by_user(&auth_user.id).await?;
 
+    let filter_params = query.filter_params();
     let template = WorkoutsListTemplate {
         user: auth_user,
-        workouts,
-        page,
-        total_pages,
+        workouts: page.workouts,
+        total,
+        filtered,
+        exercises,
+        tags,
+        query,
+        sorts: WorkoutSort::ALL,
+        filter_params,
+        prev: page.prev.map(|c| encode_query_value(&c.to_param())),
+        next: page.next.map(|c| encode_query_value(&c.to_param())),
+    };
+
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Most matches shown on the set search page.
+const SEARCH_LIMIT: i64 = 100;
+
+#[derive(Template)]
+#[template(path = "workouts/search.html")]
+struct SearchTemplate {
+    user: AuthUser,
+    query: String,
+    results: Vec<SetNoteMatch>,
+}
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    q: String,
+}
+
+/// Find sets by their notes or tempo, e.g. every set marked "pause" or
+/// "shoulder".
+pub async fn search(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<SearchQuery>,
+) -> Result<Response> {
+    let query = query.q.trim().to_string();
+    let results = if query.is_empty() {
+        Vec::new()
+    } else {
+        state
+            .workout_repo
+            .search_set_notes(&auth_user.id, &query, SEARCH_LIMIT)
+            .await?
+    };
+
+    let template = SearchTemplate {
+        user: auth_user,
+        query,
+        results,
     };
 
     Ok(Html(template.render()?).into_response())
 }
 
-pub async fn new_page(auth_user: AuthUser) -> Result<Response> {
-    let today = chrono::Local::now().date_naive();
+#[derive(Template)]
+#[template(path = "workouts/calendar.html")]
+struct CalendarTemplate {
+    user: AuthUser,
+    calendar: MonthCalendar,
+}
+
+#[derive(Deserialize)]
+pub struct CalendarQuery {
+    /// `YYYY-MM`; the current month when blank.
+    #[serde(default)]
+    month: String,
+}
+
+/// A month of workouts, shaded by volume.
+pub async fn calendar(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<CalendarQuery>,
+) -> Result<Response> {
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
+    let today = preferences.today();
+    let month = query.month.trim();
+    let month = if month.is_empty() {
+        month_start(today)
+    } else {
+        parse_month(month).ok_or_else(|| AppError::BadRequest(format!("Invalid month: {month}")))?
+    };
+    let (from, to) = month_grid_range(month, preferences.week_start);
+    let days = state
+        .workout_repo
+        .get_daily_volume(&auth_user.id, from, to)
+        .await?;
+
+    let template = CalendarTemplate {
+        user: auth_user,
+        calendar: month_calendar(month, &days, today, preferences.week_start),
+    };
+
+    Ok(Html(template.render()?).into_response())
+}
+
+pub async fn new_page(State(state): State<AppState>, auth_user: AuthUser) -> Result<Response> {
+    let today = state
+        .user_repo
+        .get_preferences(&auth_user.id)
+        .await?
+        .today();
+    let tags = state.tag_repo.find_by_user(&auth_user.id).await?;
 
     let template = NewWorkoutTemplate {
         user: auth_user,
         today,
+        tags,
+        selected_tags: Vec::new(),
         error: None,
     };
 
     Ok(Html(template.render()?).into_response())
 }
 
+/// The tag picker's selection, with any new names validated.
+fn picked_tags(fields: &HashMap<String, String>) -> Result<(Vec<String>, Vec<String>)> {
+    let (ids, names) = tag_selection(fields);
+    if let Some(error) = names.iter().find_map(|name| tag_name_error(name)) {
+        return Err(AppError::BadRequest(error));
+    }
+    Ok((ids, names))
+}
+
 pub async fn create(
     State(state): State<AppState>,
     auth_user: AuthUser,
     Form(form): Form<CreateWorkoutSession>,
 ) -> Result<Response> {
+    let (tag_ids, new_tags) = picked_tags(&form.fields)?;
     let workout = state
         .workout_repo
-        .create_session(&auth_user.id, form.date, form.notes.as_deref())
+        .create_session_with_tags(
+            &auth_user.id,
+            form.date,
+            form.notes.as_deref(),
+            tag_ids,
+            new_tags,
+        )
         .await?;
 
     Ok(Redirect::to(&format!("/workouts/{}", workout.id)).into_response())
@@ -142,15 +406,20 @@ pub async fn show(
         .workout_repo
         .find_owned_session(&id, &auth_user.id)
         .await?;
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
 
     let logs = state
         .workout_repo
-        .find_logs_by_session_with_pr(&id, &auth_user.id, recent_pr_window_start())
+        .find_logs_by_session_with_pr(&id, &auth_user.id, preferences.pr_window_start())
         .await?;
     let exercises = state
         .exercise_repo
         .find_available_for_user(&auth_user.id)
         .await?;
+    let categories = with_missing_categories(
+        state.category_repo.find_by_user(&auth_user.id).await?,
+        &exercises,
+    );
     let exercise_last_weights = state
         .workout_repo
         .get_last_weight_per_exercise_by_user(&auth_user.id)
@@ -161,22 +430,83 @@ pub async fn show(
         .as_ref()
         .map(|token| format!("/shared/{token}"));
     let share_expires_at = workout.share_expires_at;
+    let tags = state.tag_repo.find_for_session(&workout.id).await?;
+    let previous_comparable = state
+        .workout_repo
+        .find_previous_session_with_same_exercises(&auth_user.id, &workout)
+        .await?;
+    let comments = state.comment_repo.find_comments(&workout.id).await?;
 
     let template = ShowWorkoutTemplate {
         user: auth_user,
         workout,
         logs,
         exercises,
-        categories: CATEGORIES,
+        categories,
         exercise_last_weights,
         share_url,
         share_expires_at,
+        tags,
+        previous_comparable,
+        comments,
+        can_comment: true,
         error: None,
     };
 
     Ok(Html(template.render()?).into_response())
 }
 
+#[derive(Template)]
+#[template(path = "workouts/compare.html")]
+struct CompareTemplate {
+    user: AuthUser,
+    a: WorkoutSession,
+    b: WorkoutSession,
+    exercises: Vec<ExerciseComparison>,
+}
+
+#[derive(Deserialize)]
+pub struct CompareQuery {
+    a: String,
+    b: String,
+}
+
+/// Two of the user's workouts side by side, set by set. Differences are
+/// `b` minus `a`.
+pub async fn compare(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<CompareQuery>,
+) -> Result<Response> {
+    let a = state
+        .workout_repo
+        .find_owned_session(&query.a, &auth_user.id)
+        .await?;
+    let b = state
+        .workout_repo
+        .find_owned_session(&query.b, &auth_user.id)
+        .await?;
+    let exercises = compare_workouts(
+        &state
+            .workout_repo
+            .find_logs_by_session_for_share(&a.id)
+            .await?,
+        &state
+            .workout_repo
+            .find_logs_by_session_for_share(&b.id)
+            .await?,
+    );
+
+    let template = CompareTemplate {
+        user: auth_user,
+        a,
+        b,
+        exercises,
+    };
+
+    Ok(Html(template.render()?).into_response())
+}
+
 pub async fn edit_page(
     State(state): State<AppState>,
     auth_user: AuthUser,
@@ -187,9 +517,20 @@ pub async fn edit_page(
         .find_owned_session(&id, &auth_user.id)
         .await?;
 
+    let tags = state.tag_repo.find_by_user(&auth_user.id).await?;
+    let selected_tags = state
+        .tag_repo
+        .find_for_session(&workout.id)
+        .await?
+        .into_iter()
+        .map(|tag| tag.id)
+        .collect();
+
     let template = EditWorkoutTemplate {
         user: auth_user,
         workout,
+        tags,
+        selected_tags,
         error: None,
     };
 
@@ -200,6 +541,9 @@ pub async fn edit_page(
 pub struct UpdateWorkoutForm {
     pub date: NaiveDate,
     pub notes: Option<String>,
+    /// Tag picker fields, as on `CreateWorkoutSession`.
+    #[serde(flatten)]
+    pub fields: HashMap<String, String>,
 }
 
 pub async fn update(
@@ -208,9 +552,17 @@ pub async fn update(
     Path(id): Path<String>,
     Form(form): Form<UpdateWorkoutForm>,
 ) -> Result<Response> {
+    let (tag_ids, new_tags) = picked_tags(&form.fields)?;
     state
         .workout_repo
-        .update_session(&id, &auth_user.id, Some(form.date), form.notes.as_deref())
+        .update_session_with_tags(
+            &id,
+            &auth_user.id,
+            form.date,
+            form.notes.as_deref(),
+            tag_ids,
+            new_tags,
+        )
         .await?;
 
     Ok(Redirect::to(&format!("/workouts/{id}")).into_response())
@@ -242,10 +594,15 @@ pub async fn add_log(
     // `exercise_id` arrives from the form body, so owning the session is not
     // enough — without this a caller could attach a log to another user's
     // exercise, which the UI's own <select> would never offer.
-    state
+    let exercise = state
         .exercise_repo
-        .find_owned(&form.exercise_id, &auth_user.id)
+        .find_usable(&form.exercise_id, &auth_user.id)
         .await?;
+    if let Some(error) =
+        set_annotation_error(form.notes.as_deref(), form.tempo.as_deref(), form.rir)
+    {
+        return Err(AppError::BadRequest(error));
+    }
 
     let set_number = state
         .workout_repo
@@ -258,15 +615,103 @@ pub async fn add_log(
             &session_id,
             &form.exercise_id,
             set_number,
-            form.reps,
-            form.weight,
-            form.rpe,
+            &SetValues::from_form(
+                exercise.unilateral,
+                form.reps,
+                form.weight,
+                form.rpe,
+                form.right_reps,
+                form.right_weight,
+            )
+            .with_annotations(form.notes.as_deref(), form.tempo.as_deref(), form.rir),
         )
         .await?;
 
     Ok(Redirect::to(&format!("/workouts/{session_id}")).into_response())
 }
 
+#[derive(Template)]
+#[template(path = "workouts/quick_entry.html")]
+struct QuickEntryTemplate {
+    user: AuthUser,
+    workout: WorkoutSession,
+    text: String,
+    entry: QuickEntry,
+}
+
+#[derive(Deserialize)]
+pub struct QuickEntryForm {
+    #[serde(default)]
+    pub text: String,
+}
+
+/// Parse a quick entry against the user's exercises.
+async fn parse_quick_entry(state: &AppState, user_id: &str, text: &str) -> Result<QuickEntry> {
+    let exercises = state.exercise_repo.find_available_for_user(user_id).await?;
+    Ok(quick_entry::parse(text, &exercises))
+}
+
+/// Show what a quick entry would log, with any lines that did not parse.
+pub async fn quick_entry_preview(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(session_id): Path<String>,
+    Form(form): Form<QuickEntryForm>,
+) -> Result<Response> {
+    let workout = state
+        .workout_repo
+        .find_owned_session(&session_id, &auth_user.id)
+        .await?;
+    let entry = parse_quick_entry(&state, &auth_user.id, &form.text).await?;
+
+    let template = QuickEntryTemplate {
+        user: auth_user,
+        workout,
+        text: form.text,
+        entry,
+    };
+
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Log every set of a quick entry in one transaction. The text is parsed
+/// again rather than trusting the preview, and nothing is logged unless
+/// every line parses.
+pub async fn quick_entry_add(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(session_id): Path<String>,
+    Form(form): Form<QuickEntryForm>,
+) -> Result<Response> {
+    let workout = state
+        .workout_repo
+        .find_owned_session(&session_id, &auth_user.id)
+        .await?;
+    let entry = parse_quick_entry(&state, &auth_user.id, &form.text).await?;
+
+    if !entry.is_valid() {
+        let template = QuickEntryTemplate {
+            user: auth_user,
+            workout,
+            text: form.text,
+            entry,
+        };
+        return Ok(Html(template.render()?).into_response());
+    }
+
+    let sets = entry
+        .sets
+        .into_iter()
+        .map(|set| {
+            let values = set.values();
+            (set.exercise_id, values)
+        })
+        .collect();
+    state.workout_repo.create_logs(&session_id, sets).await?;
+
+    Ok(Redirect::to(&format!("/workouts/{session_id}")).into_response())
+}
+
 pub async fn delete_log(
     State(state): State<AppState>,
     auth_user: AuthUser,
@@ -313,6 +758,7 @@ pub async fn edit_log_page(
         workout: session,
         log,
         exercise_name: exercise.name,
+        unilateral: exercise.unilateral,
         error: None,
     };
 
@@ -330,9 +776,38 @@ pub async fn update_log(
         .find_owned_session(&session_id, &auth_user.id)
         .await?;
 
+    let log = state
+        .workout_repo
+        .find_log_by_id(&log_id)
+        .await?
+        .filter(|log| log.session_id == session_id)
+        .ok_or_else(|| AppError::NotFound("Log not found".to_string()))?;
+    if let Some(error) =
+        set_annotation_error(form.notes.as_deref(), form.tempo.as_deref(), form.rir)
+    {
+        return Err(AppError::BadRequest(error));
+    }
+    let unilateral = state
+        .exercise_repo
+        .find_by_id(&log.exercise_id)
+        .await?
+        .is_some_and(|e| e.unilateral);
+
     state
         .workout_repo
-        .update_log(&log_id, &session_id, form.reps, form.weight, form.rpe)
+        .update_log(
+            &log_id,
+            &session_id,
+            &SetValues::from_form(
+                unilateral,
+                form.reps,
+                form.weight,
+                form.rpe,
+                form.right_reps,
+                form.right_weight,
+            )
+            .with_annotations(form.notes.as_deref(), form.tempo.as_deref(), form.rir),
+        )
         .await?;
 
     Ok(Redirect::to(&format!("/workouts/{session_id}")).into_response())
@@ -366,19 +841,38 @@ pub struct ShareForm {
     pub expires_in_days: Option<i64>,
 }
 
+impl ShareForm {
+    /// How long the link stays valid; `None` for never. Shared by every
+    /// share form, so workout and year review links have the same limits.
+    pub fn ttl(&self) -> Result<Option<chrono::Duration>> {
+        match self.expires_in_days {
+            Some(days) if !(1..=365).contains(&days) => Err(AppError::BadRequest(
+                "Share link expiry must be between 1 and 365 days".to_string(),
+            )),
+            days => Ok(days.map(chrono::Duration::days)),
+        }
+    }
+}
+
+/// The workout share form: `ShareForm`'s expiry plus the checkbox that
+/// lets the link serve progress charts of earlier sessions.
+#[derive(Deserialize)]
+pub struct ShareWorkoutForm {
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    expires_in_days: Option<i64>,
+    include_history: Option<String>,
+}
+
 pub async fn share_workout(
     State(state): State<AppState>,
     auth_user: AuthUser,
     Path(id): Path<String>,
-    Form(form): Form<ShareForm>,
+    Form(form): Form<ShareWorkoutForm>,
 ) -> Result<Response> {
-    if let Some(days) = form.expires_in_days
-        && !(1..=365).contains(&days)
-    {
-        return Err(AppError::BadRequest(
-            "Share link expiry must be between 1 and 365 days".to_string(),
-        ));
+    let ttl = ShareForm {
+        expires_in_days: form.expires_in_days,
     }
+    .ttl()?;
 
     state
         .workout_repo
@@ -387,11 +881,7 @@ pub async fn share_workout(
 
     state
         .workout_repo
-        .set_share_token(
-            &id,
-            &auth_user.id,
-            form.expires_in_days.map(chrono::Duration::days),
-        )
+        .set_share_token(&id, &auth_user.id, ttl, form.include_history.is_some())
         .await?;
 
     Ok(Redirect::to(&format!("/workouts/{id}")).into_response())
@@ -415,10 +905,73 @@ pub async fn revoke_share(
     Ok(Redirect::to(&format!("/workouts/{id}")).into_response())
 }
 
+#[derive(Deserialize)]
+pub struct TextFormatQuery {
+    format: Option<String>,
+}
+
+/// A `?format=` that names no known format is a bad link, not a reason to
+/// fall back silently.
+fn requested_format(query: &TextFormatQuery) -> Result<Option<TextFormat>> {
+    query
+        .format
+        .as_deref()
+        .map(|f| {
+            TextFormat::from_param(f)
+                .ok_or_else(|| AppError::BadRequest(format!("Unknown format \"{f}\"")))
+        })
+        .transpose()
+}
+
+fn text_response(body: String, format: TextFormat) -> Response {
+    ([(header::CONTENT_TYPE, format.content_type())], body).into_response()
+}
+
+/// Download the workout as plain text (`?format=txt`, the default) or
+/// Markdown (`?format=md`), with PR markers as on the workout page.
+pub async fn export(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<TextFormatQuery>,
+) -> Result<Response> {
+    let format = requested_format(&query)?.unwrap_or(TextFormat::Plain);
+    let workout = state
+        .workout_repo
+        .find_owned_session(&id, &auth_user.id)
+        .await?;
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
+    let logs = state
+        .workout_repo
+        .find_logs_by_session_with_pr(&id, &auth_user.id, preferences.pr_window_start())
+        .await?;
+
+    let body = workout_text::render(&workout, &logs, None, format);
+    let disposition = format!(
+        "attachment; filename=\"workout-{}.{}\"",
+        workout.date,
+        format.extension()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// The shared page, or its text rendering when asked for with `?format=` or
+/// an `Accept` header naming `text/plain` or `text/markdown`. The text
+/// carries the same sets as the page, so no PR markers.
 pub async fn view_shared(
     State(state): State<AppState>,
     Path(token): Path<String>,
+    Query(query): Query<TextFormatQuery>,
+    headers: HeaderMap,
 ) -> Result<Response> {
+    let format = requested_format(&query)?.or_else(|| TextFormat::from_accept(&headers));
     let workout = state
         .workout_repo
         .find_session_by_share_token(&token)
@@ -436,13 +989,99 @@ pub async fn view_shared(
         .await?
         .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
-    let template = SharedWorkoutTemplate {
-        workout,
-        logs,
-        owner_username: owner.username,
+    let mut response = if let Some(format) = format {
+        let body = workout_text::render(&workout, &logs, Some(&owner.username), format);
+        text_response(body, format)
+    } else {
+        let mut chart_exercises: Vec<(String, String)> = Vec::new();
+        if workout.share_history {
+            for log in logs.iter().rev() {
+                if !chart_exercises.iter().any(|(id, _)| *id == log.exercise_id) {
+                    chart_exercises.push((log.exercise_id.clone(), log.exercise_name.clone()));
+                }
+            }
+        }
+        let origin = origin(&state, &headers);
+        let summary = share_card::summary(&logs);
+        let template = SharedWorkoutTemplate {
+            workout,
+            logs,
+            owner_username: owner.username,
+            page_url: format!("{origin}/shared/{token}"),
+            card_url: format!("{origin}/shared/{token}/card.png"),
+            summary,
+            token,
+            chart_exercises,
+        };
+        Html(template.render()?).into_response()
     };
+    response
+        .headers_mut()
+        .insert(header::VARY, HeaderValue::from_static("Accept"));
 
-    Ok(Html(template.render()?).into_response())
+    Ok(response)
+}
+
+/// The shared workout's preview card as a PNG, for link previews. Like the
+/// page it is only there while the link is live, and revalidated on every
+/// fetch so an expired or revoked link stops previewing.
+pub async fn shared_card(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Response> {
+    let workout = state
+        .workout_repo
+        .find_session_by_share_token(&token)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Shared workout not found".to_string()))?;
+    let owner = state
+        .user_repo
+        .find_by_id(&workout.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    let preferences = state.user_repo.get_preferences(&owner.id).await?;
+    let logs = state
+        .workout_repo
+        .find_logs_by_session_with_pr(&workout.id, &owner.id, preferences.pr_window_start())
+        .await?;
+
+    let mut lines = card_lines(&logs);
+    let more = lines.len().saturating_sub(MAX_LINES);
+    lines.truncate(MAX_LINES);
+    let template = ShareCardTemplate {
+        width: CARD_WIDTH,
+        height: CARD_HEIGHT,
+        date: workout.date.format("%A %-d %B %Y").to_string(),
+        owner: owner.username,
+        lines,
+        more,
+    };
+    let png = state.share_card_cache.render(template.render()?).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/png"),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        png.to_vec(),
+    )
+        .into_response())
+}
+
+/// `scheme://host` of the site, for the absolute URLs link previews need:
+/// `LIFTLOG_PUBLIC_URL` when configured, since the `Host` header is the
+/// client's to choose. Otherwise this request's `Host`, with the scheme
+/// following `cookie_secure`, which is on exactly when the site is served
+/// over HTTPS; empty without a `Host` header.
+fn origin(state: &AppState, headers: &HeaderMap) -> String {
+    if let Some(public_url) = &state.public_url {
+        return public_url.to_string();
+    }
+    let scheme = if state.cookie_secure { "https" } else { "http" };
+    headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .map(|host| format!("{scheme}://{host}"))
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -0,0 +1,159 @@
+use askama::Template;
+use axum::{
+    Form,
+    extract::{Path, Query, State},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use chrono::{DateTime, Datelike, Utc};
+use serde::Deserialize;
+
+use crate::error::{AppError, Result};
+use crate::handlers::workouts::ShareForm;
+use crate::middleware::AuthUser;
+use crate::models::UserPreferences;
+use crate::state::AppState;
+use crate::year_review::{YearReview, YearReviewData, year_range, year_review};
+
+#[derive(Template)]
+#[template(path = "stats/year.html")]
+struct YearReviewTemplate {
+    user: AuthUser,
+    review: YearReview,
+    /// The following year, unless it has not started yet.
+    next_year: Option<i32>,
+    share_url: Option<String>,
+    share_expires_at: Option<DateTime<Utc>>,
+    /// Link exercises to their stats pages; off on the public page.
+    links: bool,
+}
+
+#[derive(Template)]
+#[template(path = "stats/year_shared.html")]
+struct SharedYearReviewTemplate {
+    review: YearReview,
+    owner_username: String,
+    links: bool,
+}
+
+#[derive(Deserialize)]
+pub struct YearQuery {
+    /// The current year when absent.
+    year: Option<i32>,
+}
+
+/// The review of `year` for `user_id`, with streaks against their current
+/// weekly target and in their week, from `preferences`.
+async fn load_year_review(
+    state: &AppState,
+    user_id: &str,
+    preferences: &UserPreferences,
+    year: i32,
+) -> Result<YearReview> {
+    let (from, to) =
+        year_range(year).ok_or_else(|| AppError::BadRequest(format!("Invalid year: {year}")))?;
+    let repo = &state.workout_repo;
+    let data = YearReviewData {
+        summary: repo.get_period_summary(user_id, from, to).await?,
+        exercises: repo.get_exercise_totals_between(user_id, from, to).await?,
+        pr_gains: repo.get_pr_gains_between(user_id, from, to).await?,
+        months: repo.get_monthly_totals_between(user_id, from, to).await?,
+        weeks: repo
+            .get_weekly_session_counts(user_id, to, preferences.week_start)
+            .await?,
+        streak_target: preferences.weekly_session_target,
+    };
+    Ok(year_review(year, data))
+}
+
+/// Only years in `calendar::YEARS` that have started can be reviewed or
+/// shared. Returns the year and the current one.
+fn checked_year(preferences: &UserPreferences, year: Option<i32>) -> Result<(i32, i32)> {
+    let current = preferences.today().year();
+    let year = year.unwrap_or(current);
+    if year > current || year_range(year).is_none() {
+        return Err(AppError::BadRequest(format!("Invalid year: {year}")));
+    }
+    Ok((year, current))
+}
+
+pub async fn index(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<YearQuery>,
+) -> Result<Response> {
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
+    let (year, current) = checked_year(&preferences, query.year)?;
+    let review = load_year_review(&state, &auth_user.id, &preferences, year).await?;
+    let share = state
+        .share_repo
+        .find_year_share(&auth_user.id, year)
+        .await?;
+
+    let template = YearReviewTemplate {
+        user: auth_user,
+        review,
+        next_year: (year < current).then_some(year + 1),
+        share_url: share
+            .as_ref()
+            .map(|share| format!("/shared/year/{}", share.token)),
+        share_expires_at: share.and_then(|share| share.expires_at),
+        links: true,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+pub async fn share(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(year): Path<i32>,
+    Form(form): Form<ShareForm>,
+) -> Result<Response> {
+    let ttl = form.ttl()?;
+    let preferences = state.user_repo.get_preferences(&auth_user.id).await?;
+    let (year, _) = checked_year(&preferences, Some(year))?;
+    state
+        .share_repo
+        .set_year_share_token(&auth_user.id, year, ttl)
+        .await?;
+    Ok(Redirect::to(&format!("/stats/year?year={year}")).into_response())
+}
+
+pub async fn revoke_share(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(year): Path<i32>,
+) -> Result<Response> {
+    state
+        .share_repo
+        .revoke_year_share_token(&auth_user.id, year)
+        .await?;
+    Ok(Redirect::to(&format!("/stats/year?year={year}")).into_response())
+}
+
+/// The public copy of a review. Expired and unknown tokens are both 404, as
+/// for shared workouts.
+pub async fn view_shared(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Response> {
+    let not_found = || AppError::NotFound("Shared review not found".to_string());
+    let share = state
+        .share_repo
+        .find_year_share_by_token(&token)
+        .await?
+        .ok_or_else(not_found)?;
+    let owner = state
+        .user_repo
+        .find_by_id(&share.user_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let preferences = state.user_repo.get_preferences(&share.user_id).await?;
+    let review = load_year_review(&state, &share.user_id, &preferences, share.year).await?;
+
+    let template = SharedYearReviewTemplate {
+        review,
+        owner_username: owner.username,
+        links: false,
+    };
+    Ok(Html(template.render()?).into_response())
+}
@@ -1,5 +1,9 @@
 pub mod audit;
+pub mod calendar;
+pub mod catalog;
+pub mod chart_image;
 pub mod config;
+pub mod consistency;
 pub mod db;
 pub mod error;
 pub mod handlers;
@@ -7,9 +11,17 @@ pub mod middleware;
 pub mod migrations;
 pub mod models;
 pub mod net;
+pub mod progress_chart;
+pub mod quick_entry;
 pub mod rate_limit;
+pub mod report;
 pub mod repositories;
 pub mod routes;
 pub mod session;
+pub mod share_card;
 pub mod state;
+pub mod text_import;
 pub mod version;
+pub mod workout_compare;
+pub mod workout_text;
+pub mod year_review;
@@ -5,7 +5,11 @@ use tracing_subscriber::{
 };
 
 mod audit;
+mod calendar;
+mod catalog;
+mod chart_image;
 mod config;
+mod consistency;
 mod db;
 mod error;
 mod handlers;
@@ -13,18 +17,30 @@ mod middleware;
 mod migrations;
 mod models;
 mod net;
+mod progress_chart;
+mod quick_entry;
 mod rate_limit;
+mod report;
 mod repositories;
 mod routes;
 mod session;
+mod share_card;
 mod state;
+mod text_import;
 mod version;
+mod workout_compare;
+mod workout_text;
+mod year_review;
 
+use chart_image::PngCache;
 use config::Config;
 use migrations::run_migrations;
 use rand_core::RngCore;
 use rate_limit::{FailureBackoff, RateLimiter};
-use repositories::{ExerciseRepository, SessionRepository, UserRepository, WorkoutRepository};
+use repositories::{
+    CategoryRepository, CommentRepository, ExerciseRepository, SessionRepository, ShareRepository,
+    TagRepository, UserRepository, WorkoutRepository,
+};
 use state::AppState;
 use std::sync::Arc;
 use std::time::Duration;
@@ -92,7 +108,11 @@ async fn main() -> anyhow::Result<()> {
 
     let user_repo = UserRepository::new(pool.clone());
     let exercise_repo = ExerciseRepository::new(pool.clone());
+    let category_repo = CategoryRepository::new(pool.clone());
+    let tag_repo = TagRepository::new(pool.clone());
     let workout_repo = WorkoutRepository::new(pool.clone());
+    let share_repo = ShareRepository::new(pool.clone());
+    let comment_repo = CommentRepository::new(pool.clone());
     let session_repo = SessionRepository::new(pool.clone());
 
     // Broadcasts the shutdown request to the background sweep so it can stop
@@ -104,9 +124,10 @@ async fn main() -> anyhow::Result<()> {
     // revisited) need this sweep to avoid unbounded table growth.
     let sweep_handle = {
         let session_repo = session_repo.clone();
-        // Cloned here (not moved) because `workout_repo` is also captured by
-        // value in `app_state` below.
+        // Cloned here (not moved) because `workout_repo` and `share_repo` are
+        // also captured by value in `app_state` below.
         let workout_repo = workout_repo.clone();
+        let share_repo = share_repo.clone();
         tokio::spawn(async move {
             let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60 * 60));
             ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
@@ -137,6 +158,13 @@ async fn main() -> anyhow::Result<()> {
                                 tracing::warn!(error = ?e, "workout cleanup_expired_share_tokens failed");
                             }
                         }
+                        match share_repo.cleanup_expired_share_tokens().await {
+                            Ok(0) => {}
+                            Ok(n) => tracing::info!(count = n, "cleared expired review, block and profile share links"),
+                            Err(e) => {
+                                tracing::warn!(error = ?e, "share cleanup_expired_share_tokens failed");
+                            }
+                        }
                     }
                     _ = shutdown_rx.changed() => break,
                 }
@@ -174,7 +202,11 @@ async fn main() -> anyhow::Result<()> {
     let app_state = AppState {
         user_repo,
         exercise_repo,
+        category_repo,
+        tag_repo,
         workout_repo,
+        share_repo,
+        comment_repo,
         session_repo,
         login_rate_limiter: Arc::new(RateLimiter::new(5, Duration::from_secs(60))),
         login_backoff: Arc::new(FailureBackoff::for_login()),
@@ -184,7 +216,9 @@ async fn main() -> anyhow::Result<()> {
         cookie_secure: config.cookie_secure,
         hsts_max_age: config.hsts_max_age,
         hsts_include_subdomains: config.hsts_include_subdomains,
+        public_url: config.public_url.as_deref().map(Into::into),
         log_salt: Arc::new(log_salt),
+        share_card_cache: Arc::new(PngCache::new(64)),
     };
 
     // Build router
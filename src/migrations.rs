@@ -47,6 +47,70 @@ pub const MIGRATIONS: &[(&str, &str)] = &[
         "012_add_workout_share_expires_at.sql",
         include_str!("../migrations/012_add_workout_share_expires_at.sql"),
     ),
+    (
+        "013_create_exercise_muscles.sql",
+        include_str!("../migrations/013_create_exercise_muscles.sql"),
+    ),
+    (
+        "014_create_exercise_categories.sql",
+        include_str!("../migrations/014_create_exercise_categories.sql"),
+    ),
+    (
+        "015_add_exercise_catalog_id.sql",
+        include_str!("../migrations/015_add_exercise_catalog_id.sql"),
+    ),
+    (
+        "016_allow_global_exercises.sql",
+        include_str!("../migrations/016_allow_global_exercises.sql"),
+    ),
+    (
+        "017_add_exercise_archived_at.sql",
+        include_str!("../migrations/017_add_exercise_archived_at.sql"),
+    ),
+    (
+        "018_add_unilateral_sets.sql",
+        include_str!("../migrations/018_add_unilateral_sets.sql"),
+    ),
+    (
+        "019_add_set_annotations.sql",
+        include_str!("../migrations/019_add_set_annotations.sql"),
+    ),
+    (
+        "020_add_workout_list_search.sql",
+        include_str!("../migrations/020_add_workout_list_search.sql"),
+    ),
+    (
+        "021_create_workout_tags.sql",
+        include_str!("../migrations/021_create_workout_tags.sql"),
+    ),
+    (
+        "022_create_user_preferences.sql",
+        include_str!("../migrations/022_create_user_preferences.sql"),
+    ),
+    (
+        "023_add_user_timezone_and_week_start.sql",
+        include_str!("../migrations/023_add_user_timezone_and_week_start.sql"),
+    ),
+    (
+        "024_create_year_review_shares.sql",
+        include_str!("../migrations/024_create_year_review_shares.sql"),
+    ),
+    (
+        "025_create_public_profiles.sql",
+        include_str!("../migrations/025_create_public_profiles.sql"),
+    ),
+    (
+        "026_create_block_shares.sql",
+        include_str!("../migrations/026_create_block_shares.sql"),
+    ),
+    (
+        "027_create_coach_grants.sql",
+        include_str!("../migrations/027_create_coach_grants.sql"),
+    ),
+    (
+        "028_add_workout_share_history.sql",
+        include_str!("../migrations/028_add_workout_share_history.sql"),
+    ),
 ];
 
 /// Run all pending migrations on the database pool.
@@ -416,4 +480,62 @@ mod tests {
             assert_eq!(count, 0, "{table} row {id} should have been cleaned up");
         }
     }
+
+    #[test]
+    fn category_migration_backfills_defaults_and_stray_categories() {
+        let pool = create_memory_pool().expect("memory pool");
+        let conn = pool.get().unwrap();
+
+        let idx = MIGRATIONS
+            .iter()
+            .position(|(name, _)| *name == "014_create_exercise_categories.sql")
+            .expect("014 is registered");
+        for (_filename, sql) in &MIGRATIONS[..idx] {
+            conn.execute_batch(sql).unwrap();
+        }
+
+        conn.execute(
+            "INSERT INTO users (id, username, password_hash, created_at) \
+             VALUES ('u1', 'u1', 'hash', datetime('now'))",
+            [],
+        )
+        .unwrap();
+        // The pre-014 handlers accepted any category string.
+        conn.execute(
+            "INSERT INTO exercises (id, name, category, user_id) \
+             VALUES ('ex1', 'Clean', 'olympic', 'u1'), ('ex2', 'Carry', '', 'u1')",
+            [],
+        )
+        .unwrap();
+
+        conn.execute_batch(MIGRATIONS[idx].1).unwrap();
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT name, display_name FROM exercise_categories \
+                 WHERE user_id = 'u1' ORDER BY position, name",
+            )
+            .unwrap();
+        let categories: Vec<(String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(categories.len(), 8);
+        assert_eq!(categories[0], ("chest".to_string(), "Chest".to_string()));
+        assert_eq!(
+            categories[6],
+            ("olympic".to_string(), "Olympic".to_string())
+        );
+        // A blank category is filed under a real key, never ''.
+        assert_eq!(categories[7], ("other".to_string(), "Other".to_string()));
+        let category: String = conn
+            .query_row(
+                "SELECT category FROM exercises WHERE id = 'ex2'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(category, "other");
+    }
 }
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Months, NaiveDate, Utc};
+use rusqlite::Row;
+
+use super::FromSqliteRow;
+
+/// Most workouts listed, with their sets, on a block's public page. The
+/// totals still cover the whole block.
+pub const MAX_BLOCK_SESSIONS: i64 = 100;
+
+/// The sessions a block share covers: those dated `from` to `to`
+/// inclusive, carrying `tag_id` when set. Without a tag both dates are
+/// required; with one, either may be open. A range spans at most a year.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockScope {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub tag_id: Option<String>,
+}
+
+impl BlockScope {
+    /// The scope the share form asks for, or why it is not usable. Blank
+    /// fields are unset.
+    pub fn parse(from: &str, to: &str, tag_id: &str) -> std::result::Result<Self, String> {
+        let date = |value: &str| {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(None);
+            }
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_err| format!("Invalid date: {value}"))
+        };
+        let from = date(from)?;
+        let to = date(to)?;
+        let tag_id = Some(tag_id.trim().to_string()).filter(|id| !id.is_empty());
+        if tag_id.is_none() && (from.is_none() || to.is_none()) {
+            return Err("Pick a tag, or both ends of the date range".to_string());
+        }
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            return Err("The range must end on or after its start".to_string());
+        }
+        if let (Some(from), Some(to)) = (from, to)
+            && from
+                .checked_add_months(Months::new(12))
+                .is_none_or(|limit| to >= limit)
+        {
+            return Err("The range can span at most a year".to_string());
+        }
+        Ok(Self { from, to, tag_id })
+    }
+}
+
+/// A live link to a block of workouts.
+#[derive(Debug, Clone)]
+pub struct BlockShare {
+    pub id: String,
+    pub user_id: String,
+    pub token: String,
+    pub scope: BlockScope,
+    pub tag_name: Option<String>,
+    /// `None` means the link never expires, as for workout share links.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl BlockShare {
+    /// What the link covers, e.g. "#strength, 2024-01-01 to 2024-03-31".
+    pub fn label(&self) -> String {
+        let range = match (self.scope.from, self.scope.to) {
+            (Some(from), Some(to)) if from == to => Some(from.to_string()),
+            (Some(from), Some(to)) => Some(format!("{from} to {to}")),
+            (Some(from), None) => Some(format!("from {from}")),
+            (None, Some(to)) => Some(format!("up to {to}")),
+            (None, None) => None,
+        };
+        let tag = self.tag_name.as_ref().map(|name| format!("#{name}"));
+        tag.into_iter().chain(range).collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl FromSqliteRow for BlockShare {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            user_id: row.get("user_id")?,
+            token: row.get("token")?,
+            scope: BlockScope {
+                from: row.get("from_date")?,
+                to: row.get("to_date")?,
+                tag_id: row.get("tag_id")?,
+            },
+            tag_name: row.get("tag_name")?,
+            expires_at: row.get("expires_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(2024, 1, d)
+    }
+
+    #[test]
+    fn scope_needs_a_tag_or_a_whole_range() {
+        assert_eq!(
+            BlockScope::parse("2024-01-01", "2024-01-07", " "),
+            Ok(BlockScope {
+                from: date(1),
+                to: date(7),
+                tag_id: None,
+            })
+        );
+        assert_eq!(
+            BlockScope::parse("", "2024-01-07", "t1").unwrap().tag_id,
+            Some("t1".to_string())
+        );
+        assert!(BlockScope::parse("2024-01-01", "", "").is_err());
+        assert!(BlockScope::parse("2024-01-08", "2024-01-07", "t1").is_err());
+        assert!(BlockScope::parse("2024-13-01", "2024-01-07", "").is_err());
+    }
+
+    #[test]
+    fn ranges_span_at_most_a_year() {
+        assert!(BlockScope::parse("2024-01-01", "2024-12-31", "").is_ok());
+        for (from, to) in [
+            ("2024-01-01", "2025-01-01"),
+            ("1990-01-01", "2024-12-31"),
+            ("2024-01-01", "2025-06-01"),
+        ] {
+            assert_eq!(
+                BlockScope::parse(from, to, "t1"),
+                Err("The range can span at most a year".to_string()),
+                "{from} to {to}"
+            );
+        }
+    }
+
+    #[test]
+    fn label_names_the_tag_then_the_range() {
+        let mut share = BlockShare {
+            id: "b".to_string(),
+            user_id: "u".to_string(),
+            token: "t".to_string(),
+            scope: BlockScope {
+                from: date(1),
+                to: date(7),
+                tag_id: Some("t1".to_string()),
+            },
+            tag_name: Some("strength".to_string()),
+            expires_at: None,
+        };
+        assert_eq!(share.label(), "#strength, 2024-01-01 to 2024-01-07");
+        share.scope.from = None;
+        assert_eq!(share.label(), "#strength, up to 2024-01-07");
+        share.tag_name = None;
+        share.scope.from = date(7);
+        assert_eq!(share.label(), "2024-01-07");
+    }
+}
@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use rusqlite::Row;
+
+use super::FromSqliteRow;
+
+/// Longest comment accepted.
+pub const MAX_COMMENT_LEN: usize = 1000;
+
+/// What a user may do with another user's workouts. Owners may do
+/// everything; a coach reads, and comments or programs workouts only when
+/// the athlete allowed it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Owner,
+    Coach {
+        can_comment: bool,
+        can_program: bool,
+    },
+}
+
+impl Access {
+    pub fn is_owner(self) -> bool {
+        self == Self::Owner
+    }
+
+    pub fn can_comment(self) -> bool {
+        match self {
+            Self::Owner => true,
+            Self::Coach { can_comment, .. } => can_comment,
+        }
+    }
+
+    /// May add planned workouts to the athlete's log.
+    pub fn can_program(self) -> bool {
+        match self {
+            Self::Owner => true,
+            Self::Coach { can_program, .. } => can_program,
+        }
+    }
+}
+
+/// An athlete's grant to a coach, with both usernames for listing.
+#[derive(Debug, Clone)]
+pub struct CoachGrant {
+    pub athlete_id: String,
+    pub athlete_username: String,
+    pub coach_id: String,
+    pub coach_username: String,
+    pub can_comment: bool,
+    pub can_program: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CoachGrant {
+    pub fn access(&self) -> Access {
+        Access::Coach {
+            can_comment: self.can_comment,
+            can_program: self.can_program,
+        }
+    }
+}
+
+impl FromSqliteRow for CoachGrant {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            athlete_id: row.get("athlete_id")?,
+            athlete_username: row.get("athlete_username")?,
+            coach_id: row.get("coach_id")?,
+            coach_username: row.get("coach_username")?,
+            can_comment: row.get("can_comment")?,
+            can_program: row.get("can_program")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+/// A comment on a workout, or on one of its sets.
+#[derive(Debug, Clone)]
+pub struct WorkoutComment {
+    pub id: String,
+    /// e.g. "Squat set 2" when the comment is about a set.
+    pub set_label: Option<String>,
+    pub author_id: String,
+    pub author_username: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FromSqliteRow for WorkoutComment {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            set_label: row.get("set_label")?,
+            author_id: row.get("author_id")?,
+            author_username: row.get("author_username")?,
+            body: row.get("body")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+/// Why a comment body cannot be saved, if it cannot.
+pub fn comment_error(body: &str) -> Option<String> {
+    if body.trim().is_empty() {
+        Some("Comment cannot be empty".to_string())
+    } else if body.chars().count() > MAX_COMMENT_LEN {
+        Some(format!(
+            "Comment must be at most {MAX_COMMENT_LEN} characters"
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coaches_only_get_what_was_granted() {
+        assert!(Access::Owner.can_comment() && Access::Owner.can_program());
+        let reader = Access::Coach {
+            can_comment: false,
+            can_program: false,
+        };
+        assert!(!reader.is_owner() && !reader.can_comment() && !reader.can_program());
+        let commenter = Access::Coach {
+            can_comment: true,
+            can_program: false,
+        };
+        assert!(commenter.can_comment() && !commenter.can_program());
+    }
+
+    #[test]
+    fn comments_must_have_text_and_fit() {
+        assert!(comment_error("  ").is_some());
+        assert!(comment_error(&"a".repeat(MAX_COMMENT_LEN + 1)).is_some());
+        assert!(comment_error("Nice depth on set 3").is_none());
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use rusqlite::Row;
 use serde::{Deserialize, Serialize};
 
@@ -8,7 +11,18 @@ pub struct Exercise {
     pub id: String,
     pub name: String,
     pub category: String,
-    pub user_id: String,
+    /// `None` for a global exercise: owned by the instance, edited by admins,
+    /// and visible to every user.
+    pub user_id: Option<String>,
+    /// Id of the built-in catalog entry this exercise was imported from or
+    /// linked to; `None` for exercises the user created by hand.
+    pub catalog_id: Option<String>,
+    /// Set when the exercise was archived: hidden from the pickers, but its
+    /// logs, history and stats are kept.
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Each side is trained separately; sets record left and right reps and
+    /// weight.
+    pub unilateral: bool,
 }
 
 impl FromSqliteRow for Exercise {
@@ -18,51 +32,133 @@ impl FromSqliteRow for Exercise {
             name: row.get("name")?,
             category: row.get("category")?,
             user_id: row.get("user_id")?,
+            catalog_id: row.get("catalog_id")?,
+            archived_at: row.get("archived_at")?,
+            unilateral: row.get("unilateral")?,
         })
     }
 }
 
+impl Exercise {
+    pub fn is_global(&self) -> bool {
+        self.user_id.is_none()
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateExercise {
     pub name: String,
     pub category: String,
+    /// Admin-only "share with everyone" checkbox; ignored for other users.
+    pub global: Option<String>,
+    /// "Each side separately" checkbox; present when ticked.
+    pub unilateral: Option<String>,
+    /// The per-muscle `muscle.<name>` selects; see `muscle::muscles_from_form`.
+    #[serde(flatten)]
+    pub muscles: HashMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateExercise {
     pub name: String,
     pub category: String,
+    pub unilateral: Option<String>,
+    #[serde(flatten)]
+    pub muscles: HashMap<String, String>,
 }
 
+/// One of a user's exercise categories. `name` is the key stored in
+/// `exercises.category` and never changes; `display_name` is what renames edit.
 #[derive(Debug, Clone, Serialize)]
 pub struct ExerciseCategory {
-    pub name: &'static str,
-    pub display_name: &'static str,
+    pub name: String,
+    pub display_name: String,
+    pub position: i32,
+    /// How many of the user's exercises are filed under this category.
+    /// Categories in use cannot be deleted.
+    pub exercise_count: i64,
+}
+
+impl FromSqliteRow for ExerciseCategory {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            name: row.get("name")?,
+            display_name: row.get("display_name")?,
+            position: row.get("position")?,
+            exercise_count: row.get("exercise_count")?,
+        })
+    }
+}
+
+/// Append a heading for every category an exercise uses that is not among
+/// `categories`, so the grouped lists and pickers never drop an exercise.
+/// This happens for global exercises filed under a category the viewer does
+/// not have; the key stands in for the display name.
+pub fn with_missing_categories(
+    mut categories: Vec<ExerciseCategory>,
+    exercises: &[Exercise],
+) -> Vec<ExerciseCategory> {
+    let mut position = categories.last().map_or(0, |c| c.position + 1);
+    for exercise in exercises {
+        if !categories.iter().any(|c| c.name == exercise.category) {
+            categories.push(ExerciseCategory {
+                name: exercise.category.clone(),
+                display_name: exercise.category.clone(),
+                position,
+                exercise_count: 0,
+            });
+            position += 1;
+        }
+    }
+    categories
 }
 
-pub const CATEGORIES: &[ExerciseCategory] = &[
-    ExerciseCategory {
-        name: "chest",
-        display_name: "Chest",
-    },
-    ExerciseCategory {
-        name: "back",
-        display_name: "Back",
-    },
-    ExerciseCategory {
-        name: "legs",
-        display_name: "Legs",
-    },
-    ExerciseCategory {
-        name: "shoulders",
-        display_name: "Shoulders",
-    },
-    ExerciseCategory {
-        name: "arms",
-        display_name: "Arms",
-    },
-    ExerciseCategory {
-        name: "core",
-        display_name: "Core",
-    },
-];
+#[derive(Debug, Deserialize)]
+pub struct MergeExerciseForm {
+    pub target_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CategoryForm {
+    pub display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MoveCategoryForm {
+    pub direction: String,
+}
+
+/// Derive the stored key for a new category from its display name: lowercased,
+/// with each run of non-alphanumeric characters collapsed to a single `-`.
+/// Unicode letters are kept, so non-English names still produce a usable key.
+pub fn category_key(display_name: &str) -> String {
+    let mut key = String::new();
+    for c in display_name.trim().chars() {
+        if c.is_alphanumeric() {
+            key.extend(c.to_lowercase());
+        } else if !key.is_empty() && !key.ends_with('-') {
+            key.push('-');
+        }
+    }
+    while key.ends_with('-') {
+        key.pop();
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn category_key_slugifies_display_name() {
+        assert_eq!(category_key("Olympic"), "olympic");
+        assert_eq!(category_key("  Grip & Forearms! "), "grip-forearms");
+        assert_eq!(category_key("上半身"), "上半身");
+        assert_eq!(category_key("!!!"), "");
+    }
+}
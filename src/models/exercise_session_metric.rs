@@ -1,6 +1,5 @@
 use chrono::NaiveDate;
 use rusqlite::Row;
-use serde::Serialize;
 
 use super::FromSqliteRow;
 
@@ -25,9 +24,99 @@ impl FromSqliteRow for ExerciseSessionMetric {
     }
 }
 
-/// Chart-ready point. `e1rm` is derived via Epley from `(top_weight, top_reps)`.
-/// Serialized into the page as JSON for the client-side switch handler.
-#[derive(Debug, Clone, Serialize)]
+/// One logged set with its session's date, for the progress charts.
+/// Returned by `WorkoutRepository::get_sets_for_exercises`.
+#[derive(Debug, Clone)]
+pub struct ExerciseSet {
+    pub exercise_id: String,
+    pub session_id: String,
+    pub date: NaiveDate,
+    pub reps: i32,
+    pub weight: f64,
+    pub right_reps: Option<i32>,
+    pub right_weight: Option<f64>,
+}
+
+impl FromSqliteRow for ExerciseSet {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            exercise_id: row.get("exercise_id")?,
+            session_id: row.get("session_id")?,
+            date: row.get("date")?,
+            reps: row.get("reps")?,
+            weight: row.get("weight")?,
+            right_reps: row.get("right_reps")?,
+            right_weight: row.get("right_weight")?,
+        })
+    }
+}
+
+/// Left/right totals over every unilateral set a user logged for one
+/// exercise. Returned by `WorkoutRepository::get_side_balance`.
+#[derive(Debug, Clone)]
+pub struct SideBalance {
+    pub sets: i64,
+    pub left_reps: i64,
+    pub right_reps: i64,
+    pub left_volume: f64,
+    pub right_volume: f64,
+    pub left_top_weight: f64,
+    pub right_top_weight: f64,
+}
+
+impl FromSqliteRow for SideBalance {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            sets: row.get("sets")?,
+            left_reps: row.get("left_reps")?,
+            right_reps: row.get("right_reps")?,
+            left_volume: row.get("left_volume")?,
+            right_volume: row.get("right_volume")?,
+            left_top_weight: row.get("left_top_weight")?,
+            right_top_weight: row.get("right_top_weight")?,
+        })
+    }
+}
+
+/// Volume gap between sides, as a percentage of the stronger side, at which
+/// the stats page flags an imbalance.
+pub const IMBALANCE_WARN_PERCENT: f64 = 10.0;
+
+impl SideBalance {
+    /// How far the weaker side's volume trails the stronger one, as a
+    /// percentage of the stronger side. 0 when both are equal.
+    pub fn imbalance_percent(&self) -> f64 {
+        let stronger = self.left_volume.max(self.right_volume);
+        if stronger <= 0.0 {
+            return 0.0;
+        }
+        (self.left_volume - self.right_volume).abs() / stronger * 100.0
+    }
+
+    /// The side with less volume, or `None` when they match.
+    pub fn weaker_side(&self) -> Option<&'static str> {
+        if self.left_volume < self.right_volume {
+            Some("left")
+        } else if self.right_volume < self.left_volume {
+            Some("right")
+        } else {
+            None
+        }
+    }
+
+    pub fn is_imbalanced(&self) -> bool {
+        self.imbalance_percent() >= IMBALANCE_WARN_PERCENT
+    }
+}
+
+/// Estimated one-rep max by the Epley formula, `weight × (1 + reps / 30)`.
+pub fn estimated_one_rep_max(weight: f64, reps: i32) -> f64 {
+    weight * (1.0 + f64::from(reps) / 30.0)
+}
+
+/// One session's headline numbers, shown under an exercise's chart. `e1rm`
+/// is derived via Epley from `(top_weight, top_reps)`.
+#[derive(Debug, Clone)]
 pub struct ChartPoint {
     pub date: NaiveDate,
     pub top_weight: f64,
@@ -43,7 +132,7 @@ impl ChartPoint {
             top_weight: m.top_weight,
             top_reps: m.top_reps,
             volume: m.volume,
-            e1rm: m.top_weight * (1.0 + m.top_reps as f64 / 30.0),
+            e1rm: estimated_one_rep_max(m.top_weight, m.top_reps),
         }
     }
 }
@@ -52,6 +141,29 @@ impl ChartPoint {
 mod tests {
     use super::*;
 
+    #[test]
+    fn side_balance_reports_weaker_side_and_gap() {
+        let balance = SideBalance {
+            sets: 3,
+            left_reps: 30,
+            right_reps: 27,
+            left_volume: 600.0,
+            right_volume: 540.0,
+            left_top_weight: 20.0,
+            right_top_weight: 20.0,
+        };
+        assert_eq!(balance.weaker_side(), Some("right"));
+        assert!((balance.imbalance_percent() - 10.0).abs() < 1e-9);
+        assert!(balance.is_imbalanced());
+
+        let even = SideBalance {
+            right_volume: 600.0,
+            ..balance
+        };
+        assert_eq!(even.weaker_side(), None);
+        assert!(!even.is_imbalanced());
+    }
+
     #[test]
     #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
     fn chart_point_from_metric_computes_epley_e1rm() {
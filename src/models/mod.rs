@@ -1,21 +1,49 @@
+pub mod block_share;
+pub mod coach;
 pub mod exercise;
 pub mod exercise_session_metric;
 pub mod from_row;
+pub mod muscle;
 pub mod personal_record;
+pub mod preferences;
+pub mod public_profile;
+pub mod report;
 pub mod user;
+pub mod workout_filter;
 pub mod workout_log;
 pub mod workout_session;
+pub mod workout_tag;
 
-pub use exercise::{CreateExercise, Exercise, UpdateExercise};
-pub use exercise_session_metric::{ChartPoint, ExerciseSessionMetric};
+pub use block_share::{BlockScope, BlockShare, MAX_BLOCK_SESSIONS};
+pub use coach::{Access, CoachGrant, WorkoutComment, comment_error};
+pub use exercise::{
+    CategoryForm, CreateExercise, Exercise, ExerciseCategory, MergeExerciseForm, MoveCategoryForm,
+    UpdateExercise,
+};
+pub use exercise_session_metric::{
+    ChartPoint, ExerciseSessionMetric, ExerciseSet, SideBalance, estimated_one_rep_max,
+};
 pub use from_row::FromSqliteRow;
-pub use personal_record::{
-    DynamicPR, LastExerciseWeight, PersonalRecordSummary, recent_pr_window_start,
+pub use muscle::{ExerciseMuscle, MuscleTarget, MuscleWeeklyVolume};
+pub use personal_record::{DynamicPR, LastExerciseWeight, PersonalRecordSummary};
+pub use preferences::{TrainingPreferencesForm, UserPreferences};
+pub use public_profile::{ProfileSections, PublicProfile};
+pub use report::{
+    ExercisePeriodTotals, MonthlyTotals, PeriodPersonalRecord, PeriodSummary, PrGain,
+    YearReviewShare,
 };
 // The length bounds themselves are deliberately not re-exported here: every
 // in-crate caller goes through `password_length_error` instead, so a second
 // place enforcing its own idea of "too short" cannot quietly appear. Tests
 // that need the numbers reach for `models::user::{MIN,MAX}_PASSWORD_LEN`.
 pub use user::{CreateUser, LoginCredentials, User, UserListItem, UserRole, password_policy_error};
-pub use workout_log::{CreateWorkoutLog, UpdateWorkoutLog, WorkoutLog, WorkoutLogWithExercise};
-pub use workout_session::{CreateWorkoutSession, WorkoutSession};
+pub use workout_filter::{
+    PageCursor, WorkoutCursor, WorkoutFilter, WorkoutPage, WorkoutSort, WorkoutSummary,
+    encode_query_value,
+};
+pub use workout_log::{
+    AddSetsForm, CreateWorkoutLog, SetNoteMatch, SetValues, UpdateWorkoutLog, WorkoutLog,
+    WorkoutLogWithExercise, set_annotation_error,
+};
+pub use workout_session::{CreateWorkoutSession, DailyVolume, WeeklySessionCount, WorkoutSession};
+pub use workout_tag::{TagForm, TagMonthlyVolume, WorkoutTag, tag_name_error, tag_selection};
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use chrono::NaiveDate;
+use rusqlite::Row;
+use serde::Serialize;
+
+use super::FromSqliteRow;
+
+/// Lower bound of the default weekly target, in hard sets per muscle group.
+/// Ten to twenty sets a week is the range most hypertrophy reviews converge
+/// on; users can override it per muscle from the muscle volume page.
+pub const DEFAULT_TARGET_MIN_SETS: i32 = 10;
+/// Upper bound of the default weekly target. See [`DEFAULT_TARGET_MIN_SETS`].
+pub const DEFAULT_TARGET_MAX_SETS: i32 = 20;
+
+/// A set counts as "hard" when it was logged at this RPE or above, at
+/// `HARD_SET_MAX_RIR` or below, or with neither at all. Unrated sets are
+/// counted rather than dropped: most people only rate the sets they care
+/// about, and treating every unrated working set as a warm-up would make the
+/// page read zero for anyone who skips the field.
+pub const HARD_SET_MIN_RPE: i32 = 7;
+/// Most reps in reserve a hard set may leave, the RIR matching
+/// [`HARD_SET_MIN_RPE`].
+pub const HARD_SET_MAX_RIR: i32 = 10 - HARD_SET_MIN_RPE;
+
+/// How much of a set a *secondary* muscle is credited with. A bench press
+/// works the triceps, but not as hard as a pushdown does; half a set is the
+/// usual convention for "fractional" volume counting.
+pub const SECONDARY_SET_CREDIT: f64 = 0.5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MuscleGroup {
+    pub name: &'static str,
+    pub display_name: &'static str,
+}
+
+pub const MUSCLE_GROUPS: &[MuscleGroup] = &[
+    MuscleGroup {
+        name: "chest",
+        display_name: "Chest",
+    },
+    MuscleGroup {
+        name: "lats",
+        display_name: "Lats",
+    },
+    MuscleGroup {
+        name: "upper_back",
+        display_name: "Upper Back",
+    },
+    MuscleGroup {
+        name: "lower_back",
+        display_name: "Lower Back",
+    },
+    MuscleGroup {
+        name: "front_delts",
+        display_name: "Front Delts",
+    },
+    MuscleGroup {
+        name: "side_delts",
+        display_name: "Side Delts",
+    },
+    MuscleGroup {
+        name: "rear_delts",
+        display_name: "Rear Delts",
+    },
+    MuscleGroup {
+        name: "biceps",
+        display_name: "Biceps",
+    },
+    MuscleGroup {
+        name: "triceps",
+        display_name: "Triceps",
+    },
+    MuscleGroup {
+        name: "forearms",
+        display_name: "Forearms",
+    },
+    MuscleGroup {
+        name: "quads",
+        display_name: "Quads",
+    },
+    MuscleGroup {
+        name: "hamstrings",
+        display_name: "Hamstrings",
+    },
+    MuscleGroup {
+        name: "glutes",
+        display_name: "Glutes",
+    },
+    MuscleGroup {
+        name: "calves",
+        display_name: "Calves",
+    },
+    MuscleGroup {
+        name: "abs",
+        display_name: "Abs",
+    },
+];
+
+/// Display name for a stored muscle key, falling back to the key itself.
+pub fn muscle_display_name(name: &str) -> &str {
+    MUSCLE_GROUPS
+        .iter()
+        .find(|m| m.name == name)
+        .map_or(name, |m| m.display_name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MuscleRole {
+    Primary,
+    Secondary,
+}
+
+impl MuscleRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MuscleRole::Primary => "primary",
+            MuscleRole::Secondary => "secondary",
+        }
+    }
+
+    /// `None` for anything else, including the empty string the "not worked"
+    /// option of the exercise form submits.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "primary" => Some(MuscleRole::Primary),
+            "secondary" => Some(MuscleRole::Secondary),
+            _ => None,
+        }
+    }
+}
+
+/// One muscle an exercise works, and how directly.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExerciseMuscle {
+    pub muscle: String,
+    pub role: MuscleRole,
+}
+
+impl FromSqliteRow for ExerciseMuscle {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let role: String = row.get("role")?;
+        Ok(Self {
+            muscle: row.get("muscle")?,
+            // The column carries a CHECK constraint, so anything else cannot
+            // be stored; falling back to secondary only guards a hand-edited DB.
+            role: MuscleRole::parse(&role).unwrap_or(MuscleRole::Secondary),
+        })
+    }
+}
+
+/// Collect the `muscle.<name>` fields of an exercise form into the mapping
+/// `ExerciseRepository::set_muscles` stores. Unknown muscles and empty
+/// selections are dropped, so a tampered form can only ever narrow the set.
+pub fn muscles_from_form<S: BuildHasher>(
+    fields: &HashMap<String, String, S>,
+) -> Vec<ExerciseMuscle> {
+    MUSCLE_GROUPS
+        .iter()
+        .filter_map(|m| {
+            let role = fields
+                .get(&format!("muscle.{}", m.name))
+                .and_then(|v| MuscleRole::parse(v))?;
+            Some(ExerciseMuscle {
+                muscle: m.name.to_string(),
+                role,
+            })
+        })
+        .collect()
+}
+
+/// A user's weekly target range for one muscle group.
+#[derive(Debug, Clone, Serialize)]
+pub struct MuscleTarget {
+    pub muscle: String,
+    pub min_sets: i32,
+    pub max_sets: i32,
+}
+
+impl FromSqliteRow for MuscleTarget {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            muscle: row.get("muscle")?,
+            min_sets: row.get("min_sets")?,
+            max_sets: row.get("max_sets")?,
+        })
+    }
+}
+
+/// Hard sets and volume credited to one muscle group in one training week.
+/// Returned by `WorkoutRepository::get_weekly_muscle_volume`.
+#[derive(Debug, Clone)]
+pub struct MuscleWeeklyVolume {
+    pub week_start: NaiveDate,
+    pub muscle: String,
+    pub hard_sets: f64,
+    pub volume: f64,
+}
+
+impl FromSqliteRow for MuscleWeeklyVolume {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            week_start: row.get("week_start")?,
+            muscle: row.get("muscle")?,
+            hard_sets: row.get("hard_sets")?,
+            volume: row.get("volume")?,
+        })
+    }
+}
+
+/// Where a week's hard-set count sits relative to the target range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetStatus {
+    Under,
+    Within,
+    Over,
+}
+
+impl TargetStatus {
+    pub fn classify(hard_sets: f64, min_sets: i32, max_sets: i32) -> Self {
+        if hard_sets < f64::from(min_sets) {
+            TargetStatus::Under
+        } else if hard_sets > f64::from(max_sets) {
+            TargetStatus::Over
+        } else {
+            TargetStatus::Within
+        }
+    }
+
+    /// CSS modifier used by the muscle volume table.
+    pub fn css_class(self) -> &'static str {
+        match self {
+            TargetStatus::Under => "volume-under",
+            TargetStatus::Within => "volume-within",
+            TargetStatus::Over => "volume-over",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn muscle_role_round_trips() {
+        for role in [MuscleRole::Primary, MuscleRole::Secondary] {
+            assert_eq!(MuscleRole::parse(role.as_str()), Some(role));
+        }
+        assert_eq!(MuscleRole::parse(""), None);
+        assert_eq!(MuscleRole::parse("tertiary"), None);
+    }
+
+    #[test]
+    fn muscles_from_form_ignores_unknown_and_empty_fields() {
+        let fields: HashMap<String, String> = [
+            ("muscle.chest", "primary"),
+            ("muscle.triceps", "secondary"),
+            ("muscle.biceps", ""),
+            ("muscle.wings", "primary"),
+            ("name", "Bench Press"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let muscles = muscles_from_form(&fields);
+        assert_eq!(muscles.len(), 2);
+        assert_eq!(muscles[0].muscle, "chest");
+        assert_eq!(muscles[0].role, MuscleRole::Primary);
+        assert_eq!(muscles[1].muscle, "triceps");
+        assert_eq!(muscles[1].role, MuscleRole::Secondary);
+    }
+
+    #[test]
+    fn target_status_bounds_are_inclusive() {
+        assert_eq!(TargetStatus::classify(9.5, 10, 20), TargetStatus::Under);
+        assert_eq!(TargetStatus::classify(10.0, 10, 20), TargetStatus::Within);
+        assert_eq!(TargetStatus::classify(20.0, 10, 20), TargetStatus::Within);
+        assert_eq!(TargetStatus::classify(20.5, 10, 20), TargetStatus::Over);
+    }
+}
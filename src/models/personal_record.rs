@@ -1,4 +1,5 @@
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use rusqlite::Row;
 use serde::Serialize;
 
@@ -9,9 +10,19 @@ use super::FromSqliteRow;
 pub const RECENT_PR_WINDOW_DAYS: i64 = 30;
 
 /// Start of the rolling window every "PR (1M)" surface is measured against —
-/// the PR tables, and the per-set badges on a workout.
-pub fn recent_pr_window_start() -> DateTime<Utc> {
-    Utc::now() - Duration::days(RECENT_PR_WINDOW_DAYS)
+/// the PR tables, and the per-set badges on a workout. The window opens at
+/// local midnight `RECENT_PR_WINDOW_DAYS` days before `now`'s date in
+/// `timezone`, so it moves a whole day at a time as the user sees it.
+pub fn recent_pr_window_start(timezone: Tz, now: DateTime<Utc>) -> DateTime<Utc> {
+    let first_day =
+        now.with_timezone(&timezone).date_naive() - Duration::days(RECENT_PR_WINDOW_DAYS);
+    // Midnight can be skipped by a DST change; fall back to the rolling value.
+    timezone
+        .from_local_datetime(&first_day.and_time(NaiveTime::MIN))
+        .earliest()
+        .map_or(now - Duration::days(RECENT_PR_WINDOW_DAYS), |start| {
+            start.with_timezone(&Utc)
+        })
 }
 
 /// Dynamically computed Personal Record
@@ -0,0 +1,190 @@
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use rusqlite::Row;
+use serde::Deserialize;
+
+use super::FromSqliteRow;
+use super::personal_record::recent_pr_window_start;
+
+/// Sessions per week a user aims for until they pick their own target.
+pub const DEFAULT_WEEKLY_SESSION_TARGET: i64 = 3;
+
+/// Upper bound on the weekly target: two sessions a day.
+pub const MAX_WEEKLY_SESSION_TARGET: i64 = 14;
+
+/// Days a week may start on. Other days are valid `Weekday`s but no
+/// calendar convention uses them.
+pub const WEEK_START_DAYS: [Weekday; 3] = [Weekday::Mon, Weekday::Sun, Weekday::Sat];
+
+/// Per-user training preferences. Users without a stored row get
+/// [`UserPreferences::default`].
+///
+/// Every "today", "this week" and "this month" is worked out in the user's
+/// own timezone and calendar: a set logged at 11pm in UTC-8 belongs to that
+/// evening, not to the next UTC day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserPreferences {
+    /// Sessions per week that count towards a streak.
+    pub weekly_session_target: i64,
+    pub timezone: Tz,
+    /// First day of the training week.
+    pub week_start: Weekday,
+}
+
+impl Default for UserPreferences {
+    fn default() -> Self {
+        Self {
+            weekly_session_target: DEFAULT_WEEKLY_SESSION_TARGET,
+            timezone: Tz::UTC,
+            week_start: Weekday::Mon,
+        }
+    }
+}
+
+impl UserPreferences {
+    /// The user's calendar date at `now`.
+    pub fn date_at(&self, now: DateTime<Utc>) -> NaiveDate {
+        now.with_timezone(&self.timezone).date_naive()
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.date_at(Utc::now())
+    }
+
+    /// Start of the "PR (1M)" window, from midnight local time.
+    pub fn pr_window_start(&self) -> DateTime<Utc> {
+        recent_pr_window_start(self.timezone, Utc::now())
+    }
+}
+
+impl FromSqliteRow for UserPreferences {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let timezone: String = row.get("timezone")?;
+        let week_start: String = row.get("week_start")?;
+        let defaults = Self::default();
+        Ok(Self {
+            weekly_session_target: row.get("weekly_session_target")?,
+            timezone: timezone.parse().unwrap_or_else(|_err| {
+                tracing::warn!(timezone, "unknown timezone in DB; defaulting to UTC");
+                defaults.timezone
+            }),
+            week_start: week_start.parse().unwrap_or_else(|_err| {
+                tracing::warn!(week_start, "unknown week start in DB; defaulting to Monday");
+                defaults.week_start
+            }),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrainingPreferencesForm {
+    pub weekly_session_target: String,
+    pub timezone: String,
+    pub week_start: String,
+}
+
+impl TrainingPreferencesForm {
+    /// The preferences the form asks for, or why they are not usable.
+    pub fn parse(&self) -> std::result::Result<UserPreferences, String> {
+        let weekly_session_target = match self.weekly_session_target.trim().parse::<i64>() {
+            Ok(target) if (1..=MAX_WEEKLY_SESSION_TARGET).contains(&target) => target,
+            _ => {
+                return Err(format!(
+                    "Weekly target must be a whole number from 1 to {MAX_WEEKLY_SESSION_TARGET}"
+                ));
+            }
+        };
+        let timezone = self
+            .timezone
+            .trim()
+            .parse::<Tz>()
+            .map_err(|_err| format!("Unknown timezone: {}", self.timezone.trim()))?;
+        let week_start = self
+            .week_start
+            .parse::<Weekday>()
+            .ok()
+            .filter(|day| WEEK_START_DAYS.contains(day))
+            .ok_or_else(|| "Weeks can start on Monday, Sunday or Saturday".to_string())?;
+        Ok(UserPreferences {
+            weekly_session_target,
+            timezone,
+            week_start,
+        })
+    }
+}
+
+/// The IANA timezone names offered in the settings picker.
+pub fn timezone_names() -> impl Iterator<Item = &'static str> {
+    chrono_tz::TZ_VARIANTS.iter().map(|tz| tz.name())
+}
+
+/// Full English name of a weekday, for pickers.
+pub fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(target: &str, timezone: &str, week_start: &str) -> TrainingPreferencesForm {
+        TrainingPreferencesForm {
+            weekly_session_target: target.to_string(),
+            timezone: timezone.to_string(),
+            week_start: week_start.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_and_validates_the_form() {
+        let preferences = form(" 4 ", "America/Los_Angeles", "Sun").parse().unwrap();
+        assert_eq!(preferences.weekly_session_target, 4);
+        assert_eq!(preferences.timezone, Tz::America__Los_Angeles);
+        assert_eq!(preferences.week_start, Weekday::Sun);
+
+        assert!(form("0", "UTC", "Mon").parse().is_err());
+        assert!(form("15", "UTC", "Mon").parse().is_err());
+        assert!(form("three", "UTC", "Mon").parse().is_err());
+        assert!(form("3", "Mars/Olympus", "Mon").parse().is_err());
+        assert!(form("3", "UTC", "Wed").parse().is_err());
+    }
+
+    #[test]
+    fn today_follows_the_users_zone() {
+        let preferences = UserPreferences {
+            timezone: Tz::America__Los_Angeles,
+            ..UserPreferences::default()
+        };
+        // 05:30 UTC on Monday 15 January is 21:30 on Sunday the 14th in LA.
+        let now = DateTime::parse_from_rfc3339("2024-01-15T05:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            preferences.date_at(now),
+            NaiveDate::from_ymd_opt(2024, 1, 14).unwrap()
+        );
+        assert_eq!(
+            UserPreferences::default().date_at(now),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+    }
+
+    #[test]
+    fn pr_window_opens_at_local_midnight() {
+        // 03:00 UTC on 20 March is the evening of the 19th in LA (UTC-7);
+        // 30 days earlier is 18 February, when LA was on UTC-8.
+        let now = DateTime::parse_from_rfc3339("2024-03-20T03:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let start = recent_pr_window_start(Tz::America__Los_Angeles, now);
+        assert_eq!(start.to_rfc3339(), "2024-02-18T08:00:00+00:00");
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use chrono::{DateTime, Utc};
+use rusqlite::Row;
+
+use super::FromSqliteRow;
+
+/// What a public profile shows. Everything is off until the owner turns it
+/// on, section by section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileSections {
+    pub prs: bool,
+    /// Recent workouts that have their own live share link.
+    pub sessions: bool,
+    pub consistency: bool,
+    /// The exercises whose PRs are listed when `prs` is on.
+    pub exercise_ids: Vec<String>,
+}
+
+impl ProfileSections {
+    /// Read the settings form: a `show_prs`, `show_sessions` and
+    /// `show_consistency` checkbox, and an `ex.<id>` checkbox per exercise.
+    pub fn from_form<S: BuildHasher>(fields: &HashMap<String, String, S>) -> Self {
+        let ticked = |name: &str| fields.get(name).is_some_and(|v| v == "on");
+        let mut exercise_ids: Vec<String> = fields
+            .iter()
+            .filter(|(_, value)| *value == "on")
+            .filter_map(|(key, _)| key.strip_prefix("ex."))
+            .map(str::to_string)
+            .collect();
+        exercise_ids.sort();
+        Self {
+            prs: ticked("show_prs"),
+            sessions: ticked("show_sessions"),
+            consistency: ticked("show_consistency"),
+            exercise_ids,
+        }
+    }
+
+    /// Nothing would be shown.
+    pub fn is_empty(&self) -> bool {
+        !(self.prs || self.sessions || self.consistency)
+    }
+}
+
+/// A user's public profile settings and link. Users without a stored row
+/// have no link and every section hidden.
+#[derive(Debug, Clone, Default)]
+pub struct PublicProfile {
+    pub user_id: String,
+    /// `None` when the profile is not shared, or its link has expired.
+    pub token: Option<String>,
+    /// `None` means the link never expires, as for workout share links.
+    pub expires_at: Option<DateTime<Utc>>,
+    pub sections: ProfileSections,
+}
+
+impl FromSqliteRow for PublicProfile {
+    /// The sections' exercise list is not part of the row; the repository
+    /// fills it in.
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            user_id: row.get("user_id")?,
+            token: row.get("token")?,
+            expires_at: row.get("expires_at")?,
+            sections: ProfileSections {
+                prs: row.get("show_prs")?,
+                sessions: row.get("show_sessions")?,
+                consistency: row.get("show_consistency")?,
+                exercise_ids: Vec::new(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn form_reads_sections_and_exercises() {
+        let fields: HashMap<String, String> = [
+            ("show_prs", "on"),
+            ("show_consistency", "on"),
+            ("ex.b", "on"),
+            ("ex.a", "on"),
+            ("other", "on"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let sections = ProfileSections::from_form(&fields);
+        assert!(sections.prs && sections.consistency && !sections.sessions);
+        assert_eq!(sections.exercise_ids, vec!["a", "b"]);
+        assert!(!sections.is_empty());
+        assert!(ProfileSections::default().is_empty());
+    }
+}
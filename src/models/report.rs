@@ -0,0 +1,155 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::Row;
+
+use super::FromSqliteRow;
+
+/// Training totals over a date range. Only sets count, so an empty (planned)
+/// session adds nothing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeriodSummary {
+    /// Sessions with at least one set.
+    pub sessions: i64,
+    pub sets: i64,
+    pub volume: f64,
+    /// Mean RPE of the rated sets; `None` when no set was rated.
+    pub average_rpe: Option<f64>,
+}
+
+impl FromSqliteRow for PeriodSummary {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            sessions: row.get("sessions")?,
+            sets: row.get("sets")?,
+            volume: row.get("volume")?,
+            average_rpe: row.get("average_rpe")?,
+        })
+    }
+}
+
+/// One exercise's totals over a date range.
+#[derive(Debug, Clone)]
+pub struct ExercisePeriodTotals {
+    pub exercise_id: String,
+    pub exercise_name: String,
+    pub sessions: i64,
+    pub sets: i64,
+    pub volume: f64,
+    /// Heaviest set, and its reps (the most reps at that weight).
+    pub top_weight: f64,
+    pub top_reps: i32,
+    pub average_rpe: Option<f64>,
+}
+
+impl FromSqliteRow for ExercisePeriodTotals {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            exercise_id: row.get("exercise_id")?,
+            exercise_name: row.get("exercise_name")?,
+            sessions: row.get("sessions")?,
+            sets: row.get("sets")?,
+            volume: row.get("volume")?,
+            top_weight: row.get("top_weight")?,
+            top_reps: row.get("top_reps")?,
+            average_rpe: row.get("average_rpe")?,
+        })
+    }
+}
+
+/// A weight PR set within a date range: the exercise's best in the range
+/// beat everything logged before it.
+#[derive(Debug, Clone)]
+pub struct PeriodPersonalRecord {
+    pub exercise_id: String,
+    pub exercise_name: String,
+    pub weight: f64,
+    pub reps: i32,
+    /// Date of the first session in the range that reached `weight`.
+    pub date: NaiveDate,
+    pub session_id: String,
+    /// Best weight before the range; `None` the first time the exercise
+    /// was logged.
+    pub previous_best: Option<f64>,
+}
+
+impl FromSqliteRow for PeriodPersonalRecord {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            exercise_id: row.get("exercise_id")?,
+            exercise_name: row.get("exercise_name")?,
+            weight: row.get("weight")?,
+            reps: row.get("reps")?,
+            date: row.get("date")?,
+            session_id: row.get("session_id")?,
+            previous_best: row.get("previous_best")?,
+        })
+    }
+}
+
+/// Trained sessions and volume in one calendar month.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlyTotals {
+    /// First day of the month.
+    pub month: NaiveDate,
+    pub sessions: i64,
+    pub volume: f64,
+}
+
+impl FromSqliteRow for MonthlyTotals {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            month: row.get("month")?,
+            sessions: row.get("sessions")?,
+            volume: row.get("volume")?,
+        })
+    }
+}
+
+/// How far an exercise's best weight moved over a date range.
+#[derive(Debug, Clone)]
+pub struct PrGain {
+    pub exercise_id: String,
+    pub exercise_name: String,
+    /// Best weight before the range, or the top set of the first session in
+    /// it for an exercise new in the range.
+    pub start_weight: f64,
+    /// Best weight in the range.
+    pub end_weight: f64,
+}
+
+impl PrGain {
+    pub fn gain(&self) -> f64 {
+        self.end_weight - self.start_weight
+    }
+}
+
+impl FromSqliteRow for PrGain {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            exercise_id: row.get("exercise_id")?,
+            exercise_name: row.get("exercise_name")?,
+            start_weight: row.get("start_weight")?,
+            end_weight: row.get("end_weight")?,
+        })
+    }
+}
+
+/// A public link to one user's review of one year.
+#[derive(Debug, Clone)]
+pub struct YearReviewShare {
+    pub user_id: String,
+    pub year: i32,
+    pub token: String,
+    /// `None` means the link never expires, as for workout share links.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl FromSqliteRow for YearReviewShare {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            user_id: row.get("user_id")?,
+            year: row.get("year")?,
+            token: row.get("token")?,
+            expires_at: row.get("expires_at")?,
+        })
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt::Write;
+
+use chrono::NaiveDate;
+use rusqlite::Row;
+use serde::Serialize;
+
+use super::{FromSqliteRow, WorkoutSession};
+
+/// Sort orders offered on the workout list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WorkoutSort {
+    #[default]
+    Newest,
+    Oldest,
+    /// Largest total volume first.
+    Volume,
+}
+
+impl WorkoutSort {
+    pub const ALL: [Self; 3] = [Self::Newest, Self::Oldest, Self::Volume];
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value {
+            "newest" => Some(Self::Newest),
+            "oldest" => Some(Self::Oldest),
+            "volume" => Some(Self::Volume),
+            _ => None,
+        }
+    }
+
+    pub fn param(self) -> &'static str {
+        match self {
+            Self::Newest => "newest",
+            Self::Oldest => "oldest",
+            Self::Volume => "volume",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Newest => "Newest first",
+            Self::Oldest => "Oldest first",
+            Self::Volume => "Most volume",
+        }
+    }
+
+    pub fn descending(self) -> bool {
+        !matches!(self, Self::Oldest)
+    }
+}
+
+/// What the workout list is narrowed to. Every field is optional and the
+/// filters combine with AND.
+#[derive(Debug, Clone, Default)]
+pub struct WorkoutFilter {
+    /// First date included.
+    pub from: Option<NaiveDate>,
+    /// Last date included.
+    pub to: Option<NaiveDate>,
+    /// Only sessions with at least one set of this exercise.
+    pub exercise_id: Option<String>,
+    /// Words to find in the session notes.
+    pub text: Option<String>,
+    /// Only sessions carrying the tag of this name (in any case).
+    pub tag: Option<String>,
+}
+
+impl WorkoutFilter {
+    pub fn is_empty(&self) -> bool {
+        self.from.is_none()
+            && self.to.is_none()
+            && self.exercise_id.is_none()
+            && self.text.is_none()
+            && self.tag.is_none()
+    }
+
+    /// The notes search as an FTS5 query: each word quoted (so operators and
+    /// punctuation in user input are taken literally) and prefix-matched, so
+    /// "dead" finds "deadlift". `None` when there is nothing to search for.
+    pub fn fts_query(&self) -> Option<String> {
+        let words: Vec<String> = self
+            .text
+            .as_deref()?
+            .split_whitespace()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+            .collect();
+        (!words.is_empty()).then(|| words.join(" "))
+    }
+}
+
+/// A position in the list: the sort key and id of the last row seen. The
+/// key is a date for the date orders and a volume for [`WorkoutSort::Volume`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkoutCursor {
+    pub key: String,
+    pub id: String,
+}
+
+impl WorkoutCursor {
+    /// Parse a `key~id` query value, checking the key suits `sort`.
+    pub fn parse(value: &str, sort: WorkoutSort) -> Option<Self> {
+        let (key, id) = value.split_once('~')?;
+        if id.is_empty() {
+            return None;
+        }
+        let valid = match sort {
+            WorkoutSort::Newest | WorkoutSort::Oldest => {
+                NaiveDate::parse_from_str(key, "%Y-%m-%d").is_ok()
+            }
+            WorkoutSort::Volume => key.parse::<f64>().is_ok_and(f64::is_finite),
+        };
+        valid.then(|| Self {
+            key: key.to_string(),
+            id: id.to_string(),
+        })
+    }
+
+    pub fn for_workout(workout: &WorkoutSummary, sort: WorkoutSort) -> Self {
+        let key = match sort {
+            WorkoutSort::Newest | WorkoutSort::Oldest => workout.session.date.to_string(),
+            WorkoutSort::Volume => workout.volume.to_string(),
+        };
+        Self {
+            key,
+            id: workout.session.id.clone(),
+        }
+    }
+
+    pub fn to_param(&self) -> String {
+        format!("{}~{}", self.key, self.id)
+    }
+}
+
+/// Which side of a cursor to read.
+#[derive(Debug, Clone)]
+pub enum PageCursor {
+    After(WorkoutCursor),
+    Before(WorkoutCursor),
+}
+
+/// A session on the workout list with its totals.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkoutSummary {
+    pub session: WorkoutSession,
+    pub set_count: i64,
+    pub volume: f64,
+    /// Tag names, alphabetically. Not part of the row; the handler fills
+    /// them in for the page.
+    pub tags: Vec<String>,
+}
+
+impl FromSqliteRow for WorkoutSummary {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            session: WorkoutSession::from_row(row)?,
+            set_count: row.get("set_count")?,
+            volume: row.get("volume")?,
+            tags: Vec::new(),
+        })
+    }
+}
+
+/// One page of the workout list, with cursors for its neighbours.
+#[derive(Debug, Default)]
+pub struct WorkoutPage {
+    pub workouts: Vec<WorkoutSummary>,
+    pub prev: Option<WorkoutCursor>,
+    pub next: Option<WorkoutCursor>,
+}
+
+/// Percent-encode a query-string value.
+pub fn encode_query_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> WorkoutFilter {
+        WorkoutFilter {
+            text: Some(value.to_string()),
+            ..WorkoutFilter::default()
+        }
+    }
+
+    #[test]
+    fn fts_query_quotes_and_prefixes_each_word() {
+        assert_eq!(
+            text("heavy dead").fts_query().as_deref(),
+            Some("\"heavy\"* \"dead\"*")
+        );
+        assert_eq!(
+            text("say \"hi\" OR -").fts_query().as_deref(),
+            Some("\"say\"* \"\"\"hi\"\"\"* \"OR\"*")
+        );
+        assert_eq!(text(" - ").fts_query(), None);
+        assert_eq!(WorkoutFilter::default().fts_query(), None);
+    }
+
+    #[test]
+    fn cursor_round_trips_and_checks_the_key() {
+        let cursor = WorkoutCursor::parse("2024-01-15~abc", WorkoutSort::Newest).unwrap();
+        assert_eq!(cursor.to_param(), "2024-01-15~abc");
+        assert!(WorkoutCursor::parse("2024-01-15~abc", WorkoutSort::Volume).is_none());
+        assert!(WorkoutCursor::parse("1520.5~abc", WorkoutSort::Volume).is_some());
+        assert!(WorkoutCursor::parse("NaN~abc", WorkoutSort::Volume).is_none());
+        assert!(WorkoutCursor::parse("2024-01-15", WorkoutSort::Oldest).is_none());
+    }
+
+    #[test]
+    fn encodes_query_values() {
+        assert_eq!(
+            encode_query_value("leg day & more"),
+            "leg%20day%20%26%20more"
+        );
+        assert_eq!(encode_query_value("2024-01-15~a1"), "2024-01-15~a1");
+    }
+}
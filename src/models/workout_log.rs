@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rusqlite::Row;
 use serde::{Deserialize, Deserializer, Serialize};
 
@@ -18,6 +18,155 @@ where
     }
 }
 
+/// Deserialize an optional float from a form field, treating an empty string
+/// as `None` like `deserialize_optional_i32`.
+fn deserialize_optional_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let opt: Option<String> = Option::deserialize(deserializer)?;
+    match opt {
+        Some(s) if s.is_empty() => Ok(None),
+        Some(s) => s.parse().map(Some).map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// The recorded values of one set, as written by
+/// `WorkoutRepository::create_log` and `update_log`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SetValues {
+    /// For a unilateral set, the left side.
+    pub reps: i32,
+    /// For a unilateral set, the left side.
+    pub weight: f64,
+    pub rpe: Option<i32>,
+    /// Right-side reps of a unilateral set; `None` for ordinary sets.
+    pub right_reps: Option<i32>,
+    /// Right-side weight of a unilateral set; `None` for ordinary sets.
+    pub right_weight: Option<f64>,
+    pub notes: Option<String>,
+    /// Normalised by `normalize_tempo`.
+    pub tempo: Option<String>,
+    /// Reps in reserve, recorded instead of or alongside `rpe`.
+    pub rir: Option<i32>,
+}
+
+impl SetValues {
+    pub fn new(reps: i32, weight: f64, rpe: Option<i32>) -> Self {
+        Self {
+            reps,
+            weight,
+            rpe,
+            ..Self::default()
+        }
+    }
+
+    /// Record the right side separately, making this a unilateral set.
+    #[must_use]
+    pub fn with_right_side(mut self, reps: i32, weight: f64) -> Self {
+        self.right_reps = Some(reps);
+        self.right_weight = Some(weight);
+        self
+    }
+
+    /// Attach the optional annotations, trimming blanks to `None` and
+    /// normalising the tempo. Check them with `set_annotation_error` first;
+    /// an unparseable tempo is dropped here.
+    #[must_use]
+    pub fn with_annotations(
+        mut self,
+        notes: Option<&str>,
+        tempo: Option<&str>,
+        rir: Option<i32>,
+    ) -> Self {
+        self.notes = notes
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(str::to_string);
+        self.tempo = tempo.and_then(normalize_tempo);
+        self.rir = rir;
+        self
+    }
+
+    /// Build the values from a submitted set form. For a unilateral exercise
+    /// a blank right-side field repeats the left-side value; for any other
+    /// exercise the right-side fields are ignored.
+    pub fn from_form(
+        unilateral: bool,
+        reps: i32,
+        weight: f64,
+        rpe: Option<i32>,
+        right_reps: Option<i32>,
+        right_weight: Option<f64>,
+    ) -> Self {
+        let values = Self::new(reps, weight, rpe);
+        if unilateral {
+            values.with_right_side(right_reps.unwrap_or(reps), right_weight.unwrap_or(weight))
+        } else {
+            values
+        }
+    }
+}
+
+/// Show a unilateral value as "left / right"; ordinary sets show one value.
+fn sided<T: std::fmt::Display>(left: T, right: Option<T>) -> String {
+    match right {
+        Some(right) => format!("{left} / {right}"),
+        None => left.to_string(),
+    }
+}
+
+/// Longest per-set note accepted. Notes are meant for a word or two.
+pub const MAX_SET_NOTE_LEN: usize = 200;
+
+/// Highest reps-in-reserve value accepted; beyond this the set is a warm-up.
+pub const MAX_RIR: i32 = 10;
+
+/// Parse a tempo such as `3-1-1-0`, `31X0` or `3 1 x 0` into the stored
+/// `3-1-X-0` form: four phases (eccentric, bottom pause, concentric, top
+/// pause), each a number of seconds or `X` for explosive. Returns `None` for
+/// a blank or malformed tempo.
+pub fn normalize_tempo(tempo: &str) -> Option<String> {
+    let tempo = tempo.trim();
+    let phases: Vec<String> = if tempo.contains(['-', ' ']) {
+        tempo
+            .split(['-', ' '])
+            .filter(|p| !p.is_empty())
+            .map(str::to_ascii_uppercase)
+            .collect()
+    } else {
+        tempo
+            .chars()
+            .map(|c| c.to_ascii_uppercase().to_string())
+            .collect()
+    };
+    let valid = phases.len() == 4
+        && phases
+            .iter()
+            .all(|p| p == "X" || (p.len() <= 2 && p.chars().all(|c| c.is_ascii_digit())));
+    valid.then(|| phases.join("-"))
+}
+
+/// Validate the optional per-set annotations from a set form.
+pub fn set_annotation_error(
+    notes: Option<&str>,
+    tempo: Option<&str>,
+    rir: Option<i32>,
+) -> Option<String> {
+    if notes.is_some_and(|n| n.trim().chars().count() > MAX_SET_NOTE_LEN) {
+        Some(format!(
+            "Set notes must be at most {MAX_SET_NOTE_LEN} characters"
+        ))
+    } else if tempo.is_some_and(|t| !t.trim().is_empty() && normalize_tempo(t).is_none()) {
+        Some("Tempo must have four phases, like 3-1-1-0 (use X for explosive)".to_string())
+    } else if rir.is_some_and(|r| !(0..=MAX_RIR).contains(&r)) {
+        Some(format!("RIR must be between 0 and {MAX_RIR}"))
+    } else {
+        None
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkoutLog {
     pub id: String,
@@ -27,6 +176,12 @@ pub struct WorkoutLog {
     pub reps: i32,
     pub weight: f64,
     pub rpe: Option<i32>,
+    /// Right side of a unilateral set; `reps`/`weight` are then the left.
+    pub right_reps: Option<i32>,
+    pub right_weight: Option<f64>,
+    pub notes: Option<String>,
+    pub tempo: Option<String>,
+    pub rir: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -40,6 +195,11 @@ impl FromSqliteRow for WorkoutLog {
             reps: row.get("reps")?,
             weight: row.get("weight")?,
             rpe: row.get("rpe")?,
+            right_reps: row.get("right_reps")?,
+            right_weight: row.get("right_weight")?,
+            notes: row.get("notes")?,
+            tempo: row.get("tempo")?,
+            rir: row.get("rir")?,
             created_at: row.get("created_at")?,
         })
     }
@@ -52,6 +212,14 @@ pub struct CreateWorkoutLog {
     pub weight: f64,
     #[serde(default, deserialize_with = "deserialize_optional_i32")]
     pub rpe: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_i32")]
+    pub right_reps: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_f64")]
+    pub right_weight: Option<f64>,
+    pub notes: Option<String>,
+    pub tempo: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_i32")]
+    pub rir: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -60,6 +228,28 @@ pub struct UpdateWorkoutLog {
     pub weight: f64,
     #[serde(default, deserialize_with = "deserialize_optional_i32")]
     pub rpe: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_i32")]
+    pub right_reps: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_f64")]
+    pub right_weight: Option<f64>,
+    pub notes: Option<String>,
+    pub tempo: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_i32")]
+    pub rir: Option<i32>,
+}
+
+/// The batch editor's "add sets" form: `count` identical sets.
+#[derive(Debug, Deserialize)]
+pub struct AddSetsForm {
+    pub count: i32,
+    pub reps: i32,
+    pub weight: f64,
+    #[serde(default, deserialize_with = "deserialize_optional_i32")]
+    pub rpe: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_i32")]
+    pub right_reps: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_f64")]
+    pub right_weight: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -72,6 +262,11 @@ pub struct WorkoutLogWithExercise {
     pub reps: i32,
     pub weight: f64,
     pub rpe: Option<i32>,
+    pub right_reps: Option<i32>,
+    pub right_weight: Option<f64>,
+    pub notes: Option<String>,
+    pub tempo: Option<String>,
+    pub rir: Option<i32>,
     /// The set matches the all-time best weight for its exercise.
     pub is_pr: bool,
     /// The set is inside the rolling 1-month window *and* matches the best
@@ -91,8 +286,127 @@ impl FromSqliteRow for WorkoutLogWithExercise {
             reps: row.get("reps")?,
             weight: row.get("weight")?,
             rpe: row.get("rpe")?,
+            right_reps: row.get("right_reps")?,
+            right_weight: row.get("right_weight")?,
+            notes: row.get("notes")?,
+            tempo: row.get("tempo")?,
+            rir: row.get("rir")?,
             is_pr: row.get("is_pr")?,
             is_recent_pr: row.get("is_recent_pr")?,
         })
     }
 }
+
+impl WorkoutLogWithExercise {
+    /// Weight as shown in set lists: "left / right" for unilateral sets.
+    pub fn weight_text(&self) -> String {
+        sided(self.weight, self.right_weight)
+    }
+
+    pub fn reps_text(&self) -> String {
+        sided(self.reps, self.right_reps)
+    }
+
+    /// The effort column: RPE when recorded, otherwise RIR, otherwise "-".
+    pub fn effort_text(&self) -> String {
+        match (self.rpe, self.rir) {
+            (Some(rpe), _) => rpe.to_string(),
+            (None, Some(rir)) => format!("{rir} RIR"),
+            (None, None) => "-".to_string(),
+        }
+    }
+
+    /// Tempo and notes joined for the line under a set, e.g.
+    /// "Tempo 3-1-1-0 · belt"; `None` when the set has neither.
+    pub fn annotation_text(&self) -> Option<String> {
+        let tempo = self.tempo.as_ref().map(|t| format!("Tempo {t}"));
+        let parts: Vec<String> = tempo.into_iter().chain(self.notes.clone()).collect();
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+}
+
+/// A set whose notes or tempo matched a search, with enough of its session
+/// to link back to it.
+#[derive(Debug, Clone, Serialize)]
+pub struct SetNoteMatch {
+    pub session_id: String,
+    pub date: NaiveDate,
+    pub exercise_name: String,
+    pub set_number: i32,
+    pub reps: i32,
+    pub weight: f64,
+    pub right_reps: Option<i32>,
+    pub right_weight: Option<f64>,
+    pub notes: Option<String>,
+    pub tempo: Option<String>,
+}
+
+impl FromSqliteRow for SetNoteMatch {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            session_id: row.get("session_id")?,
+            date: row.get("date")?,
+            exercise_name: row.get("exercise_name")?,
+            set_number: row.get("set_number")?,
+            reps: row.get("reps")?,
+            weight: row.get("weight")?,
+            right_reps: row.get("right_reps")?,
+            right_weight: row.get("right_weight")?,
+            notes: row.get("notes")?,
+            tempo: row.get("tempo")?,
+        })
+    }
+}
+
+impl SetNoteMatch {
+    pub fn weight_text(&self) -> String {
+        sided(self.weight, self.right_weight)
+    }
+
+    pub fn reps_text(&self) -> String {
+        sided(self.reps, self.right_reps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_values_from_form_fills_blank_right_side_for_unilateral() {
+        let values = SetValues::from_form(true, 10, 20.0, None, None, Some(22.5));
+        assert_eq!(values.right_reps, Some(10));
+        assert_eq!(values.right_weight, Some(22.5));
+
+        let values = SetValues::from_form(false, 10, 20.0, None, Some(8), Some(22.5));
+        assert_eq!(values, SetValues::new(10, 20.0, None));
+    }
+
+    #[test]
+    fn normalize_tempo_accepts_common_notations() {
+        assert_eq!(normalize_tempo("3-1-1-0").as_deref(), Some("3-1-1-0"));
+        assert_eq!(normalize_tempo("31x0").as_deref(), Some("3-1-X-0"));
+        assert_eq!(normalize_tempo(" 3 1 X 0 ").as_deref(), Some("3-1-X-0"));
+        assert_eq!(normalize_tempo("10-0-1-0").as_deref(), Some("10-0-1-0"));
+        assert_eq!(normalize_tempo("3-1-1"), None);
+        assert_eq!(normalize_tempo("slow"), None);
+        assert_eq!(normalize_tempo(""), None);
+    }
+
+    #[test]
+    fn set_annotation_error_checks_each_field() {
+        assert!(set_annotation_error(Some("belt"), Some("3-1-1-0"), Some(2)).is_none());
+        assert!(set_annotation_error(None, Some(""), None).is_none());
+        assert!(set_annotation_error(Some(&"x".repeat(201)), None, None).is_some());
+        assert!(set_annotation_error(None, Some("fast"), None).is_some());
+        assert!(set_annotation_error(None, None, Some(11)).is_some());
+    }
+
+    #[test]
+    fn with_annotations_trims_blank_notes() {
+        let values =
+            SetValues::new(5, 100.0, None).with_annotations(Some("  "), Some("31x0"), None);
+        assert_eq!(values.notes, None);
+        assert_eq!(values.tempo.as_deref(), Some("3-1-X-0"));
+    }
+}
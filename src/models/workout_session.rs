@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, NaiveDate, Utc};
 use rusqlite::Row;
 use serde::{Deserialize, Serialize};
@@ -13,6 +15,9 @@ pub struct WorkoutSession {
     pub share_token: Option<String>,
     /// NULL means the share link (if any) never expires — see migration 012.
     pub share_expires_at: Option<DateTime<Utc>>,
+    /// Whether the share link also serves progress charts, which show
+    /// earlier sessions — see migration 028.
+    pub share_history: bool,
     pub created_at: DateTime<Utc>,
 }
 
@@ -25,6 +30,7 @@ impl FromSqliteRow for WorkoutSession {
             notes: row.get("notes")?,
             share_token: row.get("share_token")?,
             share_expires_at: row.get("share_expires_at")?,
+            share_history: row.get("share_history")?,
             created_at: row.get("created_at")?,
         })
     }
@@ -34,4 +40,47 @@ impl FromSqliteRow for WorkoutSession {
 pub struct CreateWorkoutSession {
     pub date: NaiveDate,
     pub notes: Option<String>,
+    /// The tag picker's `tag.<id>` checkboxes and `new_tags` field; see
+    /// `tag_selection`.
+    #[serde(flatten)]
+    pub fields: HashMap<String, String>,
+}
+
+/// Training totals for one day, across every session on it.
+#[derive(Debug, Clone)]
+pub struct DailyVolume {
+    pub date: NaiveDate,
+    pub sessions: i64,
+    /// One of the day's sessions, for linking when it is the only one.
+    pub session_id: String,
+    pub sets: i64,
+    pub volume: f64,
+}
+
+impl FromSqliteRow for DailyVolume {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            date: row.get("date")?,
+            sessions: row.get("sessions")?,
+            session_id: row.get("session_id")?,
+            sets: row.get("sets")?,
+            volume: row.get("volume")?,
+        })
+    }
+}
+
+/// Trained sessions in one Monday-first week.
+#[derive(Debug, Clone)]
+pub struct WeeklySessionCount {
+    pub week_start: NaiveDate,
+    pub sessions: i64,
+}
+
+impl FromSqliteRow for WeeklySessionCount {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            week_start: row.get("week_start")?,
+            sessions: row.get("sessions")?,
+        })
+    }
 }
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use chrono::NaiveDate;
+use rusqlite::Row;
+use serde::{Deserialize, Serialize};
+
+use super::FromSqliteRow;
+
+/// Longest tag name accepted. Tags are shown as chips, so they are meant to
+/// be a word or two.
+pub const MAX_TAG_NAME_LEN: usize = 30;
+
+/// A user-defined session label such as "push" or "deload".
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkoutTag {
+    pub id: String,
+    pub name: String,
+    /// How many of the user's sessions carry the tag.
+    pub session_count: i64,
+}
+
+impl FromSqliteRow for WorkoutTag {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            session_count: row.get("session_count")?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagForm {
+    pub name: String,
+}
+
+/// Sessions and total volume for one tag in one calendar month.
+#[derive(Debug, Clone)]
+pub struct TagMonthlyVolume {
+    pub tag_name: String,
+    /// First day of the month.
+    pub month: NaiveDate,
+    pub sessions: i64,
+    pub volume: f64,
+}
+
+impl FromSqliteRow for TagMonthlyVolume {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            tag_name: row.get("tag_name")?,
+            month: row.get("month")?,
+            sessions: row.get("sessions")?,
+            volume: row.get("volume")?,
+        })
+    }
+}
+
+/// Why `name` (already trimmed) cannot be used as a tag, if it cannot.
+pub fn tag_name_error(name: &str) -> Option<String> {
+    if name.is_empty() {
+        Some("Tag name is required".to_string())
+    } else if name.chars().count() > MAX_TAG_NAME_LEN {
+        Some(format!(
+            "Tag names must be at most {MAX_TAG_NAME_LEN} characters"
+        ))
+    } else if name.chars().any(|c| c.is_control() || c == ',') {
+        Some("Tag names cannot contain commas or control characters".to_string())
+    } else {
+        None
+    }
+}
+
+/// The tags picked on a workout form: the ids of the ticked `tag.<id>`
+/// checkboxes, and the names typed into the comma-separated `new_tags`
+/// field (trimmed, blanks and repeats dropped).
+pub fn tag_selection<S: BuildHasher>(
+    fields: &HashMap<String, String, S>,
+) -> (Vec<String>, Vec<String>) {
+    let mut ids: Vec<String> = fields
+        .keys()
+        .filter_map(|key| key.strip_prefix("tag."))
+        .map(str::to_string)
+        .collect();
+    ids.sort();
+
+    let mut names: Vec<String> = Vec::new();
+    for name in fields
+        .get("new_tags")
+        .map(String::as_str)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+    {
+        if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+    (ids, names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_selection_reads_checkboxes_and_new_names() {
+        let fields: HashMap<String, String> = [
+            ("tag.b", "on"),
+            ("tag.a", "on"),
+            ("date", "2024-01-15"),
+            ("new_tags", " Push, deload ,, push "),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let (ids, names) = tag_selection(&fields);
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(names, vec!["Push", "deload"]);
+    }
+
+    #[test]
+    fn validates_tag_names() {
+        assert!(tag_name_error("competition").is_none());
+        assert!(tag_name_error("").is_some());
+        assert!(tag_name_error(&"x".repeat(MAX_TAG_NAME_LEN + 1)).is_some());
+        assert!(tag_name_error("a\u{1f}b").is_some());
+    }
+}
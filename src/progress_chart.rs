@@ -0,0 +1,807 @@
+//! Server-rendered progress charts: one metric, several exercises
+//!
+//! Each exercise becomes a series with one value per session, drawn on a
+//! shared date axis and optionally smoothed with a trailing moving average.
+//! Every option comes from the query string, so the chart works without
+//! JavaScript.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::hash::BuildHasher;
+
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::models::{ExerciseSet, estimated_one_rep_max};
+
+/// Exercises one chart overlays.
+pub const MAX_SERIES: usize = 5;
+/// Most sessions drawn per series. Longer series, as an all-time range
+/// soon gives, are thinned evenly, keeping the first and last session.
+pub const MAX_POINTS: usize = 200;
+/// Moving-average windows offered, in sessions; 1 is no smoothing.
+pub const SMOOTHING_CHOICES: [usize; 4] = [1, 3, 5, 10];
+
+/// Line colour of each series, in selection order: the page's theme
+/// colour, and the fixed colour used in standalone images.
+const COLOURS: [(&str, &str); MAX_SERIES] = [
+    ("var(--accent)", "#FF5A10"),
+    ("var(--gold)", "#FFC233"),
+    ("var(--success)", "#34D86A"),
+    ("var(--silver-dark)", "#A2A9BA"),
+    ("var(--danger)", "#F45555"),
+];
+
+const PAD_L: f64 = 52.0;
+const PAD_R: f64 = 12.0;
+const PAD_T: f64 = 14.0;
+const PAD_B: f64 = 28.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartMetric {
+    /// Heaviest set.
+    TopSet,
+    /// Best Epley estimate of any set.
+    E1rm,
+    Volume,
+    /// Most reps in a set.
+    TopReps,
+    /// Heaviest set within the chosen `RepRange`.
+    RepRangeBest,
+}
+
+impl ChartMetric {
+    pub const ALL: [Self; 5] = [
+        Self::TopSet,
+        Self::E1rm,
+        Self::Volume,
+        Self::TopReps,
+        Self::RepRangeBest,
+    ];
+
+    /// The metric for a `?metric=` value; top set when blank.
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value {
+            "" | "top_set" => Some(Self::TopSet),
+            "e1rm" => Some(Self::E1rm),
+            "volume" => Some(Self::Volume),
+            "top_reps" => Some(Self::TopReps),
+            "rep_range" => Some(Self::RepRangeBest),
+            _ => None,
+        }
+    }
+
+    pub fn param(self) -> &'static str {
+        match self {
+            Self::TopSet => "top_set",
+            Self::E1rm => "e1rm",
+            Self::Volume => "volume",
+            Self::TopReps => "top_reps",
+            Self::RepRangeBest => "rep_range",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::TopSet => "Top set",
+            Self::E1rm => "e1RM",
+            Self::Volume => "Volume",
+            Self::TopReps => "Top reps",
+            Self::RepRangeBest => "Best set in rep range",
+        }
+    }
+
+    fn format_value(self, value: f64) -> String {
+        match self {
+            Self::TopReps => format!("{value:.0} reps"),
+            Self::Volume => format!("{value:.0} kg"),
+            _ => format!("{} kg", (value * 10.0).round() / 10.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepRange {
+    Low,
+    Mid,
+    High,
+}
+
+impl RepRange {
+    pub const ALL: [Self; 3] = [Self::Low, Self::Mid, Self::High];
+
+    /// The range for a `?reps=` value; 1–5 reps when blank.
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value {
+            "" | "low" => Some(Self::Low),
+            "mid" => Some(Self::Mid),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    pub fn param(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Mid => "mid",
+            Self::High => "high",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Low => "1\u{2013}5 reps",
+            Self::Mid => "6\u{2013}12 reps",
+            Self::High => "13+ reps",
+        }
+    }
+
+    pub fn contains(self, reps: i32) -> bool {
+        match self {
+            Self::Low => (1..=5).contains(&reps),
+            Self::Mid => (6..=12).contains(&reps),
+            Self::High => reps >= 13,
+        }
+    }
+}
+
+/// Preset date ranges, ending today.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartRange {
+    ThreeMonths,
+    SixMonths,
+    Year,
+    All,
+}
+
+impl ChartRange {
+    pub const ALL: [Self; 4] = [Self::ThreeMonths, Self::SixMonths, Self::Year, Self::All];
+
+    /// The range for a `?range=` value; a year when blank.
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value {
+            "3m" => Some(Self::ThreeMonths),
+            "6m" => Some(Self::SixMonths),
+            "" | "1y" => Some(Self::Year),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+
+    pub fn param(self) -> &'static str {
+        match self {
+            Self::ThreeMonths => "3m",
+            Self::SixMonths => "6m",
+            Self::Year => "1y",
+            Self::All => "all",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ThreeMonths => "3 months",
+            Self::SixMonths => "6 months",
+            Self::Year => "1 year",
+            Self::All => "All time",
+        }
+    }
+
+    /// First day of the range ending `today`; `None` for all time.
+    pub fn start(self, today: NaiveDate) -> Option<NaiveDate> {
+        let months = match self {
+            Self::ThreeMonths => 3,
+            Self::SixMonths => 6,
+            Self::Year => 12,
+            Self::All => return None,
+        };
+        today.checked_sub_months(Months::new(months))
+    }
+}
+
+/// Chart size in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChartSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ChartSize {
+    pub const DEFAULT: Self = Self {
+        width: 600,
+        height: 260,
+    };
+    pub const MIN: Self = Self {
+        width: 300,
+        height: 160,
+    };
+    pub const MAX: Self = Self {
+        width: 2400,
+        height: 1600,
+    };
+
+    /// The size for `?width=` and `?height=` values, each defaulting when
+    /// blank.
+    pub fn parse(width: &str, height: &str) -> Result<Self, String> {
+        let dimension = |name: &str, value: &str, default: u32, min: u32, max: u32| {
+            if value.is_empty() {
+                return Ok(default);
+            }
+            value
+                .parse()
+                .ok()
+                .filter(|n| (min..=max).contains(n))
+                .ok_or_else(|| format!("The {name} must be from {min} to {max} pixels"))
+        };
+        Ok(Self {
+            width: dimension(
+                "width",
+                width,
+                Self::DEFAULT.width,
+                Self::MIN.width,
+                Self::MAX.width,
+            )?,
+            height: dimension(
+                "height",
+                height,
+                Self::DEFAULT.height,
+                Self::MIN.height,
+                Self::MAX.height,
+            )?,
+        })
+    }
+}
+
+/// What to chart, from the progress page's query string. Exercises are
+/// ticked `ex.<id>` checkboxes, as tags are on the workout form.
+#[derive(Debug, Clone)]
+pub struct ChartOptions {
+    /// Sorted; at most `MAX_SERIES`.
+    pub exercise_ids: Vec<String>,
+    pub metric: ChartMetric,
+    /// Only used by `ChartMetric::RepRangeBest`.
+    pub rep_range: RepRange,
+    pub range: ChartRange,
+    /// A custom range; either end replaces the preset `range`.
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Moving-average window in sessions; 1 is no smoothing.
+    pub smoothing: usize,
+    pub size: ChartSize,
+}
+
+impl ChartOptions {
+    pub fn parse<S: BuildHasher>(fields: &HashMap<String, String, S>) -> Result<Self, String> {
+        let field = |name: &str| fields.get(name).map_or("", |v| v.trim());
+
+        let mut exercise_ids: Vec<String> = fields
+            .keys()
+            .filter_map(|key| key.strip_prefix("ex."))
+            .map(str::to_string)
+            .collect();
+        exercise_ids.sort();
+        if exercise_ids.len() > MAX_SERIES {
+            return Err(format!("Pick at most {MAX_SERIES} exercises"));
+        }
+
+        let metric = ChartMetric::from_param(field("metric"))
+            .ok_or_else(|| format!("Invalid metric: {}", field("metric")))?;
+        let rep_range = RepRange::from_param(field("reps"))
+            .ok_or_else(|| format!("Invalid rep range: {}", field("reps")))?;
+        let range = ChartRange::from_param(field("range"))
+            .ok_or_else(|| format!("Invalid range: {}", field("range")))?;
+        let date = |name: &str| -> Result<Option<NaiveDate>, String> {
+            let value = field(name);
+            if value.is_empty() {
+                return Ok(None);
+            }
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_err| format!("Invalid date: {value}"))
+        };
+        let (from, to) = (date("from")?, date("to")?);
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            return Err("The start date is after the end date".to_string());
+        }
+        let smoothing = match field("smooth") {
+            "" => 1,
+            value => value
+                .parse()
+                .ok()
+                .filter(|n| SMOOTHING_CHOICES.contains(n))
+                .ok_or_else(|| format!("Invalid smoothing: {value}"))?,
+        };
+        let size = ChartSize::parse(field("width"), field("height"))?;
+
+        Ok(Self {
+            exercise_ids,
+            metric,
+            rep_range,
+            range,
+            from,
+            to,
+            smoothing,
+            size,
+        })
+    }
+
+    /// The metric, range and smoothing as a query string, for links to the
+    /// same chart in another form.
+    pub fn query(&self) -> String {
+        let mut query = format!(
+            "metric={}&reps={}&range={}&smooth={}",
+            self.metric.param(),
+            self.rep_range.param(),
+            self.range.param(),
+            self.smoothing
+        );
+        if let Some(from) = self.from {
+            let _ = write!(query, "&from={from}");
+        }
+        if let Some(to) = self.to {
+            let _ = write!(query, "&to={to}");
+        }
+        query
+    }
+
+    /// The dates to chart: the custom range when one was given, otherwise
+    /// the preset range ending `today`. Either end may be open.
+    pub fn dates(&self, today: NaiveDate) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        if self.from.is_some() || self.to.is_some() {
+            (self.from, self.to)
+        } else {
+            (self.range.start(today), None)
+        }
+    }
+}
+
+/// One exercise's chart values, one per session, oldest first.
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub exercise_id: String,
+    pub exercise_name: String,
+    pub values: Vec<(NaiveDate, f64)>,
+}
+
+/// `metric` over one session's sets of one exercise. Each side of a
+/// unilateral set counts as its own set. `None` when no set qualifies.
+fn session_value(sets: &[&ExerciseSet], metric: ChartMetric, rep_range: RepRange) -> Option<f64> {
+    let sides = sets.iter().flat_map(|set| {
+        std::iter::once((set.weight, set.reps)).chain(set.right_weight.zip(set.right_reps))
+    });
+    match metric {
+        ChartMetric::TopSet => sides.map(|(weight, _)| weight).reduce(f64::max),
+        ChartMetric::E1rm => sides
+            .map(|(weight, reps)| estimated_one_rep_max(weight, reps))
+            .reduce(f64::max),
+        ChartMetric::Volume => Some(sides.map(|(weight, reps)| weight * f64::from(reps)).sum()),
+        ChartMetric::TopReps => sides.map(|(_, reps)| reps).max().map(f64::from),
+        ChartMetric::RepRangeBest => sides
+            .filter(|(_, reps)| rep_range.contains(*reps))
+            .map(|(weight, _)| weight)
+            .reduce(f64::max),
+    }
+}
+
+/// One series per `(id, name)` in `exercises`, in that order, from `sets`
+/// in session order (as `get_sets_for_exercises` returns them).
+pub fn series(
+    sets: &[ExerciseSet],
+    exercises: &[(String, String)],
+    metric: ChartMetric,
+    rep_range: RepRange,
+) -> Vec<Series> {
+    exercises
+        .iter()
+        .map(|(id, name)| {
+            let sets: Vec<&ExerciseSet> = sets.iter().filter(|s| s.exercise_id == *id).collect();
+            let values = sets
+                .chunk_by(|a, b| a.session_id == b.session_id)
+                .filter_map(|session| {
+                    session_value(session, metric, rep_range).map(|v| (session[0].date, v))
+                })
+                .collect();
+            Series {
+                exercise_id: id.clone(),
+                exercise_name: name.clone(),
+                values,
+            }
+        })
+        .collect()
+}
+
+/// Trailing mean of up to `window` values ending at each value.
+pub fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    (0..values.len())
+        .map(|i| {
+            let slice = &values[(i + 1).saturating_sub(window)..=i];
+            slice.iter().sum::<f64>() / slice.len() as f64
+        })
+        .collect()
+}
+
+pub struct ChartDot {
+    pub x: f64,
+    pub y: f64,
+    /// Hover text, e.g. "Squat 2024-03-05: 120 kg".
+    pub title: String,
+    /// The session beat every earlier one in the series: a running best.
+    pub is_pr: bool,
+}
+
+pub struct RenderedSeries {
+    pub exercise_id: String,
+    pub exercise_name: String,
+    pub colour: &'static str,
+    /// `colour` as a literal, for standalone images.
+    pub hex: &'static str,
+    /// Polyline `points` attribute through the (smoothed) values.
+    pub polyline: String,
+    /// One dot per session drawn, at the raw value.
+    pub dots: Vec<ChartDot>,
+    /// The latest raw value, formatted; `None` with nothing in range.
+    pub latest: Option<String>,
+}
+
+/// Geometry for the progress chart SVG, computed here so the template
+/// stays declarative.
+pub struct ProgressChart {
+    pub width: f64,
+    pub height: f64,
+    pub padding_left: f64,
+    pub padding_right: f64,
+    pub series: Vec<RenderedSeries>,
+    /// Y-axis labels: (`y_pixel`, `label_text`).
+    pub y_ticks: Vec<(f64, String)>,
+    /// X-axis date labels: (`x_pixel`, `label_text`).
+    pub x_ticks: Vec<(f64, String)>,
+}
+
+/// Indices of the values drawn from a series of `len`: all of them, or
+/// `MAX_POINTS` spread evenly from the first to the last.
+fn drawn_indices(len: usize) -> Vec<usize> {
+    if len <= MAX_POINTS {
+        (0..len).collect()
+    } else {
+        (0..MAX_POINTS)
+            .map(|i| i * (len - 1) / (MAX_POINTS - 1))
+            .collect()
+    }
+}
+
+/// Whether each value beats every one before it.
+fn running_bests(values: &[(NaiveDate, f64)]) -> Vec<bool> {
+    values
+        .iter()
+        .scan(f64::NEG_INFINITY, |best, (_, value)| {
+            let is_best = *value > *best;
+            *best = best.max(*value);
+            Some(is_best)
+        })
+        .collect()
+}
+
+/// Draw `series` on shared axes at `options.size`, lines smoothed over
+/// `options.smoothing` sessions. `None` when no series has a value.
+pub fn render_chart(series: &[Series], options: &ChartOptions) -> Option<ProgressChart> {
+    let ChartOptions {
+        metric,
+        smoothing,
+        size,
+        ..
+    } = *options;
+    let smoothed: Vec<Vec<f64>> = series
+        .iter()
+        .map(|s| {
+            let raw: Vec<f64> = s.values.iter().map(|(_, v)| *v).collect();
+            moving_average(&raw, smoothing)
+        })
+        .collect();
+    let values = series.iter().flat_map(|s| s.values.iter().map(|(_, v)| *v));
+    let min = values.clone().reduce(f64::min)?;
+    let max = values.reduce(f64::max)?;
+    let dates = series.iter().flat_map(|s| s.values.iter().map(|(d, _)| *d));
+    let first = dates.clone().min()?;
+    let last = dates.max()?;
+
+    // Pad the y range a little so lines aren't flush against the edges.
+    let (y_min, y_max) = if (max - min).abs() < 1e-9 {
+        (min - 1.0, max + 1.0)
+    } else {
+        let pad = (max - min) * 0.1;
+        (min - pad, max + pad)
+    };
+    let (width, height) = (f64::from(size.width), f64::from(size.height));
+    let plot_w = width - PAD_L - PAD_R;
+    let plot_h = height - PAD_T - PAD_B;
+    let span = (last - first).num_days();
+    let x_of = |date: NaiveDate| {
+        if span == 0 {
+            PAD_L + plot_w / 2.0
+        } else {
+            PAD_L + (date - first).num_days() as f64 / span as f64 * plot_w
+        }
+    };
+    let y_of = |value: f64| PAD_T + (1.0 - (value - y_min) / (y_max - y_min)) * plot_h;
+
+    let rendered = series
+        .iter()
+        .zip(&smoothed)
+        .zip(COLOURS.iter().cycle())
+        .map(|((s, line), (colour, hex))| {
+            let drawn = drawn_indices(s.values.len());
+            let bests = running_bests(&s.values);
+            RenderedSeries {
+                exercise_id: s.exercise_id.clone(),
+                exercise_name: s.exercise_name.clone(),
+                colour,
+                hex,
+                polyline: drawn
+                    .iter()
+                    .map(|&i| format!("{:.2},{:.2}", x_of(s.values[i].0), y_of(line[i])))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                dots: drawn
+                    .iter()
+                    .map(|&i| {
+                        let (date, value) = s.values[i];
+                        ChartDot {
+                            x: x_of(date),
+                            y: y_of(value),
+                            title: format!(
+                                "{} {date}: {}",
+                                s.exercise_name,
+                                metric.format_value(value)
+                            ),
+                            is_pr: bests[i],
+                        }
+                    })
+                    .collect(),
+                latest: s.values.last().map(|(_, v)| metric.format_value(*v)),
+            }
+        })
+        .collect();
+
+    // 4 evenly spaced y ticks.
+    let y_ticks = (0..4)
+        .map(|i| {
+            let frac = f64::from(i) / 3.0;
+            let value = y_max - frac * (y_max - y_min);
+            (PAD_T + frac * plot_h, format!("{value:.0}"))
+        })
+        .collect();
+
+    // Up to 5 evenly spaced dates; the year is shown once the range
+    // crosses one.
+    let format = if first.year() == last.year() {
+        "%m-%d"
+    } else {
+        "%Y-%m"
+    };
+    let label_count = if span == 0 { 1 } else { 5.min(span + 1) };
+    let x_ticks = (0..label_count)
+        .map(|i| {
+            let offset = if label_count == 1 {
+                0
+            } else {
+                i * span / (label_count - 1)
+            };
+            let date = first + chrono::Duration::days(offset);
+            (x_of(date), date.format(format).to_string())
+        })
+        .collect();
+
+    Some(ProgressChart {
+        width,
+        height,
+        padding_left: PAD_L,
+        padding_right: PAD_R,
+        series: rendered,
+        y_ticks,
+        x_ticks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, m, d).unwrap()
+    }
+
+    fn set(exercise: &str, session: &str, day: u32, reps: i32, weight: f64) -> ExerciseSet {
+        ExerciseSet {
+            exercise_id: exercise.to_string(),
+            session_id: session.to_string(),
+            date: date(1, day),
+            reps,
+            weight,
+            right_reps: None,
+            right_weight: None,
+        }
+    }
+
+    fn fields(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    fn one_value_per_session_for_each_metric() {
+        let sets = vec![
+            set("squat", "a", 1, 5, 100.0),
+            set("squat", "a", 1, 15, 80.0),
+            set("bench", "a", 1, 3, 90.0),
+            set("squat", "b", 8, 2, 110.0),
+        ];
+        let exercises = vec![
+            ("squat".to_string(), "Squat".to_string()),
+            ("bench".to_string(), "Bench".to_string()),
+        ];
+        let values = |metric, reps| {
+            series(&sets, &exercises, metric, reps)
+                .into_iter()
+                .map(|s| s.values.into_iter().map(|(_, v)| v).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        let top = values(ChartMetric::TopSet, RepRange::Low);
+        assert_eq!(top, vec![vec![100.0, 110.0], vec![90.0]]);
+        assert_eq!(
+            values(ChartMetric::Volume, RepRange::Low)[0],
+            vec![1700.0, 220.0]
+        );
+        assert_eq!(
+            values(ChartMetric::TopReps, RepRange::Low)[0],
+            vec![15.0, 2.0]
+        );
+        // The 15-rep set beats the 5-rep one on e1RM.
+        let e1rm = values(ChartMetric::E1rm, RepRange::Low);
+        assert!((e1rm[0][0] - 120.0).abs() < 1e-9);
+        // Sessions without a set in range are skipped.
+        assert_eq!(
+            values(ChartMetric::RepRangeBest, RepRange::High)[0],
+            vec![80.0]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    fn unilateral_sides_count_as_sets() {
+        let mut lunge = set("lunge", "a", 1, 8, 20.0);
+        lunge.right_reps = Some(6);
+        lunge.right_weight = Some(24.0);
+        let exercises = vec![("lunge".to_string(), "Lunge".to_string())];
+        let top = series(&[lunge], &exercises, ChartMetric::TopSet, RepRange::Low);
+        assert_eq!(top[0].values[0].1, 24.0);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    fn moving_average_trails() {
+        assert_eq!(
+            moving_average(&[3.0, 6.0, 9.0, 12.0], 3),
+            vec![3.0, 4.5, 6.0, 9.0]
+        );
+        assert_eq!(moving_average(&[3.0, 6.0], 1), vec![3.0, 6.0]);
+    }
+
+    #[test]
+    fn renders_series_on_shared_axes() {
+        let series = vec![
+            Series {
+                exercise_id: "squat".to_string(),
+                exercise_name: "Squat".to_string(),
+                values: vec![(date(1, 1), 100.0), (date(3, 1), 120.0)],
+            },
+            Series {
+                exercise_id: "bench".to_string(),
+                exercise_name: "Bench".to_string(),
+                values: vec![(date(2, 1), 80.0)],
+            },
+        ];
+        let options = ChartOptions::parse(&fields(&[])).unwrap();
+        let chart = render_chart(&series, &options).unwrap();
+        assert_eq!(chart.series.len(), 2);
+        let squat = &chart.series[0];
+        assert!((squat.dots[0].x - PAD_L).abs() < 1e-9);
+        assert!((squat.dots[1].x - (f64::from(ChartSize::DEFAULT.width) - PAD_R)).abs() < 1e-9);
+        assert_eq!(squat.dots[1].title, "Squat 2024-03-01: 120 kg");
+        assert_eq!(squat.latest.as_deref(), Some("120 kg"));
+        // The bench dot sits between the squat sessions, lowest on the chart.
+        let bench = &chart.series[1];
+        assert!(bench.dots[0].x > squat.dots[0].x && bench.dots[0].x < squat.dots[1].x);
+        assert!(bench.dots[0].y > squat.dots[0].y);
+        assert_eq!(chart.x_ticks.first().unwrap().1, "01-01");
+
+        let empty = vec![Series {
+            values: Vec::new(),
+            ..series[1].clone()
+        }];
+        assert!(render_chart(&empty, &options).is_none());
+
+        let large = ChartOptions::parse(&fields(&[("width", "1200"), ("height", "600")])).unwrap();
+        let chart = render_chart(&series, &large).unwrap();
+        assert!((chart.series[0].dots[1].x - (1200.0 - PAD_R)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn marks_running_bests() {
+        let values: Vec<(NaiveDate, f64)> = [100.0, 100.0, 110.0, 105.0, 120.0]
+            .into_iter()
+            .zip(1..)
+            .map(|(value, day)| (date(1, day), value))
+            .collect();
+        assert_eq!(running_bests(&values), vec![true, false, true, false, true]);
+    }
+
+    #[test]
+    fn long_series_are_thinned_to_max_points() {
+        let first = date(1, 1);
+        let values: Vec<(NaiveDate, f64)> = (0..MAX_POINTS as i64 * 3)
+            .map(|day| (first + chrono::Duration::days(day), day as f64))
+            .collect();
+        let last = *values.last().unwrap();
+        let series = vec![Series {
+            exercise_id: "squat".to_string(),
+            exercise_name: "Squat".to_string(),
+            values,
+        }];
+        let options = ChartOptions::parse(&fields(&[("range", "all")])).unwrap();
+        let chart = render_chart(&series, &options).unwrap();
+        let squat = &chart.series[0];
+        assert_eq!(squat.dots.len(), MAX_POINTS);
+        assert_eq!(squat.polyline.split(' ').count(), MAX_POINTS);
+        assert!((squat.dots[0].x - PAD_L).abs() < 1e-9);
+        assert!(
+            squat
+                .dots
+                .last()
+                .unwrap()
+                .title
+                .ends_with(&format!("{}: {:.0} kg", last.0, last.1))
+        );
+        assert_eq!(drawn_indices(3), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn parses_options_from_the_query() {
+        let options = ChartOptions::parse(&fields(&[
+            ("ex.b", "on"),
+            ("ex.a", "on"),
+            ("metric", "rep_range"),
+            ("reps", "mid"),
+            ("smooth", "3"),
+        ]))
+        .unwrap();
+        assert_eq!(options.exercise_ids, vec!["a", "b"]);
+        assert_eq!(options.metric, ChartMetric::RepRangeBest);
+        assert_eq!(options.rep_range, RepRange::Mid);
+        assert_eq!(options.smoothing, 3);
+        assert_eq!(
+            options.dates(date(6, 15)),
+            (Some(NaiveDate::from_ymd_opt(2023, 6, 15).unwrap()), None)
+        );
+
+        let custom =
+            ChartOptions::parse(&fields(&[("range", "3m"), ("from", "2024-02-01")])).unwrap();
+        assert_eq!(custom.dates(date(6, 15)), (Some(date(2, 1)), None));
+
+        assert!(ChartOptions::parse(&fields(&[("metric", "speed")])).is_err());
+        assert!(ChartOptions::parse(&fields(&[("smooth", "4")])).is_err());
+        assert!(ChartOptions::parse(&fields(&[("width", "5000")])).is_err());
+        assert!(ChartOptions::parse(&fields(&[("height", "tall")])).is_err());
+        assert!(
+            ChartOptions::parse(&fields(&[("from", "2024-03-01"), ("to", "2024-02-01")])).is_err()
+        );
+        let too_many: HashMap<String, String> = (0..=MAX_SERIES)
+            .map(|i| (format!("ex.{i}"), "on".to_string()))
+            .collect();
+        assert!(ChartOptions::parse(&too_many).is_err());
+    }
+}
@@ -0,0 +1,543 @@
+//! Quick-entry set notation
+//!
+//! Parses the compact notation lifters already write in notebooks into sets
+//! the workout page can log in one go. Each line names an exercise followed
+//! by one or more set groups:
+//!
+//! ```text
+//! bench 100x5x3 @8        three sets of 5 at 100 kg, RPE 8
+//! squat 140x5,5,4         three sets with 5, 5 and 4 reps
+//! ohp 60x8 65x6 @9        two groups on one line; the RPE applies to 65x6
+//! row 225lb x 10          pounds are converted to kilograms
+//! 110x3                   no name: continues the previous line's exercise
+//! pull up bw x 8          bodyweight, logged as 0 kg
+//! squat: 3x5 140          notebook order: sets x reps, then the weight
+//! ```
+//!
+//! The exercise name only has to identify one of the user's exercises:
+//! `bench` finds "Bench Press", `inc dumb` finds "Incline Dumbbell Press", and
+//! a typo or two is tolerated. Parsing is pure; the handler previews the
+//! result and logs it with `WorkoutRepository::create_logs`.
+
+use crate::models::{Exercise, SetValues};
+
+/// Most sets one group may expand to (`100x5x20`).
+pub const MAX_SETS_PER_GROUP: usize = 20;
+
+/// Most sets a single quick entry may log.
+pub const MAX_SETS: usize = 100;
+
+const KG_PER_LB: f64 = 0.453_592_37;
+
+/// One set ready to log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickSet {
+    pub exercise_id: String,
+    pub exercise_name: String,
+    pub unilateral: bool,
+    pub weight: f64,
+    pub reps: i32,
+    pub rpe: Option<i32>,
+}
+
+impl QuickSet {
+    pub(crate) fn from_parsed(exercise: &Exercise, set: &ParsedSet) -> Self {
+        Self {
+            exercise_id: exercise.id.clone(),
+            exercise_name: exercise.name.clone(),
+            unilateral: exercise.unilateral,
+            weight: set.weight,
+            reps: set.reps,
+            rpe: set.rpe,
+        }
+    }
+
+    /// The values to log: a unilateral exercise gets the same on both sides.
+    pub fn values(&self) -> SetValues {
+        SetValues::from_form(
+            self.unilateral,
+            self.reps,
+            self.weight,
+            self.rpe,
+            None,
+            None,
+        )
+    }
+}
+
+/// A line that could not be parsed, numbered from 1 as shown to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineError {
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+/// The result of parsing a whole quick entry. Lines that parse are kept even
+/// when others fail, so the preview can show both.
+#[derive(Debug, Default)]
+pub struct QuickEntry {
+    pub sets: Vec<QuickSet>,
+    pub errors: Vec<LineError>,
+}
+
+impl QuickEntry {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty() && !self.sets.is_empty()
+    }
+}
+
+/// A line's sets before the exercise name is resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParsedLine {
+    pub(crate) name: Option<String>,
+    pub(crate) sets: Vec<ParsedSet>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ParsedSet {
+    pub(crate) weight: f64,
+    pub(crate) reps: i32,
+    pub(crate) rpe: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Bodyweight,
+    Pounds,
+    Kilograms,
+    Times,
+    Comma,
+    At,
+}
+
+/// Parse `input` against the user's `exercises`.
+pub fn parse(input: &str, exercises: &[Exercise]) -> QuickEntry {
+    let mut entry = QuickEntry::default();
+    let mut current: Option<&Exercise> = None;
+
+    for (index, text) in input.lines().enumerate() {
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let mut fail = |message: String| {
+            entry.errors.push(LineError {
+                line: index + 1,
+                text: text.to_string(),
+                message,
+            });
+        };
+
+        let line = match parse_line(text) {
+            Ok(line) => line,
+            Err(message) => {
+                fail(message);
+                continue;
+            }
+        };
+        if let Some(name) = &line.name {
+            current = match_exercise(name, exercises);
+            if current.is_none() {
+                fail(format!("No exercise matches \"{name}\""));
+                continue;
+            }
+        }
+        let Some(exercise) = current else {
+            fail("Start the line with an exercise name".to_string());
+            continue;
+        };
+
+        entry.sets.extend(
+            line.sets
+                .iter()
+                .map(|set| QuickSet::from_parsed(exercise, set)),
+        );
+    }
+
+    if entry.sets.len() > MAX_SETS {
+        entry.errors.push(LineError {
+            line: 0,
+            text: String::new(),
+            message: format!("At most {MAX_SETS} sets can be added at once"),
+        });
+    }
+    entry
+}
+
+/// Split a line into its exercise name (everything before the first number
+/// or `bw`, minus a trailing `:` or `-`) and its set groups.
+pub(crate) fn parse_line(text: &str) -> Result<ParsedLine, String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let spec_start = words
+        .iter()
+        .position(|w| {
+            w.starts_with(|c: char| c.is_ascii_digit() || c == '@' || c == '.')
+                || w.eq_ignore_ascii_case("bw")
+                || w.to_ascii_lowercase().starts_with("bwx")
+        })
+        .unwrap_or(words.len());
+    let name = words[..spec_start]
+        .join(" ")
+        .trim_end_matches([':', '-', '–'])
+        .trim_end()
+        .to_string();
+    let spec = words[spec_start..].join(" ");
+    if spec.is_empty() {
+        return Err("Expected sets such as 100x5".to_string());
+    }
+
+    Ok(ParsedLine {
+        name: (!name.is_empty()).then_some(name),
+        sets: parse_groups(&tokenize(&spec)?)?,
+    })
+}
+
+fn tokenize(spec: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = spec.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit() || **d == '.') {
+                number.push(d);
+                chars.next();
+            }
+            let value = number
+                .parse()
+                .map_err(|_err| format!("\"{number}\" is not a number"))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() {
+            let mut word = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_alphabetic()) {
+                word.push(d.to_ascii_lowercase());
+                chars.next();
+            }
+            // "x" may run into a unit or "bw": "100kgx5", "bwx8".
+            let mut rest = word.as_str();
+            while !rest.is_empty() {
+                let (token, len) = if let Some(unit) =
+                    ["kgs", "kg"].iter().find(|u| rest.starts_with(**u))
+                {
+                    (Token::Kilograms, unit.len())
+                } else if let Some(unit) = ["lbs", "lb"].iter().find(|u| rest.starts_with(**u)) {
+                    (Token::Pounds, unit.len())
+                } else if rest.starts_with("bw") {
+                    (Token::Bodyweight, 2)
+                } else if rest.starts_with('x') {
+                    (Token::Times, 1)
+                } else {
+                    return Err(format!("Unexpected \"{word}\""));
+                };
+                tokens.push(token);
+                rest = &rest[len..];
+            }
+        } else {
+            let token = match c {
+                '×' | '*' => Token::Times,
+                ',' => Token::Comma,
+                '@' => Token::At,
+                _ => return Err(format!("Unexpected \"{c}\"")),
+            };
+            tokens.push(token);
+            chars.next();
+        }
+    }
+    Ok(tokens)
+}
+
+/// `group := weight [unit] x reps (, reps)* [x sets] [@ rpe]`, repeated.
+/// The notebook order `sets x reps weight [unit]` ("3x5 140") is accepted
+/// too: a plain `a x b` followed by a weight that is not itself followed by
+/// `x` can only mean that.
+fn parse_groups(tokens: &[Token]) -> Result<Vec<ParsedSet>, String> {
+    let mut sets = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let Some(mut weight) = weight_at(tokens, &mut i) else {
+            return Err("Each group must start with a weight, like 100x5".to_string());
+        };
+        if tokens.get(i) != Some(&Token::Times) {
+            return Err("Expected \"x\" between weight and reps, like 100x5".to_string());
+        }
+        i += 1;
+
+        let mut reps = vec![whole_number(tokens.get(i), "reps")?];
+        i += 1;
+        while tokens.get(i) == Some(&Token::Comma) {
+            reps.push(whole_number(tokens.get(i + 1), "reps")?);
+            i += 2;
+        }
+        let mut count = None;
+        if tokens.get(i) == Some(&Token::Times) {
+            if reps.len() > 1 {
+                return Err("Use either 100x5x3 or 100x5,5,5, not both".to_string());
+            }
+            count = Some(whole_number(tokens.get(i + 1), "sets")?);
+            i += 2;
+        } else if reps.len() == 1 {
+            let mut next = i;
+            if let Some(notebook_weight) = weight_at(tokens, &mut next)
+                && tokens.get(next) != Some(&Token::Times)
+            {
+                count = Some(whole_number(Some(&Token::Number(weight)), "sets")?);
+                weight = notebook_weight;
+                i = next;
+            }
+        }
+        if let Some(count) = count {
+            let count = usize::try_from(count).unwrap_or(0);
+            if !(1..=MAX_SETS_PER_GROUP).contains(&count) {
+                return Err(format!("Sets must be between 1 and {MAX_SETS_PER_GROUP}"));
+            }
+            reps = vec![reps[0]; count];
+        }
+        let rpe = if tokens.get(i) == Some(&Token::At) {
+            let rpe = whole_number(tokens.get(i + 1), "RPE")?;
+            if !(1..=10).contains(&rpe) {
+                return Err("RPE must be between 1 and 10".to_string());
+            }
+            i += 2;
+            Some(rpe)
+        } else {
+            None
+        };
+
+        if reps.iter().any(|&r| r < 1) {
+            return Err("Reps must be at least 1".to_string());
+        }
+        sets.extend(reps.into_iter().map(|reps| ParsedSet { weight, reps, rpe }));
+    }
+    Ok(sets)
+}
+
+/// Read a weight and its optional unit at `tokens[*i]`, in kilograms,
+/// advancing `i` past them.
+fn weight_at(tokens: &[Token], i: &mut usize) -> Option<f64> {
+    let weight = match tokens.get(*i)? {
+        Token::Number(w) => *w,
+        Token::Bodyweight => 0.0,
+        _ => return None,
+    };
+    *i += 1;
+    match tokens.get(*i) {
+        Some(Token::Pounds) => {
+            *i += 1;
+            Some((weight * KG_PER_LB * 100.0).round() / 100.0)
+        }
+        Some(Token::Kilograms) => {
+            *i += 1;
+            Some(weight)
+        }
+        _ => Some(weight),
+    }
+}
+
+fn whole_number(token: Option<&Token>, what: &str) -> Result<i32, String> {
+    match token {
+        Some(Token::Number(n)) if n.fract() == 0.0 && *n <= f64::from(i32::MAX) =>
+        {
+            #[allow(
+                clippy::cast_possible_truncation,
+                reason = "checked whole and in range"
+            )]
+            Ok(*n as i32)
+        }
+        _ => Err(format!("Expected a whole number of {what}")),
+    }
+}
+
+/// Find the exercise `query` most plausibly refers to. In order of
+/// preference: an exact name, a name starting with the query, a name whose
+/// words each start with one of the query's words ("inc db press"), and a
+/// name within a small edit distance of the query. Ties go to the shortest
+/// name, so "bench" picks "Bench Press" over "Bench Press (Close Grip)".
+pub fn match_exercise<'a>(query: &str, exercises: &'a [Exercise]) -> Option<&'a Exercise> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    let query_words: Vec<&str> = query.split_whitespace().collect();
+
+    exercises
+        .iter()
+        .filter_map(|exercise| {
+            let name = exercise.name.to_lowercase();
+            let name_words: Vec<&str> = name
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| !w.is_empty())
+                .collect();
+            let tier = if name == query {
+                0
+            } else if name.starts_with(&query) {
+                1
+            } else if query_words
+                .iter()
+                .all(|q| name_words.iter().any(|w| w.starts_with(q)))
+            {
+                2
+            } else if edit_distance(&name, &query) <= (query.chars().count() / 4).min(2) {
+                3
+            } else {
+                return None;
+            };
+            Some((tier, name.len(), exercise))
+        })
+        .min_by_key(|(tier, len, _)| (*tier, *len))
+        .map(|(_, _, exercise)| exercise)
+}
+
+/// Levenshtein distance over characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1)
+                .min(row[j] + 1)
+                .min(diagonal + usize::from(ca != cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(id: &str, name: &str) -> Exercise {
+        Exercise {
+            id: id.to_string(),
+            name: name.to_string(),
+            category: "chest".to_string(),
+            user_id: Some("user1".to_string()),
+            catalog_id: None,
+            archived_at: None,
+            unilateral: false,
+        }
+    }
+
+    fn exercises() -> Vec<Exercise> {
+        vec![
+            exercise("bench", "Bench Press"),
+            exercise("bench-cg", "Bench Press (Close Grip)"),
+            exercise("incline", "Incline Dumbbell Press"),
+            exercise("squat", "Back Squat"),
+            exercise("pullup", "Pull Up"),
+        ]
+    }
+
+    fn sets(entry: &QuickEntry) -> Vec<(&str, f64, i32, Option<i32>)> {
+        entry
+            .sets
+            .iter()
+            .map(|s| (s.exercise_id.as_str(), s.weight, s.reps, s.rpe))
+            .collect()
+    }
+
+    #[test]
+    fn expands_weight_reps_sets_with_rpe() {
+        let entry = parse("bench 100x5x3 @8", &exercises());
+        assert!(entry.is_valid());
+        assert_eq!(sets(&entry), vec![("bench", 100.0, 5, Some(8)); 3]);
+    }
+
+    #[test]
+    fn per_set_reps_and_multiple_groups() {
+        let entry = parse("squat 140x5,5,4 150 x 3 @9", &exercises());
+        assert_eq!(
+            sets(&entry),
+            vec![
+                ("squat", 140.0, 5, None),
+                ("squat", 140.0, 5, None),
+                ("squat", 140.0, 4, None),
+                ("squat", 150.0, 3, Some(9)),
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_pounds_and_accepts_bodyweight() {
+        let entry = parse("bench 225lbx5\npull up bw x 8", &exercises());
+        assert_eq!(
+            sets(&entry),
+            vec![("bench", 102.06, 5, None), ("pullup", 0.0, 8, None)]
+        );
+        let entry = parse("bench 100 kg × 5", &exercises());
+        assert_eq!(sets(&entry), vec![("bench", 100.0, 5, None)]);
+    }
+
+    #[test]
+    fn accepts_notebook_sets_by_reps_then_weight() {
+        let entry = parse("Squat: 3x5 140\nbench - 2x8 60kg @7", &exercises());
+        assert_eq!(
+            sets(&entry),
+            vec![
+                ("squat", 140.0, 5, None),
+                ("squat", 140.0, 5, None),
+                ("squat", 140.0, 5, None),
+                ("bench", 60.0, 8, Some(7)),
+                ("bench", 60.0, 8, Some(7)),
+            ]
+        );
+        // Followed by "x", the third number starts a new group instead.
+        let entry = parse("bench 100x5 110x3", &exercises());
+        assert_eq!(
+            sets(&entry),
+            vec![("bench", 100.0, 5, None), ("bench", 110.0, 3, None)]
+        );
+    }
+
+    #[test]
+    fn lines_without_a_name_continue_the_previous_exercise() {
+        let entry = parse("bench 100x5\n110x3", &exercises());
+        assert_eq!(
+            sets(&entry),
+            vec![("bench", 100.0, 5, None), ("bench", 110.0, 3, None)]
+        );
+
+        let entry = parse("100x5", &exercises());
+        assert_eq!(entry.errors.len(), 1);
+        assert!(!entry.is_valid());
+    }
+
+    #[test]
+    fn reports_errors_per_line_and_keeps_good_lines() {
+        let entry = parse(
+            "bench 100x5\ndeadlift 200x1\nsquat 100x\nbench 100x5 @11",
+            &exercises(),
+        );
+        assert_eq!(entry.sets.len(), 1);
+        let lines: Vec<usize> = entry.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![2, 3, 4]);
+        assert!(entry.errors[0].message.contains("deadlift"));
+    }
+
+    #[test]
+    fn rejects_mixed_and_oversized_groups() {
+        assert!(!parse("bench 100x5,5x3", &exercises()).is_valid());
+        assert!(!parse("bench 100x5x21", &exercises()).is_valid());
+        assert!(!parse("bench 100x0", &exercises()).is_valid());
+        assert!(!parse("bench", &exercises()).is_valid());
+    }
+
+    #[test]
+    fn matches_exercises_by_prefix_words_and_typos() {
+        let all = exercises();
+        let id = |q: &str| match_exercise(q, &all).map(|e| e.id.as_str());
+        assert_eq!(id("bench"), Some("bench"));
+        assert_eq!(id("Bench Press (Close Grip)"), Some("bench-cg"));
+        assert_eq!(id("inc db"), None);
+        assert_eq!(id("inc dumb"), Some("incline"));
+        assert_eq!(id("squat"), Some("squat"));
+        assert_eq!(id("bnech press"), Some("bench"));
+        assert_eq!(id("deadlift"), None);
+    }
+}
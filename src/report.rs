@@ -0,0 +1,462 @@
+//! Weekly and monthly training reports
+//!
+//! A report covers one calendar week (starting on the user's first day of
+//! the week) or one calendar month, in the user's timezone, and compares it
+//! with the period just before. Everything comes from the
+//! `WorkoutRepository` period aggregates; this module only lines the two
+//! periods up.
+
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::calendar::{YEARS, month_end, month_start, parse_month, week_start};
+use crate::models::{ExercisePeriodTotals, PeriodPersonalRecord, PeriodSummary};
+
+/// Heaviest lifts listed in a report.
+pub const TOP_LIFTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportKind {
+    Week,
+    Month,
+}
+
+impl ReportKind {
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value {
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            _ => None,
+        }
+    }
+
+    pub fn param(self) -> &'static str {
+        match self {
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+}
+
+/// The dates a report covers, both inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportPeriod {
+    pub kind: ReportKind,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl ReportPeriod {
+    /// The week containing `date`.
+    pub fn week(date: NaiveDate, first_day: Weekday) -> Self {
+        let start = week_start(date, first_day);
+        Self {
+            kind: ReportKind::Week,
+            start,
+            end: start + Duration::days(6),
+        }
+    }
+
+    /// The calendar month containing `date`.
+    pub fn month(date: NaiveDate) -> Self {
+        Self {
+            kind: ReportKind::Month,
+            start: month_start(date),
+            end: month_end(date),
+        }
+    }
+
+    /// The period of `kind` named by a `?week=YYYY-MM-DD` (any day of the
+    /// week) or `?month=YYYY-MM` value; the one containing `today` when
+    /// blank. Years outside `calendar::YEARS` are refused, so the periods
+    /// either side can always be worked out.
+    pub fn parse(
+        kind: ReportKind,
+        value: &str,
+        today: NaiveDate,
+        first_day: Weekday,
+    ) -> Result<Self, String> {
+        let value = value.trim();
+        match kind {
+            ReportKind::Week if value.is_empty() => Ok(Self::week(today, first_day)),
+            ReportKind::Week => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .filter(|date| YEARS.contains(&date.year()))
+                .map(|date| Self::week(date, first_day))
+                .ok_or_else(|| format!("Invalid week: {value}")),
+            ReportKind::Month if value.is_empty() => Ok(Self::month(today)),
+            ReportKind::Month => parse_month(value)
+                .map(Self::month)
+                .ok_or_else(|| format!("Invalid month: {value}")),
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self.kind {
+            ReportKind::Week => Self {
+                start: self.start - Duration::weeks(1),
+                end: self.end - Duration::weeks(1),
+                ..self
+            },
+            ReportKind::Month => Self::month(self.start - Duration::days(1)),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self.kind {
+            ReportKind::Week => Self {
+                start: self.start + Duration::weeks(1),
+                end: self.end + Duration::weeks(1),
+                ..self
+            },
+            ReportKind::Month => Self::month(self.end + Duration::days(1)),
+        }
+    }
+
+    /// "Week of 15 Jan 2024" or "January 2024".
+    pub fn title(&self) -> String {
+        match self.kind {
+            ReportKind::Week => format!("Week of {}", self.start.format("%-d %b %Y")),
+            ReportKind::Month => self.start.format("%B %Y").to_string(),
+        }
+    }
+
+    /// Short name for navigation, e.g. "8 Jan" or "Dec".
+    pub fn short_label(&self) -> String {
+        match self.kind {
+            ReportKind::Week => self.start.format("%-d %b").to_string(),
+            ReportKind::Month => self.start.format("%b").to_string(),
+        }
+    }
+
+    /// "Mon 15 Jan – Sun 21 Jan 2024".
+    pub fn range_label(&self) -> String {
+        format!(
+            "{} \u{2013} {}",
+            self.start.format("%a %-d %b"),
+            self.end.format("%a %-d %b %Y")
+        )
+    }
+
+    /// "week" or "month", for "Previous week" and the like.
+    pub fn noun(&self) -> &'static str {
+        self.kind.param()
+    }
+
+    /// Query string selecting this period, e.g. `week=2024-01-15`.
+    pub fn query(&self) -> String {
+        match self.kind {
+            ReportKind::Week => format!("week={}", self.start),
+            ReportKind::Month => format!("month={}", self.start.format("%Y-%m")),
+        }
+    }
+
+    pub fn href(&self) -> String {
+        format!("/reports/{}?{}", self.kind.param(), self.query())
+    }
+
+    pub fn download_href(&self) -> String {
+        format!("/reports/{}/download?{}", self.kind.param(), self.query())
+    }
+
+    /// Name for the downloaded copy, e.g. `liftlog-week-2024-01-15.html`.
+    pub fn filename(&self) -> String {
+        match self.kind {
+            ReportKind::Week => format!("liftlog-week-{}.html", self.start),
+            ReportKind::Month => format!("liftlog-month-{}.html", self.start.format("%Y-%m")),
+        }
+    }
+}
+
+/// One line of the period-over-period table, formatted for display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub label: &'static str,
+    pub current: String,
+    pub previous: String,
+    /// Signed change, e.g. "+2" or "-12%"; blank when there is nothing to
+    /// compare.
+    pub change: String,
+    /// `change-up`, `change-down` or empty.
+    pub css_class: &'static str,
+}
+
+fn direction(delta: f64) -> &'static str {
+    if delta > 0.0 {
+        "change-up"
+    } else if delta < 0.0 {
+        "change-down"
+    } else {
+        ""
+    }
+}
+
+fn count_comparison(label: &'static str, current: i64, previous: i64) -> Comparison {
+    let delta = current - previous;
+    Comparison {
+        label,
+        current: current.to_string(),
+        previous: previous.to_string(),
+        change: format!("{delta:+}"),
+        css_class: direction(delta as f64),
+    }
+}
+
+fn volume_comparison(current: f64, previous: f64) -> Comparison {
+    Comparison {
+        label: "Volume (kg)",
+        current: format!("{current:.0}"),
+        previous: format!("{previous:.0}"),
+        change: percent_change(current, previous),
+        css_class: direction(current - previous),
+    }
+}
+
+fn rpe_comparison(current: Option<f64>, previous: Option<f64>) -> Comparison {
+    let (change, css_class) = match (current, previous) {
+        (Some(current), Some(previous)) => {
+            let delta = current - previous;
+            (format!("{delta:+.1}"), direction(delta))
+        }
+        _ => (String::new(), ""),
+    };
+    Comparison {
+        label: "Average RPE",
+        current: format_rpe(current),
+        previous: format_rpe(previous),
+        change,
+        css_class,
+    }
+}
+
+/// "+12%", "-5%", "0%", or "new" when there was nothing before.
+pub fn percent_change(current: f64, previous: f64) -> String {
+    if previous <= 0.0 {
+        return if current > 0.0 {
+            "new".to_string()
+        } else {
+            String::new()
+        };
+    }
+    format!("{:+.0}%", (current - previous) / previous * 100.0)
+}
+
+/// An RPE average to one decimal, or a dash when no set was rated.
+pub fn format_rpe(rpe: Option<f64>) -> String {
+    rpe.map_or_else(|| "\u{2014}".to_string(), |rpe| format!("{rpe:.1}"))
+}
+
+/// An exercise's totals in the report period next to its volume in the one
+/// before.
+#[derive(Debug, Clone)]
+pub struct ExerciseReportRow {
+    pub totals: ExercisePeriodTotals,
+    /// 0 when the exercise was not trained in the previous period.
+    pub previous_volume: f64,
+    pub change: String,
+    pub css_class: &'static str,
+}
+
+impl ExerciseReportRow {
+    pub fn average_rpe(&self) -> String {
+        format_rpe(self.totals.average_rpe)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub period: ReportPeriod,
+    pub previous: ReportPeriod,
+    /// The following period, unless it has not started yet.
+    pub next: Option<ReportPeriod>,
+    pub summary: PeriodSummary,
+    pub comparisons: Vec<Comparison>,
+    /// Most volume first.
+    pub exercises: Vec<ExerciseReportRow>,
+    pub prs: Vec<PeriodPersonalRecord>,
+    /// Heaviest top sets, up to `TOP_LIFTS`.
+    pub top_lifts: Vec<ExercisePeriodTotals>,
+}
+
+/// Everything the repository aggregates a report needs, for `period` and
+/// `period.previous()`.
+#[derive(Debug, Default)]
+pub struct ReportData {
+    pub summary: PeriodSummary,
+    pub previous_summary: PeriodSummary,
+    pub exercises: Vec<ExercisePeriodTotals>,
+    pub previous_exercises: Vec<ExercisePeriodTotals>,
+    pub prs: Vec<PeriodPersonalRecord>,
+}
+
+/// Assemble the report for `period` as of `today`.
+pub fn report(period: ReportPeriod, data: ReportData, today: NaiveDate) -> Report {
+    let ReportData {
+        summary,
+        previous_summary,
+        exercises,
+        previous_exercises,
+        prs,
+    } = data;
+
+    let comparisons = vec![
+        count_comparison("Workouts", summary.sessions, previous_summary.sessions),
+        count_comparison("Sets", summary.sets, previous_summary.sets),
+        volume_comparison(summary.volume, previous_summary.volume),
+        rpe_comparison(summary.average_rpe, previous_summary.average_rpe),
+    ];
+
+    let mut top_lifts = exercises.clone();
+    top_lifts.sort_by(|a, b| {
+        b.top_weight
+            .total_cmp(&a.top_weight)
+            .then(b.top_reps.cmp(&a.top_reps))
+    });
+    top_lifts.truncate(TOP_LIFTS);
+
+    let previous_volume: HashMap<&str, f64> = previous_exercises
+        .iter()
+        .map(|e| (e.exercise_id.as_str(), e.volume))
+        .collect();
+    let exercises = exercises
+        .into_iter()
+        .map(|totals| {
+            let previous_volume = previous_volume
+                .get(totals.exercise_id.as_str())
+                .copied()
+                .unwrap_or(0.0);
+            ExerciseReportRow {
+                change: percent_change(totals.volume, previous_volume),
+                css_class: direction(totals.volume - previous_volume),
+                previous_volume,
+                totals,
+            }
+        })
+        .collect();
+
+    let next = period.next();
+    Report {
+        period,
+        previous: period.previous(),
+        next: (next.start <= today).then_some(next),
+        summary,
+        comparisons,
+        exercises,
+        prs,
+        top_lifts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn totals(id: &str, volume: f64, top_weight: f64, top_reps: i32) -> ExercisePeriodTotals {
+        ExercisePeriodTotals {
+            exercise_id: id.to_string(),
+            exercise_name: id.to_string(),
+            sessions: 1,
+            sets: 3,
+            volume,
+            top_weight,
+            top_reps,
+            average_rpe: None,
+        }
+    }
+
+    #[test]
+    fn parses_weeks_and_months() {
+        let today = date(2024, 1, 17);
+        let week = ReportPeriod::parse(ReportKind::Week, "", today, Weekday::Mon).unwrap();
+        assert_eq!(
+            (week.start, week.end),
+            (date(2024, 1, 15), date(2024, 1, 21))
+        );
+        let week =
+            ReportPeriod::parse(ReportKind::Week, "2024-01-02", today, Weekday::Sun).unwrap();
+        assert_eq!(week.start, date(2023, 12, 31));
+        assert_eq!(week.query(), "week=2023-12-31");
+
+        let month = ReportPeriod::parse(ReportKind::Month, "2024-02", today, Weekday::Mon).unwrap();
+        assert_eq!(
+            (month.start, month.end),
+            (date(2024, 2, 1), date(2024, 2, 29))
+        );
+        assert_eq!(month.filename(), "liftlog-month-2024-02.html");
+
+        assert!(ReportPeriod::parse(ReportKind::Week, "soon", today, Weekday::Mon).is_err());
+        assert!(ReportPeriod::parse(ReportKind::Month, "2024-13", today, Weekday::Mon).is_err());
+    }
+
+    #[test]
+    fn refuses_dates_at_the_edges_of_the_calendar() {
+        let today = date(2024, 1, 17);
+        for week in ["+262142-12-31", "-262143-01-01", "10000-01-03"] {
+            assert!(
+                ReportPeriod::parse(ReportKind::Week, week, today, Weekday::Mon).is_err(),
+                "{week}"
+            );
+        }
+        for month in ["+262142-12", "-262143-01", "1899-12"] {
+            assert!(
+                ReportPeriod::parse(ReportKind::Month, month, today, Weekday::Mon).is_err(),
+                "{month}"
+            );
+        }
+        let last =
+            ReportPeriod::parse(ReportKind::Week, "9999-12-31", today, Weekday::Mon).unwrap();
+        assert_eq!(last.next().start, date(10000, 1, 3));
+    }
+
+    #[test]
+    fn steps_across_year_boundaries() {
+        let january = ReportPeriod::month(date(2024, 1, 10));
+        assert_eq!(january.previous().start, date(2023, 12, 1));
+        assert_eq!(january.previous().end, date(2023, 12, 31));
+        assert_eq!(january.previous().next(), january);
+
+        let week = ReportPeriod::week(date(2024, 1, 3), Weekday::Mon);
+        assert_eq!(week.previous().start, date(2023, 12, 25));
+        assert_eq!(week.title(), "Week of 1 Jan 2024");
+    }
+
+    #[test]
+    fn formats_changes() {
+        assert_eq!(percent_change(1100.0, 1000.0), "+10%");
+        assert_eq!(percent_change(500.0, 1000.0), "-50%");
+        assert_eq!(percent_change(500.0, 0.0), "new");
+        assert_eq!(percent_change(0.0, 0.0), "");
+        assert_eq!(count_comparison("Sets", 10, 12).change, "-2");
+        assert_eq!(rpe_comparison(Some(8.0), None).change, "");
+        assert_eq!(rpe_comparison(Some(8.0), Some(7.5)).change, "+0.5");
+    }
+
+    #[test]
+    fn report_ranks_top_lifts_and_lines_up_the_previous_period() {
+        let period = ReportPeriod::week(date(2024, 1, 15), Weekday::Mon);
+        let data = ReportData {
+            exercises: vec![
+                totals("squat", 3000.0, 140.0, 5),
+                totals("bench", 2000.0, 100.0, 5),
+                totals("row", 1500.0, 100.0, 8),
+            ],
+            previous_exercises: vec![totals("bench", 2500.0, 100.0, 3)],
+            ..ReportData::default()
+        };
+        let r = report(period, data, date(2024, 1, 17));
+
+        let top: Vec<&str> = r.top_lifts.iter().map(|e| e.exercise_id.as_str()).collect();
+        assert_eq!(top, vec!["squat", "row", "bench"]);
+        assert_eq!(r.exercises[0].change, "new");
+        assert_eq!(r.exercises[1].change, "-20%");
+        assert_eq!(r.exercises[1].css_class, "change-down");
+        // The week in progress has no next week yet.
+        assert!(r.next.is_none());
+        assert_eq!(r.previous.start, date(2024, 1, 8));
+    }
+}
@@ -0,0 +1,374 @@
+use rusqlite::OptionalExtension;
+
+use crate::db::DbPool;
+use crate::error::{AppError, Result};
+use crate::models::exercise::category_key;
+use crate::models::{ExerciseCategory, FromSqliteRow};
+
+/// Selects a user's categories with their exercise counts. `?1` is the user id.
+const SELECT_CATEGORIES: &str = "SELECT c.name, c.display_name, c.position,
+        (SELECT COUNT(*) FROM exercises e
+         WHERE e.user_id = c.user_id AND e.category = c.name) AS exercise_count
+     FROM exercise_categories c
+     WHERE c.user_id = ?1";
+
+#[derive(Clone)]
+pub struct CategoryRepository {
+    pool: DbPool,
+}
+
+impl CategoryRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// A user's categories in display order.
+    pub async fn find_by_user(&self, user_id: &str) -> Result<Vec<ExerciseCategory>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt =
+                conn.prepare(&format!("{SELECT_CATEGORIES} ORDER BY c.position, c.name"))?;
+            let categories = stmt
+                .query_map([&user_id], ExerciseCategory::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(categories)
+        })
+        .await?
+    }
+
+    pub async fn find_by_name(
+        &self,
+        user_id: &str,
+        name: &str,
+    ) -> Result<Option<ExerciseCategory>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let name = name.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!("{SELECT_CATEGORIES} AND c.name = ?2"))?;
+            let result = stmt
+                .query_row(rusqlite::params![user_id, name], ExerciseCategory::from_row)
+                .optional()?;
+            Ok(result)
+        })
+        .await?
+    }
+
+    /// Append a category at the end of the user's list, keyed `name` or, if
+    /// a renamed category still holds that key, `name-2`, `name-3` and so
+    /// on. Returns the key used, or `BadRequest` if a category already
+    /// shows a name with the same key.
+    pub async fn create(&self, user_id: &str, name: &str, display_name: &str) -> Result<String> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let name = name.to_string();
+        let display_name = display_name.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            let existing: Vec<(String, String)> = {
+                let mut stmt = tx.prepare(
+                    "SELECT name, display_name FROM exercise_categories WHERE user_id = ?",
+                )?;
+                stmt.query_map([&user_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?
+            };
+            let already_exists =
+                || AppError::BadRequest("A category with that name already exists".to_string());
+            if existing
+                .iter()
+                .any(|(_, shown)| category_key(shown) == name)
+            {
+                return Err(already_exists());
+            }
+            // One more candidate than there are categories, so one is free.
+            let key = std::iter::once(name.clone())
+                .chain((2..=existing.len() + 1).map(|n| format!("{name}-{n}")))
+                .find(|key| existing.iter().all(|(taken, _)| taken != key))
+                .unwrap_or(name);
+            let result = tx.execute(
+                "INSERT INTO exercise_categories (user_id, name, display_name, position)
+                 VALUES (?1, ?2, ?3,
+                         (SELECT COALESCE(MAX(position) + 1, 0)
+                          FROM exercise_categories WHERE user_id = ?1))",
+                rusqlite::params![user_id, key, display_name],
+            );
+            match result {
+                Ok(_) => {
+                    tx.commit()?;
+                    Ok(key)
+                }
+                Err(rusqlite::Error::SqliteFailure(e, _))
+                    if e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_PRIMARYKEY =>
+                {
+                    Err(already_exists())
+                }
+                Err(e) => Err(AppError::from(e)),
+            }
+        })
+        .await?
+    }
+
+    /// Change the name a category is shown under; its key stays. Refused
+    /// with `BadRequest`, as in `create`, if another category already shows
+    /// a name with the same key.
+    pub async fn rename(&self, user_id: &str, name: &str, display_name: &str) -> Result<bool> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let name = name.to_string();
+        let display_name = display_name.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            let others: Vec<String> = {
+                let mut stmt = tx.prepare(
+                    "SELECT display_name FROM exercise_categories
+                     WHERE user_id = ? AND name != ?",
+                )?;
+                stmt.query_map(rusqlite::params![user_id, name], |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?
+            };
+            let key = category_key(&display_name);
+            if others.iter().any(|shown| category_key(shown) == key) {
+                return Err(AppError::BadRequest(
+                    "A category with that name already exists".to_string(),
+                ));
+            }
+            let rows = tx.execute(
+                "UPDATE exercise_categories SET display_name = ? WHERE user_id = ? AND name = ?",
+                rusqlite::params![display_name, user_id, name],
+            )?;
+            tx.commit()?;
+            Ok(rows > 0)
+        })
+        .await?
+    }
+
+    /// Swap a category with its neighbour one step up (`up = true`) or down.
+    /// Positions are rewritten as 0..n in the same transaction, which also
+    /// repairs ties left by the migration backfill. Moving past either end is
+    /// a no-op.
+    pub async fn move_category(&self, user_id: &str, name: &str, up: bool) -> Result<bool> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let name = name.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            let mut names: Vec<String> = {
+                let mut stmt = tx.prepare(
+                    "SELECT name FROM exercise_categories WHERE user_id = ?
+                     ORDER BY position, name",
+                )?;
+                stmt.query_map([&user_id], |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?
+            };
+            let Some(idx) = names.iter().position(|n| *n == name) else {
+                return Ok(false);
+            };
+            if up && idx > 0 {
+                names.swap(idx, idx - 1);
+            } else if !up && idx + 1 < names.len() {
+                names.swap(idx, idx + 1);
+            }
+            for (position, n) in (0_i32..).zip(&names) {
+                tx.execute(
+                    "UPDATE exercise_categories SET position = ? WHERE user_id = ? AND name = ?",
+                    rusqlite::params![position, user_id, n],
+                )?;
+            }
+            tx.commit()?;
+            Ok(true)
+        })
+        .await?
+    }
+
+    /// Delete a category. Refused with `BadRequest` while any exercise is still
+    /// filed under it, so no exercise is ever left without a category. The
+    /// check and the delete are one statement, so an exercise filed under
+    /// the category meanwhile cannot slip in between them.
+    pub async fn delete(&self, user_id: &str, name: &str) -> Result<bool> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let name = name.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let rows = conn.execute(
+                "DELETE FROM exercise_categories WHERE user_id = ?1 AND name = ?2
+                 AND NOT EXISTS (SELECT 1 FROM exercises WHERE user_id = ?1 AND category = ?2)",
+                rusqlite::params![user_id, name],
+            )?;
+            if rows > 0 {
+                return Ok(true);
+            }
+            let in_use: bool = conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM exercises WHERE user_id = ? AND category = ?)",
+                rusqlite::params![user_id, name],
+                |row| row.get(0),
+            )?;
+            if in_use {
+                return Err(AppError::BadRequest(
+                    "This category still has exercises; move them to another category first"
+                        .to_string(),
+                ));
+            }
+            Ok(false)
+        })
+        .await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_memory_pool;
+    use crate::migrations::run_migrations_for_tests;
+
+    fn setup_test_db() -> DbPool {
+        let pool = create_memory_pool().expect("Failed to create test database");
+        run_migrations_for_tests(&pool).expect("Failed to run migrations");
+        pool
+    }
+
+    fn create_test_user(pool: &DbPool, user_id: &str) {
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO users (id, username, password_hash, role, created_at) VALUES (?, ?, ?, ?, datetime('now'))",
+            rusqlite::params![user_id, format!("user_{}", user_id), "hash", "user"],
+        ).unwrap();
+    }
+
+    fn names(categories: &[ExerciseCategory]) -> Vec<&str> {
+        categories.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_new_users_get_default_categories() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = CategoryRepository::new(pool);
+
+        let categories = repo.find_by_user("user1").await.unwrap();
+        assert_eq!(
+            names(&categories),
+            ["chest", "back", "legs", "shoulders", "arms", "core"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_appends_and_rejects_duplicates() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        let repo = CategoryRepository::new(pool);
+
+        repo.create("user1", "olympic", "Olympic").await.unwrap();
+        let categories = repo.find_by_user("user1").await.unwrap();
+        assert_eq!(categories.last().unwrap().name, "olympic");
+        assert_eq!(categories.last().unwrap().position, 6);
+
+        let err = repo
+            .create("user1", "olympic", "Olympic")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(_)));
+
+        // Keys are per user.
+        repo.create("user2", "olympic", "Olympic").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_after_rename_picks_a_free_key() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = CategoryRepository::new(pool);
+
+        // "legs" is still the key of the category now shown as "Lower".
+        assert!(repo.rename("user1", "legs", "Lower").await.unwrap());
+        assert_eq!(
+            repo.create("user1", "legs", "Legs").await.unwrap(),
+            "legs-2"
+        );
+        let created = repo.find_by_name("user1", "legs-2").await.unwrap().unwrap();
+        assert_eq!(created.display_name, "Legs");
+        assert_eq!(
+            repo.find_by_name("user1", "legs")
+                .await
+                .unwrap()
+                .unwrap()
+                .display_name,
+            "Lower"
+        );
+
+        // A category shown as "Legs" again is a duplicate.
+        let err = repo.create("user1", "legs", "LEGS").await.unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(_)));
+    }
+
+    #[tokio::test]
+    async fn test_rename_rejects_a_name_already_shown() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = CategoryRepository::new(pool);
+
+        let err = repo.rename("user1", "back", "CHEST").await.unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(_)));
+        assert_eq!(
+            repo.find_by_name("user1", "back")
+                .await
+                .unwrap()
+                .unwrap()
+                .display_name,
+            "Back"
+        );
+
+        // Renaming a category to its own name with other casing is fine.
+        assert!(repo.rename("user1", "back", "BACK").await.unwrap());
+        assert!(!repo.rename("user1", "missing", "Missing").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_move_category_swaps_neighbours() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = CategoryRepository::new(pool);
+
+        assert!(repo.move_category("user1", "back", true).await.unwrap());
+        assert!(repo.move_category("user1", "core", false).await.unwrap());
+        let categories = repo.find_by_user("user1").await.unwrap();
+        assert_eq!(
+            names(&categories),
+            ["back", "chest", "legs", "shoulders", "arms", "core"]
+        );
+
+        assert!(!repo.move_category("user1", "missing", true).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_delete_refuses_category_in_use() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        {
+            let conn = pool.get().unwrap();
+            conn.execute(
+                "INSERT INTO exercises (id, name, category, user_id) VALUES ('ex1', 'Squat', 'legs', 'user1')",
+                [],
+            )
+            .unwrap();
+        }
+        let repo = CategoryRepository::new(pool);
+
+        let err = repo.delete("user1", "legs").await.unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(_)));
+        assert!(repo.delete("user1", "core").await.unwrap());
+        assert!(!repo.delete("user1", "core").await.unwrap());
+
+        let categories = repo.find_by_user("user1").await.unwrap();
+        assert!(!names(&categories).contains(&"core"));
+        let legs = categories.iter().find(|c| c.name == "legs").unwrap();
+        assert_eq!(legs.exercise_count, 1);
+    }
+}
@@ -0,0 +1,241 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{AppError, Result};
+use crate::models::{FromSqliteRow, WorkoutComment};
+
+#[derive(Clone)]
+pub struct CommentRepository {
+    pool: DbPool,
+}
+
+impl CommentRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Comment on a session, or on `log_id` when given, which must be one of
+    /// the session's sets.
+    pub async fn add_comment(
+        &self,
+        session_id: &str,
+        log_id: Option<&str>,
+        author_id: &str,
+        body: &str,
+    ) -> Result<String> {
+        let pool = self.pool.clone();
+        let id = Uuid::new_v4().to_string();
+        let id_clone = id.clone();
+        let session_id = session_id.to_string();
+        let log_id = log_id.map(str::to_string);
+        let author_id = author_id.to_string();
+        let body = body.trim().to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let rows = conn.execute(
+                "INSERT INTO workout_comments (id, session_id, log_id, author_id, body, created_at)
+                 SELECT ?1, ?2, ?3, ?4, ?5, ?6
+                 WHERE ?3 IS NULL
+                    OR EXISTS (SELECT 1 FROM workout_logs WHERE id = ?3 AND session_id = ?2)",
+                rusqlite::params![id_clone, session_id, log_id, author_id, body, Utc::now()],
+            )?;
+            if rows == 0 {
+                return Err(AppError::BadRequest(
+                    "That set is not part of this workout".to_string(),
+                ));
+            }
+            Ok(())
+        })
+        .await??;
+        Ok(id)
+    }
+
+    /// A session's comments, oldest first, each labelled with its set.
+    pub async fn find_comments(&self, session_id: &str) -> Result<Vec<WorkoutComment>> {
+        let pool = self.pool.clone();
+        let session_id = session_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT c.*, u.username AS author_username,
+                        CASE WHEN wl.id IS NOT NULL THEN e.name || ' set ' || wl.set_number END AS set_label
+                 FROM workout_comments c
+                 JOIN users u ON u.id = c.author_id
+                 LEFT JOIN workout_logs wl ON wl.id = c.log_id
+                 LEFT JOIN exercises e ON e.id = wl.exercise_id
+                 WHERE c.session_id = ?
+                 ORDER BY c.created_at, c.id",
+            )?;
+            let comments = stmt
+                .query_map([&session_id], WorkoutComment::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(comments)
+        })
+        .await?
+    }
+
+    /// Delete a comment on `session_id`, if `author_id` wrote it.
+    pub async fn delete_comment(
+        &self,
+        session_id: &str,
+        comment_id: &str,
+        author_id: &str,
+    ) -> Result<bool> {
+        let pool = self.pool.clone();
+        let session_id = session_id.to_string();
+        let comment_id = comment_id.to_string();
+        let author_id = author_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let rows = conn.execute(
+                "DELETE FROM workout_comments WHERE id = ? AND session_id = ? AND author_id = ?",
+                rusqlite::params![comment_id, session_id, author_id],
+            )?;
+            Ok(rows > 0)
+        })
+        .await?
+    }
+
+    /// Delete any comment on `session_id`, if `owner_id` owns the session.
+    pub async fn delete_comment_as_owner(
+        &self,
+        session_id: &str,
+        comment_id: &str,
+        owner_id: &str,
+    ) -> Result<bool> {
+        let pool = self.pool.clone();
+        let session_id = session_id.to_string();
+        let comment_id = comment_id.to_string();
+        let owner_id = owner_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let rows = conn.execute(
+                "DELETE FROM workout_comments WHERE id = ?1 AND session_id = ?2
+                 AND EXISTS (SELECT 1 FROM workout_sessions WHERE id = ?2 AND user_id = ?3)",
+                rusqlite::params![comment_id, session_id, owner_id],
+            )?;
+            Ok(rows > 0)
+        })
+        .await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_memory_pool;
+    use crate::migrations::run_migrations_for_tests;
+    use crate::models::SetValues;
+    use crate::repositories::WorkoutRepository;
+    use chrono::NaiveDate;
+
+    fn setup_test_db() -> DbPool {
+        let pool = create_memory_pool().expect("Failed to create test database");
+        run_migrations_for_tests(&pool).expect("Failed to run migrations");
+        pool
+    }
+
+    fn create_test_user(pool: &DbPool, user_id: &str) {
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO users (id, username, password_hash, role, created_at) VALUES (?, ?, ?, ?, datetime('now'))",
+            rusqlite::params![user_id, format!("user_{}", user_id), "hash", "user"],
+        ).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_comments_on_sessions_and_sets() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "athlete");
+        create_test_user(&pool, "coach");
+        pool.get()
+            .unwrap()
+            .execute(
+                "INSERT INTO exercises (id, name, category, user_id)
+                 VALUES ('ex1', 'Test Exercise', 'chest', 'athlete')",
+                [],
+            )
+            .unwrap();
+        let workouts = WorkoutRepository::new(pool.clone());
+        let repo = CommentRepository::new(pool);
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let session = workouts
+            .create_session("athlete", date, None)
+            .await
+            .unwrap();
+        let log = workouts
+            .create_log(&session.id, "ex1", 1, &SetValues::new(5, 100.0, None))
+            .await
+            .unwrap();
+        let other = workouts
+            .create_session("athlete", date, None)
+            .await
+            .unwrap();
+        let other_log = workouts
+            .create_log(&other.id, "ex1", 1, &SetValues::new(5, 100.0, None))
+            .await
+            .unwrap();
+
+        repo.add_comment(&session.id, None, "athlete", "  Felt heavy  ")
+            .await
+            .unwrap();
+        let on_set = repo
+            .add_comment(&session.id, Some(&log.id), "coach", "Brace harder")
+            .await
+            .unwrap();
+        assert!(matches!(
+            repo.add_comment(&session.id, Some(&other_log.id), "coach", "Wrong set")
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let comments = repo.find_comments(&session.id).await.unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].body, "Felt heavy");
+        assert!(comments[0].set_label.is_none());
+        assert_eq!(
+            comments[1].set_label.as_deref(),
+            Some("Test Exercise set 1")
+        );
+        assert_eq!(comments[1].author_username, "user_coach");
+        assert!(repo.find_comments(&other.id).await.unwrap().is_empty());
+
+        assert!(
+            !repo
+                .delete_comment(&session.id, &on_set, "athlete")
+                .await
+                .unwrap()
+        );
+        assert!(
+            repo.delete_comment(&session.id, &on_set, "coach")
+                .await
+                .unwrap()
+        );
+        assert_eq!(repo.find_comments(&session.id).await.unwrap().len(), 1);
+
+        // The owner may delete anyone's comment; nobody else may use that.
+        let from_coach = repo
+            .add_comment(&session.id, None, "coach", "Deload next week")
+            .await
+            .unwrap();
+        assert!(
+            !repo
+                .delete_comment_as_owner(&session.id, &from_coach, "coach")
+                .await
+                .unwrap()
+        );
+        assert!(
+            !repo
+                .delete_comment_as_owner(&other.id, &from_coach, "athlete")
+                .await
+                .unwrap()
+        );
+        assert!(
+            repo.delete_comment_as_owner(&session.id, &from_coach, "athlete")
+                .await
+                .unwrap()
+        );
+        assert_eq!(repo.find_comments(&session.id).await.unwrap().len(), 1);
+    }
+}
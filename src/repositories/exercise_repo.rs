@@ -1,9 +1,19 @@
+use chrono::Utc;
 use rusqlite::OptionalExtension;
 use uuid::Uuid;
 
+use crate::catalog::{CatalogExercise, ImportSummary};
 use crate::db::DbPool;
 use crate::error::{AppError, Result};
-use crate::models::{Exercise, FromSqliteRow};
+use crate::models::{Exercise, ExerciseMuscle, FromSqliteRow};
+
+/// Exercises listed for user `?1`: their own plus every global exercise they
+/// have not hidden, archived or not. Parenthesised so callers can append
+/// `AND ...` conditions.
+const SELECT_VISIBLE: &str = "SELECT * FROM exercises
+     WHERE (user_id = ?1
+            OR (user_id IS NULL
+                AND id NOT IN (SELECT exercise_id FROM hidden_exercises WHERE user_id = ?1)))";
 
 #[derive(Clone)]
 pub struct ExerciseRepository {
@@ -27,28 +37,84 @@ impl ExerciseRepository {
         .await?
     }
 
-    /// Fetch an exercise owned by `user_id`. Returns `NotFound` if no such row,
-    /// `Forbidden` if it exists but belongs to another user.
-    pub async fn find_owned(&self, id: &str, user_id: &str) -> Result<Exercise> {
+    /// Fetch an exercise `user_id` may log sets against and view stats for:
+    /// their own or any global one (hidden or not, since hiding is only a
+    /// display preference). Returns `NotFound` if no such row, `Forbidden` if
+    /// it belongs to another user.
+    pub async fn find_usable(&self, id: &str, user_id: &str) -> Result<Exercise> {
         let exercise = self
             .find_by_id(id)
             .await?
             .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))?;
-        if exercise.user_id != user_id {
+        match exercise.user_id.as_deref() {
+            None => Ok(exercise),
+            Some(owner) if owner == user_id => Ok(exercise),
+            Some(_) => Err(AppError::Forbidden(
+                "You can only use your own exercises".to_string(),
+            )),
+        }
+    }
+
+    /// Fetch an exercise the caller may edit or delete: their own, or a global
+    /// one when `is_admin`.
+    pub async fn find_editable(&self, id: &str, user_id: &str, is_admin: bool) -> Result<Exercise> {
+        let exercise = self.find_usable(id, user_id).await?;
+        if exercise.is_global() && !is_admin {
             return Err(AppError::Forbidden(
-                "You can only modify your own exercises".to_string(),
+                "Shared exercises can only be changed by an admin".to_string(),
             ));
         }
         Ok(exercise)
     }
 
+    /// The user's own exercises plus the global ones they have not hidden,
+    /// leaving out archived ones. This is what the pickers offer.
     pub async fn find_available_for_user(&self, user_id: &str) -> Result<Vec<Exercise>> {
         let pool = self.pool.clone();
         let user_id = user_id.to_string();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
-            let mut stmt =
-                conn.prepare("SELECT * FROM exercises WHERE user_id = ? ORDER BY category, name")?;
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_VISIBLE} AND archived_at IS NULL ORDER BY category, name"
+            ))?;
+            let exercises = stmt
+                .query_map([&user_id], Exercise::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(exercises)
+        })
+        .await?
+    }
+
+    /// Archived exercises the user can see, for the exercise list's
+    /// "Archived" section.
+    pub async fn find_archived_for_user(&self, user_id: &str) -> Result<Vec<Exercise>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_VISIBLE} AND archived_at IS NOT NULL ORDER BY category, name"
+            ))?;
+            let exercises = stmt
+                .query_map([&user_id], Exercise::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(exercises)
+        })
+        .await?
+    }
+
+    /// Global exercises the user has hidden, for the "show again" list.
+    pub async fn find_hidden_for_user(&self, user_id: &str) -> Result<Vec<Exercise>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT e.* FROM exercises e
+                 JOIN hidden_exercises h ON h.exercise_id = e.id
+                 WHERE h.user_id = ? AND e.user_id IS NULL
+                 ORDER BY e.category, e.name",
+            )?;
             let exercises = stmt
                 .query_map([&user_id], Exercise::from_row)?
                 .collect::<rusqlite::Result<Vec<_>>>()?;
@@ -57,29 +123,51 @@ impl ExerciseRepository {
         .await?
     }
 
-    pub async fn create(&self, name: &str, category: &str, user_id: &str) -> Result<Exercise> {
+    /// Create an exercise and its muscle mapping in one transaction, so a
+    /// failed mapping never leaves a half-made exercise behind. `user_id`
+    /// is `None` for a global exercise; callers check the admin role first.
+    pub async fn create_with_muscles(
+        &self,
+        name: &str,
+        category: &str,
+        user_id: Option<&str>,
+        unilateral: bool,
+        muscles: Vec<ExerciseMuscle>,
+    ) -> Result<Exercise> {
         let id = Uuid::new_v4().to_string();
         let exercise = Exercise {
             id: id.clone(),
             name: name.to_string(),
             category: category.to_string(),
-            user_id: user_id.to_string(),
+            user_id: user_id.map(str::to_string),
+            catalog_id: None,
+            archived_at: None,
+            unilateral,
         };
         let exercise_clone = exercise.clone();
 
         let pool = self.pool.clone();
         tokio::task::spawn_blocking(move || -> Result<()> {
-            let conn = pool.get()?;
-            conn.execute(
-                "INSERT INTO exercises (id, name, category, user_id)
-                 VALUES (?, ?, ?, ?)",
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT INTO exercises (id, name, category, user_id, unilateral)
+                 VALUES (?, ?, ?, ?, ?)",
                 rusqlite::params![
                     exercise_clone.id,
                     exercise_clone.name,
                     exercise_clone.category,
-                    exercise_clone.user_id
+                    exercise_clone.user_id,
+                    exercise_clone.unilateral
                 ],
             )?;
+            for m in &muscles {
+                tx.execute(
+                    "INSERT INTO exercise_muscles (exercise_id, muscle, role) VALUES (?, ?, ?)",
+                    rusqlite::params![exercise_clone.id, m.muscle, m.role.as_str()],
+                )?;
+            }
+            tx.commit()?;
             Ok(())
         })
         .await??;
@@ -87,29 +175,338 @@ impl ExerciseRepository {
         Ok(exercise)
     }
 
-    pub async fn update(
+    /// Update an exercise's name, category and unilateral flag and replace
+    /// its muscle mapping, all in one transaction like
+    /// [`Self::create_with_muscles`]. `user_id` is `None` for a global
+    /// exercise; callers check the admin role first. `false` when there is
+    /// no such exercise, and then nothing changes.
+    pub async fn update_with_muscles(
         &self,
         id: &str,
-        user_id: &str,
+        user_id: Option<&str>,
         name: &str,
         category: &str,
+        unilateral: bool,
+        muscles: Vec<ExerciseMuscle>,
     ) -> Result<bool> {
         let pool = self.pool.clone();
         let id = id.to_string();
-        let user_id = user_id.to_string();
+        let user_id = user_id.map(str::to_string);
         let name = name.to_string();
         let category = category.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            let rows = tx.execute(
+                "UPDATE exercises SET name = ?, category = ?, unilateral = ?
+                 WHERE id = ? AND user_id IS ?",
+                rusqlite::params![name, category, unilateral, id, user_id],
+            )?;
+            if rows == 0 {
+                return Ok(false);
+            }
+            tx.execute("DELETE FROM exercise_muscles WHERE exercise_id = ?", [&id])?;
+            for m in &muscles {
+                tx.execute(
+                    "INSERT INTO exercise_muscles (exercise_id, muscle, role) VALUES (?, ?, ?)",
+                    rusqlite::params![id, m.muscle, m.role.as_str()],
+                )?;
+            }
+            tx.commit()?;
+            Ok(true)
+        })
+        .await?
+    }
+
+    /// Archive or restore an exercise. Callers check ownership first
+    /// (`find_editable`); this only scopes by exercise id.
+    pub async fn set_archived(&self, id: &str, archived: bool) -> Result<bool> {
+        let pool = self.pool.clone();
+        let id = id.to_string();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
+            let archived_at = archived.then(Utc::now);
             let rows = conn.execute(
-                "UPDATE exercises SET name = ?, category = ? WHERE id = ? AND user_id = ?",
-                rusqlite::params![name, category, id, user_id],
+                "UPDATE exercises SET archived_at = ? WHERE id = ?",
+                rusqlite::params![archived_at, id],
             )?;
             Ok(rows > 0)
         })
         .await?
     }
 
+    /// Fold `source_id` into `target_id` in one transaction: every log of the
+    /// source moves to the target and the source is deleted. Moved sets are
+    /// renumbered after the target's sets in the same session so set numbers
+    /// stay unique per exercise. A personal target inherits the source's
+    /// catalog link if it has none; a global one is never rewritten. Returns
+    /// the number of logs moved.
+    ///
+    /// Callers check that the user may edit the source and use the target.
+    pub async fn merge(&self, source_id: &str, target_id: &str) -> Result<usize> {
+        if source_id == target_id {
+            return Err(AppError::BadRequest(
+                "An exercise cannot be merged into itself".to_string(),
+            ));
+        }
+        let pool = self.pool.clone();
+        let source_id = source_id.to_string();
+        let target_id = target_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            let find = |id: &str| {
+                tx.query_row(
+                    "SELECT * FROM exercises WHERE id = ?",
+                    [id],
+                    Exercise::from_row,
+                )
+                .optional()?
+                .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))
+            };
+            let source = find(&source_id)?;
+            let target = find(&target_id)?;
+
+            // Renumber before moving: the offset must only count the
+            // target's own sets, not source sets already moved over.
+            tx.execute(
+                "UPDATE workout_logs
+                 SET set_number = set_number + (
+                         SELECT COALESCE(MAX(t.set_number), 0) FROM workout_logs t
+                         WHERE t.session_id = workout_logs.session_id
+                           AND t.exercise_id = ?2)
+                 WHERE exercise_id = ?1",
+                rusqlite::params![source_id, target_id],
+            )?;
+            let moved = tx.execute(
+                "UPDATE workout_logs SET exercise_id = ?2 WHERE exercise_id = ?1",
+                rusqlite::params![source_id, target_id],
+            )?;
+            // Muscles and hidden_exercises rows cascade.
+            tx.execute("DELETE FROM exercises WHERE id = ?", [&source_id])?;
+            if target.catalog_id.is_none() && source.catalog_id.is_some() && !target.is_global() {
+                tx.execute(
+                    "UPDATE exercises SET catalog_id = ? WHERE id = ?",
+                    rusqlite::params![source.catalog_id, target_id],
+                )?;
+            }
+            tx.commit()?;
+            Ok(moved)
+        })
+        .await?
+    }
+
+    pub async fn hide(&self, exercise_id: &str, user_id: &str) -> Result<()> {
+        let pool = self.pool.clone();
+        let exercise_id = exercise_id.to_string();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            conn.execute(
+                "INSERT OR IGNORE INTO hidden_exercises (user_id, exercise_id) VALUES (?, ?)",
+                rusqlite::params![user_id, exercise_id],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn unhide(&self, exercise_id: &str, user_id: &str) -> Result<()> {
+        let pool = self.pool.clone();
+        let exercise_id = exercise_id.to_string();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            conn.execute(
+                "DELETE FROM hidden_exercises WHERE user_id = ? AND exercise_id = ?",
+                rusqlite::params![user_id, exercise_id],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Turn a global exercise into a personal copy for `user_id`, in one
+    /// transaction: copy the row and its muscles, move the user's own logs of
+    /// the global exercise onto the copy, and hide the global so the user is
+    /// not left with two of the same lift. Other users' logs are untouched.
+    ///
+    /// The copy keeps the global's `catalog_id` unless the user already has a
+    /// personal exercise linked to that entry.
+    pub async fn fork(&self, global_id: &str, user_id: &str) -> Result<Exercise> {
+        let pool = self.pool.clone();
+        let global_id = global_id.to_string();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            let global = tx
+                .query_row(
+                    "SELECT * FROM exercises WHERE id = ? AND user_id IS NULL",
+                    [&global_id],
+                    Exercise::from_row,
+                )
+                .optional()?
+                .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))?;
+
+            let catalog_taken: bool = tx.query_row(
+                "SELECT EXISTS (SELECT 1 FROM exercises WHERE user_id = ? AND catalog_id = ?)",
+                rusqlite::params![user_id, global.catalog_id],
+                |row| row.get(0),
+            )?;
+            let copy = Exercise {
+                id: Uuid::new_v4().to_string(),
+                name: global.name,
+                category: global.category,
+                user_id: Some(user_id.clone()),
+                catalog_id: if catalog_taken {
+                    None
+                } else {
+                    global.catalog_id
+                },
+                archived_at: None,
+                unilateral: global.unilateral,
+            };
+            tx.execute(
+                "INSERT INTO exercises (id, name, category, user_id, catalog_id, unilateral)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                rusqlite::params![
+                    copy.id,
+                    copy.name,
+                    copy.category,
+                    copy.user_id,
+                    copy.catalog_id,
+                    copy.unilateral
+                ],
+            )?;
+            ensure_category(&tx, &user_id, &copy.category)?;
+            tx.execute(
+                "INSERT INTO exercise_muscles (exercise_id, muscle, role)
+                 SELECT ?, muscle, role FROM exercise_muscles WHERE exercise_id = ?",
+                rusqlite::params![copy.id, global_id],
+            )?;
+            tx.execute(
+                "UPDATE workout_logs SET exercise_id = ?1
+                 WHERE exercise_id = ?2
+                   AND session_id IN (SELECT id FROM workout_sessions WHERE user_id = ?3)",
+                rusqlite::params![copy.id, global_id, user_id],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO hidden_exercises (user_id, exercise_id) VALUES (?, ?)",
+                rusqlite::params![user_id, global_id],
+            )?;
+            tx.commit()?;
+            Ok(copy)
+        })
+        .await?
+    }
+
+    /// Muscles worked by an exercise, primaries first.
+    pub async fn find_muscles(&self, exercise_id: &str) -> Result<Vec<ExerciseMuscle>> {
+        let pool = self.pool.clone();
+        let exercise_id = exercise_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT muscle, role FROM exercise_muscles WHERE exercise_id = ?
+                 ORDER BY role = 'secondary', muscle",
+            )?;
+            let muscles = stmt
+                .query_map([&exercise_id], ExerciseMuscle::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(muscles)
+        })
+        .await?
+    }
+
+    /// Copy catalog entries into a user's exercises in one transaction.
+    ///
+    /// Entries the user already has (by `catalog_id`) are skipped. A hand-made
+    /// exercise whose name matches an entry's name or alias is linked to it
+    /// rather than duplicated, and picks up the entry's muscles only if it had
+    /// none of its own. New exercises are filed under the entry's category,
+    /// which is recreated at the end of the list if the user had deleted it.
+    pub async fn import_from_catalog(
+        &self,
+        user_id: &str,
+        entries: Vec<&'static CatalogExercise>,
+    ) -> Result<ImportSummary> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            // Visible globals count as "already have it" for skipping, but are
+            // never linked below: that would rewrite an instance-wide row.
+            let mut existing: Vec<Exercise> = {
+                let mut stmt = tx.prepare(SELECT_VISIBLE)?;
+                stmt.query_map([&user_id], Exercise::from_row)?
+                    .collect::<rusqlite::Result<Vec<_>>>()?
+            };
+
+            let mut summary = ImportSummary::default();
+            for entry in entries {
+                if existing
+                    .iter()
+                    .any(|e| e.catalog_id.as_deref() == Some(entry.id.as_str()))
+                {
+                    summary.skipped += 1;
+                    continue;
+                }
+
+                if let Some(exercise) = existing.iter_mut().find(|e| {
+                    !e.is_global() && e.catalog_id.is_none() && entry.matches_name(&e.name)
+                }) {
+                    tx.execute(
+                        "UPDATE exercises SET catalog_id = ? WHERE id = ?",
+                        rusqlite::params![entry.id, exercise.id],
+                    )?;
+                    let has_muscles: bool = tx.query_row(
+                        "SELECT EXISTS (SELECT 1 FROM exercise_muscles WHERE exercise_id = ?)",
+                        [&exercise.id],
+                        |row| row.get(0),
+                    )?;
+                    if !has_muscles {
+                        insert_muscles(&tx, &exercise.id, entry)?;
+                    }
+                    exercise.catalog_id = Some(entry.id.clone());
+                    summary.linked += 1;
+                    continue;
+                }
+
+                ensure_category(&tx, &user_id, &entry.category)?;
+
+                let exercise = Exercise {
+                    id: Uuid::new_v4().to_string(),
+                    name: entry.name.clone(),
+                    category: entry.category.clone(),
+                    user_id: Some(user_id.clone()),
+                    catalog_id: Some(entry.id.clone()),
+                    archived_at: None,
+                    unilateral: entry.unilateral,
+                };
+                tx.execute(
+                    "INSERT INTO exercises (id, name, category, user_id, catalog_id, unilateral)
+                     VALUES (?, ?, ?, ?, ?, ?)",
+                    rusqlite::params![
+                        exercise.id,
+                        exercise.name,
+                        exercise.category,
+                        exercise.user_id,
+                        exercise.catalog_id,
+                        exercise.unilateral
+                    ],
+                )?;
+                insert_muscles(&tx, &exercise.id, entry)?;
+                existing.push(exercise);
+                summary.created += 1;
+            }
+            tx.commit()?;
+            Ok(summary)
+        })
+        .await?
+    }
+
     pub async fn delete(&self, id: &str, user_id: &str) -> Result<bool> {
         let pool = self.pool.clone();
         let id = id.to_string();
@@ -120,40 +517,98 @@ impl ExerciseRepository {
                 "DELETE FROM exercises WHERE id = ? AND user_id = ?",
                 rusqlite::params![id, user_id],
             );
-            match result {
-                Ok(rows) => Ok(rows > 0),
-                // workout_logs.exercise_id REFERENCES exercises(id) ON DELETE
-                // RESTRICT is now enforced (PRAGMA foreign_keys=ON, see
-                // src/db.rs). Deleting an exercise still referenced by a
-                // workout log fails here; surface it as a 400 the user can
-                // act on rather than the generic 500 AppError::Database
-                // would otherwise produce. Any other rusqlite error still
-                // falls through to that 500.
-                //
-                // The extended code is SQLITE_CONSTRAINT_TRIGGER, not the
-                // seemingly-obvious SQLITE_CONSTRAINT_FOREIGNKEY — verified
-                // empirically against this exact schema with the
-                // libsqlite3-sys version this crate pins.
-                // SQLITE_CONSTRAINT_FOREIGNKEY is reserved by SQLite for
-                // *deferred* FK violations caught at COMMIT; RESTRICT is an
-                // immediate check enforced through SQLite's internal FK
-                // action-trigger machinery, which reports
-                // SQLITE_CONSTRAINT_TRIGGER instead.
-                Err(rusqlite::Error::SqliteFailure(e, _))
-                    if e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_TRIGGER =>
-                {
-                    Err(AppError::BadRequest(
-                        "This exercise is used by existing workout logs and cannot be deleted"
-                            .to_string(),
-                    ))
-                }
-                Err(e) => Err(AppError::from(e)),
-            }
+            deleted_or_in_use(result)
+        })
+        .await?
+    }
+
+    /// Delete a global exercise. Callers check the admin role first. Fails
+    /// with `BadRequest` while any user still has logs against it.
+    pub async fn delete_global(&self, id: &str) -> Result<bool> {
+        let pool = self.pool.clone();
+        let id = id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let result = conn.execute(
+                "DELETE FROM exercises WHERE id = ? AND user_id IS NULL",
+                [&id],
+            );
+            deleted_or_in_use(result)
         })
         .await?
     }
 }
 
+fn deleted_or_in_use(result: rusqlite::Result<usize>) -> Result<bool> {
+    match result {
+        Ok(rows) => Ok(rows > 0),
+        // workout_logs.exercise_id REFERENCES exercises(id) ON DELETE
+        // RESTRICT is now enforced (PRAGMA foreign_keys=ON, see
+        // src/db.rs). Deleting an exercise still referenced by a
+        // workout log fails here; surface it as a 400 the user can
+        // act on rather than the generic 500 AppError::Database
+        // would otherwise produce. Any other rusqlite error still
+        // falls through to that 500.
+        //
+        // The extended code is SQLITE_CONSTRAINT_TRIGGER, not the
+        // seemingly-obvious SQLITE_CONSTRAINT_FOREIGNKEY — verified
+        // empirically against this exact schema with the
+        // libsqlite3-sys version this crate pins.
+        // SQLITE_CONSTRAINT_FOREIGNKEY is reserved by SQLite for
+        // *deferred* FK violations caught at COMMIT; RESTRICT is an
+        // immediate check enforced through SQLite's internal FK
+        // action-trigger machinery, which reports
+        // SQLITE_CONSTRAINT_TRIGGER instead.
+        Err(rusqlite::Error::SqliteFailure(e, _))
+            if e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_TRIGGER =>
+        {
+            Err(AppError::BadRequest(
+                "This exercise is used by existing workout logs and cannot be deleted; \
+                 merge it into another exercise or archive it instead"
+                    .to_string(),
+            ))
+        }
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+/// Make sure `user_id` has a category with key `key`, appending it with a
+/// capitalised key as its display name if not. Used when an exercise lands in
+/// a user's list from elsewhere (the catalog, a forked global) under a
+/// category they may have deleted or never had.
+fn ensure_category(
+    tx: &rusqlite::Transaction<'_>,
+    user_id: &str,
+    key: &str,
+) -> rusqlite::Result<()> {
+    let mut display_name = key.to_string();
+    if let Some(first) = display_name.get_mut(..1) {
+        first.make_ascii_uppercase();
+    }
+    tx.execute(
+        "INSERT OR IGNORE INTO exercise_categories (user_id, name, display_name, position)
+         VALUES (?1, ?2, ?3,
+                 (SELECT COALESCE(MAX(position) + 1, 0)
+                  FROM exercise_categories WHERE user_id = ?1))",
+        rusqlite::params![user_id, key, display_name],
+    )?;
+    Ok(())
+}
+
+fn insert_muscles(
+    tx: &rusqlite::Transaction<'_>,
+    exercise_id: &str,
+    entry: &CatalogExercise,
+) -> rusqlite::Result<()> {
+    for m in entry.muscles() {
+        tx.execute(
+            "INSERT INTO exercise_muscles (exercise_id, muscle, role) VALUES (?, ?, ?)",
+            rusqlite::params![exercise_id, m.muscle, m.role.as_str()],
+        )?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,11 +635,14 @@ mod tests {
         create_test_user(&pool, "user1");
         let repo = ExerciseRepository::new(pool);
 
-        let exercise = repo.create("Bench Press", "chest", "user1").await.unwrap();
+        let exercise = repo
+            .create_with_muscles("Bench Press", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
 
         assert_eq!(exercise.name, "Bench Press");
         assert_eq!(exercise.category, "chest");
-        assert_eq!(exercise.user_id, "user1");
+        assert_eq!(exercise.user_id.as_deref(), Some("user1"));
         assert!(!exercise.id.is_empty());
     }
 
@@ -194,7 +652,10 @@ mod tests {
         create_test_user(&pool, "user1");
         let repo = ExerciseRepository::new(pool);
 
-        let created = repo.create("Bench Press", "chest", "user1").await.unwrap();
+        let created = repo
+            .create_with_muscles("Bench Press", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
         let found = repo.find_by_id(&created.id).await.unwrap();
 
         assert!(found.is_some());
@@ -220,9 +681,15 @@ mod tests {
         create_test_user(&pool, "user2");
         let repo = ExerciseRepository::new(pool);
 
-        repo.create("Bench Press", "chest", "user1").await.unwrap();
-        repo.create("Squat", "legs", "user1").await.unwrap();
-        repo.create("Deadlift", "back", "user2").await.unwrap();
+        repo.create_with_muscles("Bench Press", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
+        repo.create_with_muscles("Squat", "legs", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
+        repo.create_with_muscles("Deadlift", "back", Some("user2"), false, Vec::new())
+            .await
+            .unwrap();
 
         let user1_exercises = repo.find_available_for_user("user1").await.unwrap();
         let user2_exercises = repo.find_available_for_user("user2").await.unwrap();
@@ -237,9 +704,19 @@ mod tests {
         create_test_user(&pool, "user1");
         let repo = ExerciseRepository::new(pool);
 
-        let exercise = repo.create("Bench Press", "chest", "user1").await.unwrap();
+        let exercise = repo
+            .create_with_muscles("Bench Press", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
         let updated = repo
-            .update(&exercise.id, "user1", "Incline Bench", "chest")
+            .update_with_muscles(
+                &exercise.id,
+                Some("user1"),
+                "Incline Bench",
+                "chest",
+                false,
+                Vec::new(),
+            )
             .await
             .unwrap();
 
@@ -256,9 +733,19 @@ mod tests {
         create_test_user(&pool, "user2");
         let repo = ExerciseRepository::new(pool);
 
-        let exercise = repo.create("Bench Press", "chest", "user1").await.unwrap();
+        let exercise = repo
+            .create_with_muscles("Bench Press", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
         let updated = repo
-            .update(&exercise.id, "user2", "Hacked", "chest")
+            .update_with_muscles(
+                &exercise.id,
+                Some("user2"),
+                "Hacked",
+                "chest",
+                false,
+                Vec::new(),
+            )
             .await
             .unwrap();
 
@@ -275,7 +762,10 @@ mod tests {
         create_test_user(&pool, "user1");
         let repo = ExerciseRepository::new(pool);
 
-        let exercise = repo.create("Bench Press", "chest", "user1").await.unwrap();
+        let exercise = repo
+            .create_with_muscles("Bench Press", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
         let deleted = repo.delete(&exercise.id, "user1").await.unwrap();
 
         assert!(deleted);
@@ -291,7 +781,10 @@ mod tests {
         create_test_user(&pool, "user2");
         let repo = ExerciseRepository::new(pool);
 
-        let exercise = repo.create("Bench Press", "chest", "user1").await.unwrap();
+        let exercise = repo
+            .create_with_muscles("Bench Press", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
         let deleted = repo.delete(&exercise.id, "user2").await.unwrap();
 
         assert!(!deleted);
@@ -300,4 +793,483 @@ mod tests {
         let found = repo.find_by_id(&exercise.id).await.unwrap();
         assert!(found.is_some());
     }
+
+    #[tokio::test]
+    async fn test_find_muscles_lists_primaries_first() {
+        use crate::models::muscle::MuscleRole;
+
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = ExerciseRepository::new(pool);
+
+        let muscle = |name: &str, role| ExerciseMuscle {
+            muscle: name.to_string(),
+            role,
+        };
+        let exercise = repo
+            .create_with_muscles(
+                "Bench Press",
+                "chest",
+                Some("user1"),
+                false,
+                vec![
+                    muscle("triceps", MuscleRole::Secondary),
+                    muscle("chest", MuscleRole::Primary),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let found = repo.find_muscles(&exercise.id).await.unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].muscle, "chest");
+        assert_eq!(found[1].muscle, "triceps");
+    }
+
+    #[tokio::test]
+    async fn test_create_with_muscles_is_all_or_nothing() {
+        use crate::models::muscle::MuscleRole;
+
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = ExerciseRepository::new(pool);
+        let muscle = |name: &str, role| ExerciseMuscle {
+            muscle: name.to_string(),
+            role,
+        };
+
+        let exercise = repo
+            .create_with_muscles(
+                "Bench Press",
+                "chest",
+                Some("user1"),
+                false,
+                vec![muscle("chest", MuscleRole::Primary)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(repo.find_muscles(&exercise.id).await.unwrap().len(), 1);
+
+        // A duplicate muscle fails the mapping, and the exercise with it.
+        let result = repo
+            .create_with_muscles(
+                "Dip",
+                "chest",
+                Some("user1"),
+                false,
+                vec![
+                    muscle("triceps", MuscleRole::Primary),
+                    muscle("triceps", MuscleRole::Secondary),
+                ],
+            )
+            .await;
+        assert!(result.is_err());
+        let names: Vec<_> = repo
+            .find_available_for_user("user1")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert!(!names.contains(&"Dip".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_update_with_muscles_is_all_or_nothing() {
+        use crate::models::muscle::MuscleRole;
+
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        let repo = ExerciseRepository::new(pool);
+        let muscle = |name: &str, role| ExerciseMuscle {
+            muscle: name.to_string(),
+            role,
+        };
+        let exercise = repo
+            .create_with_muscles(
+                "Bench Press",
+                "chest",
+                Some("user1"),
+                true,
+                vec![muscle("chest", MuscleRole::Primary)],
+            )
+            .await
+            .unwrap();
+        assert!(exercise.unilateral);
+
+        assert!(
+            repo.update_with_muscles(
+                &exercise.id,
+                Some("user1"),
+                "Close-Grip Bench",
+                "arms",
+                false,
+                vec![
+                    muscle("triceps", MuscleRole::Primary),
+                    muscle("chest", MuscleRole::Secondary),
+                ],
+            )
+            .await
+            .unwrap()
+        );
+        let updated = repo.find_by_id(&exercise.id).await.unwrap().unwrap();
+        assert_eq!(updated.name, "Close-Grip Bench");
+        assert_eq!(updated.category, "arms");
+        assert!(!updated.unilateral);
+        assert_eq!(repo.find_muscles(&exercise.id).await.unwrap().len(), 2);
+
+        // A duplicate muscle fails the mapping, and the rename with it.
+        let result = repo
+            .update_with_muscles(
+                &exercise.id,
+                Some("user1"),
+                "Dip",
+                "chest",
+                true,
+                vec![
+                    muscle("triceps", MuscleRole::Primary),
+                    muscle("triceps", MuscleRole::Secondary),
+                ],
+            )
+            .await;
+        assert!(result.is_err());
+        let unchanged = repo.find_by_id(&exercise.id).await.unwrap().unwrap();
+        assert_eq!(unchanged.name, "Close-Grip Bench");
+        assert!(!unchanged.unilateral);
+        assert_eq!(repo.find_muscles(&exercise.id).await.unwrap().len(), 2);
+
+        // Another user's exercise, or the wrong scope, changes nothing.
+        for owner in [Some("user2"), None] {
+            assert!(
+                !repo
+                    .update_with_muscles(&exercise.id, owner, "Dip", "chest", true, Vec::new())
+                    .await
+                    .unwrap()
+            );
+        }
+        assert_eq!(repo.find_muscles(&exercise.id).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_import_from_catalog_creates_links_and_skips() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = ExerciseRepository::new(pool);
+
+        // Typed in by hand before the catalog existed, under an alias.
+        let hand_made = repo
+            .create_with_muscles("ohp", "shoulders", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
+
+        let entries = vec![
+            crate::catalog::find("barbell-bench-press").unwrap(),
+            crate::catalog::find("overhead-press").unwrap(),
+        ];
+        let summary = repo
+            .import_from_catalog("user1", entries.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                created: 1,
+                linked: 1,
+                skipped: 0
+            }
+        );
+
+        let linked = repo.find_by_id(&hand_made.id).await.unwrap().unwrap();
+        assert_eq!(linked.catalog_id.as_deref(), Some("overhead-press"));
+        assert!(!repo.find_muscles(&hand_made.id).await.unwrap().is_empty());
+
+        let summary = repo.import_from_catalog("user1", entries).await.unwrap();
+        assert_eq!(summary.skipped, 2);
+        assert_eq!(
+            repo.find_available_for_user("user1").await.unwrap().len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_global_exercises_visible_until_hidden() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        let repo = ExerciseRepository::new(pool);
+
+        let global = repo
+            .create_with_muscles("Deadlift", "back", None, false, Vec::new())
+            .await
+            .unwrap();
+        assert!(global.is_global());
+        repo.create_with_muscles("Curl", "arms", Some("user2"), false, Vec::new())
+            .await
+            .unwrap();
+
+        let visible = repo.find_available_for_user("user1").await.unwrap();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].id, global.id);
+
+        repo.hide(&global.id, "user1").await.unwrap();
+        assert!(
+            repo.find_available_for_user("user1")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(repo.find_hidden_for_user("user1").await.unwrap().len(), 1);
+        // Hiding is per user and does not stop the exercise being used.
+        assert_eq!(
+            repo.find_available_for_user("user2").await.unwrap().len(),
+            2
+        );
+        repo.find_usable(&global.id, "user1").await.unwrap();
+
+        repo.unhide(&global.id, "user1").await.unwrap();
+        assert_eq!(
+            repo.find_available_for_user("user1").await.unwrap().len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_editable_requires_admin_for_global() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        let repo = ExerciseRepository::new(pool);
+
+        let global = repo
+            .create_with_muscles("Deadlift", "back", None, false, Vec::new())
+            .await
+            .unwrap();
+        let own = repo
+            .create_with_muscles("Curl", "arms", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
+
+        let err = repo
+            .find_editable(&global.id, "user1", false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(_)));
+        repo.find_editable(&global.id, "user1", true).await.unwrap();
+        repo.find_editable(&own.id, "user1", false).await.unwrap();
+
+        let err = repo.find_usable(&own.id, "user2").await.unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(_)));
+    }
+
+    #[tokio::test]
+    async fn test_fork_moves_only_the_users_logs() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        let repo = ExerciseRepository::new(pool.clone());
+
+        let global = repo
+            .create_with_muscles(
+                "Deadlift",
+                "olympic",
+                None,
+                false,
+                vec![ExerciseMuscle {
+                    muscle: "hamstrings".to_string(),
+                    role: crate::models::muscle::MuscleRole::Primary,
+                }],
+            )
+            .await
+            .unwrap();
+        {
+            let conn = pool.get().unwrap();
+            for (session, user) in [("s1", "user1"), ("s2", "user2")] {
+                conn.execute(
+                    "INSERT INTO workout_sessions (id, user_id, date) VALUES (?, ?, '2026-01-05')",
+                    rusqlite::params![session, user],
+                )
+                .unwrap();
+                conn.execute(
+                    "INSERT INTO workout_logs (id, session_id, exercise_id, set_number, reps, weight)
+                     VALUES (?, ?, ?, 1, 5, 100.0)",
+                    rusqlite::params![format!("log-{session}"), session, global.id],
+                )
+                .unwrap();
+            }
+        }
+
+        let copy = repo.fork(&global.id, "user1").await.unwrap();
+        assert_eq!(copy.user_id.as_deref(), Some("user1"));
+        assert_eq!(copy.name, "Deadlift");
+        assert_eq!(repo.find_muscles(&copy.id).await.unwrap().len(), 1);
+
+        let conn = pool.get().unwrap();
+        let exercise_of = |log: &str| -> String {
+            conn.query_row(
+                "SELECT exercise_id FROM workout_logs WHERE id = ?",
+                [log],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(exercise_of("log-s1"), copy.id);
+        assert_eq!(exercise_of("log-s2"), global.id);
+
+        // The user is given the category the global used, and only sees the copy.
+        let has_category: bool = conn
+            .query_row(
+                "SELECT EXISTS (SELECT 1 FROM exercise_categories WHERE user_id = 'user1' AND name = 'olympic')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(has_category);
+        drop(conn);
+
+        let visible = repo.find_available_for_user("user1").await.unwrap();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].id, copy.id);
+    }
+
+    #[tokio::test]
+    async fn test_archived_exercises_leave_the_pickers() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = ExerciseRepository::new(pool);
+
+        let squat = repo
+            .create_with_muscles("Squat", "legs", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
+        repo.create_with_muscles("Bench Press", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
+
+        assert!(repo.set_archived(&squat.id, true).await.unwrap());
+        let available = repo.find_available_for_user("user1").await.unwrap();
+        assert_eq!(available.len(), 1);
+        let archived = repo.find_archived_for_user("user1").await.unwrap();
+        assert_eq!(archived.len(), 1);
+        assert!(archived[0].is_archived());
+
+        assert!(repo.set_archived(&squat.id, false).await.unwrap());
+        assert_eq!(
+            repo.find_available_for_user("user1").await.unwrap().len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_moves_logs_and_renumbers_sets() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = ExerciseRepository::new(pool.clone());
+
+        let typo = repo
+            .create_with_muscles("Bench", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
+        let bench = repo
+            .create_with_muscles("Bench Press", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
+        {
+            let conn = pool.get().unwrap();
+            conn.execute(
+                "UPDATE exercises SET catalog_id = 'barbell-bench-press' WHERE id = ?",
+                [&typo.id],
+            )
+            .unwrap();
+            for (id, date) in [("s1", "2026-01-05"), ("s2", "2026-01-07")] {
+                conn.execute(
+                    "INSERT INTO workout_sessions (id, user_id, date) VALUES (?, 'user1', ?)",
+                    [id, date],
+                )
+                .unwrap();
+            }
+            // s1 has both exercises; s2 only the one being merged away.
+            for (id, session_id, exercise_id, set_number) in [
+                ("a", "s1", &bench.id, 1),
+                ("b", "s1", &bench.id, 2),
+                ("c", "s1", &typo.id, 1),
+                ("d", "s1", &typo.id, 2),
+                ("e", "s1", &typo.id, 3),
+                ("f", "s2", &typo.id, 1),
+                ("g", "s2", &typo.id, 2),
+                ("h", "s2", &typo.id, 3),
+            ] {
+                conn.execute(
+                    "INSERT INTO workout_logs (id, session_id, exercise_id, set_number, reps, weight)
+                     VALUES (?, ?, ?, ?, 5, 100.0)",
+                    rusqlite::params![id, session_id, exercise_id, set_number],
+                )
+                .unwrap();
+            }
+        }
+
+        assert_eq!(repo.merge(&typo.id, &bench.id).await.unwrap(), 6);
+        assert!(repo.find_by_id(&typo.id).await.unwrap().is_none());
+        let merged = repo.find_by_id(&bench.id).await.unwrap().unwrap();
+        assert_eq!(merged.catalog_id.as_deref(), Some("barbell-bench-press"));
+
+        let conn = pool.get().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT session_id, set_number FROM workout_logs
+                 WHERE exercise_id = ? ORDER BY session_id, set_number",
+            )
+            .unwrap();
+        let sets: Vec<(String, i32)> = stmt
+            .query_map([&bench.id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        let expected: Vec<(String, i32)> = [
+            ("s1", 1),
+            ("s1", 2),
+            ("s1", 3),
+            ("s1", 4),
+            ("s1", 5),
+            ("s2", 1),
+            ("s2", 2),
+            ("s2", 3),
+        ]
+        .into_iter()
+        .map(|(session, set)| (session.to_string(), set))
+        .collect();
+        assert_eq!(sets, expected);
+        drop(stmt);
+        drop(conn);
+
+        let err = repo.merge(&bench.id, &bench.id).await.unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(_)));
+    }
+
+    #[tokio::test]
+    async fn test_merge_into_global_keeps_its_catalog_link() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = ExerciseRepository::new(pool.clone());
+
+        let mine = repo
+            .create_with_muscles("Bench", "chest", Some("user1"), false, Vec::new())
+            .await
+            .unwrap();
+        let shared = repo
+            .create_with_muscles("Bench Press", "chest", None, false, Vec::new())
+            .await
+            .unwrap();
+        pool.get()
+            .unwrap()
+            .execute(
+                "UPDATE exercises SET catalog_id = 'barbell-bench-press' WHERE id = ?",
+                [&mine.id],
+            )
+            .unwrap();
+
+        repo.merge(&mine.id, &shared.id).await.unwrap();
+        let shared = repo.find_by_id(&shared.id).await.unwrap().unwrap();
+        assert!(shared.catalog_id.is_none());
+    }
 }
@@ -1,9 +1,17 @@
+pub mod category_repo;
+pub mod comment_repo;
 pub mod exercise_repo;
 pub mod session_repo;
+pub mod share_repo;
+pub mod tag_repo;
 pub mod user_repo;
 pub mod workout_repo;
 
+pub use category_repo::CategoryRepository;
+pub use comment_repo::CommentRepository;
 pub use exercise_repo::ExerciseRepository;
 pub use session_repo::{SessionListRow, SessionRepository, ValidateOutcome};
+pub use share_repo::ShareRepository;
+pub use tag_repo::TagRepository;
 pub use user_repo::UserRepository;
 pub use workout_repo::WorkoutRepository;
@@ -0,0 +1,512 @@
+use chrono::Utc;
+use rusqlite::OptionalExtension;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::Result;
+use crate::models::{
+    BlockScope, BlockShare, FromSqliteRow, ProfileSections, PublicProfile, YearReviewShare,
+};
+
+#[derive(Clone)]
+pub struct ShareRepository {
+    pool: DbPool,
+}
+
+impl ShareRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    // Year reviews
+    /// Create (or replace) the public link to the user's review of `year`,
+    /// like `WorkoutRepository::set_share_token` does for a workout.
+    pub async fn set_year_share_token(
+        &self,
+        user_id: &str,
+        year: i32,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<String> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let token = Uuid::new_v4().to_string();
+        let token_clone = token.clone();
+        let expires_at = ttl.map(|d| Utc::now() + d);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            conn.execute(
+                "INSERT INTO year_review_shares (user_id, year, token, expires_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (user_id, year)
+                 DO UPDATE SET token = excluded.token, expires_at = excluded.expires_at,
+                               created_at = CURRENT_TIMESTAMP",
+                rusqlite::params![user_id, year, token_clone, expires_at],
+            )?;
+            Ok(token_clone)
+        })
+        .await?
+    }
+
+    pub async fn revoke_year_share_token(&self, user_id: &str, year: i32) -> Result<bool> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let rows = conn.execute(
+                "DELETE FROM year_review_shares WHERE user_id = ? AND year = ?",
+                rusqlite::params![user_id, year],
+            )?;
+            Ok(rows > 0)
+        })
+        .await?
+    }
+
+    /// The user's live link to their review of `year`, if any.
+    pub async fn find_year_share(
+        &self,
+        user_id: &str,
+        year: i32,
+    ) -> Result<Option<YearReviewShare>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let share = conn
+                .query_row(
+                    "SELECT * FROM year_review_shares
+                     WHERE user_id = ? AND year = ? AND (expires_at IS NULL OR expires_at > ?)",
+                    rusqlite::params![user_id, year, Utc::now()],
+                    YearReviewShare::from_row,
+                )
+                .optional()?;
+            Ok(share)
+        })
+        .await?
+    }
+
+    /// Find a year review share by token. As with
+    /// `WorkoutRepository::find_session_by_share_token`, an expired token and
+    /// an unknown one are the same `None`.
+    pub async fn find_year_share_by_token(&self, token: &str) -> Result<Option<YearReviewShare>> {
+        let pool = self.pool.clone();
+        let token = token.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let share = conn
+                .query_row(
+                    "SELECT * FROM year_review_shares
+                     WHERE token = ? AND (expires_at IS NULL OR expires_at > ?)",
+                    rusqlite::params![token, Utc::now()],
+                    YearReviewShare::from_row,
+                )
+                .optional()?;
+            Ok(share)
+        })
+        .await?
+    }
+
+    // Blocks
+    /// Create a public link to the user's sessions in `scope`, like
+    /// `WorkoutRepository::set_share_token` does for one workout. A user may
+    /// have any number.
+    pub async fn create_block_share(
+        &self,
+        user_id: &str,
+        scope: &BlockScope,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<String> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let scope = scope.clone();
+        let token = Uuid::new_v4().to_string();
+        let expires_at = ttl.map(|d| Utc::now() + d);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            conn.execute(
+                "INSERT INTO block_shares (id, user_id, token, from_date, to_date, tag_id, expires_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![
+                    Uuid::new_v4().to_string(),
+                    user_id,
+                    token,
+                    scope.from,
+                    scope.to,
+                    scope.tag_id,
+                    expires_at
+                ],
+            )?;
+            Ok(token)
+        })
+        .await?
+    }
+
+    /// The user's live block links, newest first.
+    pub async fn find_block_shares(&self, user_id: &str) -> Result<Vec<BlockShare>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT bs.*, t.name AS tag_name
+                 FROM block_shares bs
+                 LEFT JOIN workout_tags t ON bs.tag_id = t.id
+                 WHERE bs.user_id = ? AND (bs.expires_at IS NULL OR bs.expires_at > ?)
+                 ORDER BY bs.created_at DESC, bs.rowid DESC",
+            )?;
+            let shares = stmt
+                .query_map(rusqlite::params![user_id, Utc::now()], BlockShare::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(shares)
+        })
+        .await?
+    }
+
+    pub async fn revoke_block_share(&self, user_id: &str, id: &str) -> Result<bool> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let id = id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let rows = conn.execute(
+                "DELETE FROM block_shares WHERE id = ? AND user_id = ?",
+                rusqlite::params![id, user_id],
+            )?;
+            Ok(rows > 0)
+        })
+        .await?
+    }
+
+    /// Find a block link by token. As with
+    /// `WorkoutRepository::find_session_by_share_token`, an expired token and
+    /// an unknown one are the same `None`.
+    pub async fn find_block_share_by_token(&self, token: &str) -> Result<Option<BlockShare>> {
+        let pool = self.pool.clone();
+        let token = token.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let share = conn
+                .query_row(
+                    "SELECT bs.*, t.name AS tag_name
+                     FROM block_shares bs
+                     LEFT JOIN workout_tags t ON bs.tag_id = t.id
+                     WHERE bs.token = ? AND (bs.expires_at IS NULL OR bs.expires_at > ?)",
+                    rusqlite::params![token, Utc::now()],
+                    BlockShare::from_row,
+                )
+                .optional()?;
+            Ok(share)
+        })
+        .await?
+    }
+
+    // Public profiles
+    /// The user's public profile settings. An expired link reads as no
+    /// link, so the settings page offers to share again.
+    pub async fn get_public_profile(&self, user_id: &str) -> Result<PublicProfile> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let profile = conn
+                .query_row(
+                    "SELECT user_id, show_prs, show_sessions, show_consistency,
+                            CASE WHEN expires_at IS NULL OR expires_at > ?2 THEN token END AS token,
+                            CASE WHEN expires_at > ?2 THEN expires_at END AS expires_at
+                     FROM public_profiles WHERE user_id = ?1",
+                    rusqlite::params![user_id, Utc::now()],
+                    PublicProfile::from_row,
+                )
+                .optional()?;
+            let mut profile = profile.unwrap_or_else(|| PublicProfile {
+                user_id: user_id.clone(),
+                ..PublicProfile::default()
+            });
+            profile.sections.exercise_ids = profile_exercise_ids(&conn, &user_id)?;
+            Ok(profile)
+        })
+        .await?
+    }
+
+    /// Find a public profile by its link. As with
+    /// `WorkoutRepository::find_session_by_share_token`, an expired token and
+    /// an unknown one are the same `None`.
+    pub async fn find_public_profile_by_token(&self, token: &str) -> Result<Option<PublicProfile>> {
+        let pool = self.pool.clone();
+        let token = token.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let profile = conn
+                .query_row(
+                    "SELECT * FROM public_profiles
+                     WHERE token = ? AND (expires_at IS NULL OR expires_at > ?)",
+                    rusqlite::params![token, Utc::now()],
+                    PublicProfile::from_row,
+                )
+                .optional()?;
+            let Some(mut profile) = profile else {
+                return Ok(None);
+            };
+            profile.sections.exercise_ids = profile_exercise_ids(&conn, &profile.user_id)?;
+            Ok(Some(profile))
+        })
+        .await?
+    }
+
+    /// Save which sections the profile shows, keeping its link.
+    pub async fn set_profile_sections(
+        &self,
+        user_id: &str,
+        sections: ProfileSections,
+    ) -> Result<()> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT INTO public_profiles (user_id, show_prs, show_sessions, show_consistency)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(user_id) DO UPDATE SET
+                     show_prs = ?2, show_sessions = ?3, show_consistency = ?4",
+                rusqlite::params![
+                    user_id,
+                    sections.prs,
+                    sections.sessions,
+                    sections.consistency
+                ],
+            )?;
+            tx.execute(
+                "DELETE FROM public_profile_exercises WHERE user_id = ?",
+                [&user_id],
+            )?;
+            for exercise_id in &sections.exercise_ids {
+                tx.execute(
+                    "INSERT INTO public_profile_exercises (user_id, exercise_id) VALUES (?, ?)",
+                    rusqlite::params![user_id, exercise_id],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Create (or replace) the public link to the user's profile, like
+    /// `WorkoutRepository::set_share_token` does for a workout.
+    pub async fn set_profile_share_token(
+        &self,
+        user_id: &str,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<String> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let token = Uuid::new_v4().to_string();
+        let token_clone = token.clone();
+        let expires_at = ttl.map(|d| Utc::now() + d);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            conn.execute(
+                "INSERT INTO public_profiles (user_id, token, expires_at)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT(user_id) DO UPDATE SET token = ?2, expires_at = ?3",
+                rusqlite::params![user_id, token_clone, expires_at],
+            )?;
+            Ok(token_clone)
+        })
+        .await?
+    }
+
+    /// Stop sharing the profile. Its sections are kept for next time.
+    pub async fn revoke_profile_share_token(&self, user_id: &str) -> Result<bool> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let rows = conn.execute(
+                "UPDATE public_profiles SET token = NULL, expires_at = NULL
+                 WHERE user_id = ? AND token IS NOT NULL",
+                [&user_id],
+            )?;
+            Ok(rows > 0)
+        })
+        .await?
+    }
+
+    /// Clear year review, block and profile links whose expiry has passed,
+    /// as `WorkoutRepository::cleanup_expired_share_tokens` does for
+    /// workouts. Returns the number of links cleared. Lookups already ignore
+    /// expired links; this keeps the rows from piling up.
+    pub async fn cleanup_expired_share_tokens(&self) -> Result<usize> {
+        let pool = self.pool.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let now = Utc::now();
+            let reviews = conn.execute(
+                "DELETE FROM year_review_shares WHERE expires_at IS NOT NULL AND expires_at <= ?",
+                rusqlite::params![now],
+            )?;
+            let blocks = conn.execute(
+                "DELETE FROM block_shares WHERE expires_at IS NOT NULL AND expires_at <= ?",
+                rusqlite::params![now],
+            )?;
+            let profiles = conn.execute(
+                "UPDATE public_profiles SET token = NULL, expires_at = NULL WHERE expires_at IS NOT NULL AND expires_at <= ?",
+                rusqlite::params![now],
+            )?;
+            Ok(reviews + blocks + profiles)
+        })
+        .await?
+    }
+}
+
+fn profile_exercise_ids(
+    conn: &rusqlite::Connection,
+    user_id: &str,
+) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT exercise_id FROM public_profile_exercises WHERE user_id = ? ORDER BY exercise_id",
+    )?;
+    stmt.query_map([user_id], |row| row.get(0))?.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_memory_pool;
+    use crate::migrations::run_migrations_for_tests;
+
+    fn setup_test_db() -> DbPool {
+        let pool = create_memory_pool().expect("Failed to create test database");
+        run_migrations_for_tests(&pool).expect("Failed to run migrations");
+        pool
+    }
+
+    fn create_test_user(pool: &DbPool, user_id: &str) {
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO users (id, username, password_hash, role, created_at) VALUES (?, ?, ?, ?, datetime('now'))",
+            rusqlite::params![user_id, format!("user_{}", user_id), "hash", "user"],
+        ).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_year_share_token_replaces_revokes_and_expires() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = ShareRepository::new(pool.clone());
+
+        let first = repo
+            .set_year_share_token("user1", 2024, None)
+            .await
+            .unwrap();
+        let second = repo
+            .set_year_share_token("user1", 2024, Some(chrono::Duration::days(7)))
+            .await
+            .unwrap();
+        assert_ne!(first, second);
+        assert!(
+            repo.find_year_share_by_token(&first)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let share = repo
+            .find_year_share_by_token(&second)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((share.user_id.as_str(), share.year), ("user1", 2024));
+        assert!(share.expires_at.is_some());
+
+        pool.get()
+            .unwrap()
+            .execute(
+                "UPDATE year_review_shares SET expires_at = datetime('now', '-1 hour')",
+                [],
+            )
+            .unwrap();
+        assert!(repo.find_year_share("user1", 2024).await.unwrap().is_none());
+        assert!(
+            repo.find_year_share_by_token(&second)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(repo.cleanup_expired_share_tokens().await.unwrap(), 1);
+
+        repo.set_year_share_token("user1", 2023, None)
+            .await
+            .unwrap();
+        assert!(repo.revoke_year_share_token("user1", 2023).await.unwrap());
+        assert!(repo.find_year_share("user1", 2023).await.unwrap().is_none());
+        assert!(!repo.revoke_year_share_token("user1", 2023).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_public_profile_link_expires_and_revokes_but_sections_stay() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = ShareRepository::new(pool.clone());
+
+        let profile = repo.get_public_profile("user1").await.unwrap();
+        assert!(profile.token.is_none());
+        assert!(profile.sections.is_empty());
+
+        let sections = ProfileSections {
+            consistency: true,
+            ..ProfileSections::default()
+        };
+        repo.set_profile_sections("user1", sections.clone())
+            .await
+            .unwrap();
+        let token = repo
+            .set_profile_share_token("user1", Some(chrono::Duration::days(7)))
+            .await
+            .unwrap();
+        let shared = repo
+            .find_public_profile_by_token(&token)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(shared.user_id, "user1");
+        assert_eq!(shared.sections, sections);
+
+        pool.get()
+            .unwrap()
+            .execute(
+                "UPDATE public_profiles SET expires_at = datetime('now', '-1 hour')",
+                [],
+            )
+            .unwrap();
+        assert!(
+            repo.find_public_profile_by_token(&token)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            repo.get_public_profile("user1")
+                .await
+                .unwrap()
+                .token
+                .is_none()
+        );
+
+        let token = repo.set_profile_share_token("user1", None).await.unwrap();
+        assert!(repo.revoke_profile_share_token("user1").await.unwrap());
+        assert!(!repo.revoke_profile_share_token("user1").await.unwrap());
+        assert!(
+            repo.find_public_profile_by_token(&token)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            repo.get_public_profile("user1").await.unwrap().sections,
+            sections
+        );
+    }
+}
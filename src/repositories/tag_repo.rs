@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use rusqlite::OptionalExtension;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{AppError, Result};
+use crate::models::{FromSqliteRow, WorkoutTag};
+
+/// Selects tags with their session counts. Callers append the `WHERE`.
+const SELECT_TAGS: &str = "SELECT t.id, t.name,
+        (SELECT COUNT(*) FROM workout_session_tags st WHERE st.tag_id = t.id) AS session_count
+     FROM workout_tags t";
+
+#[derive(Clone)]
+pub struct TagRepository {
+    pool: DbPool,
+}
+
+impl TagRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// A user's tags, alphabetically.
+    pub async fn find_by_user(&self, user_id: &str) -> Result<Vec<WorkoutTag>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_TAGS} WHERE t.user_id = ? ORDER BY t.name COLLATE NOCASE"
+            ))?;
+            let tags = stmt
+                .query_map([&user_id], WorkoutTag::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(tags)
+        })
+        .await?
+    }
+
+    /// The tags on one session, alphabetically.
+    pub async fn find_for_session(&self, session_id: &str) -> Result<Vec<WorkoutTag>> {
+        let pool = self.pool.clone();
+        let session_id = session_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_TAGS}
+                 JOIN workout_session_tags link ON link.tag_id = t.id
+                 WHERE link.session_id = ?
+                 ORDER BY t.name COLLATE NOCASE"
+            ))?;
+            let tags = stmt
+                .query_map([&session_id], WorkoutTag::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(tags)
+        })
+        .await?
+    }
+
+    /// Add a tag. Returns `BadRequest` if the user already has one by that
+    /// name, in any case.
+    pub async fn create(&self, user_id: &str, name: &str) -> Result<WorkoutTag> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let name = name.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let id = Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO workout_tags (id, user_id, name) VALUES (?, ?, ?)",
+                rusqlite::params![id, user_id, name],
+            )
+            .map_err(duplicate_name)?;
+            Ok(WorkoutTag {
+                id,
+                name,
+                session_count: 0,
+            })
+        })
+        .await?
+    }
+
+    pub async fn rename(&self, user_id: &str, id: &str, name: &str) -> Result<bool> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let id = id.to_string();
+        let name = name.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let rows = conn
+                .execute(
+                    "UPDATE workout_tags SET name = ? WHERE id = ? AND user_id = ?",
+                    rusqlite::params![name, id, user_id],
+                )
+                .map_err(duplicate_name)?;
+            Ok(rows > 0)
+        })
+        .await?
+    }
+
+    /// Delete a tag; it comes off every session that carried it.
+    pub async fn delete(&self, user_id: &str, id: &str) -> Result<bool> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let id = id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let rows = conn.execute(
+                "DELETE FROM workout_tags WHERE id = ? AND user_id = ?",
+                rusqlite::params![id, user_id],
+            )?;
+            Ok(rows > 0)
+        })
+        .await?
+    }
+
+    /// Tag names per session for a page of sessions, alphabetically.
+    /// Sessions without tags are left out of the map.
+    pub async fn find_names_for_sessions(
+        &self,
+        session_ids: Vec<String>,
+    ) -> Result<HashMap<String, Vec<String>>> {
+        if session_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let pool = self.pool.clone();
+        let placeholders = vec!["?"; session_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT st.session_id, t.name FROM workout_tags t
+             JOIN workout_session_tags st ON st.tag_id = t.id
+             WHERE st.session_id IN ({placeholders})
+             ORDER BY t.name COLLATE NOCASE"
+        );
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(session_ids), |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            let mut names: HashMap<String, Vec<String>> = HashMap::new();
+            for (session_id, name) in rows {
+                names.entry(session_id).or_default().push(name);
+            }
+            Ok(names)
+        })
+        .await?
+    }
+}
+
+fn duplicate_name(e: rusqlite::Error) -> AppError {
+    match e {
+        rusqlite::Error::SqliteFailure(ref failure, _)
+            if failure.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE =>
+        {
+            AppError::BadRequest("A tag with that name already exists".to_string())
+        }
+        e => AppError::from(e),
+    }
+}
+
+/// Replace a session's tags inside `tx` with `tag_ids` plus `new_names`.
+/// New names reuse an existing tag of the same name (in any case) and create
+/// the rest; ids of tags the user does not own are ignored. Used by the
+/// workout writes that save a session and its tags together, whose callers
+/// check the user owns the session.
+pub(crate) fn replace_session_tags(
+    tx: &rusqlite::Transaction<'_>,
+    user_id: &str,
+    session_id: &str,
+    tag_ids: Vec<String>,
+    new_names: &[String],
+) -> rusqlite::Result<()> {
+    let mut ids = tag_ids;
+    for name in new_names {
+        let existing: Option<String> = tx
+            .query_row(
+                "SELECT id FROM workout_tags WHERE user_id = ? AND name = ?",
+                rusqlite::params![user_id, name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let id = if let Some(id) = existing {
+            id
+        } else {
+            let id = Uuid::new_v4().to_string();
+            tx.execute(
+                "INSERT INTO workout_tags (id, user_id, name) VALUES (?, ?, ?)",
+                rusqlite::params![id, user_id, name],
+            )?;
+            id
+        };
+        ids.push(id);
+    }
+
+    tx.execute(
+        "DELETE FROM workout_session_tags WHERE session_id = ?",
+        [session_id],
+    )?;
+    for id in &ids {
+        tx.execute(
+            "INSERT OR IGNORE INTO workout_session_tags (session_id, tag_id)
+             SELECT ?, id FROM workout_tags WHERE id = ? AND user_id = ?",
+            rusqlite::params![session_id, id, user_id],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_memory_pool;
+    use crate::migrations::run_migrations_for_tests;
+
+    fn setup_test_db() -> DbPool {
+        let pool = create_memory_pool().expect("Failed to create test database");
+        run_migrations_for_tests(&pool).expect("Failed to run migrations");
+        pool
+    }
+
+    fn create_test_user(pool: &DbPool, user_id: &str) {
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO users (id, username, password_hash, role, created_at) VALUES (?, ?, ?, ?, datetime('now'))",
+            rusqlite::params![user_id, format!("user_{}", user_id), "hash", "user"],
+        ).unwrap();
+    }
+
+    fn create_test_session(pool: &DbPool, session_id: &str, user_id: &str) {
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO workout_sessions (id, user_id, date) VALUES (?, ?, '2024-01-15')",
+            rusqlite::params![session_id, user_id],
+        )
+        .unwrap();
+    }
+
+    /// Replace a test session's tags the way the workout editor does.
+    async fn set_session_tags(
+        pool: &DbPool,
+        session_id: &str,
+        tag_ids: Vec<String>,
+        new_names: Vec<String>,
+    ) {
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let updated = crate::repositories::WorkoutRepository::new(pool.clone())
+            .update_session_with_tags(session_id, "user1", date, None, tag_ids, new_names)
+            .await
+            .unwrap();
+        assert!(updated);
+    }
+
+    fn names(tags: &[WorkoutTag]) -> Vec<&str> {
+        tags.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_names_are_unique_per_user_in_any_case() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        let repo = TagRepository::new(pool);
+
+        repo.create("user1", "Push").await.unwrap();
+        let duplicate = repo.create("user1", "push").await;
+        assert!(matches!(duplicate, Err(AppError::BadRequest(_))));
+        repo.create("user2", "push").await.unwrap();
+
+        let legs = repo.create("user1", "legs").await.unwrap();
+        let clash = repo.rename("user1", &legs.id, "PUSH").await;
+        assert!(matches!(clash, Err(AppError::BadRequest(_))));
+        assert!(!repo.rename("user2", &legs.id, "Legs").await.unwrap());
+        assert_eq!(
+            names(&repo.find_by_user("user1").await.unwrap()),
+            vec!["legs", "Push"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_tags_reuse_create_and_replace() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        create_test_session(&pool, "s1", "user1");
+        create_test_session(&pool, "s2", "user1");
+        create_test_session(&pool, "s3", "user1");
+        let repo = TagRepository::new(pool.clone());
+
+        let push = repo.create("user1", "Push").await.unwrap();
+        let foreign = repo.create("user2", "secret").await.unwrap();
+        set_session_tags(
+            &pool,
+            "s1",
+            vec![foreign.id.clone()],
+            vec!["push".to_string(), "Heavy".to_string()],
+        )
+        .await;
+
+        let tags = repo.find_for_session("s1").await.unwrap();
+        assert_eq!(names(&tags), vec!["Heavy", "Push"]);
+        assert_eq!(tags[1].id, push.id);
+        assert_eq!(repo.find_by_user("user1").await.unwrap().len(), 2);
+
+        set_session_tags(&pool, "s1", vec![push.id.clone()], Vec::new()).await;
+        set_session_tags(
+            &pool,
+            "s2",
+            Vec::new(),
+            vec!["Legs".to_string(), "heavy".to_string()],
+        )
+        .await;
+        let names_by_session = repo
+            .find_names_for_sessions(vec!["s1".to_string(), "s2".to_string(), "s3".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(names_by_session["s1"], vec!["Push"]);
+        assert_eq!(names_by_session["s2"], vec!["Heavy", "Legs"]);
+        assert!(!names_by_session.contains_key("s3"));
+
+        assert!(repo.delete("user1", &push.id).await.unwrap());
+        assert!(repo.find_for_session("s1").await.unwrap().is_empty());
+    }
+}
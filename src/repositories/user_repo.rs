@@ -8,7 +8,9 @@ use uuid::Uuid;
 
 use crate::db::DbPool;
 use crate::error::{AppError, Result};
-use crate::models::{FromSqliteRow, User, UserListItem, UserRole};
+use crate::models::{
+    CoachGrant, FromSqliteRow, MuscleTarget, User, UserListItem, UserPreferences, UserRole,
+};
 
 #[derive(Clone)]
 pub struct UserRepository {
@@ -185,6 +187,199 @@ impl UserRepository {
         })
         .await?
     }
+
+    /// Weekly hard-set targets the user has customised. Muscles without a row
+    /// fall back to the defaults in `models::muscle`.
+    pub async fn get_muscle_targets(&self, user_id: &str) -> Result<Vec<MuscleTarget>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT muscle, min_sets, max_sets FROM muscle_volume_targets WHERE user_id = ?",
+            )?;
+            let targets = stmt
+                .query_map([&user_id], MuscleTarget::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(targets)
+        })
+        .await?
+    }
+
+    /// Replace the user's targets wholesale, in one transaction.
+    pub async fn set_muscle_targets(
+        &self,
+        user_id: &str,
+        targets: Vec<MuscleTarget>,
+    ) -> Result<()> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            tx.execute(
+                "DELETE FROM muscle_volume_targets WHERE user_id = ?",
+                [&user_id],
+            )?;
+            for t in &targets {
+                tx.execute(
+                    "INSERT INTO muscle_volume_targets (user_id, muscle, min_sets, max_sets)
+                     VALUES (?, ?, ?, ?)",
+                    rusqlite::params![user_id, t.muscle, t.min_sets, t.max_sets],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// The user's training preferences, or the defaults if they have never
+    /// saved any.
+    pub async fn get_preferences(&self, user_id: &str) -> Result<UserPreferences> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let preferences = conn
+                .query_row(
+                    "SELECT weekly_session_target, timezone, week_start
+                     FROM user_preferences WHERE user_id = ?",
+                    [&user_id],
+                    UserPreferences::from_row,
+                )
+                .optional()?;
+            Ok(preferences.unwrap_or_default())
+        })
+        .await?
+    }
+
+    pub async fn set_preferences(&self, user_id: &str, preferences: UserPreferences) -> Result<()> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            conn.execute(
+                "INSERT INTO user_preferences (user_id, weekly_session_target, timezone, week_start)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(user_id) DO UPDATE SET
+                     weekly_session_target = ?2, timezone = ?3, week_start = ?4",
+                rusqlite::params![
+                    user_id,
+                    preferences.weekly_session_target,
+                    preferences.timezone.name(),
+                    preferences.week_start.to_string(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Give `coach_id` read access to the athlete's workouts and stats, or
+    /// change what an existing coach may do.
+    pub async fn grant_coach(
+        &self,
+        athlete_id: &str,
+        coach_id: &str,
+        can_comment: bool,
+        can_program: bool,
+    ) -> Result<()> {
+        if athlete_id == coach_id {
+            return Err(AppError::BadRequest(
+                "You cannot coach yourself".to_string(),
+            ));
+        }
+        let pool = self.pool.clone();
+        let athlete_id = athlete_id.to_string();
+        let coach_id = coach_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            conn.execute(
+                "INSERT INTO coach_grants (athlete_id, coach_id, can_comment, can_program, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(athlete_id, coach_id) DO UPDATE SET
+                     can_comment = ?3, can_program = ?4",
+                rusqlite::params![athlete_id, coach_id, can_comment, can_program, Utc::now()],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn revoke_coach(&self, athlete_id: &str, coach_id: &str) -> Result<bool> {
+        let pool = self.pool.clone();
+        let athlete_id = athlete_id.to_string();
+        let coach_id = coach_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let rows = conn.execute(
+                "DELETE FROM coach_grants WHERE athlete_id = ? AND coach_id = ?",
+                rusqlite::params![athlete_id, coach_id],
+            )?;
+            Ok(rows > 0)
+        })
+        .await?
+    }
+
+    /// The grants where `user_id` is the athlete (`as_athlete`) or the
+    /// coach, by the other user's name.
+    pub async fn find_coach_grants(
+        &self,
+        user_id: &str,
+        as_athlete: bool,
+    ) -> Result<Vec<CoachGrant>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let (column, order) = if as_athlete {
+            ("athlete_id", "coach_username")
+        } else {
+            ("coach_id", "athlete_username")
+        };
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT g.*, a.username AS athlete_username, c.username AS coach_username
+                 FROM coach_grants g
+                 JOIN users a ON a.id = g.athlete_id
+                 JOIN users c ON c.id = g.coach_id
+                 WHERE g.{column} = ?
+                 ORDER BY {order} COLLATE NOCASE"
+            ))?;
+            let grants = stmt
+                .query_map([&user_id], CoachGrant::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(grants)
+        })
+        .await?
+    }
+
+    /// The grant letting `coach_id` coach `athlete_id`, if there is one.
+    pub async fn find_coach_grant(
+        &self,
+        athlete_id: &str,
+        coach_id: &str,
+    ) -> Result<Option<CoachGrant>> {
+        let pool = self.pool.clone();
+        let athlete_id = athlete_id.to_string();
+        let coach_id = coach_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let grant = conn
+                .query_row(
+                    "SELECT g.*, a.username AS athlete_username, c.username AS coach_username
+                     FROM coach_grants g
+                     JOIN users a ON a.id = g.athlete_id
+                     JOIN users c ON c.id = g.coach_id
+                     WHERE g.athlete_id = ? AND g.coach_id = ?",
+                    rusqlite::params![athlete_id, coach_id],
+                    CoachGrant::from_row,
+                )
+                .optional()?;
+            Ok(grant)
+        })
+        .await?
+    }
 }
 
 /// Arbitrary; it is never a real credential. Only the hash derived from it is
@@ -558,4 +753,98 @@ mod tests {
             .unwrap();
         assert!(!changed);
     }
+
+    #[tokio::test]
+    async fn test_preferences_default_until_saved() {
+        let pool = setup_test_db();
+        let repo = UserRepository::new(pool);
+        let user = repo
+            .create("prefuser", "password123", UserRole::User)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repo.get_preferences(&user.id).await.unwrap(),
+            UserPreferences::default()
+        );
+        let mut preferences = UserPreferences {
+            weekly_session_target: 5,
+            ..UserPreferences::default()
+        };
+        repo.set_preferences(&user.id, preferences.clone())
+            .await
+            .unwrap();
+        preferences.timezone = chrono_tz::Tz::Europe__Berlin;
+        preferences.week_start = chrono::Weekday::Sun;
+        repo.set_preferences(&user.id, preferences.clone())
+            .await
+            .unwrap();
+        assert_eq!(repo.get_preferences(&user.id).await.unwrap(), preferences);
+    }
+
+    #[tokio::test]
+    async fn test_coach_grants_update_in_place_and_revoke() {
+        let pool = setup_test_db();
+        let repo = UserRepository::new(pool);
+        let athlete = repo
+            .create("athlete", "password123", UserRole::User)
+            .await
+            .unwrap();
+        let coach = repo
+            .create("coach", "password123", UserRole::User)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            repo.grant_coach(&athlete.id, &athlete.id, true, true).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(
+            repo.find_coach_grant(&athlete.id, &coach.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        repo.grant_coach(&athlete.id, &coach.id, false, false)
+            .await
+            .unwrap();
+        repo.grant_coach(&athlete.id, &coach.id, true, false)
+            .await
+            .unwrap();
+        let grant = repo
+            .find_coach_grant(&athlete.id, &coach.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(grant.coach_username, "coach");
+        assert!(grant.can_comment && !grant.can_program);
+
+        let coaches = repo.find_coach_grants(&athlete.id, true).await.unwrap();
+        assert_eq!(coaches.len(), 1);
+        let athletes = repo.find_coach_grants(&coach.id, false).await.unwrap();
+        assert_eq!(athletes[0].athlete_username, "athlete");
+        assert!(
+            repo.find_coach_grants(&coach.id, true)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        // Grants run one way: the athlete does not coach their coach.
+        assert!(
+            repo.find_coach_grant(&coach.id, &athlete.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(repo.revoke_coach(&athlete.id, &coach.id).await.unwrap());
+        assert!(!repo.revoke_coach(&athlete.id, &coach.id).await.unwrap());
+        assert!(
+            repo.find_coach_grants(&athlete.id, true)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
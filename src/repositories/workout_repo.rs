@@ -1,13 +1,48 @@
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
 use rusqlite::OptionalExtension;
+use rusqlite::types::Value;
 use uuid::Uuid;
 
 use crate::db::DbPool;
 use crate::error::{AppError, Result};
+use crate::models::muscle::{HARD_SET_MAX_RIR, HARD_SET_MIN_RPE, SECONDARY_SET_CREDIT};
 use crate::models::{
-    DynamicPR, FromSqliteRow, LastExerciseWeight, PersonalRecordSummary, WorkoutLog,
-    WorkoutLogWithExercise, WorkoutSession,
+    Access, BlockScope, DailyVolume, DynamicPR, ExercisePeriodTotals, ExerciseSet, FromSqliteRow,
+    LastExerciseWeight, MonthlyTotals, MuscleWeeklyVolume, PageCursor, PeriodPersonalRecord,
+    PeriodSummary, PersonalRecordSummary, PrGain, SetNoteMatch, SetValues, SideBalance,
+    TagMonthlyVolume, WeeklySessionCount, WorkoutCursor, WorkoutFilter, WorkoutLog,
+    WorkoutLogWithExercise, WorkoutPage, WorkoutSession, WorkoutSort, WorkoutSummary,
 };
+use crate::repositories::tag_repo::replace_session_tags;
+use crate::text_import::ImportedSession;
+
+/// Volume of the set `wl`: weight × reps, summed over both sides for a
+/// unilateral set.
+const SET_VOLUME: &str = "(wl.weight * wl.reps + COALESCE(wl.right_weight * wl.right_reps, 0))";
+
+/// Filter for the sessions aliased `ws` in a `BlockScope`, bound by
+/// `block_params` as user id, from, to and tag id; unset bounds are NULL.
+fn block_scope(ws: &str) -> String {
+    format!(
+        "{ws}.user_id = ?1
+         AND (?2 IS NULL OR {ws}.date >= ?2) AND (?3 IS NULL OR {ws}.date <= ?3)
+         AND (?4 IS NULL OR EXISTS (SELECT 1 FROM workout_session_tags {ws}_tags
+                                    WHERE {ws}_tags.session_id = {ws}.id
+                                      AND {ws}_tags.tag_id = ?4))"
+    )
+}
+
+/// `block_scope`'s parameters.
+fn block_params(user_id: &str, scope: &BlockScope) -> [Value; 4] {
+    [
+        Value::Text(user_id.to_string()),
+        scope
+            .from
+            .map_or(Value::Null, |d| Value::Text(d.to_string())),
+        scope.to.map_or(Value::Null, |d| Value::Text(d.to_string())),
+        scope.tag_id.clone().map_or(Value::Null, Value::Text),
+    ]
+}
 
 #[derive(Clone)]
 pub struct WorkoutRepository {
@@ -35,6 +70,7 @@ impl WorkoutRepository {
             notes: notes.map(std::string::ToString::to_string),
             share_token: None,
             share_expires_at: None,
+            share_history: false,
             created_at: now,
         };
         let session_clone = session.clone();
@@ -59,8 +95,61 @@ impl WorkoutRepository {
         Ok(session)
     }
 
+    /// Create a session with the tags picked for it (`tag_ids` plus
+    /// `new_names`, as `update_session_with_tags` takes them) in one
+    /// transaction, so a failed tag write leaves no untagged workout behind.
+    pub async fn create_session_with_tags(
+        &self,
+        user_id: &str,
+        date: NaiveDate,
+        notes: Option<&str>,
+        tag_ids: Vec<String>,
+        new_names: Vec<String>,
+    ) -> Result<WorkoutSession> {
+        let session = WorkoutSession {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            date,
+            notes: notes.map(std::string::ToString::to_string),
+            share_token: None,
+            share_expires_at: None,
+            share_history: false,
+            created_at: Utc::now(),
+        };
+        let session_clone = session.clone();
+
+        let pool = self.pool.clone();
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT INTO workout_sessions (id, user_id, date, notes, created_at) VALUES (?, ?, ?, ?, ?)",
+                rusqlite::params![
+                    session_clone.id,
+                    session_clone.user_id,
+                    session_clone.date,
+                    session_clone.notes,
+                    session_clone.created_at
+                ],
+            )?;
+            replace_session_tags(
+                &tx,
+                &session_clone.user_id,
+                &session_clone.id,
+                tag_ids,
+                &new_names,
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await??;
+
+        Ok(session)
+    }
+
     /// Fetch a session owned by `user_id`. Returns `NotFound` for both
     /// missing rows and rows belonging to another user (don't leak existence).
+    /// Coaches count as another user here: editing stays with the owner.
     pub async fn find_owned_session(&self, id: &str, user_id: &str) -> Result<WorkoutSession> {
         let session = self
             .find_session_by_id(id)
@@ -72,6 +161,47 @@ impl WorkoutRepository {
         Ok(session)
     }
 
+    /// Fetch a session `user_id` may see, with what they may do with it:
+    /// their own, or one of an athlete they coach. `NotFound` for anything
+    /// else, as for `find_owned_session`.
+    pub async fn find_accessible_session(
+        &self,
+        id: &str,
+        user_id: &str,
+    ) -> Result<(WorkoutSession, Access)> {
+        let session = self
+            .find_session_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))?;
+        if session.user_id == user_id {
+            return Ok((session, Access::Owner));
+        }
+        let pool = self.pool.clone();
+        let athlete_id = session.user_id.clone();
+        let user_id = user_id.to_string();
+        let access = tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let access = conn
+                .query_row(
+                    "SELECT can_comment, can_program FROM coach_grants
+                     WHERE athlete_id = ? AND coach_id = ?",
+                    rusqlite::params![athlete_id, user_id],
+                    |row| {
+                        Ok(Access::Coach {
+                            can_comment: row.get(0)?,
+                            can_program: row.get(1)?,
+                        })
+                    },
+                )
+                .optional()?;
+            Ok::<_, AppError>(access)
+        })
+        .await??;
+        access
+            .map(|access| (session, access))
+            .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))
+    }
+
     pub async fn find_session_by_id(&self, id: &str) -> Result<Option<WorkoutSession>> {
         let pool = self.pool.clone();
         let id = id.to_string();
@@ -120,12 +250,124 @@ impl WorkoutRepository {
         .await?
     }
 
-    pub async fn update_session(
+    /// One page of the user's sessions matching `filter`, in `sort` order,
+    /// read from `cursor` with keyset pagination (no `OFFSET`, so deep pages
+    /// cost the same as the first).
+    pub async fn find_sessions_page(
+        &self,
+        user_id: &str,
+        filter: WorkoutFilter,
+        sort: WorkoutSort,
+        cursor: Option<PageCursor>,
+        limit: i64,
+    ) -> Result<WorkoutPage> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut params = vec![Value::Text(user_id)];
+            let filters = session_filter_sql(&filter, &mut params);
+
+            let key = if sort == WorkoutSort::Volume {
+                "volume"
+            } else {
+                "date"
+            };
+            // Reading backwards flips the scan and the comparison; the rows
+            // are put back in display order below.
+            let backwards = matches!(cursor, Some(PageCursor::Before(_)));
+            let ascending = sort.descending() == backwards;
+            let (order, comparison) = if ascending {
+                ("ASC", ">")
+            } else {
+                ("DESC", "<")
+            };
+            let mut bound = String::new();
+            if let Some(PageCursor::After(c) | PageCursor::Before(c)) = &cursor {
+                bound = format!("WHERE (s.{key}, s.id) {comparison} (?, ?)");
+                params.push(if sort == WorkoutSort::Volume {
+                    Value::Real(c.key.parse().unwrap_or_default())
+                } else {
+                    Value::Text(c.key.clone())
+                });
+                params.push(Value::Text(c.id.clone()));
+            }
+            params.push(Value::Integer(limit + 1));
+
+            let sql = format!(
+                "SELECT * FROM (
+                     SELECT ws.*,
+                            (SELECT COUNT(*) FROM workout_logs wl
+                             WHERE wl.session_id = ws.id) AS set_count,
+                            COALESCE((SELECT SUM({SET_VOLUME}) FROM workout_logs wl
+                                      WHERE wl.session_id = ws.id), 0.0) AS volume
+                     FROM workout_sessions ws
+                     WHERE ws.user_id = ?{filters}
+                 ) AS s
+                 {bound}
+                 ORDER BY s.{key} {order}, s.id {order}
+                 LIMIT ?"
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let mut workouts = stmt
+                .query_map(rusqlite::params_from_iter(params), WorkoutSummary::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let more = workouts.len() > usize::try_from(limit).unwrap_or(0);
+            workouts.truncate(usize::try_from(limit).unwrap_or(0));
+            if backwards {
+                workouts.reverse();
+            }
+            let first = workouts
+                .first()
+                .map(|w| WorkoutCursor::for_workout(w, sort));
+            let last = workouts.last().map(|w| WorkoutCursor::for_workout(w, sort));
+            let (prev, next) = match cursor {
+                None => (None, last.filter(|_| more)),
+                Some(PageCursor::After(_)) => (first, last.filter(|_| more)),
+                Some(PageCursor::Before(_)) => (first.filter(|_| more), last),
+            };
+            Ok(WorkoutPage {
+                workouts,
+                prev,
+                next,
+            })
+        })
+        .await?
+    }
+
+    pub async fn count_sessions_filtered(
+        &self,
+        user_id: &str,
+        filter: WorkoutFilter,
+    ) -> Result<i64> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut params = vec![Value::Text(user_id)];
+            let filters = session_filter_sql(&filter, &mut params);
+            let count: i64 = conn.query_row(
+                &format!("SELECT COUNT(*) FROM workout_sessions ws WHERE ws.user_id = ?{filters}"),
+                rusqlite::params_from_iter(params),
+                |row| row.get(0),
+            )?;
+            Ok(count)
+        })
+        .await?
+    }
+
+    /// Update a session's date and notes and replace its tags in one
+    /// transaction. `false`, with nothing changed, when `user_id` has no
+    /// such session.
+    pub async fn update_session_with_tags(
         &self,
         id: &str,
         user_id: &str,
-        date: Option<NaiveDate>,
+        date: NaiveDate,
         notes: Option<&str>,
+        tag_ids: Vec<String>,
+        new_names: Vec<String>,
     ) -> Result<bool> {
         let pool = self.pool.clone();
         let id = id.to_string();
@@ -133,19 +375,18 @@ impl WorkoutRepository {
         let notes = notes.map(std::string::ToString::to_string);
 
         tokio::task::spawn_blocking(move || {
-            let conn = pool.get()?;
-            let rows = if let Some(d) = date {
-                conn.execute(
-                    "UPDATE workout_sessions SET date = ?, notes = ? WHERE id = ? AND user_id = ?",
-                    rusqlite::params![d, notes, id, user_id],
-                )?
-            } else {
-                conn.execute(
-                    "UPDATE workout_sessions SET notes = ? WHERE id = ? AND user_id = ?",
-                    rusqlite::params![notes, id, user_id],
-                )?
-            };
-            Ok(rows > 0)
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            let rows = tx.execute(
+                "UPDATE workout_sessions SET date = ?, notes = ? WHERE id = ? AND user_id = ?",
+                rusqlite::params![date, notes, id, user_id],
+            )?;
+            if rows == 0 {
+                return Ok(false);
+            }
+            replace_session_tags(&tx, &user_id, &id, tag_ids, &new_names)?;
+            tx.commit()?;
+            Ok(true)
         })
         .await?
     }
@@ -171,9 +412,7 @@ impl WorkoutRepository {
         session_id: &str,
         exercise_id: &str,
         set_number: i32,
-        reps: i32,
-        weight: f64,
-        rpe: Option<i32>,
+        values: &SetValues,
     ) -> Result<WorkoutLog> {
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
@@ -182,9 +421,14 @@ impl WorkoutRepository {
             session_id: session_id.to_string(),
             exercise_id: exercise_id.to_string(),
             set_number,
-            reps,
-            weight,
-            rpe,
+            reps: values.reps,
+            weight: values.weight,
+            rpe: values.rpe,
+            right_reps: values.right_reps,
+            right_weight: values.right_weight,
+            notes: values.notes.clone(),
+            tempo: values.tempo.clone(),
+            rir: values.rir,
             created_at: now,
         };
         let log_clone = log.clone();
@@ -193,8 +437,9 @@ impl WorkoutRepository {
         tokio::task::spawn_blocking(move || -> Result<()> {
             let conn = pool.get()?;
             conn.execute(
-                "INSERT INTO workout_logs (id, session_id, exercise_id, set_number, reps, weight, rpe, created_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                "INSERT INTO workout_logs (id, session_id, exercise_id, set_number, reps, weight, rpe,
+                                           right_reps, right_weight, notes, tempo, rir, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                 rusqlite::params![
                     log_clone.id,
                     log_clone.session_id,
@@ -203,6 +448,11 @@ impl WorkoutRepository {
                     log_clone.reps,
                     log_clone.weight,
                     log_clone.rpe,
+                    log_clone.right_reps,
+                    log_clone.right_weight,
+                    log_clone.notes,
+                    log_clone.tempo,
+                    log_clone.rir,
                     log_clone.created_at
                 ],
             )?;
@@ -213,6 +463,56 @@ impl WorkoutRepository {
         Ok(log)
     }
 
+    /// Log several sets in one transaction, numbering each after the sets
+    /// already in the session for its exercise. `sets` pairs an exercise id
+    /// with the set's values, in the order they were performed.
+    pub async fn create_logs(
+        &self,
+        session_id: &str,
+        sets: Vec<(String, SetValues)>,
+    ) -> Result<usize> {
+        let pool = self.pool.clone();
+        let session_id = session_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            for (exercise_id, values) in &sets {
+                insert_next_log(&tx, &session_id, exercise_id, values)?;
+            }
+            tx.commit()?;
+            Ok(sets.len())
+        })
+        .await?
+    }
+
+    /// Create the sessions of a text import, with their sets, in one
+    /// transaction. Returns the number of sessions created.
+    pub async fn import_sessions(
+        &self,
+        user_id: &str,
+        sessions: Vec<ImportedSession>,
+    ) -> Result<usize> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            for session in &sessions {
+                let session_id = Uuid::new_v4().to_string();
+                tx.execute(
+                    "INSERT INTO workout_sessions (id, user_id, date, notes, created_at) VALUES (?, ?, ?, ?, ?)",
+                    rusqlite::params![session_id, user_id, session.date, session.notes, Utc::now()],
+                )?;
+                for set in &session.sets {
+                    insert_next_log(&tx, &session_id, &set.exercise_id, &set.values())?;
+                }
+            }
+            tx.commit()?;
+            Ok(sessions.len())
+        })
+        .await?
+    }
+
     /// Find logs by session with dynamically computed `is_pr` and
     /// `is_recent_pr`, the latter measured over the window starting at `since`.
     pub async fn find_logs_by_session_with_pr(
@@ -228,7 +528,8 @@ impl WorkoutRepository {
             let conn = pool.get()?;
             let mut stmt = conn.prepare(
                 "SELECT wl.id, wl.session_id, wl.exercise_id, e.name as exercise_name,
-                        wl.set_number, wl.reps, wl.weight, wl.rpe,
+                        wl.set_number, wl.reps, wl.weight, wl.rpe, wl.right_reps, wl.right_weight,
+                        wl.notes, wl.tempo, wl.rir,
                         CASE WHEN wl.weight = (
                             SELECT MAX(wl2.weight) FROM workout_logs wl2
                             JOIN workout_sessions ws2 ON wl2.session_id = ws2.id
@@ -268,37 +569,200 @@ impl WorkoutRepository {
         .await?
     }
 
+    /// Delete a set and renumber the exercise's remaining sets in the
+    /// session, so deleting set 2 of 4 leaves sets 1–3.
     pub async fn delete_log(&self, id: &str, session_id: &str) -> Result<bool> {
         let pool = self.pool.clone();
         let id = id.to_string();
         let session_id = session_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            let exercise_id: Option<String> = tx
+                .query_row(
+                    "SELECT exercise_id FROM workout_logs WHERE id = ? AND session_id = ?",
+                    rusqlite::params![id, session_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let Some(exercise_id) = exercise_id else {
+                return Ok(false);
+            };
+            tx.execute("DELETE FROM workout_logs WHERE id = ?", [&id])?;
+            renumber_sets(&tx, &session_id, &exercise_id)?;
+            tx.commit()?;
+            Ok(true)
+        })
+        .await?
+    }
+
+    /// The sets of one exercise in a session, in set order.
+    pub async fn find_logs_for_exercise(
+        &self,
+        session_id: &str,
+        exercise_id: &str,
+    ) -> Result<Vec<WorkoutLog>> {
+        let pool = self.pool.clone();
+        let session_id = session_id.to_string();
+        let exercise_id = exercise_id.to_string();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
-            let rows = conn.execute(
-                "DELETE FROM workout_logs WHERE id = ? AND session_id = ?",
-                rusqlite::params![id, session_id],
+            let mut stmt = conn.prepare(
+                "SELECT * FROM workout_logs WHERE session_id = ? AND exercise_id = ?
+                 ORDER BY set_number, created_at",
             )?;
-            Ok(rows > 0)
+            let logs = stmt
+                .query_map(
+                    rusqlite::params![session_id, exercise_id],
+                    WorkoutLog::from_row,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(logs)
         })
         .await?
     }
 
-    pub async fn update_log(
+    /// Renumber the exercise's sets in the session to follow `order`, which
+    /// must list exactly the ids of those sets. A list that no longer matches
+    /// (a set was added or deleted in another tab) is rejected rather than
+    /// half-applied.
+    pub async fn reorder_logs(
         &self,
-        id: &str,
         session_id: &str,
-        reps: i32,
-        weight: f64,
-        rpe: Option<i32>,
-    ) -> Result<bool> {
+        exercise_id: &str,
+        order: Vec<String>,
+    ) -> Result<()> {
+        let pool = self.pool.clone();
+        let session_id = session_id.to_string();
+        let exercise_id = exercise_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            let mut current: Vec<String> = {
+                let mut stmt = tx.prepare(
+                    "SELECT id FROM workout_logs WHERE session_id = ? AND exercise_id = ?",
+                )?;
+                stmt.query_map(rusqlite::params![session_id, exercise_id], |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?
+            };
+            let mut requested = order.clone();
+            current.sort();
+            requested.sort();
+            if current != requested {
+                return Err(AppError::BadRequest(
+                    "The sets changed since the page was loaded; reload and try again".to_string(),
+                ));
+            }
+            for (position, id) in order.iter().enumerate() {
+                tx.execute(
+                    "UPDATE workout_logs SET set_number = ? WHERE id = ?",
+                    rusqlite::params![i64::try_from(position).unwrap_or(i64::MAX) + 1, id],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Move sets of `session_id` to `target_exercise_id` in
+    /// `target_session_id` (which may be the same session), in one
+    /// transaction. Moved sets keep their relative order and are numbered
+    /// after the target's existing sets; the sets left behind are renumbered.
+    /// The right side is filled in or cleared to match `target_unilateral`,
+    /// and comments on the moved sets follow them to the target session.
+    ///
+    /// Callers check that the user owns both sessions and may use the
+    /// target exercise. Returns `NotFound` if any id is not a set of
+    /// `session_id`.
+    pub async fn move_logs(
+        &self,
+        session_id: &str,
+        log_ids: Vec<String>,
+        target_session_id: &str,
+        target_exercise_id: &str,
+        target_unilateral: bool,
+    ) -> Result<usize> {
+        let pool = self.pool.clone();
+        let session_id = session_id.to_string();
+        let target_session_id = target_session_id.to_string();
+        let target_exercise_id = target_exercise_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let tx = conn.transaction()?;
+            let mut logs: Vec<WorkoutLog> = Vec::with_capacity(log_ids.len());
+            for id in &log_ids {
+                let log = tx
+                    .query_row(
+                        "SELECT * FROM workout_logs WHERE id = ? AND session_id = ?",
+                        rusqlite::params![id, session_id],
+                        WorkoutLog::from_row,
+                    )
+                    .optional()?
+                    .ok_or_else(|| AppError::NotFound("Log not found".to_string()))?;
+                logs.push(log);
+            }
+            logs.sort_by_key(|log| (log.set_number, log.created_at));
+
+            let mut sources: Vec<String> = Vec::new();
+            for log in &logs {
+                tx.execute(
+                    "UPDATE workout_logs
+                     SET set_number = (
+                             SELECT COALESCE(MAX(t.set_number), 0) + 1 FROM workout_logs t
+                             WHERE t.session_id = ?2 AND t.exercise_id = ?3),
+                         session_id = ?2,
+                         exercise_id = ?3,
+                         right_reps = CASE WHEN ?4 THEN COALESCE(right_reps, reps) END,
+                         right_weight = CASE WHEN ?4 THEN COALESCE(right_weight, weight) END
+                     WHERE id = ?1",
+                    rusqlite::params![
+                        log.id,
+                        target_session_id,
+                        target_exercise_id,
+                        target_unilateral
+                    ],
+                )?;
+                tx.execute(
+                    "UPDATE workout_comments SET session_id = ? WHERE log_id = ?",
+                    rusqlite::params![target_session_id, log.id],
+                )?;
+                if !sources.contains(&log.exercise_id) {
+                    sources.push(log.exercise_id.clone());
+                }
+            }
+            for exercise_id in &sources {
+                renumber_sets(&tx, &session_id, exercise_id)?;
+            }
+            tx.commit()?;
+            Ok(logs.len())
+        })
+        .await?
+    }
+
+    pub async fn update_log(&self, id: &str, session_id: &str, values: &SetValues) -> Result<bool> {
         let pool = self.pool.clone();
         let id = id.to_string();
         let session_id = session_id.to_string();
+        let values = values.clone();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
             let rows = conn.execute(
-                "UPDATE workout_logs SET reps = ?, weight = ?, rpe = ? WHERE id = ? AND session_id = ?",
-                rusqlite::params![reps, weight, rpe, id, session_id],
+                "UPDATE workout_logs SET reps = ?, weight = ?, rpe = ?, right_reps = ?, right_weight = ?,
+                                         notes = ?, tempo = ?, rir = ?
+                 WHERE id = ? AND session_id = ?",
+                rusqlite::params![
+                    values.reps,
+                    values.weight,
+                    values.rpe,
+                    values.right_reps,
+                    values.right_weight,
+                    values.notes,
+                    values.tempo,
+                    values.rir,
+                    id,
+                    session_id
+                ],
             )?;
             Ok(rows > 0)
         })
@@ -446,7 +910,7 @@ impl WorkoutRepository {
         let exercise_id = exercise_id.to_string();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
-            let mut stmt = conn.prepare(
+            let mut stmt = conn.prepare(&format!(
                 "SELECT
                      ws.date AS date,
                      MAX(wl.weight) AS top_weight,
@@ -456,13 +920,13 @@ impl WorkoutRepository {
                          AND wl2.exercise_id = wl.exercise_id
                        ORDER BY wl2.weight DESC, wl2.reps DESC
                        LIMIT 1) AS top_reps,
-                     SUM(wl.weight * wl.reps) AS volume
+                     SUM({SET_VOLUME}) AS volume
                  FROM workout_logs wl
                  JOIN workout_sessions ws ON wl.session_id = ws.id
                  WHERE ws.user_id = ? AND wl.exercise_id = ?
                  GROUP BY ws.id
-                 ORDER BY ws.date ASC, ws.created_at ASC",
-            )?;
+                 ORDER BY ws.date ASC, ws.created_at ASC"
+            ))?;
             let rows = stmt
                 .query_map(
                     rusqlite::params![user_id, exercise_id],
@@ -474,32 +938,98 @@ impl WorkoutRepository {
         .await?
     }
 
-    // Statistics
-    pub async fn count_workouts_this_week(&self, user_id: &str) -> Result<i64> {
+    /// Every set the user logged of `exercise_ids` between `from` and `to`
+    /// (either end open), oldest session first and in set order.
+    pub async fn get_sets_for_exercises(
+        &self,
+        user_id: &str,
+        exercise_ids: &[String],
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<ExerciseSet>> {
+        if exercise_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let pool = self.pool.clone();
+        let mut params = vec![Value::Text(user_id.to_string())];
+        params.extend(exercise_ids.iter().cloned().map(Value::Text));
+        let placeholders = vec!["?"; exercise_ids.len()].join(", ");
+        let mut sql = format!(
+            "SELECT wl.exercise_id, wl.session_id, ws.date,
+                    wl.reps, wl.weight, wl.right_reps, wl.right_weight
+             FROM workout_logs wl
+             JOIN workout_sessions ws ON wl.session_id = ws.id
+             WHERE ws.user_id = ? AND wl.exercise_id IN ({placeholders})"
+        );
+        if let Some(from) = from {
+            sql.push_str(" AND ws.date >= ?");
+            params.push(Value::Text(from.to_string()));
+        }
+        if let Some(to) = to {
+            sql.push_str(" AND ws.date <= ?");
+            params.push(Value::Text(to.to_string()));
+        }
+        sql.push_str(" ORDER BY ws.date, ws.created_at, ws.id, wl.set_number");
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&sql)?;
+            let sets = stmt
+                .query_map(rusqlite::params_from_iter(params), ExerciseSet::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(sets)
+        })
+        .await?
+    }
+
+    /// Left/right totals over the user's unilateral sets of one exercise, or
+    /// `None` if they have logged none.
+    pub async fn get_side_balance(
+        &self,
+        user_id: &str,
+        exercise_id: &str,
+    ) -> Result<Option<SideBalance>> {
         let pool = self.pool.clone();
         let user_id = user_id.to_string();
+        let exercise_id = exercise_id.to_string();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
-            let count: i64 = conn.query_row(
-                "SELECT COUNT(*) FROM workout_sessions
-                 WHERE user_id = ? AND date >= date('now', '-7 days')",
-                [&user_id],
-                |row| row.get(0),
+            let balance = conn.query_row(
+                "SELECT COUNT(*) AS sets,
+                        COALESCE(SUM(wl.reps), 0) AS left_reps,
+                        COALESCE(SUM(wl.right_reps), 0) AS right_reps,
+                        COALESCE(SUM(wl.weight * wl.reps), 0.0) AS left_volume,
+                        COALESCE(SUM(wl.right_weight * wl.right_reps), 0.0) AS right_volume,
+                        COALESCE(MAX(wl.weight), 0.0) AS left_top_weight,
+                        COALESCE(MAX(wl.right_weight), 0.0) AS right_top_weight
+                 FROM workout_logs wl
+                 JOIN workout_sessions ws ON wl.session_id = ws.id
+                 WHERE ws.user_id = ? AND wl.exercise_id = ? AND wl.right_reps IS NOT NULL",
+                rusqlite::params![user_id, exercise_id],
+                SideBalance::from_row,
             )?;
-            Ok(count)
+            Ok((balance.sets > 0).then_some(balance))
         })
         .await?
     }
 
-    pub async fn count_workouts_this_month(&self, user_id: &str) -> Result<i64> {
+    // Statistics
+
+    /// Sessions dated `from` to `to` inclusive, e.g. the user's current
+    /// calendar week or month.
+    pub async fn count_sessions_between(
+        &self,
+        user_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<i64> {
         let pool = self.pool.clone();
         let user_id = user_id.to_string();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
             let count: i64 = conn.query_row(
                 "SELECT COUNT(*) FROM workout_sessions
-                 WHERE user_id = ? AND date >= date('now', '-30 days')",
-                [&user_id],
+                 WHERE user_id = ?1 AND date >= ?2 AND date <= ?3",
+                rusqlite::params![user_id, from, to],
                 |row| row.get(0),
             )?;
             Ok(count)
@@ -507,18 +1037,26 @@ impl WorkoutRepository {
         .await?
     }
 
-    pub async fn get_total_volume_this_week(&self, user_id: &str) -> Result<f64> {
+    /// Total volume of sessions dated `from` to `to` inclusive.
+    pub async fn get_volume_between(
+        &self,
+        user_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<f64> {
         let pool = self.pool.clone();
         let user_id = user_id.to_string();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
             let result: Option<f64> = conn
                 .query_row(
-                    "SELECT SUM(wl.weight * wl.reps)
-                     FROM workout_logs wl
-                     JOIN workout_sessions ws ON wl.session_id = ws.id
-                     WHERE ws.user_id = ? AND ws.date >= date('now', '-7 days')",
-                    [&user_id],
+                    &format!(
+                        "SELECT SUM({SET_VOLUME})
+                         FROM workout_logs wl
+                         JOIN workout_sessions ws ON wl.session_id = ws.id
+                         WHERE ws.user_id = ?1 AND ws.date >= ?2 AND ws.date <= ?3"
+                    ),
+                    rusqlite::params![user_id, from, to],
                     |row| row.get(0),
                 )
                 .optional()?
@@ -528,74 +1066,488 @@ impl WorkoutRepository {
         .await?
     }
 
-    /// Get exercise history with dynamically computed `is_pr` and
-    /// `is_recent_pr` (see `find_logs_by_session_with_pr`).
-    pub async fn get_exercise_history_with_pr(
+    /// Hard sets and volume per muscle group per training week (starting on
+    /// `first_day`), for every week starting on or after `since`. Primary muscles are
+    /// credited with the whole set, secondary ones with
+    /// `SECONDARY_SET_CREDIT` of it; only sets meeting `HARD_SET_MIN_RPE` or
+    /// `HARD_SET_MAX_RIR` (or with neither) count towards `hard_sets`, while
+    /// `volume` covers every set.
+    /// Exercises with no muscle mapping contribute nothing.
+    pub async fn get_weekly_muscle_volume(
         &self,
         user_id: &str,
-        exercise_id: &str,
-        limit: i64,
-        since: DateTime<Utc>,
-    ) -> Result<Vec<WorkoutLogWithExercise>> {
+        since: NaiveDate,
+        first_day: Weekday,
+    ) -> Result<Vec<MuscleWeeklyVolume>> {
         let pool = self.pool.clone();
         let user_id = user_id.to_string();
-        let exercise_id = exercise_id.to_string();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
-            let mut stmt = conn.prepare(
-                "SELECT wl.id, wl.session_id, wl.exercise_id, e.name as exercise_name,
-                        wl.set_number, wl.reps, wl.weight, wl.rpe,
-                        CASE WHEN wl.weight = (
-                            SELECT MAX(wl2.weight) FROM workout_logs wl2
-                            JOIN workout_sessions ws2 ON wl2.session_id = ws2.id
-                            WHERE ws2.user_id = ?1 AND wl2.exercise_id = wl.exercise_id
-                        ) THEN 1 ELSE 0 END as is_pr,
-                        CASE WHEN datetime(wl.created_at) >= datetime(?4) AND wl.weight = (
-                            SELECT MAX(wl3.weight) FROM workout_logs wl3
-                            JOIN workout_sessions ws3 ON wl3.session_id = ws3.id
-                            WHERE ws3.user_id = ?1 AND wl3.exercise_id = wl.exercise_id
-                              AND datetime(wl3.created_at) >= datetime(?4)
-                        ) THEN 1 ELSE 0 END as is_recent_pr
+            let week_start = week_start_sql(first_day);
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {week_start} AS week_start,
+                        em.muscle AS muscle,
+                        SUM(CASE WHEN (wl.rpe IS NULL AND wl.rir IS NULL)
+                                      OR wl.rpe >= ?4 OR wl.rir <= ?5
+                                 THEN CASE WHEN em.role = 'primary' THEN 1.0 ELSE ?3 END
+                                 ELSE 0.0 END) AS hard_sets,
+                        SUM({SET_VOLUME}
+                            * CASE WHEN em.role = 'primary' THEN 1.0 ELSE ?3 END) AS volume
                  FROM workout_logs wl
                  JOIN workout_sessions ws ON wl.session_id = ws.id
-                 JOIN exercises e ON wl.exercise_id = e.id
-                 WHERE ws.user_id = ?1 AND wl.exercise_id = ?2
-                 ORDER BY ws.date DESC, wl.set_number
-                 LIMIT ?3",
-            )?;
-            let logs = stmt
+                 JOIN exercise_muscles em ON em.exercise_id = wl.exercise_id
+                 WHERE ws.user_id = ?1 AND ws.date >= ?2
+                 GROUP BY week_start, em.muscle
+                 ORDER BY week_start, em.muscle"
+            ))?;
+            let rows = stmt
                 .query_map(
-                    rusqlite::params![user_id, exercise_id, limit, since],
-                    WorkoutLogWithExercise::from_row,
+                    rusqlite::params![
+                        user_id,
+                        since,
+                        SECONDARY_SET_CREDIT,
+                        HARD_SET_MIN_RPE,
+                        HARD_SET_MAX_RIR
+                    ],
+                    MuscleWeeklyVolume::from_row,
                 )?
                 .collect::<rusqlite::Result<Vec<_>>>()?;
-            Ok(logs)
+            Ok(rows)
         })
         .await?
     }
 
-    // Share functionality
-
-    /// Set share token for a workout session (creates a new token). `ttl` of
-    /// `None` means the link never expires (see migration 012's rationale).
-    pub async fn set_share_token(
+    /// Per-day session count, sets and volume for days from `from` to `to`
+    /// (inclusive) that have at least one session.
+    pub async fn get_daily_volume(
         &self,
-        id: &str,
         user_id: &str,
-        ttl: Option<chrono::Duration>,
-    ) -> Result<String> {
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyVolume>> {
         let pool = self.pool.clone();
-        let id = id.to_string();
         let user_id = user_id.to_string();
-        let token = Uuid::new_v4().to_string();
-        let token_clone = token.clone();
-        let expires_at = ttl.map(|d| Utc::now() + d);
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT ws.date AS date,
+                        COUNT(*) AS sessions,
+                        MIN(ws.id) AS session_id,
+                        SUM((SELECT COUNT(*) FROM workout_logs wl
+                             WHERE wl.session_id = ws.id)) AS sets,
+                        SUM(COALESCE((SELECT SUM({SET_VOLUME}) FROM workout_logs wl
+                                      WHERE wl.session_id = ws.id), 0.0)) AS volume
+                 FROM workout_sessions ws
+                 WHERE ws.user_id = ?1 AND ws.date >= ?2 AND ws.date <= ?3
+                 GROUP BY ws.date
+                 ORDER BY ws.date"
+            ))?;
+            let days = stmt
+                .query_map(rusqlite::params![user_id, from, to], DailyVolume::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(days)
+        })
+        .await?
+    }
+
+    /// Sessions with at least one set, per week starting on `first_day`, for
+    /// every week up to `through` (inclusive). Weeks without training are omitted, and
+    /// so are empty sessions, which are plans rather than training.
+    pub async fn get_weekly_session_counts(
+        &self,
+        user_id: &str,
+        through: NaiveDate,
+        first_day: Weekday,
+    ) -> Result<Vec<WeeklySessionCount>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} AS week_start,
+                        COUNT(*) AS sessions
+                 FROM workout_sessions ws
+                 WHERE ws.user_id = ?1 AND ws.date <= ?2
+                   AND EXISTS (SELECT 1 FROM workout_logs wl WHERE wl.session_id = ws.id)
+                 GROUP BY week_start
+                 ORDER BY week_start",
+                week_start_sql(first_day)
+            ))?;
+            let weeks = stmt
+                .query_map(
+                    rusqlite::params![user_id, through],
+                    WeeklySessionCount::from_row,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(weeks)
+        })
+        .await?
+    }
+
+    /// Sessions and total volume per tag per calendar month, for sessions on
+    /// or after `since`. A session with two tags counts towards both.
+    pub async fn get_monthly_tag_volume(
+        &self,
+        user_id: &str,
+        since: NaiveDate,
+    ) -> Result<Vec<TagMonthlyVolume>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT t.name AS tag_name,
+                        date(ws.date, 'start of month') AS month,
+                        COUNT(*) AS sessions,
+                        SUM(COALESCE((SELECT SUM({SET_VOLUME}) FROM workout_logs wl
+                                      WHERE wl.session_id = ws.id), 0.0)) AS volume
+                 FROM workout_session_tags st
+                 JOIN workout_tags t ON t.id = st.tag_id
+                 JOIN workout_sessions ws ON ws.id = st.session_id
+                 WHERE t.user_id = ?1 AND ws.date >= ?2
+                 GROUP BY t.id, month
+                 ORDER BY t.name COLLATE NOCASE, month"
+            ))?;
+            let rows = stmt
+                .query_map(
+                    rusqlite::params![user_id, since],
+                    TagMonthlyVolume::from_row,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows)
+        })
+        .await?
+    }
+
+    /// Sessions, sets, volume and average RPE of the sets in sessions dated
+    /// `from` to `to` inclusive.
+    pub async fn get_period_summary(
+        &self,
+        user_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<PeriodSummary> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let summary = conn.query_row(
+                &format!(
+                    "SELECT COUNT(DISTINCT ws.id) AS sessions,
+                            COUNT(wl.id) AS sets,
+                            COALESCE(SUM({SET_VOLUME}), 0.0) AS volume,
+                            AVG(wl.rpe) AS average_rpe
+                     FROM workout_logs wl
+                     JOIN workout_sessions ws ON wl.session_id = ws.id
+                     WHERE ws.user_id = ?1 AND ws.date >= ?2 AND ws.date <= ?3"
+                ),
+                rusqlite::params![user_id, from, to],
+                PeriodSummary::from_row,
+            )?;
+            Ok(summary)
+        })
+        .await?
+    }
+
+    /// Per-exercise totals for sessions dated `from` to `to` inclusive, most
+    /// volume first.
+    pub async fn get_exercise_totals_between(
+        &self,
+        user_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ExercisePeriodTotals>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT wl.exercise_id, e.name AS exercise_name,
+                        COUNT(DISTINCT ws.id) AS sessions,
+                        COUNT(*) AS sets,
+                        SUM({SET_VOLUME}) AS volume,
+                        MAX(wl.weight) AS top_weight,
+                        (SELECT wl2.reps FROM workout_logs wl2
+                         JOIN workout_sessions ws2 ON wl2.session_id = ws2.id
+                         WHERE ws2.user_id = ?1 AND ws2.date >= ?2 AND ws2.date <= ?3
+                           AND wl2.exercise_id = wl.exercise_id
+                         ORDER BY wl2.weight DESC, wl2.reps DESC
+                         LIMIT 1) AS top_reps,
+                        AVG(wl.rpe) AS average_rpe
+                 FROM workout_logs wl
+                 JOIN workout_sessions ws ON wl.session_id = ws.id
+                 JOIN exercises e ON wl.exercise_id = e.id
+                 WHERE ws.user_id = ?1 AND ws.date >= ?2 AND ws.date <= ?3
+                 GROUP BY wl.exercise_id
+                 ORDER BY volume DESC, e.name COLLATE NOCASE"
+            ))?;
+            let rows = stmt
+                .query_map(
+                    rusqlite::params![user_id, from, to],
+                    ExercisePeriodTotals::from_row,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows)
+        })
+        .await?
+    }
+
+    /// Exercises whose heaviest set in sessions dated `from` to `to`
+    /// (inclusive) beat every set in earlier sessions, heaviest first.
+    /// Unlike the all-time `is_pr` badge, a PR stays a PR here even once a
+    /// later session beats it.
+    pub async fn get_prs_between(
+        &self,
+        user_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PeriodPersonalRecord>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "WITH best AS (
+                     SELECT wl.exercise_id, MAX(wl.weight) AS weight
+                     FROM workout_logs wl
+                     JOIN workout_sessions ws ON wl.session_id = ws.id
+                     WHERE ws.user_id = ?1 AND ws.date >= ?2 AND ws.date <= ?3
+                     GROUP BY wl.exercise_id
+                 ),
+                 first_set AS (
+                     SELECT best.exercise_id, best.weight,
+                            (SELECT wl.id FROM workout_logs wl
+                             JOIN workout_sessions ws ON wl.session_id = ws.id
+                             WHERE ws.user_id = ?1 AND ws.date >= ?2 AND ws.date <= ?3
+                               AND wl.exercise_id = best.exercise_id
+                               AND wl.weight = best.weight
+                             ORDER BY ws.date, wl.reps DESC, wl.created_at
+                             LIMIT 1) AS log_id,
+                            (SELECT MAX(wl.weight) FROM workout_logs wl
+                             JOIN workout_sessions ws ON wl.session_id = ws.id
+                             WHERE ws.user_id = ?1 AND ws.date < ?2
+                               AND wl.exercise_id = best.exercise_id) AS previous_best
+                     FROM best
+                 )
+                 SELECT f.exercise_id, e.name AS exercise_name, f.weight, wl.reps,
+                        ws.date AS date, ws.id AS session_id, f.previous_best
+                 FROM first_set f
+                 JOIN workout_logs wl ON wl.id = f.log_id
+                 JOIN workout_sessions ws ON wl.session_id = ws.id
+                 JOIN exercises e ON f.exercise_id = e.id
+                 WHERE f.previous_best IS NULL OR f.weight > f.previous_best
+                 ORDER BY f.weight DESC, e.name COLLATE NOCASE",
+            )?;
+            let rows = stmt
+                .query_map(
+                    rusqlite::params![user_id, from, to],
+                    PeriodPersonalRecord::from_row,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows)
+        })
+        .await?
+    }
+
+    /// Sessions with at least one set, and their volume, per calendar month
+    /// for sessions dated `from` to `to` inclusive. Months without training
+    /// are omitted.
+    pub async fn get_monthly_totals_between(
+        &self,
+        user_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<MonthlyTotals>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT date(ws.date, 'start of month') AS month,
+                        COUNT(DISTINCT ws.id) AS sessions,
+                        SUM({SET_VOLUME}) AS volume
+                 FROM workout_logs wl
+                 JOIN workout_sessions ws ON wl.session_id = ws.id
+                 WHERE ws.user_id = ?1 AND ws.date >= ?2 AND ws.date <= ?3
+                 GROUP BY month
+                 ORDER BY month"
+            ))?;
+            let rows = stmt
+                .query_map(
+                    rusqlite::params![user_id, from, to],
+                    MonthlyTotals::from_row,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows)
+        })
+        .await?
+    }
+
+    /// Exercises whose best weight rose over sessions dated `from` to `to`
+    /// (inclusive), biggest gain first. The starting point is the best
+    /// before `from`, or, for an exercise first logged in the range, the top
+    /// set of its first session there.
+    pub async fn get_pr_gains_between(
+        &self,
+        user_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PrGain>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "WITH in_range AS (
+                     SELECT wl.exercise_id, wl.weight, ws.date
+                     FROM workout_logs wl
+                     JOIN workout_sessions ws ON wl.session_id = ws.id
+                     WHERE ws.user_id = ?1 AND ws.date >= ?2 AND ws.date <= ?3
+                 ),
+                 per_exercise AS (
+                     SELECT exercise_id, MAX(weight) AS end_weight, MIN(date) AS first_date
+                     FROM in_range
+                     GROUP BY exercise_id
+                 ),
+                 gains AS (
+                     SELECT p.exercise_id, p.end_weight,
+                            COALESCE(
+                                (SELECT MAX(wl.weight) FROM workout_logs wl
+                                 JOIN workout_sessions ws ON wl.session_id = ws.id
+                                 WHERE ws.user_id = ?1 AND ws.date < ?2
+                                   AND wl.exercise_id = p.exercise_id),
+                                (SELECT MAX(r.weight) FROM in_range r
+                                 WHERE r.exercise_id = p.exercise_id AND r.date = p.first_date)
+                            ) AS start_weight
+                     FROM per_exercise p
+                 )
+                 SELECT g.exercise_id, e.name AS exercise_name, g.start_weight, g.end_weight
+                 FROM gains g
+                 JOIN exercises e ON g.exercise_id = e.id
+                 WHERE g.end_weight > g.start_weight
+                 ORDER BY g.end_weight - g.start_weight DESC, e.name COLLATE NOCASE",
+            )?;
+            let rows = stmt
+                .query_map(rusqlite::params![user_id, from, to], PrGain::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows)
+        })
+        .await?
+    }
+
+    /// Get exercise history with dynamically computed `is_pr` and
+    /// `is_recent_pr` (see `find_logs_by_session_with_pr`).
+    pub async fn get_exercise_history_with_pr(
+        &self,
+        user_id: &str,
+        exercise_id: &str,
+        limit: i64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<WorkoutLogWithExercise>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let exercise_id = exercise_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT wl.id, wl.session_id, wl.exercise_id, e.name as exercise_name,
+                        wl.set_number, wl.reps, wl.weight, wl.rpe, wl.right_reps, wl.right_weight,
+                        wl.notes, wl.tempo, wl.rir,
+                        CASE WHEN wl.weight = (
+                            SELECT MAX(wl2.weight) FROM workout_logs wl2
+                            JOIN workout_sessions ws2 ON wl2.session_id = ws2.id
+                            WHERE ws2.user_id = ?1 AND wl2.exercise_id = wl.exercise_id
+                        ) THEN 1 ELSE 0 END as is_pr,
+                        CASE WHEN datetime(wl.created_at) >= datetime(?4) AND wl.weight = (
+                            SELECT MAX(wl3.weight) FROM workout_logs wl3
+                            JOIN workout_sessions ws3 ON wl3.session_id = ws3.id
+                            WHERE ws3.user_id = ?1 AND wl3.exercise_id = wl.exercise_id
+                              AND datetime(wl3.created_at) >= datetime(?4)
+                        ) THEN 1 ELSE 0 END as is_recent_pr
+                 FROM workout_logs wl
+                 JOIN workout_sessions ws ON wl.session_id = ws.id
+                 JOIN exercises e ON wl.exercise_id = e.id
+                 WHERE ws.user_id = ?1 AND wl.exercise_id = ?2
+                 ORDER BY ws.date DESC, wl.set_number
+                 LIMIT ?3",
+            )?;
+            let logs = stmt
+                .query_map(
+                    rusqlite::params![user_id, exercise_id, limit, since],
+                    WorkoutLogWithExercise::from_row,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(logs)
+        })
+        .await?
+    }
+
+    /// Sets of the user's whose notes or tempo contain `query`, newest first.
+    /// A plain substring match; `%` and `_` in the query are taken literally.
+    pub async fn search_set_notes(
+        &self,
+        user_id: &str,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<SetNoteMatch>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let pattern = format!(
+            "%{}%",
+            query
+                .trim()
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT wl.session_id, ws.date, e.name as exercise_name, wl.set_number,
+                        wl.reps, wl.weight, wl.right_reps, wl.right_weight, wl.notes, wl.tempo
+                 FROM workout_logs wl
+                 JOIN workout_sessions ws ON wl.session_id = ws.id
+                 JOIN exercises e ON wl.exercise_id = e.id
+                 WHERE ws.user_id = ?1
+                   AND (wl.notes LIKE ?2 ESCAPE '\\' OR wl.tempo LIKE ?2 ESCAPE '\\')
+                 ORDER BY ws.date DESC, wl.created_at DESC, wl.set_number
+                 LIMIT ?3",
+            )?;
+            let matches = stmt
+                .query_map(
+                    rusqlite::params![user_id, pattern, limit],
+                    SetNoteMatch::from_row,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(matches)
+        })
+        .await?
+    }
+
+    // Share functionality
+
+    /// Set share token for a workout session (creates a new token). `ttl` of
+    /// `None` means the link never expires (see migration 012's rationale).
+    /// `history` lets the link serve progress charts (migration 028).
+    pub async fn set_share_token(
+        &self,
+        id: &str,
+        user_id: &str,
+        ttl: Option<chrono::Duration>,
+        history: bool,
+    ) -> Result<String> {
+        let pool = self.pool.clone();
+        let id = id.to_string();
+        let user_id = user_id.to_string();
+        let token = Uuid::new_v4().to_string();
+        let token_clone = token.clone();
+        let expires_at = ttl.map(|d| Utc::now() + d);
 
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
             let rows = conn.execute(
-                "UPDATE workout_sessions SET share_token = ?, share_expires_at = ? WHERE id = ? AND user_id = ?",
-                rusqlite::params![token_clone, expires_at, id, user_id],
+                "UPDATE workout_sessions SET share_token = ?, share_expires_at = ?, share_history = ? WHERE id = ? AND user_id = ?",
+                rusqlite::params![token_clone, expires_at, history, id, user_id],
             )?;
             if rows > 0 {
                 Ok(token_clone)
@@ -618,7 +1570,7 @@ impl WorkoutRepository {
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
             let rows = conn.execute(
-                "UPDATE workout_sessions SET share_token = NULL, share_expires_at = NULL WHERE id = ? AND user_id = ?",
+                "UPDATE workout_sessions SET share_token = NULL, share_expires_at = NULL, share_history = 0 WHERE id = ? AND user_id = ?",
                 rusqlite::params![id, user_id],
             )?;
             Ok(rows > 0)
@@ -647,6 +1599,34 @@ impl WorkoutRepository {
         .await?
     }
 
+    /// The user's most recent workouts with a live share link, newest first.
+    pub async fn find_shared_sessions_by_user(
+        &self,
+        user_id: &str,
+        limit: i64,
+    ) -> Result<Vec<WorkoutSession>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT * FROM workout_sessions
+                 WHERE user_id = ? AND share_token IS NOT NULL
+                   AND (share_expires_at IS NULL OR share_expires_at > ?)
+                 ORDER BY date DESC, created_at DESC
+                 LIMIT ?",
+            )?;
+            let sessions = stmt
+                .query_map(
+                    rusqlite::params![user_id, Utc::now(), limit],
+                    WorkoutSession::from_row,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(sessions)
+        })
+        .await?
+    }
+
     /// Clear share tokens whose expiry has passed. Returns the number of rows
     /// cleared.
     ///
@@ -654,12 +1634,14 @@ impl WorkoutRepository {
     /// `find_session_by_share_token` already does that on every lookup. It
     /// exists so the owner's workout page shows "not shared" rather than
     /// "shared, but the link is dead" once the row is stale.
+    /// `ShareRepository::cleanup_expired_share_tokens` does the same for the
+    /// other kinds of link.
     pub async fn cleanup_expired_share_tokens(&self) -> Result<usize> {
         let pool = self.pool.clone();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
             let rows = conn.execute(
-                "UPDATE workout_sessions SET share_token = NULL, share_expires_at = NULL WHERE share_expires_at IS NOT NULL AND share_expires_at <= ?",
+                "UPDATE workout_sessions SET share_token = NULL, share_expires_at = NULL, share_history = 0 WHERE share_expires_at IS NOT NULL AND share_expires_at <= ?",
                 rusqlite::params![Utc::now()],
             )?;
             Ok(rows)
@@ -667,42 +1649,355 @@ impl WorkoutRepository {
         .await?
     }
 
-    /// Find logs by session for sharing (without PR calculation)
-    pub async fn find_logs_by_session_for_share(
+    /// The user's first `limit` sessions in `scope`, oldest first.
+    pub async fn find_block_sessions(
         &self,
-        session_id: &str,
-    ) -> Result<Vec<WorkoutLogWithExercise>> {
+        user_id: &str,
+        scope: &BlockScope,
+        limit: i64,
+    ) -> Result<Vec<WorkoutSession>> {
         let pool = self.pool.clone();
-        let session_id = session_id.to_string();
+        let params = block_params(user_id, scope);
         tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
-            let mut stmt = conn.prepare(
-                "SELECT wl.id, wl.session_id, wl.exercise_id, e.name as exercise_name,
-                        wl.set_number, wl.reps, wl.weight, wl.rpe,
-                        0 as is_pr, 0 as is_recent_pr
-                 FROM workout_logs wl
-                 JOIN exercises e ON wl.exercise_id = e.id
-                 WHERE wl.session_id = ?
-                 ORDER BY wl.created_at DESC, wl.set_number",
-            )?;
-            let logs = stmt
-                .query_map([&session_id], WorkoutLogWithExercise::from_row)?
+            let filter = block_scope("ws");
+            let mut stmt = conn.prepare(&format!(
+                "SELECT ws.* FROM workout_sessions ws
+                 WHERE {filter}
+                 ORDER BY ws.date, ws.created_at
+                 LIMIT ?5"
+            ))?;
+            let sessions = stmt
+                .query_map(
+                    rusqlite::params_from_iter(params.into_iter().chain([Value::Integer(limit)])),
+                    WorkoutSession::from_row,
+                )?
                 .collect::<rusqlite::Result<Vec<_>>>()?;
-            Ok(logs)
+            Ok(sessions)
         })
         .await?
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::db::create_memory_pool;
-    use crate::migrations::run_migrations_for_tests;
-    use crate::models::recent_pr_window_start;
-
-    fn setup_test_db() -> DbPool {
-        let pool = create_memory_pool().expect("Failed to create test database");
+    /// How many of the user's sessions fall in `scope`, with sets or not.
+    pub async fn count_block_sessions(&self, user_id: &str, scope: &BlockScope) -> Result<i64> {
+        let pool = self.pool.clone();
+        let params = block_params(user_id, scope);
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let filter = block_scope("ws");
+            let count: i64 = conn.query_row(
+                &format!("SELECT COUNT(*) FROM workout_sessions ws WHERE {filter}"),
+                params,
+                |row| row.get(0),
+            )?;
+            Ok(count)
+        })
+        .await?
+    }
+
+    /// Every set of the first `limit` sessions in `scope`, the ones
+    /// `find_block_sessions` returns, ordered as
+    /// `find_logs_by_session_for_share` orders one session's, without PRs.
+    pub async fn find_block_logs(
+        &self,
+        user_id: &str,
+        scope: &BlockScope,
+        limit: i64,
+    ) -> Result<Vec<WorkoutLogWithExercise>> {
+        let pool = self.pool.clone();
+        let params = block_params(user_id, scope);
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let filter = block_scope("ws");
+            let mut stmt = conn.prepare(&format!(
+                "SELECT wl.id, wl.session_id, wl.exercise_id, e.name as exercise_name,
+                        wl.set_number, wl.reps, wl.weight, wl.rpe, wl.right_reps, wl.right_weight,
+                        wl.notes, wl.tempo, wl.rir,
+                        0 as is_pr, 0 as is_recent_pr
+                 FROM workout_logs wl
+                 JOIN exercises e ON wl.exercise_id = e.id
+                 WHERE wl.session_id IN (SELECT ws.id FROM workout_sessions ws
+                                         WHERE {filter}
+                                         ORDER BY ws.date, ws.created_at
+                                         LIMIT ?5)
+                 ORDER BY wl.created_at DESC, wl.set_number"
+            ))?;
+            let logs = stmt
+                .query_map(
+                    rusqlite::params_from_iter(params.into_iter().chain([Value::Integer(limit)])),
+                    WorkoutLogWithExercise::from_row,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(logs)
+        })
+        .await?
+    }
+
+    /// `get_period_summary` over the sessions in `scope`.
+    pub async fn get_block_summary(
+        &self,
+        user_id: &str,
+        scope: &BlockScope,
+    ) -> Result<PeriodSummary> {
+        let pool = self.pool.clone();
+        let params = block_params(user_id, scope);
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let filter = block_scope("ws");
+            let summary = conn.query_row(
+                &format!(
+                    "SELECT COUNT(DISTINCT ws.id) AS sessions,
+                            COUNT(wl.id) AS sets,
+                            COALESCE(SUM({SET_VOLUME}), 0.0) AS volume,
+                            AVG(wl.rpe) AS average_rpe
+                     FROM workout_logs wl
+                     JOIN workout_sessions ws ON wl.session_id = ws.id
+                     WHERE {filter}"
+                ),
+                params,
+                PeriodSummary::from_row,
+            )?;
+            Ok(summary)
+        })
+        .await?
+    }
+
+    /// `get_exercise_totals_between` over the sessions in `scope`.
+    pub async fn get_block_exercise_totals(
+        &self,
+        user_id: &str,
+        scope: &BlockScope,
+    ) -> Result<Vec<ExercisePeriodTotals>> {
+        let pool = self.pool.clone();
+        let params = block_params(user_id, scope);
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let filter = block_scope("ws");
+            let inner = block_scope("ws2");
+            let mut stmt = conn.prepare(&format!(
+                "SELECT wl.exercise_id, e.name AS exercise_name,
+                        COUNT(DISTINCT ws.id) AS sessions,
+                        COUNT(*) AS sets,
+                        SUM({SET_VOLUME}) AS volume,
+                        MAX(wl.weight) AS top_weight,
+                        (SELECT wl2.reps FROM workout_logs wl2
+                         JOIN workout_sessions ws2 ON wl2.session_id = ws2.id
+                         WHERE {inner}
+                           AND wl2.exercise_id = wl.exercise_id
+                         ORDER BY wl2.weight DESC, wl2.reps DESC
+                         LIMIT 1) AS top_reps,
+                        AVG(wl.rpe) AS average_rpe
+                 FROM workout_logs wl
+                 JOIN workout_sessions ws ON wl.session_id = ws.id
+                 JOIN exercises e ON wl.exercise_id = e.id
+                 WHERE {filter}
+                 GROUP BY wl.exercise_id
+                 ORDER BY volume DESC, e.name COLLATE NOCASE"
+            ))?;
+            let rows = stmt
+                .query_map(params, ExercisePeriodTotals::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows)
+        })
+        .await?
+    }
+
+    /// Find logs by session for sharing (without PR calculation)
+    pub async fn find_logs_by_session_for_share(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<WorkoutLogWithExercise>> {
+        let pool = self.pool.clone();
+        let session_id = session_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT wl.id, wl.session_id, wl.exercise_id, e.name as exercise_name,
+                        wl.set_number, wl.reps, wl.weight, wl.rpe, wl.right_reps, wl.right_weight,
+                        wl.notes, wl.tempo, wl.rir,
+                        0 as is_pr, 0 as is_recent_pr
+                 FROM workout_logs wl
+                 JOIN exercises e ON wl.exercise_id = e.id
+                 WHERE wl.session_id = ?
+                 ORDER BY wl.created_at DESC, wl.set_number",
+            )?;
+            let logs = stmt
+                .query_map([&session_id], WorkoutLogWithExercise::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(logs)
+        })
+        .await?
+    }
+
+    /// The latest of the user's sessions before `session` that logged every
+    /// exercise `session` did. Sessions on the same date are ordered by
+    /// creation time; a session without sets has nothing to compare.
+    pub async fn find_previous_session_with_same_exercises(
+        &self,
+        user_id: &str,
+        session: &WorkoutSession,
+    ) -> Result<Option<WorkoutSession>> {
+        let pool = self.pool.clone();
+        let user_id = user_id.to_string();
+        let session_id = session.id.clone();
+        let date = session.date;
+        let created_at = session.created_at;
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT ws.* FROM workout_sessions ws
+                 WHERE ws.user_id = ?1 AND ws.id != ?2
+                   AND (ws.date < ?3
+                        OR (ws.date = ?3 AND datetime(ws.created_at) < datetime(?4)))
+                   AND EXISTS (SELECT 1 FROM workout_logs WHERE session_id = ?2)
+                   AND NOT EXISTS (
+                       SELECT 1 FROM workout_logs cur
+                       WHERE cur.session_id = ?2
+                         AND NOT EXISTS (
+                             SELECT 1 FROM workout_logs prev
+                             WHERE prev.session_id = ws.id
+                               AND prev.exercise_id = cur.exercise_id
+                         )
+                   )
+                 ORDER BY ws.date DESC, datetime(ws.created_at) DESC
+                 LIMIT 1",
+            )?;
+            let previous = stmt
+                .query_row(
+                    rusqlite::params![user_id, session_id, date, created_at],
+                    WorkoutSession::from_row,
+                )
+                .optional()?;
+            Ok(previous)
+        })
+        .await?
+    }
+}
+
+/// SQL for the first day of the week (starting on `first_day`) holding
+/// `ws.date`. strftime('%w') is 0 for Sunday, so `(w + 7 - first) % 7` is the
+/// number of days since the preceding `first_day`. The day number is an
+/// integer from chrono, so formatting it in is safe.
+fn week_start_sql(first_day: Weekday) -> String {
+    format!(
+        "date(ws.date, '-' || ((CAST(strftime('%w', ws.date) AS INTEGER) + 7 - {}) % 7) || ' days')",
+        first_day.num_days_from_sunday()
+    )
+}
+
+/// `AND` clauses narrowing `workout_sessions ws` to `filter`, pushing their
+/// parameters onto `params`.
+fn session_filter_sql(filter: &WorkoutFilter, params: &mut Vec<Value>) -> String {
+    let mut sql = String::new();
+    if let Some(from) = filter.from {
+        sql.push_str(" AND ws.date >= ?");
+        params.push(Value::Text(from.to_string()));
+    }
+    if let Some(to) = filter.to {
+        sql.push_str(" AND ws.date <= ?");
+        params.push(Value::Text(to.to_string()));
+    }
+    if let Some(exercise_id) = &filter.exercise_id {
+        sql.push_str(
+            " AND EXISTS (SELECT 1 FROM workout_logs wl
+                          WHERE wl.session_id = ws.id AND wl.exercise_id = ?)",
+        );
+        params.push(Value::Text(exercise_id.clone()));
+    }
+    if let Some(tag) = &filter.tag {
+        sql.push_str(
+            " AND EXISTS (SELECT 1 FROM workout_session_tags st
+                          JOIN workout_tags t ON t.id = st.tag_id
+                          WHERE st.session_id = ws.id AND t.name = ?)",
+        );
+        params.push(Value::Text(tag.clone()));
+    }
+    if filter.text.is_some() {
+        // Text that reduces to no searchable words matches nothing, rather
+        // than silently dropping the filter.
+        match filter.fts_query() {
+            Some(query) => {
+                sql.push_str(
+                    " AND ws.id IN (SELECT session_id FROM workout_sessions_fts
+                                    WHERE workout_sessions_fts MATCH ?)",
+                );
+                params.push(Value::Text(query));
+            }
+            None => sql.push_str(" AND 0"),
+        }
+    }
+    sql
+}
+
+/// Renumber the exercise's sets in the session 1..n, keeping their order.
+fn renumber_sets(
+    conn: &rusqlite::Connection,
+    session_id: &str,
+    exercise_id: &str,
+) -> rusqlite::Result<()> {
+    let ids: Vec<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT id FROM workout_logs WHERE session_id = ? AND exercise_id = ?
+             ORDER BY set_number, created_at",
+        )?;
+        stmt.query_map(rusqlite::params![session_id, exercise_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+    };
+    for (position, id) in ids.iter().enumerate() {
+        conn.execute(
+            "UPDATE workout_logs SET set_number = ? WHERE id = ?",
+            rusqlite::params![i64::try_from(position).unwrap_or(i64::MAX) + 1, id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Insert a set numbered after the session's existing sets of the exercise.
+fn insert_next_log(
+    conn: &rusqlite::Connection,
+    session_id: &str,
+    exercise_id: &str,
+    values: &SetValues,
+) -> rusqlite::Result<()> {
+    let set_number: i32 = conn.query_row(
+        "SELECT COALESCE(MAX(set_number), 0) + 1 FROM workout_logs
+         WHERE session_id = ? AND exercise_id = ?",
+        rusqlite::params![session_id, exercise_id],
+        |row| row.get(0),
+    )?;
+    conn.execute(
+        "INSERT INTO workout_logs (id, session_id, exercise_id, set_number, reps, weight, rpe,
+                                   right_reps, right_weight, notes, tempo, rir, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![
+            Uuid::new_v4().to_string(),
+            session_id,
+            exercise_id,
+            set_number,
+            values.reps,
+            values.weight,
+            values.rpe,
+            values.right_reps,
+            values.right_weight,
+            values.notes,
+            values.tempo,
+            values.rir,
+            Utc::now()
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_memory_pool;
+    use crate::migrations::run_migrations_for_tests;
+    use crate::models::UserPreferences;
+    use crate::repositories::CommentRepository;
+
+    fn setup_test_db() -> DbPool {
+        let pool = create_memory_pool().expect("Failed to create test database");
         run_migrations_for_tests(&pool).expect("Failed to run migrations");
         pool
     }
@@ -725,445 +2020,1136 @@ mod tests {
         .unwrap();
     }
 
-    // Workout Session Tests
+    // Workout Session Tests
+
+    #[tokio::test]
+    async fn test_create_session() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo
+            .create_session("user1", date, Some("Leg day"))
+            .await
+            .unwrap();
+
+        assert_eq!(session.user_id, "user1");
+        assert_eq!(session.date, date);
+        assert_eq!(session.notes, Some("Leg day".to_string()));
+        assert!(!session.id.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_session_by_id_exists() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let created = repo.create_session("user1", date, None).await.unwrap();
+        let found = repo.find_session_by_id(&created.id).await.unwrap();
+
+        assert!(found.is_some());
+        assert_eq!(found.unwrap().id, created.id);
+    }
+
+    #[tokio::test]
+    async fn test_find_session_by_id_not_exists() {
+        let pool = setup_test_db();
+        let repo = WorkoutRepository::new(pool);
+
+        let found = repo.find_session_by_id("nonexistent").await.unwrap();
+
+        assert!(found.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_sessions_by_user_ordered() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date1 = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let date2 = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let date3 = NaiveDate::from_ymd_opt(2024, 1, 12).unwrap();
+
+        repo.create_session("user1", date1, None).await.unwrap();
+        repo.create_session("user1", date2, None).await.unwrap();
+        repo.create_session("user1", date3, None).await.unwrap();
+
+        let sessions = repo
+            .find_sessions_by_user_paginated("user1", 10, 0)
+            .await
+            .unwrap();
+
+        assert_eq!(sessions.len(), 3);
+        // Should be ordered by date DESC
+        assert_eq!(sessions[0].date, date2);
+        assert_eq!(sessions[1].date, date3);
+        assert_eq!(sessions[2].date, date1);
+    }
+
+    #[tokio::test]
+    async fn test_count_sessions_by_user() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        repo.create_session("user1", date, None).await.unwrap();
+        repo.create_session("user1", date, None).await.unwrap();
+        repo.create_session("user2", date, None).await.unwrap();
+
+        let count = repo.count_sessions_by_user("user1").await.unwrap();
+
+        assert_eq!(count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_delete_session_success() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+        let deleted = repo.delete_session(&session.id, "user1").await.unwrap();
+
+        assert!(deleted);
+        let found = repo.find_session_by_id(&session.id).await.unwrap();
+        assert!(found.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_delete_session_wrong_user() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+        let deleted = repo.delete_session(&session.id, "user2").await.unwrap();
+
+        assert!(!deleted);
+    }
+
+    // Workout Log Tests
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_create_log() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+
+        let log = repo
+            .create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(10, 100.0, Some(8)),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(log.session_id, session.id);
+        assert_eq!(log.exercise_id, "ex-bench-press");
+        assert_eq!(log.set_number, 1);
+        assert_eq!(log.reps, 10);
+        assert_eq!(log.weight, 100.0);
+        assert_eq!(log.rpe, Some(8));
+    }
+
+    #[tokio::test]
+    async fn test_find_logs_by_session_with_pr() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        create_test_exercise(&pool, "ex-squat", "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(10, 100.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            2,
+            &SetValues::new(8, 105.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(&session.id, "ex-squat", 1, &SetValues::new(5, 120.0, None))
+            .await
+            .unwrap();
+
+        let logs = repo
+            .find_logs_by_session_with_pr(
+                &session.id,
+                "user1",
+                UserPreferences::default().pr_window_start(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(logs.len(), 3);
+        // With DESC ordering: newest first
+        // 105.0 is PR for bench press, 120.0 is PR for squat
+        assert!(logs[0].is_pr); // 120.0 squat - PR (created last)
+        assert!(logs[1].is_pr); // 105.0 bench - PR
+        assert!(!logs[2].is_pr); // 100.0 bench (created first)
+        // All three were logged just now, so every all-time PR is also a
+        // 1-month PR.
+        assert!(logs[0].is_recent_pr);
+        assert!(logs[1].is_recent_pr);
+        assert!(!logs[2].is_recent_pr);
+    }
+
+    #[tokio::test]
+    async fn test_find_logs_by_session_marks_recent_pr_below_the_all_time_best() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        let repo = WorkoutRepository::new(pool.clone());
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let old_session = repo.create_session("user1", date, None).await.unwrap();
+        let recent_session = repo.create_session("user1", date, None).await.unwrap();
+
+        // An all-time best set from well outside the window.
+        let old_log = repo
+            .create_log(
+                &old_session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(3, 140.0, None),
+            )
+            .await
+            .unwrap();
+        backdate_log(&pool, &old_log.id, Utc::now() - chrono::Duration::days(90));
+
+        // A lighter set logged today: not an all-time PR, but the best inside
+        // the rolling window.
+        repo.create_log(
+            &recent_session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(8, 110.0, None),
+        )
+        .await
+        .unwrap();
+
+        let recent_logs = repo
+            .find_logs_by_session_with_pr(
+                &recent_session.id,
+                "user1",
+                UserPreferences::default().pr_window_start(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(recent_logs.len(), 1);
+        assert!(!recent_logs[0].is_pr);
+        assert!(recent_logs[0].is_recent_pr);
+
+        // The old session still owns the all-time PR, but sits outside the
+        // window so it carries no 1-month badge.
+        let old_logs = repo
+            .find_logs_by_session_with_pr(
+                &old_session.id,
+                "user1",
+                UserPreferences::default().pr_window_start(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(old_logs.len(), 1);
+        assert!(old_logs[0].is_pr);
+        assert!(!old_logs[0].is_recent_pr);
+    }
+
+    #[tokio::test]
+    async fn test_delete_log_success() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+        let log = repo
+            .create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(10, 100.0, None),
+            )
+            .await
+            .unwrap();
+
+        let deleted = repo.delete_log(&log.id, &session.id).await.unwrap();
+
+        assert!(deleted);
+        let found = repo.find_log_by_id(&log.id).await.unwrap();
+        assert!(found.is_none());
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_update_log_success() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+        let log = repo
+            .create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(10, 100.0, Some(7)),
+            )
+            .await
+            .unwrap();
+
+        let updated = repo
+            .update_log(&log.id, &session.id, &SetValues::new(12, 110.0, Some(8)))
+            .await
+            .unwrap();
+
+        assert!(updated);
+        let found = repo.find_log_by_id(&log.id).await.unwrap().unwrap();
+        assert_eq!(found.reps, 12);
+        assert_eq!(found.weight, 110.0);
+        assert_eq!(found.rpe, Some(8));
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_update_log_wrong_session() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+        let log = repo
+            .create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(10, 100.0, None),
+            )
+            .await
+            .unwrap();
+
+        // Try to update with wrong session_id
+        let updated = repo
+            .update_log(
+                &log.id,
+                "wrong-session",
+                &SetValues::new(12, 110.0, Some(8)),
+            )
+            .await
+            .unwrap();
+
+        assert!(!updated);
+        // Verify log was not modified
+        let found = repo.find_log_by_id(&log.id).await.unwrap().unwrap();
+        assert_eq!(found.reps, 10);
+        assert_eq!(found.weight, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_get_next_set_number() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+
+        // First set should be 1
+        let next = repo
+            .get_next_set_number(&session.id, "ex-bench-press")
+            .await
+            .unwrap();
+        assert_eq!(next, 1);
+
+        // After creating a log, next should be 2
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(10, 100.0, None),
+        )
+        .await
+        .unwrap();
+        let next = repo
+            .get_next_set_number(&session.id, "ex-bench-press")
+            .await
+            .unwrap();
+        assert_eq!(next, 2);
+    }
+
+    // Dynamic Personal Record Tests
+
+    /// Backdate a log so it falls outside the recent PR window.
+    fn backdate_log(pool: &DbPool, log_id: &str, at: DateTime<Utc>) {
+        let conn = pool.get().unwrap();
+        let updated = conn
+            .execute(
+                "UPDATE workout_logs SET created_at = ? WHERE id = ?",
+                rusqlite::params![at, log_id],
+            )
+            .unwrap();
+        assert_eq!(updated, 1, "backdate targeted no row");
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_get_pr_summaries_by_user() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        create_test_exercise(&pool, "ex-squat", "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(10, 100.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            2,
+            &SetValues::new(8, 110.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(&session.id, "ex-squat", 1, &SetValues::new(5, 150.0, None))
+            .await
+            .unwrap();
+
+        let since = Utc::now() - chrono::Duration::days(30);
+        let prs = repo.get_pr_summaries_by_user("user1", since).await.unwrap();
+
+        assert_eq!(prs.len(), 2);
+        // Find each exercise's PR
+        let bench_pr = prs.iter().find(|p| p.exercise_id == "ex-bench-press");
+        let squat_pr = prs.iter().find(|p| p.exercise_id == "ex-squat");
+        assert!(bench_pr.is_some());
+        assert!(squat_pr.is_some());
+        assert_eq!(bench_pr.unwrap().all_time_value, 110.0);
+        assert_eq!(squat_pr.unwrap().all_time_value, 150.0);
+        // Everything was just logged, so the recent window mirrors all-time.
+        assert_eq!(bench_pr.unwrap().recent_value, Some(110.0));
+        assert!(bench_pr.unwrap().recent_is_all_time());
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_get_pr_summaries_recent_window_excludes_older_logs() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        create_test_exercise(&pool, "ex-squat", "user1");
+        let repo = WorkoutRepository::new(pool.clone());
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+
+        // Bench: the all-time best is old, a lighter set is inside the window.
+        let old_bench = repo
+            .create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(3, 140.0, None),
+            )
+            .await
+            .unwrap();
+        backdate_log(
+            &pool,
+            &old_bench.id,
+            Utc::now() - chrono::Duration::days(90),
+        );
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            2,
+            &SetValues::new(8, 110.0, None),
+        )
+        .await
+        .unwrap();
+
+        // Squat: only trained outside the window at all.
+        let old_squat = repo
+            .create_log(&session.id, "ex-squat", 1, &SetValues::new(5, 150.0, None))
+            .await
+            .unwrap();
+        backdate_log(
+            &pool,
+            &old_squat.id,
+            Utc::now() - chrono::Duration::days(45),
+        );
+
+        let since = Utc::now() - chrono::Duration::days(30);
+        let prs = repo.get_pr_summaries_by_user("user1", since).await.unwrap();
+
+        let bench = prs
+            .iter()
+            .find(|p| p.exercise_id == "ex-bench-press")
+            .unwrap();
+        assert_eq!(bench.all_time_value, 140.0);
+        assert_eq!(bench.recent_value, Some(110.0));
+        assert!(!bench.recent_is_all_time());
+        assert!(bench.recent_achieved_at.is_some());
+
+        let squat = prs.iter().find(|p| p.exercise_id == "ex-squat").unwrap();
+        assert_eq!(squat.all_time_value, 150.0);
+        assert_eq!(squat.recent_value, None);
+        assert_eq!(squat.recent_achieved_at, None);
+        assert!(!squat.recent_is_all_time());
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_get_max_weight_for_exercise() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(10, 100.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            2,
+            &SetValues::new(8, 110.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            3,
+            &SetValues::new(5, 105.0, None),
+        )
+        .await
+        .unwrap();
+
+        let pr = repo
+            .get_max_weight_for_exercise("user1", "ex-bench-press")
+            .await
+            .unwrap();
+
+        assert!(pr.is_some());
+        assert_eq!(pr.unwrap().value, 110.0);
+    }
 
     #[tokio::test]
-    async fn test_create_session() {
+    async fn test_dynamic_pr_updates_when_heavier_set_added() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo
-            .create_session("user1", date, Some("Leg day"))
+        let session = repo.create_session("user1", date, None).await.unwrap();
+
+        // First set
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(10, 100.0, None),
+        )
+        .await
+        .unwrap();
+
+        let logs = repo
+            .find_logs_by_session_with_pr(
+                &session.id,
+                "user1",
+                UserPreferences::default().pr_window_start(),
+            )
             .await
             .unwrap();
+        assert!(logs[0].is_pr); // 100.0 is the only set, so it's PR
 
-        assert_eq!(session.user_id, "user1");
-        assert_eq!(session.date, date);
-        assert_eq!(session.notes, Some("Leg day".to_string()));
-        assert!(!session.id.is_empty());
+        // Add heavier set
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            2,
+            &SetValues::new(8, 110.0, None),
+        )
+        .await
+        .unwrap();
+
+        let logs = repo
+            .find_logs_by_session_with_pr(
+                &session.id,
+                "user1",
+                UserPreferences::default().pr_window_start(),
+            )
+            .await
+            .unwrap();
+        // With DESC ordering: newest (110.0) first
+        assert!(logs[0].is_pr); // 110.0 is now PR (created last)
+        assert!(!logs[1].is_pr); // 100.0 is no longer PR (created first)
     }
 
     #[tokio::test]
-    async fn test_find_session_by_id_exists() {
+    async fn test_dynamic_pr_updates_when_pr_deleted() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let created = repo.create_session("user1", date, None).await.unwrap();
-        let found = repo.find_session_by_id(&created.id).await.unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
 
-        assert!(found.is_some());
-        assert_eq!(found.unwrap().id, created.id);
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(10, 100.0, None),
+        )
+        .await
+        .unwrap();
+        let heavy_log = repo
+            .create_log(
+                &session.id,
+                "ex-bench-press",
+                2,
+                &SetValues::new(8, 110.0, None),
+            )
+            .await
+            .unwrap();
+
+        // Delete the PR set
+        repo.delete_log(&heavy_log.id, &session.id).await.unwrap();
+
+        let logs = repo
+            .find_logs_by_session_with_pr(
+                &session.id,
+                "user1",
+                UserPreferences::default().pr_window_start(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].is_pr); // 100.0 becomes PR again
     }
 
     #[tokio::test]
-    async fn test_find_session_by_id_not_exists() {
+    async fn test_update_session() {
         let pool = setup_test_db();
+        create_test_user(&pool, "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let found = repo.find_session_by_id("nonexistent").await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
 
-        assert!(found.is_none());
+        let new_date = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
+        let updated = repo
+            .update_session_with_tags(
+                &session.id,
+                "user1",
+                new_date,
+                Some("Updated notes"),
+                Vec::new(),
+                Vec::new(),
+            )
+            .await
+            .unwrap();
+
+        assert!(updated);
+
+        let found = repo.find_session_by_id(&session.id).await.unwrap().unwrap();
+        assert_eq!(found.date, new_date);
+        assert_eq!(found.notes, Some("Updated notes".to_string()));
     }
 
     #[tokio::test]
-    async fn test_find_sessions_by_user_ordered() {
+    async fn test_session_and_tag_writes_are_all_or_nothing() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        let repo = WorkoutRepository::new(pool);
-
-        let date1 = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
-        let date2 = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let date3 = NaiveDate::from_ymd_opt(2024, 1, 12).unwrap();
-
-        repo.create_session("user1", date1, None).await.unwrap();
-        repo.create_session("user1", date2, None).await.unwrap();
-        repo.create_session("user1", date3, None).await.unwrap();
+        let repo = WorkoutRepository::new(pool.clone());
+        let tags = crate::repositories::TagRepository::new(pool.clone());
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
 
-        let sessions = repo
-            .find_sessions_by_user_paginated("user1", 10, 0)
+        let session = repo
+            .create_session_with_tags("user1", date, Some("Push"), Vec::new(), vec!["push".into()])
             .await
             .unwrap();
+        let push = tags.find_for_session(&session.id).await.unwrap();
+        assert_eq!(push.len(), 1);
+
+        let new_date = NaiveDate::from_ymd_opt(2024, 1, 16).unwrap();
+        assert!(
+            repo.update_session_with_tags(
+                &session.id,
+                "user1",
+                new_date,
+                None,
+                Vec::new(),
+                vec!["deload".into()],
+            )
+            .await
+            .unwrap()
+        );
+        let found = repo.find_session_by_id(&session.id).await.unwrap().unwrap();
+        assert_eq!(found.date, new_date);
+        assert_eq!(
+            tags.find_for_session(&session.id).await.unwrap()[0].name,
+            "deload"
+        );
+        assert!(
+            !repo
+                .update_session_with_tags(&session.id, "user2", date, None, Vec::new(), Vec::new())
+                .await
+                .unwrap()
+        );
+        assert_eq!(tags.find_for_session(&session.id).await.unwrap().len(), 1);
 
-        assert_eq!(sessions.len(), 3);
-        // Should be ordered by date DESC
-        assert_eq!(sessions[0].date, date2);
-        assert_eq!(sessions[1].date, date3);
-        assert_eq!(sessions[2].date, date1);
+        // When a new tag cannot be saved, neither is the session or its edit.
+        pool.get()
+            .unwrap()
+            .execute_batch(
+                "CREATE TRIGGER no_new_tags BEFORE INSERT ON workout_tags
+                 BEGIN SELECT RAISE(ABORT, 'no new tags'); END;",
+            )
+            .unwrap();
+        let result = repo
+            .create_session_with_tags("user1", date, None, Vec::new(), vec!["legs".into()])
+            .await;
+        assert!(result.is_err());
+        let result = repo
+            .update_session_with_tags(
+                &session.id,
+                "user1",
+                date,
+                Some("Legs"),
+                vec![push[0].id.clone()],
+                vec!["legs".into()],
+            )
+            .await;
+        assert!(result.is_err());
+        assert_eq!(repo.count_sessions_by_user("user1").await.unwrap(), 1);
+        let found = repo.find_session_by_id(&session.id).await.unwrap().unwrap();
+        assert_eq!((found.date, found.notes), (new_date, None));
+        assert_eq!(
+            tags.find_for_session(&session.id).await.unwrap()[0].name,
+            "deload"
+        );
     }
 
     #[tokio::test]
-    async fn test_count_sessions_by_user() {
+    async fn test_find_sessions_by_user_paginated() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        create_test_user(&pool, "user2");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        repo.create_session("user1", date, None).await.unwrap();
-        repo.create_session("user1", date, None).await.unwrap();
-        repo.create_session("user2", date, None).await.unwrap();
+        for i in 1..=5 {
+            let date = NaiveDate::from_ymd_opt(2024, 1, i).unwrap();
+            repo.create_session("user1", date, None).await.unwrap();
+        }
 
-        let count = repo.count_sessions_by_user("user1").await.unwrap();
+        let page1 = repo
+            .find_sessions_by_user_paginated("user1", 2, 0)
+            .await
+            .unwrap();
+        assert_eq!(page1.len(), 2);
 
-        assert_eq!(count, 2);
+        let page2 = repo
+            .find_sessions_by_user_paginated("user1", 2, 2)
+            .await
+            .unwrap();
+        assert_eq!(page2.len(), 2);
+
+        let page3 = repo
+            .find_sessions_by_user_paginated("user1", 2, 4)
+            .await
+            .unwrap();
+        assert_eq!(page3.len(), 1);
     }
 
+    // Edge case tests for statistics
+
     #[tokio::test]
-    async fn test_delete_session_success() {
+    async fn test_count_sessions_between_empty() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
-        let deleted = repo.delete_session(&session.id, "user1").await.unwrap();
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let count = repo
+            .count_sessions_between("user1", from, to)
+            .await
+            .unwrap();
 
-        assert!(deleted);
-        let found = repo.find_session_by_id(&session.id).await.unwrap();
-        assert!(found.is_none());
+        assert_eq!(count, 0);
     }
 
     #[tokio::test]
-    async fn test_delete_session_wrong_user() {
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_get_volume_between_empty() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        create_test_user(&pool, "user2");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
-        let deleted = repo.delete_session(&session.id, "user2").await.unwrap();
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 1, 7).unwrap();
+        let volume = repo.get_volume_between("user1", from, to).await.unwrap();
 
-        assert!(!deleted);
+        assert_eq!(volume, 0.0);
     }
 
-    // Workout Log Tests
-
     #[tokio::test]
     #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
-    async fn test_create_log() {
+    async fn test_counts_and_volume_between_include_both_ends() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
-
-        let log = repo
-            .create_log(&session.id, "ex-bench-press", 1, 10, 100.0, Some(8))
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        for day in [date(7), date(8), date(14), date(15)] {
+            let session = repo.create_session("user1", day, None).await.unwrap();
+            repo.create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(10, 50.0, None),
+            )
             .await
             .unwrap();
+        }
 
-        assert_eq!(log.session_id, session.id);
-        assert_eq!(log.exercise_id, "ex-bench-press");
-        assert_eq!(log.set_number, 1);
-        assert_eq!(log.reps, 10);
-        assert_eq!(log.weight, 100.0);
-        assert_eq!(log.rpe, Some(8));
+        assert_eq!(
+            repo.count_sessions_between("user1", date(8), date(14))
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            repo.get_volume_between("user1", date(8), date(14))
+                .await
+                .unwrap(),
+            1000.0
+        );
     }
 
     #[tokio::test]
-    async fn test_find_logs_by_session_with_pr() {
+    async fn test_get_pr_summaries_by_user_empty() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        create_test_exercise(&pool, "ex-bench-press", "user1");
-        create_test_exercise(&pool, "ex-squat", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
-
-        repo.create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&session.id, "ex-bench-press", 2, 8, 105.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&session.id, "ex-squat", 1, 5, 120.0, None)
-            .await
-            .unwrap();
-
-        let logs = repo
-            .find_logs_by_session_with_pr(&session.id, "user1", recent_pr_window_start())
-            .await
-            .unwrap();
+        let since = Utc::now() - chrono::Duration::days(30);
+        let prs = repo.get_pr_summaries_by_user("user1", since).await.unwrap();
 
-        assert_eq!(logs.len(), 3);
-        // With DESC ordering: newest first
-        // 105.0 is PR for bench press, 120.0 is PR for squat
-        assert!(logs[0].is_pr); // 120.0 squat - PR (created last)
-        assert!(logs[1].is_pr); // 105.0 bench - PR
-        assert!(!logs[2].is_pr); // 100.0 bench (created first)
-        // All three were logged just now, so every all-time PR is also a
-        // 1-month PR.
-        assert!(logs[0].is_recent_pr);
-        assert!(logs[1].is_recent_pr);
-        assert!(!logs[2].is_recent_pr);
+        assert!(prs.is_empty());
     }
 
     #[tokio::test]
-    async fn test_find_logs_by_session_marks_recent_pr_below_the_all_time_best() {
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_get_last_weight_per_exercise_by_user() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_exercise(&pool, "ex-bench-press", "user1");
-        let repo = WorkoutRepository::new(pool.clone());
+        create_test_exercise(&pool, "ex-squat", "user1");
+        let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let old_session = repo.create_session("user1", date, None).await.unwrap();
-        let recent_session = repo.create_session("user1", date, None).await.unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
 
-        // An all-time best set from well outside the window.
-        let old_log = repo
-            .create_log(&old_session.id, "ex-bench-press", 1, 3, 140.0, None)
+        // Bench press: latest set (105) is lower than max (110)
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(10, 100.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            2,
+            &SetValues::new(8, 110.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            3,
+            &SetValues::new(5, 105.0, Some(8)),
+        )
+        .await
+        .unwrap();
+        // Squat: single set, no RPE recorded
+        repo.create_log(&session.id, "ex-squat", 1, &SetValues::new(5, 150.0, None))
             .await
             .unwrap();
-        backdate_log(&pool, &old_log.id, Utc::now() - chrono::Duration::days(90));
 
-        // A lighter set logged today: not an all-time PR, but the best inside
-        // the rolling window.
-        repo.create_log(&recent_session.id, "ex-bench-press", 1, 8, 110.0, None)
+        let entries = repo
+            .get_last_weight_per_exercise_by_user("user1")
             .await
             .unwrap();
 
-        let recent_logs = repo
-            .find_logs_by_session_with_pr(&recent_session.id, "user1", recent_pr_window_start())
+        assert_eq!(entries.len(), 2);
+        let bench = entries
+            .iter()
+            .find(|e| e.exercise_id == "ex-bench-press")
+            .expect("bench entry");
+        let squat = entries
+            .iter()
+            .find(|e| e.exercise_id == "ex-squat")
+            .expect("squat entry");
+        // Last logged weight, NOT the max
+        assert_eq!(bench.weight, 105.0);
+        assert_eq!(squat.weight, 150.0);
+        // RPE comes from the same latest row as the weight
+        assert_eq!(bench.rpe, Some(8));
+        assert_eq!(squat.rpe, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_last_weight_per_exercise_by_user_empty() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        let entries = repo
+            .get_last_weight_per_exercise_by_user("user1")
             .await
             .unwrap();
-        assert_eq!(recent_logs.len(), 1);
-        assert!(!recent_logs[0].is_pr);
-        assert!(recent_logs[0].is_recent_pr);
 
-        // The old session still owns the all-time PR, but sits outside the
-        // window so it carries no 1-month badge.
-        let old_logs = repo
-            .find_logs_by_session_with_pr(&old_session.id, "user1", recent_pr_window_start())
-            .await
-            .unwrap();
-        assert_eq!(old_logs.len(), 1);
-        assert!(old_logs[0].is_pr);
-        assert!(!old_logs[0].is_recent_pr);
+        assert!(entries.is_empty());
     }
 
+    // Share functionality tests
+
     #[tokio::test]
-    async fn test_delete_log_success() {
+    async fn test_set_share_token() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
-        let log = repo
-            .create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
+        assert!(session.share_token.is_none());
+
+        let token = repo
+            .set_share_token(&session.id, "user1", None, false)
             .await
             .unwrap();
+        assert!(!token.is_empty());
 
-        let deleted = repo.delete_log(&log.id, &session.id).await.unwrap();
+        let found = repo.find_session_by_id(&session.id).await.unwrap().unwrap();
+        assert_eq!(found.share_token, Some(token));
+    }
 
-        assert!(deleted);
-        let found = repo.find_log_by_id(&log.id).await.unwrap();
-        assert!(found.is_none());
+    #[tokio::test]
+    async fn test_set_share_token_wrong_user() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        let repo = WorkoutRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+
+        let result = repo
+            .set_share_token(&session.id, "user2", None, false)
+            .await;
+        assert!(result.is_err());
     }
 
     #[tokio::test]
-    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
-    async fn test_update_log_success() {
+    async fn test_revoke_share_token() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
-        let log = repo
-            .create_log(&session.id, "ex-bench-press", 1, 10, 100.0, Some(7))
-            .await
-            .unwrap();
 
-        let updated = repo
-            .update_log(&log.id, &session.id, 12, 110.0, Some(8))
+        let token = repo
+            .set_share_token(&session.id, "user1", None, false)
             .await
             .unwrap();
+        assert!(!token.is_empty());
 
-        assert!(updated);
-        let found = repo.find_log_by_id(&log.id).await.unwrap().unwrap();
-        assert_eq!(found.reps, 12);
-        assert_eq!(found.weight, 110.0);
-        assert_eq!(found.rpe, Some(8));
+        let revoked = repo.revoke_share_token(&session.id, "user1").await.unwrap();
+        assert!(revoked);
+
+        let found = repo.find_session_by_id(&session.id).await.unwrap().unwrap();
+        assert!(found.share_token.is_none());
     }
 
     #[tokio::test]
-    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
-    async fn test_update_log_wrong_session() {
+    async fn test_revoke_share_token_wrong_user() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        create_test_exercise(&pool, "ex-bench-press", "user1");
+        create_test_user(&pool, "user2");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
-        let log = repo
-            .create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
+        repo.set_share_token(&session.id, "user1", None, false)
             .await
             .unwrap();
 
-        // Try to update with wrong session_id
-        let updated = repo
-            .update_log(&log.id, "wrong-session", 12, 110.0, Some(8))
-            .await
-            .unwrap();
+        let revoked = repo.revoke_share_token(&session.id, "user2").await.unwrap();
+        assert!(!revoked);
 
-        assert!(!updated);
-        // Verify log was not modified
-        let found = repo.find_log_by_id(&log.id).await.unwrap().unwrap();
-        assert_eq!(found.reps, 10);
-        assert_eq!(found.weight, 100.0);
+        // Token should still exist
+        let found = repo.find_session_by_id(&session.id).await.unwrap().unwrap();
+        assert!(found.share_token.is_some());
     }
 
     #[tokio::test]
-    async fn test_get_next_set_number() {
+    async fn test_find_session_by_share_token() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
-
-        // First set should be 1
-        let next = repo
-            .get_next_set_number(&session.id, "ex-bench-press")
+        let token = repo
+            .set_share_token(&session.id, "user1", None, false)
             .await
             .unwrap();
-        assert_eq!(next, 1);
 
-        // After creating a log, next should be 2
-        repo.create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
-            .await
-            .unwrap();
-        let next = repo
-            .get_next_set_number(&session.id, "ex-bench-press")
+        let found = repo
+            .find_session_by_share_token(&token)
             .await
+            .unwrap()
             .unwrap();
-        assert_eq!(next, 2);
+        assert_eq!(found.id, session.id);
     }
 
-    // Dynamic Personal Record Tests
+    #[tokio::test]
+    async fn test_find_session_by_share_token_not_found() {
+        let pool = setup_test_db();
+        let repo = WorkoutRepository::new(pool);
 
-    /// Backdate a log so it falls outside the recent PR window.
-    fn backdate_log(pool: &DbPool, log_id: &str, at: DateTime<Utc>) {
-        let conn = pool.get().unwrap();
-        let updated = conn
-            .execute(
-                "UPDATE workout_logs SET created_at = ? WHERE id = ?",
-                rusqlite::params![at, log_id],
-            )
+        let result = repo
+            .find_session_by_share_token("nonexistent-token")
+            .await
             .unwrap();
-        assert_eq!(updated, 1, "backdate targeted no row");
+        assert!(result.is_none());
     }
 
     #[tokio::test]
-    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
-    async fn test_get_pr_summaries_by_user() {
+    async fn test_find_logs_by_session_for_share() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_exercise(&pool, "ex-bench-press", "user1");
-        create_test_exercise(&pool, "ex-squat", "user1");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
 
-        repo.create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&session.id, "ex-bench-press", 2, 8, 110.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&session.id, "ex-squat", 1, 5, 150.0, None)
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(10, 100.0, None),
+        )
+        .await
+        .unwrap();
+
+        let logs = repo
+            .find_logs_by_session_for_share(&session.id)
             .await
             .unwrap();
 
-        let since = Utc::now() - chrono::Duration::days(30);
-        let prs = repo.get_pr_summaries_by_user("user1", since).await.unwrap();
-
-        assert_eq!(prs.len(), 2);
-        // Find each exercise's PR
-        let bench_pr = prs.iter().find(|p| p.exercise_id == "ex-bench-press");
-        let squat_pr = prs.iter().find(|p| p.exercise_id == "ex-squat");
-        assert!(bench_pr.is_some());
-        assert!(squat_pr.is_some());
-        assert_eq!(bench_pr.unwrap().all_time_value, 110.0);
-        assert_eq!(squat_pr.unwrap().all_time_value, 150.0);
-        // Everything was just logged, so the recent window mirrors all-time.
-        assert_eq!(bench_pr.unwrap().recent_value, Some(110.0));
-        assert!(bench_pr.unwrap().recent_is_all_time());
+        assert_eq!(logs.len(), 1);
+        // is_pr should be false for share (we don't calculate PRs)
+        assert!(!logs[0].is_pr);
     }
 
     #[tokio::test]
-    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
-    async fn test_get_pr_summaries_recent_window_excludes_older_logs() {
+    async fn test_reshare_generates_new_token() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        create_test_exercise(&pool, "ex-bench-press", "user1");
-        create_test_exercise(&pool, "ex-squat", "user1");
-        let repo = WorkoutRepository::new(pool.clone());
+        let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
 
-        // Bench: the all-time best is old, a lighter set is inside the window.
-        let old_bench = repo
-            .create_log(&session.id, "ex-bench-press", 1, 3, 140.0, None)
+        let token1 = repo
+            .set_share_token(&session.id, "user1", None, false)
             .await
             .unwrap();
-        backdate_log(
-            &pool,
-            &old_bench.id,
-            Utc::now() - chrono::Duration::days(90),
-        );
-        repo.create_log(&session.id, "ex-bench-press", 2, 8, 110.0, None)
+        repo.revoke_share_token(&session.id, "user1").await.unwrap();
+        let token2 = repo
+            .set_share_token(&session.id, "user1", None, false)
             .await
             .unwrap();
 
-        // Squat: only trained outside the window at all.
-        let old_squat = repo
-            .create_log(&session.id, "ex-squat", 1, 5, 150.0, None)
-            .await
-            .unwrap();
-        backdate_log(
-            &pool,
-            &old_squat.id,
-            Utc::now() - chrono::Duration::days(45),
-        );
+        assert_ne!(token1, token2);
+    }
 
-        let since = Utc::now() - chrono::Duration::days(30);
-        let prs = repo.get_pr_summaries_by_user("user1", since).await.unwrap();
+    #[tokio::test]
+    async fn test_get_session_metrics_for_exercise_empty() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        let repo = WorkoutRepository::new(pool);
 
-        let bench = prs
-            .iter()
-            .find(|p| p.exercise_id == "ex-bench-press")
+        let metrics = repo
+            .get_session_metrics_for_exercise("user1", "ex-bench-press")
+            .await
             .unwrap();
-        assert_eq!(bench.all_time_value, 140.0);
-        assert_eq!(bench.recent_value, Some(110.0));
-        assert!(!bench.recent_is_all_time());
-        assert!(bench.recent_achieved_at.is_some());
 
-        let squat = prs.iter().find(|p| p.exercise_id == "ex-squat").unwrap();
-        assert_eq!(squat.all_time_value, 150.0);
-        assert_eq!(squat.recent_value, None);
-        assert_eq!(squat.recent_achieved_at, None);
-        assert!(!squat.recent_is_all_time());
+        assert!(metrics.is_empty());
     }
 
     #[tokio::test]
-    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
-    async fn test_get_max_weight_for_exercise() {
+    async fn test_get_session_metrics_for_exercise_single_session_aggregates_correctly() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_exercise(&pool, "ex-bench-press", "user1");
@@ -1172,192 +3158,425 @@ mod tests {
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
 
-        repo.create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&session.id, "ex-bench-press", 2, 8, 110.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&session.id, "ex-bench-press", 3, 5, 105.0, None)
+        // Three sets: 100x10, 110x8, 105x5 — top weight 110 with 8 reps; volume = 1000+880+525 = 2405
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(10, 100.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            2,
+            &SetValues::new(8, 110.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            3,
+            &SetValues::new(5, 105.0, None),
+        )
+        .await
+        .unwrap();
+
+        let metrics = repo
+            .get_session_metrics_for_exercise("user1", "ex-bench-press")
             .await
             .unwrap();
 
-        let pr = repo
-            .get_max_weight_for_exercise("user1", "ex-bench-press")
+        assert_eq!(metrics.len(), 1);
+        let m = &metrics[0];
+        assert_eq!(m.date, date);
+        assert!((m.top_weight - 110.0).abs() < 1e-9);
+        assert_eq!(m.top_reps, 8);
+        assert!((m.volume - 2405.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_get_session_metrics_for_exercise_multiple_sessions_ordered_asc() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        let repo = WorkoutRepository::new(pool);
+
+        // Insert sessions out of order: middle first, oldest second, newest third.
+        let d_mid = NaiveDate::from_ymd_opt(2024, 1, 12).unwrap();
+        let d_old = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let d_new = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
+
+        let s_mid = repo.create_session("user1", d_mid, None).await.unwrap();
+        let s_old = repo.create_session("user1", d_old, None).await.unwrap();
+        let s_new = repo.create_session("user1", d_new, None).await.unwrap();
+
+        repo.create_log(
+            &s_mid.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(5, 100.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &s_old.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(5, 90.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &s_new.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(5, 110.0, None),
+        )
+        .await
+        .unwrap();
+
+        let metrics = repo
+            .get_session_metrics_for_exercise("user1", "ex-bench-press")
             .await
             .unwrap();
 
-        assert!(pr.is_some());
-        assert_eq!(pr.unwrap().value, 110.0);
+        assert_eq!(metrics.len(), 3);
+        assert_eq!(metrics[0].date, d_old);
+        assert_eq!(metrics[1].date, d_mid);
+        assert_eq!(metrics[2].date, d_new);
     }
 
     #[tokio::test]
-    async fn test_dynamic_pr_updates_when_heavier_set_added() {
+    async fn test_get_session_metrics_for_exercise_excludes_other_exercises() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_exercise(&pool, "ex-bench-press", "user1");
+        create_test_exercise(&pool, "ex-squat", "user1");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
 
-        // First set
-        repo.create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
-            .await
-            .unwrap();
-
-        let logs = repo
-            .find_logs_by_session_with_pr(&session.id, "user1", recent_pr_window_start())
+        // Bench: 100x10 (volume 1000), Squat: 200x5 (volume 1000) — same session.
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(10, 100.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(&session.id, "ex-squat", 1, &SetValues::new(5, 200.0, None))
             .await
             .unwrap();
-        assert!(logs[0].is_pr); // 100.0 is the only set, so it's PR
 
-        // Add heavier set
-        repo.create_log(&session.id, "ex-bench-press", 2, 8, 110.0, None)
+        let metrics = repo
+            .get_session_metrics_for_exercise("user1", "ex-bench-press")
             .await
             .unwrap();
 
-        let logs = repo
-            .find_logs_by_session_with_pr(&session.id, "user1", recent_pr_window_start())
-            .await
-            .unwrap();
-        // With DESC ordering: newest (110.0) first
-        assert!(logs[0].is_pr); // 110.0 is now PR (created last)
-        assert!(!logs[1].is_pr); // 100.0 is no longer PR (created first)
+        assert_eq!(metrics.len(), 1);
+        let m = &metrics[0];
+        assert!((m.top_weight - 100.0).abs() < 1e-9);
+        assert_eq!(m.top_reps, 10);
+        assert!((m.volume - 1000.0).abs() < 1e-9);
     }
 
     #[tokio::test]
-    async fn test_dynamic_pr_updates_when_pr_deleted() {
+    async fn test_get_session_metrics_for_exercise_user_isolation() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
         create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
+        let s1 = repo.create_session("user1", date, None).await.unwrap();
+        let s2 = repo.create_session("user2", date, None).await.unwrap();
 
-        repo.create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
+        repo.create_log(&s1.id, "ex-bench-press", 1, &SetValues::new(5, 100.0, None))
             .await
             .unwrap();
-        let heavy_log = repo
-            .create_log(&session.id, "ex-bench-press", 2, 8, 110.0, None)
+        repo.create_log(&s2.id, "ex-bench-press", 1, &SetValues::new(5, 200.0, None))
             .await
             .unwrap();
 
-        // Delete the PR set
-        repo.delete_log(&heavy_log.id, &session.id).await.unwrap();
-
-        let logs = repo
-            .find_logs_by_session_with_pr(&session.id, "user1", recent_pr_window_start())
+        let metrics = repo
+            .get_session_metrics_for_exercise("user1", "ex-bench-press")
             .await
             .unwrap();
-        assert_eq!(logs.len(), 1);
-        assert!(logs[0].is_pr); // 100.0 becomes PR again
+
+        assert_eq!(metrics.len(), 1);
+        assert!((metrics[0].top_weight - 100.0).abs() < 1e-9);
     }
 
     #[tokio::test]
-    async fn test_update_session() {
+    async fn test_get_session_metrics_for_exercise_top_set_tie_picks_higher_reps() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
 
-        let new_date = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
-        let updated = repo
-            .update_session(&session.id, "user1", Some(new_date), Some("Updated notes"))
+        // Same max weight 100, two different rep counts. Tie-break must select 8 reps.
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(5, 100.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            2,
+            &SetValues::new(8, 100.0, None),
+        )
+        .await
+        .unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            3,
+            &SetValues::new(6, 100.0, None),
+        )
+        .await
+        .unwrap();
+
+        let metrics = repo
+            .get_session_metrics_for_exercise("user1", "ex-bench-press")
             .await
             .unwrap();
 
-        assert!(updated);
-
-        let found = repo.find_session_by_id(&session.id).await.unwrap().unwrap();
-        assert_eq!(found.date, new_date);
-        assert_eq!(found.notes, Some("Updated notes".to_string()));
+        assert_eq!(metrics.len(), 1);
+        assert!((metrics[0].top_weight - 100.0).abs() < 1e-9);
+        assert_eq!(metrics[0].top_reps, 8);
     }
 
     #[tokio::test]
-    async fn test_find_sessions_by_user_paginated() {
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_get_weekly_muscle_volume_credits_secondary_and_skips_easy_sets() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        let repo = WorkoutRepository::new(pool);
-
-        for i in 1..=5 {
-            let date = NaiveDate::from_ymd_opt(2024, 1, i).unwrap();
-            repo.create_session("user1", date, None).await.unwrap();
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        {
+            let conn = pool.get().unwrap();
+            conn.execute_batch(
+                "INSERT INTO exercise_muscles (exercise_id, muscle, role) VALUES
+                     ('ex-bench-press', 'chest', 'primary'),
+                     ('ex-bench-press', 'triceps', 'secondary');",
+            )
+            .unwrap();
         }
+        let repo = WorkoutRepository::new(pool);
 
-        let page1 = repo
-            .find_sessions_by_user_paginated("user1", 2, 0)
+        // Wednesday and the following Sunday share a week; the Monday after
+        // starts a new one.
+        let wed = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let sun = NaiveDate::from_ymd_opt(2024, 1, 14).unwrap();
+        let next_mon = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        for (date, rpe) in [(wed, Some(8)), (sun, None), (next_mon, Some(5))] {
+            let session = repo.create_session("user1", date, None).await.unwrap();
+            repo.create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(10, 100.0, rpe),
+            )
             .await
             .unwrap();
-        assert_eq!(page1.len(), 2);
+        }
 
-        let page2 = repo
-            .find_sessions_by_user_paginated("user1", 2, 2)
+        let since = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+        let rows = repo
+            .get_weekly_muscle_volume("user1", since, Weekday::Mon)
             .await
             .unwrap();
-        assert_eq!(page2.len(), 2);
 
-        let page3 = repo
-            .find_sessions_by_user_paginated("user1", 2, 4)
-            .await
-            .unwrap();
-        assert_eq!(page3.len(), 1);
+        let find = |week: NaiveDate, muscle: &str| {
+            rows.iter()
+                .find(|r| r.week_start == week && r.muscle == muscle)
+                .unwrap()
+        };
+        let first = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+        assert_eq!(find(first, "chest").hard_sets, 2.0);
+        assert_eq!(find(first, "chest").volume, 2000.0);
+        assert_eq!(find(first, "triceps").hard_sets, 1.0);
+        assert_eq!(find(first, "triceps").volume, 1000.0);
+        // RPE 5 is below the hard-set threshold but still counts as volume.
+        assert_eq!(find(next_mon, "chest").hard_sets, 0.0);
+        assert_eq!(find(next_mon, "chest").volume, 1000.0);
     }
 
-    // Edge case tests for statistics
-
     #[tokio::test]
-    async fn test_count_workouts_this_week_empty() {
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_get_weekly_muscle_volume_rates_sets_by_rir_too() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        pool.get()
+            .unwrap()
+            .execute(
+                "INSERT INTO exercise_muscles (exercise_id, muscle, role)
+                 VALUES ('ex-bench-press', 'chest', 'primary')",
+                [],
+            )
+            .unwrap();
         let repo = WorkoutRepository::new(pool);
 
-        let count = repo.count_workouts_this_week("user1").await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+        // RIR 5 is easy, RIR 2 hard; an easy RPE with a hard RIR still counts.
+        for (set, rpe, rir) in [(1, None, 5), (2, None, 2), (3, Some(5), 1)] {
+            repo.create_log(
+                &session.id,
+                "ex-bench-press",
+                set,
+                &SetValues::new(10, 100.0, rpe).with_annotations(None, None, Some(rir)),
+            )
+            .await
+            .unwrap();
+        }
 
-        assert_eq!(count, 0);
+        let since = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+        let rows = repo
+            .get_weekly_muscle_volume("user1", since, Weekday::Mon)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].hard_sets, 2.0);
+        assert_eq!(rows[0].volume, 3000.0);
     }
 
     #[tokio::test]
-    async fn test_count_workouts_this_month_empty() {
+    async fn test_unilateral_sets_count_volume_per_side() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-row", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let count = repo.count_workouts_this_month("user1").await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+        let log = repo
+            .create_log(
+                &session.id,
+                "ex-row",
+                1,
+                &SetValues::new(10, 30.0, None).with_right_side(8, 30.0),
+            )
+            .await
+            .unwrap();
+        assert_eq!(log.right_reps, Some(8));
+
+        let metrics = repo
+            .get_session_metrics_for_exercise("user1", "ex-row")
+            .await
+            .unwrap();
+        // 30 × 10 left + 30 × 8 right.
+        assert!((metrics[0].volume - 540.0).abs() < 1e-9);
 
-        assert_eq!(count, 0);
+        let balance = repo
+            .get_side_balance("user1", "ex-row")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(balance.sets, 1);
+        assert_eq!((balance.left_reps, balance.right_reps), (10, 8));
+        assert_eq!(balance.weaker_side(), Some("right"));
     }
 
     #[tokio::test]
-    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
-    async fn test_get_total_volume_this_week_empty() {
+    async fn test_side_balance_is_none_without_unilateral_sets() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let volume = repo.get_total_volume_this_week("user1").await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(5, 100.0, None),
+        )
+        .await
+        .unwrap();
 
-        assert_eq!(volume, 0.0);
+        assert!(
+            repo.get_side_balance("user1", "ex-bench-press")
+                .await
+                .unwrap()
+                .is_none()
+        );
     }
 
     #[tokio::test]
-    async fn test_get_pr_summaries_by_user_empty() {
+    async fn test_search_set_notes_matches_notes_and_tempo_for_owner_only() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let since = Utc::now() - chrono::Duration::days(30);
-        let prs = repo.get_pr_summaries_by_user("user1", since).await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+        let log = repo
+            .create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(5, 100.0, None).with_annotations(
+                    Some("Left shoulder pinch"),
+                    Some("31x0"),
+                    Some(2),
+                ),
+            )
+            .await
+            .unwrap();
+        assert_eq!(log.tempo.as_deref(), Some("3-1-X-0"));
+        assert_eq!(log.rir, Some(2));
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            2,
+            &SetValues::new(5, 100.0, None).with_annotations(Some("100% effort"), None, None),
+        )
+        .await
+        .unwrap();
 
-        assert!(prs.is_empty());
+        let found = repo
+            .search_set_notes("user1", "shoulder", 50)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].set_number, 1);
+        assert_eq!(found[0].date, date);
+
+        let by_tempo = repo.search_set_notes("user1", "3-1-X", 50).await.unwrap();
+        assert_eq!(by_tempo.len(), 1);
+
+        // `%` is literal, not a wildcard.
+        let percent = repo.search_set_notes("user1", "0%", 50).await.unwrap();
+        assert_eq!(percent.len(), 1);
+        assert_eq!(percent[0].set_number, 2);
+
+        assert!(
+            repo.search_set_notes("user2", "shoulder", 50)
+                .await
+                .unwrap()
+                .is_empty()
+        );
     }
 
     #[tokio::test]
-    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
-    async fn test_get_last_weight_per_exercise_by_user() {
+    async fn test_create_logs_numbers_sets_after_existing_ones() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_exercise(&pool, "ex-bench-press", "user1");
@@ -1366,389 +3585,939 @@ mod tests {
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
+        repo.create_log(
+            &session.id,
+            "ex-bench-press",
+            1,
+            &SetValues::new(5, 100.0, None),
+        )
+        .await
+        .unwrap();
 
-        // Bench press: latest set (105) is lower than max (110)
-        repo.create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&session.id, "ex-bench-press", 2, 8, 110.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&session.id, "ex-bench-press", 3, 5, 105.0, Some(8))
-            .await
-            .unwrap();
-        // Squat: single set, no RPE recorded
-        repo.create_log(&session.id, "ex-squat", 1, 5, 150.0, None)
+        let created = repo
+            .create_logs(
+                &session.id,
+                vec![
+                    (
+                        "ex-bench-press".to_string(),
+                        SetValues::new(5, 100.0, Some(8)),
+                    ),
+                    ("ex-squat".to_string(), SetValues::new(5, 140.0, None)),
+                    (
+                        "ex-bench-press".to_string(),
+                        SetValues::new(4, 100.0, Some(9)),
+                    ),
+                ],
+            )
             .await
             .unwrap();
+        assert_eq!(created, 3);
 
-        let entries = repo
-            .get_last_weight_per_exercise_by_user("user1")
+        let logs = repo
+            .find_logs_by_session_for_share(&session.id)
             .await
             .unwrap();
-
-        assert_eq!(entries.len(), 2);
-        let bench = entries
+        let mut numbers: Vec<(String, i32)> = logs
             .iter()
-            .find(|e| e.exercise_id == "ex-bench-press")
-            .expect("bench entry");
-        let squat = entries
+            .map(|l| (l.exercise_id.clone(), l.set_number))
+            .collect();
+        numbers.sort();
+        assert_eq!(
+            numbers,
+            vec![
+                ("ex-bench-press".to_string(), 1),
+                ("ex-bench-press".to_string(), 2),
+                ("ex-bench-press".to_string(), 3),
+                ("ex-squat".to_string(), 1),
+            ]
+        );
+    }
+
+    async fn create_sets(
+        repo: &WorkoutRepository,
+        session_id: &str,
+        weights: &[f64],
+    ) -> Vec<String> {
+        let sets = weights
             .iter()
-            .find(|e| e.exercise_id == "ex-squat")
-            .expect("squat entry");
-        // Last logged weight, NOT the max
-        assert_eq!(bench.weight, 105.0);
-        assert_eq!(squat.weight, 150.0);
-        // RPE comes from the same latest row as the weight
-        assert_eq!(bench.rpe, Some(8));
-        assert_eq!(squat.rpe, None);
+            .map(|&w| ("ex-bench-press".to_string(), SetValues::new(5, w, None)))
+            .collect();
+        repo.create_logs(session_id, sets).await.unwrap();
+        repo.find_logs_for_exercise(session_id, "ex-bench-press")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|l| l.id)
+            .collect()
     }
 
     #[tokio::test]
-    async fn test_get_last_weight_per_exercise_by_user_empty() {
+    async fn test_delete_log_renumbers_remaining_sets() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let entries = repo
-            .get_last_weight_per_exercise_by_user("user1")
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let session = repo.create_session("user1", date, None).await.unwrap();
+        let ids = create_sets(&repo, &session.id, &[100.0, 102.5, 105.0]).await;
+
+        assert!(repo.delete_log(&ids[1], &session.id).await.unwrap());
+
+        let logs = repo
+            .find_logs_for_exercise(&session.id, "ex-bench-press")
             .await
             .unwrap();
-
-        assert!(entries.is_empty());
+        let numbered: Vec<(&str, i32)> =
+            logs.iter().map(|l| (l.id.as_str(), l.set_number)).collect();
+        assert_eq!(numbered, vec![(ids[0].as_str(), 1), (ids[2].as_str(), 2)]);
     }
 
-    // Share functionality tests
-
     #[tokio::test]
-    async fn test_set_share_token() {
+    async fn test_reorder_logs_applies_order_and_rejects_stale_lists() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
         let session = repo.create_session("user1", date, None).await.unwrap();
-        assert!(session.share_token.is_none());
+        let ids = create_sets(&repo, &session.id, &[100.0, 102.5, 105.0]).await;
 
-        let token = repo
-            .set_share_token(&session.id, "user1", None)
+        let order = vec![ids[2].clone(), ids[0].clone(), ids[1].clone()];
+        repo.reorder_logs(&session.id, "ex-bench-press", order.clone())
             .await
             .unwrap();
-        assert!(!token.is_empty());
+        let logs = repo
+            .find_logs_for_exercise(&session.id, "ex-bench-press")
+            .await
+            .unwrap();
+        assert_eq!(logs.iter().map(|l| l.id.clone()).collect::<Vec<_>>(), order);
+        assert_eq!(
+            logs.iter().map(|l| l.set_number).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
 
-        let found = repo.find_session_by_id(&session.id).await.unwrap().unwrap();
-        assert_eq!(found.share_token, Some(token));
+        let stale = repo
+            .reorder_logs(
+                &session.id,
+                "ex-bench-press",
+                vec![ids[1].clone(), ids[0].clone()],
+            )
+            .await;
+        assert!(matches!(stale, Err(AppError::BadRequest(_))));
     }
 
     #[tokio::test]
-    async fn test_set_share_token_wrong_user() {
+    async fn test_move_logs_to_unilateral_exercise_in_another_session() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
-        create_test_user(&pool, "user2");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        create_test_exercise(&pool, "ex-db-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let source = repo.create_session("user1", date, None).await.unwrap();
+        let target = repo
+            .create_session("user1", date.succ_opt().unwrap(), None)
+            .await
+            .unwrap();
+        let ids = create_sets(&repo, &source.id, &[100.0, 102.5, 105.0]).await;
+        repo.create_log(&target.id, "ex-db-press", 1, &SetValues::new(8, 30.0, None))
+            .await
+            .unwrap();
+
+        let moved = repo
+            .move_logs(
+                &source.id,
+                vec![ids[2].clone(), ids[0].clone()],
+                &target.id,
+                "ex-db-press",
+                true,
+            )
+            .await
+            .unwrap();
+        assert_eq!(moved, 2);
+
+        let left = repo
+            .find_logs_for_exercise(&source.id, "ex-bench-press")
+            .await
+            .unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(
+            (left[0].id.as_str(), left[0].set_number),
+            (ids[1].as_str(), 1)
+        );
+
+        let arrived = repo
+            .find_logs_for_exercise(&target.id, "ex-db-press")
+            .await
+            .unwrap();
+        let summary: Vec<(i32, Option<i32>)> = arrived
+            .iter()
+            .map(|l| (l.set_number, l.right_reps))
+            .collect();
+        assert_eq!(summary, vec![(1, None), (2, Some(5)), (3, Some(5))]);
+        assert_eq!(arrived[1].id, ids[0]);
+        assert_eq!(arrived[2].id, ids[2]);
+
+        let missing = repo
+            .move_logs(
+                &source.id,
+                vec![ids[0].clone()],
+                &target.id,
+                "ex-db-press",
+                true,
+            )
+            .await;
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_move_logs_takes_set_comments_along() {
+        let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        let repo = WorkoutRepository::new(pool.clone());
+        let comments = CommentRepository::new(pool);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let source = repo.create_session("user1", date, None).await.unwrap();
+        let target = repo
+            .create_session("user1", date.succ_opt().unwrap(), None)
+            .await
+            .unwrap();
+        let ids = create_sets(&repo, &source.id, &[100.0, 102.5]).await;
+        comments
+            .add_comment(&source.id, None, "user1", "Good session")
+            .await
+            .unwrap();
+        let on_set = comments
+            .add_comment(&source.id, Some(&ids[1]), "user1", "Grindy")
+            .await
+            .unwrap();
+
+        repo.move_logs(
+            &source.id,
+            vec![ids[1].clone()],
+            &target.id,
+            "ex-bench-press",
+            false,
+        )
+        .await
+        .unwrap();
 
-        let result = repo.set_share_token(&session.id, "user2", None).await;
-        assert!(result.is_err());
+        let left = comments.find_comments(&source.id).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].body, "Good session");
+        let moved = comments.find_comments(&target.id).await.unwrap();
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].id, on_set);
+        assert_eq!(moved[0].set_label.as_deref(), Some("Test Exercise set 1"));
+        assert!(
+            !comments
+                .delete_comment(&source.id, &on_set, "user1")
+                .await
+                .unwrap()
+        );
+        assert!(
+            comments
+                .delete_comment(&target.id, &on_set, "user1")
+                .await
+                .unwrap()
+        );
     }
 
     #[tokio::test]
-    async fn test_revoke_share_token() {
+    async fn test_find_sessions_page_walks_both_ways_by_cursor() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
+        for day in 1..=5 {
+            let date = NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+            repo.create_session("user1", date, None).await.unwrap();
+        }
+        let days = |page: &WorkoutPage| -> Vec<u32> {
+            page.workouts
+                .iter()
+                .map(|w| chrono::Datelike::day(&w.session.date))
+                .collect()
+        };
+        let page = |sort, cursor| {
+            repo.find_sessions_page("user1", WorkoutFilter::default(), sort, cursor, 2)
+        };
 
-        let token = repo
-            .set_share_token(&session.id, "user1", None)
-            .await
-            .unwrap();
-        assert!(!token.is_empty());
+        let first = page(WorkoutSort::Newest, None).await.unwrap();
+        assert_eq!(days(&first), vec![5, 4]);
+        assert!(first.prev.is_none());
+        let second = page(
+            WorkoutSort::Newest,
+            first.next.clone().map(PageCursor::After),
+        )
+        .await
+        .unwrap();
+        assert_eq!(days(&second), vec![3, 2]);
+        let third = page(
+            WorkoutSort::Newest,
+            second.next.clone().map(PageCursor::After),
+        )
+        .await
+        .unwrap();
+        assert_eq!(days(&third), vec![1]);
+        assert!(third.next.is_none());
 
-        let revoked = repo.revoke_share_token(&session.id, "user1").await.unwrap();
-        assert!(revoked);
+        let back = page(
+            WorkoutSort::Newest,
+            third.prev.clone().map(PageCursor::Before),
+        )
+        .await
+        .unwrap();
+        assert_eq!(days(&back), vec![3, 2]);
+        let start = page(
+            WorkoutSort::Newest,
+            back.prev.clone().map(PageCursor::Before),
+        )
+        .await
+        .unwrap();
+        assert_eq!(days(&start), vec![5, 4]);
+        assert!(start.prev.is_none());
+        assert!(start.next.is_some());
 
-        let found = repo.find_session_by_id(&session.id).await.unwrap().unwrap();
-        assert!(found.share_token.is_none());
+        let oldest = page(WorkoutSort::Oldest, None).await.unwrap();
+        assert_eq!(days(&oldest), vec![1, 2]);
     }
 
     #[tokio::test]
-    async fn test_revoke_share_token_wrong_user() {
+    async fn test_find_sessions_page_filters_and_sorts_by_volume() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_user(&pool, "user2");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
+        create_test_exercise(&pool, "ex-squat", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
-        repo.set_share_token(&session.id, "user1", None)
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let light = repo
+            .create_session("user1", date(1), Some("Easy bench"))
+            .await
+            .unwrap();
+        let heavy = repo
+            .create_session("user1", date(2), Some("Heavy bench, new belt"))
+            .await
+            .unwrap();
+        let legs = repo
+            .create_session("user1", date(3), Some("Legs"))
+            .await
+            .unwrap();
+        repo.create_session("user2", date(2), Some("Heavy bench"))
             .await
             .unwrap();
+        for (session, exercise, weight) in [
+            (&light, "ex-bench-press", 60.0),
+            (&heavy, "ex-bench-press", 100.0),
+            (&legs, "ex-squat", 140.0),
+        ] {
+            repo.create_log(&session.id, exercise, 1, &SetValues::new(5, weight, None))
+                .await
+                .unwrap();
+        }
+        let ids = |page: WorkoutPage| -> Vec<String> {
+            page.workouts.into_iter().map(|w| w.session.id).collect()
+        };
 
-        let revoked = repo.revoke_share_token(&session.id, "user2").await.unwrap();
-        assert!(!revoked);
+        let bench = WorkoutFilter {
+            exercise_id: Some("ex-bench-press".to_string()),
+            ..WorkoutFilter::default()
+        };
+        let page = repo
+            .find_sessions_page("user1", bench.clone(), WorkoutSort::Volume, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(ids(page), vec![heavy.id.clone(), light.id.clone()]);
+        assert_eq!(
+            repo.count_sessions_filtered("user1", bench).await.unwrap(),
+            2
+        );
 
-        // Token should still exist
-        let found = repo.find_session_by_id(&session.id).await.unwrap().unwrap();
-        assert!(found.share_token.is_some());
+        let notes = |text: &str| WorkoutFilter {
+            text: Some(text.to_string()),
+            ..WorkoutFilter::default()
+        };
+        let page = repo
+            .find_sessions_page("user1", notes("bel"), WorkoutSort::Newest, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(ids(page), vec![heavy.id.clone()]);
+
+        // The index follows edits to the notes.
+        repo.update_session_with_tags(
+            &legs.id,
+            "user1",
+            legs.date,
+            Some("Legs, belt"),
+            Vec::new(),
+            Vec::new(),
+        )
+        .await
+        .unwrap();
+        let ranged = WorkoutFilter {
+            from: Some(date(2)),
+            to: Some(date(3)),
+            ..notes("belt")
+        };
+        let page = repo
+            .find_sessions_page("user1", ranged, WorkoutSort::Oldest, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(ids(page), vec![heavy.id.clone(), legs.id.clone()]);
+
+        let page = repo
+            .find_sessions_page("user1", notes("- *"), WorkoutSort::Newest, None, 10)
+            .await
+            .unwrap();
+        assert!(page.workouts.is_empty());
     }
 
     #[tokio::test]
-    async fn test_find_session_by_share_token() {
+    async fn test_tag_filter_and_monthly_tag_volume() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
-        let token = repo
-            .set_share_token(&session.id, "user1", None)
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let mut sessions = Vec::new();
+        for (day, weight, tag) in [
+            (date(1, 5), 100.0, "Push"),
+            (date(1, 20), 60.0, "Push"),
+            (date(2, 3), 80.0, "Push"),
+            (date(2, 10), 50.0, "Deload"),
+        ] {
+            let session = repo
+                .create_session_with_tags("user1", day, None, Vec::new(), vec![tag.to_string()])
+                .await
+                .unwrap();
+            repo.create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(10, weight, None),
+            )
             .await
             .unwrap();
+            sessions.push(session);
+        }
 
-        let found = repo
-            .find_session_by_share_token(&token)
+        let push = WorkoutFilter {
+            tag: Some("push".to_string()),
+            ..WorkoutFilter::default()
+        };
+        assert_eq!(
+            repo.count_sessions_filtered("user1", push).await.unwrap(),
+            3
+        );
+
+        let rows = repo
+            .get_monthly_tag_volume("user1", date(1, 10))
             .await
-            .unwrap()
             .unwrap();
-        assert_eq!(found.id, session.id);
+        let summary: Vec<(&str, NaiveDate, i64, f64)> = rows
+            .iter()
+            .map(|r| (r.tag_name.as_str(), r.month, r.sessions, r.volume))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Deload", date(2, 1), 1, 500.0),
+                ("Push", date(1, 1), 1, 600.0),
+                ("Push", date(2, 1), 1, 800.0),
+            ]
+        );
     }
 
     #[tokio::test]
-    async fn test_find_session_by_share_token_not_found() {
+    async fn test_daily_volume_groups_sessions_by_date() {
         let pool = setup_test_db();
+        create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
+        create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let result = repo
-            .find_session_by_share_token("nonexistent-token")
+        let date = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let morning = repo.create_session("user1", date(4), None).await.unwrap();
+        let evening = repo.create_session("user1", date(4), None).await.unwrap();
+        let single = repo.create_session("user1", date(6), None).await.unwrap();
+        repo.create_session("user1", date(9), None).await.unwrap();
+        repo.create_session("user1", date(20), None).await.unwrap();
+        repo.create_session("user2", date(6), None).await.unwrap();
+        for (session, weight) in [(&morning, 100.0), (&evening, 50.0), (&single, 80.0)] {
+            repo.create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(5, weight, None),
+            )
             .await
             .unwrap();
-        assert!(result.is_none());
+        }
+
+        let days = repo
+            .get_daily_volume("user1", date(1), date(10))
+            .await
+            .unwrap();
+        let summary: Vec<(NaiveDate, i64, i64, f64)> = days
+            .iter()
+            .map(|d| (d.date, d.sessions, d.sets, d.volume))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (date(4), 2, 2, 750.0),
+                (date(6), 1, 1, 400.0),
+                (date(9), 1, 0, 0.0),
+            ]
+        );
+        assert_eq!(days[1].session_id, single.id);
     }
 
     #[tokio::test]
-    async fn test_find_logs_by_session_for_share() {
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_period_aggregates_for_reports() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_exercise(&pool, "ex-bench-press", "user1");
+        create_test_exercise(&pool, "ex-squat", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        // The week before: bench 100, squat 120.
+        let before = repo.create_session("user1", date(10), None).await.unwrap();
+        for (exercise, weight) in [("ex-bench-press", 100.0), ("ex-squat", 120.0)] {
+            repo.create_log(&before.id, exercise, 1, &SetValues::new(5, weight, Some(8)))
+                .await
+                .unwrap();
+        }
+        // The week of 15 January: bench 105 twice (first on the 16th), squat
+        // only 110, and an empty planned session.
+        let tuesday = repo.create_session("user1", date(16), None).await.unwrap();
+        let thursday = repo.create_session("user1", date(18), None).await.unwrap();
+        repo.create_session("user1", date(20), None).await.unwrap();
+        for (session, exercise, reps, weight, rpe) in [
+            (&tuesday, "ex-bench-press", 3, 105.0, Some(9)),
+            (&tuesday, "ex-squat", 5, 110.0, None),
+            (&thursday, "ex-bench-press", 2, 105.0, Some(7)),
+            (&thursday, "ex-bench-press", 8, 80.0, None),
+        ] {
+            repo.create_log(&session.id, exercise, 1, &SetValues::new(reps, weight, rpe))
+                .await
+                .unwrap();
+        }
 
-        repo.create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
+        let summary = repo
+            .get_period_summary("user1", date(15), date(21))
             .await
             .unwrap();
+        assert_eq!((summary.sessions, summary.sets), (2, 4));
+        assert_eq!(summary.volume, 315.0 + 550.0 + 210.0 + 640.0);
+        assert_eq!(summary.average_rpe, Some(8.0));
 
-        let logs = repo
-            .find_logs_by_session_for_share(&session.id)
+        let totals = repo
+            .get_exercise_totals_between("user1", date(15), date(21))
             .await
             .unwrap();
+        assert_eq!(totals[0].exercise_id, "ex-bench-press");
+        assert_eq!((totals[0].sessions, totals[0].sets), (2, 3));
+        assert_eq!((totals[0].top_weight, totals[0].top_reps), (105.0, 3));
+        assert_eq!(totals[1].average_rpe, None);
 
-        assert_eq!(logs.len(), 1);
-        // is_pr should be false for share (we don't calculate PRs)
-        assert!(!logs[0].is_pr);
-    }
-
-    #[tokio::test]
-    async fn test_reshare_generates_new_token() {
-        let pool = setup_test_db();
-        create_test_user(&pool, "user1");
-        let repo = WorkoutRepository::new(pool);
-
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
-
-        let token1 = repo
-            .set_share_token(&session.id, "user1", None)
+        let prs = repo
+            .get_prs_between("user1", date(15), date(21))
             .await
             .unwrap();
-        repo.revoke_share_token(&session.id, "user1").await.unwrap();
-        let token2 = repo
-            .set_share_token(&session.id, "user1", None)
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].exercise_id, "ex-bench-press");
+        assert_eq!((prs[0].weight, prs[0].reps), (105.0, 3));
+        assert_eq!(prs[0].session_id, tuesday.id);
+        assert_eq!(prs[0].previous_best, Some(100.0));
+
+        // Everything is new in the first week trained.
+        let prs = repo
+            .get_prs_between("user1", date(8), date(14))
             .await
             .unwrap();
+        assert_eq!(prs.len(), 2);
+        assert!(prs.iter().all(|pr| pr.previous_best.is_none()));
 
-        assert_ne!(token1, token2);
+        let empty = repo
+            .get_period_summary("user1", date(1), date(7))
+            .await
+            .unwrap();
+        assert_eq!(empty, PeriodSummary::default());
     }
 
     #[tokio::test]
-    async fn test_get_session_metrics_for_exercise_empty() {
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    async fn test_pr_gains_and_monthly_totals_over_a_year() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_exercise(&pool, "ex-bench-press", "user1");
+        create_test_exercise(&pool, "ex-squat", "user1");
+        create_test_exercise(&pool, "ex-row", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let metrics = repo
-            .get_session_metrics_for_exercise("user1", "ex-bench-press")
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        // Bench was 100 before the year and reached 110; squat is new in the
+        // year, opening at 100 and reaching 140; row never beat 2023.
+        for (day, exercise, weight) in [
+            (date(2023, 11, 20), "ex-bench-press", 100.0),
+            (date(2023, 11, 20), "ex-row", 90.0),
+            (date(2024, 1, 8), "ex-squat", 100.0),
+            (date(2024, 1, 8), "ex-bench-press", 95.0),
+            (date(2024, 1, 10), "ex-row", 80.0),
+            (date(2024, 6, 3), "ex-squat", 140.0),
+            (date(2024, 6, 5), "ex-bench-press", 110.0),
+        ] {
+            let session = repo.create_session("user1", day, None).await.unwrap();
+            repo.create_log(&session.id, exercise, 1, &SetValues::new(1, weight, None))
+                .await
+                .unwrap();
+        }
+        repo.create_session("user1", date(2024, 7, 1), None)
             .await
             .unwrap();
 
-        assert!(metrics.is_empty());
+        let (from, to) = (date(2024, 1, 1), date(2024, 12, 31));
+        let gains = repo.get_pr_gains_between("user1", from, to).await.unwrap();
+        let gains: Vec<(&str, f64, f64)> = gains
+            .iter()
+            .map(|g| (g.exercise_id.as_str(), g.start_weight, g.end_weight))
+            .collect();
+        assert_eq!(
+            gains,
+            vec![("ex-squat", 100.0, 140.0), ("ex-bench-press", 100.0, 110.0)]
+        );
+
+        let months = repo
+            .get_monthly_totals_between("user1", from, to)
+            .await
+            .unwrap();
+        assert_eq!(
+            months,
+            vec![
+                MonthlyTotals {
+                    month: date(2024, 1, 1),
+                    sessions: 3,
+                    volume: 275.0,
+                },
+                MonthlyTotals {
+                    month: date(2024, 6, 1),
+                    sessions: 2,
+                    volume: 250.0,
+                },
+            ]
+        );
     }
 
     #[tokio::test]
-    async fn test_get_session_metrics_for_exercise_single_session_aggregates_correctly() {
+    async fn test_weekly_session_counts_skip_empty_and_later_sessions() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_exercise(&pool, "ex-bench-press", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
-
-        // Three sets: 100x10, 110x8, 105x5 — top weight 110 with 8 reps; volume = 1000+880+525 = 2405
-        repo.create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        // Sunday 7 January closes the week of 1 January.
+        for day in [date(1, 1), date(1, 7), date(1, 8), date(1, 22), date(1, 25)] {
+            let session = repo.create_session("user1", day, None).await.unwrap();
+            repo.create_log(
+                &session.id,
+                "ex-bench-press",
+                1,
+                &SetValues::new(5, 100.0, None),
+            )
             .await
             .unwrap();
-        repo.create_log(&session.id, "ex-bench-press", 2, 8, 110.0, None)
+        }
+        repo.create_session("user1", date(1, 9), None)
             .await
             .unwrap();
-        repo.create_log(&session.id, "ex-bench-press", 3, 5, 105.0, None)
+
+        let weeks = repo
+            .get_weekly_session_counts("user1", date(1, 24), Weekday::Mon)
             .await
             .unwrap();
+        let summary: Vec<(NaiveDate, i64)> =
+            weeks.iter().map(|w| (w.week_start, w.sessions)).collect();
+        assert_eq!(
+            summary,
+            vec![(date(1, 1), 2), (date(1, 8), 1), (date(1, 22), 1)]
+        );
 
-        let metrics = repo
-            .get_session_metrics_for_exercise("user1", "ex-bench-press")
+        // With Sunday-first weeks the 7th opens a week with the 8th.
+        let weeks = repo
+            .get_weekly_session_counts("user1", date(1, 24), Weekday::Sun)
             .await
             .unwrap();
-
-        assert_eq!(metrics.len(), 1);
-        let m = &metrics[0];
-        assert_eq!(m.date, date);
-        assert!((m.top_weight - 110.0).abs() < 1e-9);
-        assert_eq!(m.top_reps, 8);
-        assert!((m.volume - 2405.0).abs() < 1e-9);
+        let summary: Vec<(NaiveDate, i64)> =
+            weeks.iter().map(|w| (w.week_start, w.sessions)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(), 1),
+                (date(1, 7), 2),
+                (date(1, 21), 1)
+            ]
+        );
     }
 
     #[tokio::test]
-    async fn test_get_session_metrics_for_exercise_multiple_sessions_ordered_asc() {
+    async fn test_previous_session_with_same_exercises() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_exercise(&pool, "ex-bench-press", "user1");
+        create_test_exercise(&pool, "ex-squat", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        // Insert sessions out of order: middle first, oldest second, newest third.
-        let d_mid = NaiveDate::from_ymd_opt(2024, 1, 12).unwrap();
-        let d_old = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
-        let d_new = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
-
-        let s_mid = repo.create_session("user1", d_mid, None).await.unwrap();
-        let s_old = repo.create_session("user1", d_old, None).await.unwrap();
-        let s_new = repo.create_session("user1", d_new, None).await.unwrap();
-
-        repo.create_log(&s_mid.id, "ex-bench-press", 1, 5, 100.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&s_old.id, "ex-bench-press", 1, 5, 90.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&s_new.id, "ex-bench-press", 1, 5, 110.0, None)
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        let log = |session: &WorkoutSession, exercise: &'static str| {
+            let session_id = session.id.clone();
+            let repo = &repo;
+            async move {
+                repo.create_log(&session_id, exercise, 1, &SetValues::new(5, 100.0, None))
+                    .await
+                    .unwrap();
+            }
+        };
+        let full = repo.create_session("user1", date(1), None).await.unwrap();
+        log(&full, "ex-bench-press").await;
+        log(&full, "ex-squat").await;
+        // Later, but missing the squat.
+        let bench_only = repo.create_session("user1", date(3), None).await.unwrap();
+        log(&bench_only, "ex-bench-press").await;
+        let current = repo.create_session("user1", date(5), None).await.unwrap();
+        log(&current, "ex-bench-press").await;
+        log(&current, "ex-squat").await;
+        let later = repo.create_session("user1", date(8), None).await.unwrap();
+        log(&later, "ex-bench-press").await;
+        log(&later, "ex-squat").await;
+
+        let previous = repo
+            .find_previous_session_with_same_exercises("user1", &current)
             .await
             .unwrap();
+        assert_eq!(previous.unwrap().id, full.id);
 
-        let metrics = repo
-            .get_session_metrics_for_exercise("user1", "ex-bench-press")
+        // A bench-only workout matches an earlier one that trained bench
+        // alongside other exercises; only earlier workouts count.
+        let previous = repo
+            .find_previous_session_with_same_exercises("user1", &bench_only)
             .await
             .unwrap();
+        assert_eq!(previous.unwrap().id, full.id);
 
-        assert_eq!(metrics.len(), 3);
-        assert_eq!(metrics[0].date, d_old);
-        assert_eq!(metrics[1].date, d_mid);
-        assert_eq!(metrics[2].date, d_new);
+        assert!(
+            repo.find_previous_session_with_same_exercises("user1", &full)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let empty = repo.create_session("user1", date(9), None).await.unwrap();
+        assert!(
+            repo.find_previous_session_with_same_exercises("user1", &empty)
+                .await
+                .unwrap()
+                .is_none()
+        );
     }
 
     #[tokio::test]
-    async fn test_get_session_metrics_for_exercise_excludes_other_exercises() {
+    async fn test_sets_for_exercises_in_a_date_range() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
+        create_test_user(&pool, "user2");
         create_test_exercise(&pool, "ex-bench-press", "user1");
         create_test_exercise(&pool, "ex-squat", "user1");
         let repo = WorkoutRepository::new(pool);
 
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
-
-        // Bench: 100x10 (volume 1000), Squat: 200x5 (volume 1000) — same session.
-        repo.create_log(&session.id, "ex-bench-press", 1, 10, 100.0, None)
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        for (user, day, exercise, set_number) in [
+            ("user1", 5, "ex-squat", 2),
+            ("user1", 5, "ex-squat", 1),
+            ("user1", 1, "ex-bench-press", 1),
+            ("user1", 9, "ex-squat", 1),
+            ("user2", 5, "ex-squat", 1),
+        ] {
+            let session = repo.create_session(user, date(day), None).await.unwrap();
+            repo.create_log(
+                &session.id,
+                exercise,
+                set_number,
+                &SetValues::new(5, 100.0, None),
+            )
             .await
             .unwrap();
-        repo.create_log(&session.id, "ex-squat", 1, 5, 200.0, None)
+        }
+
+        let ids = vec!["ex-squat".to_string(), "ex-bench-press".to_string()];
+        let sets = repo
+            .get_sets_for_exercises("user1", &ids, Some(date(1)), Some(date(5)))
             .await
             .unwrap();
+        let rows: Vec<(&str, NaiveDate, i32)> = sets
+            .iter()
+            .map(|s| (s.exercise_id.as_str(), s.date, s.reps))
+            .collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], ("ex-bench-press", date(1), 5));
+        assert!(
+            rows[1..]
+                .iter()
+                .all(|(id, d, _)| *id == "ex-squat" && *d == date(5))
+        );
 
-        let metrics = repo
-            .get_session_metrics_for_exercise("user1", "ex-bench-press")
+        let all = repo
+            .get_sets_for_exercises("user1", &ids[..1], None, None)
             .await
             .unwrap();
-
-        assert_eq!(metrics.len(), 1);
-        let m = &metrics[0];
-        assert!((m.top_weight - 100.0).abs() < 1e-9);
-        assert_eq!(m.top_reps, 10);
-        assert!((m.volume - 1000.0).abs() < 1e-9);
+        assert_eq!(all.len(), 3);
+        assert!(
+            repo.get_sets_for_exercises("user1", &[], None, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
     }
 
     #[tokio::test]
-    async fn test_get_session_metrics_for_exercise_user_isolation() {
+    async fn test_block_scope_filters_by_range_and_tag() {
         let pool = setup_test_db();
         create_test_user(&pool, "user1");
         create_test_user(&pool, "user2");
-        create_test_exercise(&pool, "ex-bench-press", "user1");
-        let repo = WorkoutRepository::new(pool);
-
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let s1 = repo.create_session("user1", date, None).await.unwrap();
-        let s2 = repo.create_session("user2", date, None).await.unwrap();
-
-        repo.create_log(&s1.id, "ex-bench-press", 1, 5, 100.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&s2.id, "ex-bench-press", 1, 5, 200.0, None)
-            .await
+        create_test_exercise(&pool, "ex1", "user1");
+        let repo = WorkoutRepository::new(pool.clone());
+        let date = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+
+        let mut ids = Vec::new();
+        for d in [1, 8, 15] {
+            let session = repo.create_session("user1", date(d), None).await.unwrap();
+            repo.create_log(&session.id, "ex1", 1, &SetValues::new(5, 100.0, Some(8)))
+                .await
+                .unwrap();
+            ids.push(session.id);
+        }
+        repo.create_session("user2", date(8), None).await.unwrap();
+        {
+            let conn = pool.get().unwrap();
+            conn.execute(
+                "INSERT INTO workout_tags (id, user_id, name) VALUES ('tag1', 'user1', 'peak')",
+                [],
+            )
             .unwrap();
+            for id in &ids[1..] {
+                conn.execute(
+                    "INSERT INTO workout_session_tags (session_id, tag_id) VALUES (?, 'tag1')",
+                    [id],
+                )
+                .unwrap();
+            }
+        }
 
-        let metrics = repo
-            .get_session_metrics_for_exercise("user1", "ex-bench-press")
+        let range = BlockScope::parse("2024-03-01", "2024-03-08", "").unwrap();
+        let sessions = repo.find_block_sessions("user1", &range, 10).await.unwrap();
+        let dates: Vec<NaiveDate> = sessions.iter().map(|s| s.date).collect();
+        assert_eq!(dates, vec![date(1), date(8)]);
+        let summary = repo.get_block_summary("user1", &range).await.unwrap();
+        assert_eq!((summary.sessions, summary.sets), (2, 2));
+        assert!((summary.volume - 1000.0).abs() < f64::EPSILON);
+
+        let tagged = BlockScope::parse("", "", "tag1").unwrap();
+        let dates: Vec<NaiveDate> = repo
+            .find_block_sessions("user1", &tagged, 10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|s| s.date)
+            .collect();
+        assert_eq!(dates, vec![date(8), date(15)]);
+        assert_eq!(
+            repo.find_block_logs("user1", &tagged, 10)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        let totals = repo
+            .get_block_exercise_totals("user1", &tagged)
             .await
             .unwrap();
+        assert_eq!(totals.len(), 1);
+        assert_eq!((totals[0].sessions, totals[0].sets), (2, 2));
+
+        // A limit keeps the first sessions and only their sets.
+        let first = repo.find_block_sessions("user1", &tagged, 1).await.unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].date, date(8));
+        let logs = repo.find_block_logs("user1", &tagged, 1).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].session_id, first[0].id);
+        assert_eq!(
+            repo.count_block_sessions("user1", &tagged).await.unwrap(),
+            2
+        );
 
-        assert_eq!(metrics.len(), 1);
-        assert!((metrics[0].top_weight - 100.0).abs() < 1e-9);
+        let both = BlockScope::parse("2024-03-10", "", "tag1").unwrap();
+        let sessions = repo.find_block_sessions("user1", &both, 10).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].date, date(15));
     }
 
     #[tokio::test]
-    async fn test_get_session_metrics_for_exercise_top_set_tie_picks_higher_reps() {
+    async fn test_find_accessible_session_follows_coach_grants() {
         let pool = setup_test_db();
-        create_test_user(&pool, "user1");
-        create_test_exercise(&pool, "ex-bench-press", "user1");
-        let repo = WorkoutRepository::new(pool);
-
-        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
-        let session = repo.create_session("user1", date, None).await.unwrap();
+        create_test_user(&pool, "athlete");
+        create_test_user(&pool, "coach");
+        create_test_user(&pool, "stranger");
+        let repo = WorkoutRepository::new(pool.clone());
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let session = repo.create_session("athlete", date, None).await.unwrap();
 
-        // Same max weight 100, two different rep counts. Tie-break must select 8 reps.
-        repo.create_log(&session.id, "ex-bench-press", 1, 5, 100.0, None)
-            .await
-            .unwrap();
-        repo.create_log(&session.id, "ex-bench-press", 2, 8, 100.0, None)
+        let (_, access) = repo
+            .find_accessible_session(&session.id, "athlete")
             .await
             .unwrap();
-        repo.create_log(&session.id, "ex-bench-press", 3, 6, 100.0, None)
-            .await
+        assert_eq!(access, Access::Owner);
+        assert!(
+            repo.find_accessible_session(&session.id, "coach")
+                .await
+                .is_err()
+        );
+        pool.get()
+            .unwrap()
+            .execute(
+                "INSERT INTO coach_grants (athlete_id, coach_id, can_comment, can_program, created_at)
+                 VALUES ('athlete', 'coach', 1, 0, datetime('now'))",
+                [],
+            )
             .unwrap();
-
-        let metrics = repo
-            .get_session_metrics_for_exercise("user1", "ex-bench-press")
+        let (found, access) = repo
+            .find_accessible_session(&session.id, "coach")
             .await
             .unwrap();
-
-        assert_eq!(metrics.len(), 1);
-        assert!((metrics[0].top_weight - 100.0).abs() < 1e-9);
-        assert_eq!(metrics[0].top_reps, 8);
+        assert_eq!(found.id, session.id);
+        assert_eq!(
+            access,
+            Access::Coach {
+                can_comment: true,
+                can_program: false
+            }
+        );
+        assert!(
+            repo.find_accessible_session(&session.id, "stranger")
+                .await
+                .is_err()
+        );
+        // Coaches still cannot edit.
+        assert!(repo.find_owned_session(&session.id, "coach").await.is_err());
     }
 }
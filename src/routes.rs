@@ -4,7 +4,11 @@ use axum::{
     routing::{get, post},
 };
 
-use crate::handlers::{auth, dashboard, exercises, favicon, health, settings, stats, workouts};
+use crate::handlers::{
+    auth, block_shares, catalog, categories, chart_images, coaching, dashboard, exercises, favicon,
+    health, profile, reports, set_editor, settings, stats, tags, text_import, workouts,
+    year_review,
+};
 use crate::middleware::{
     HstsHeader, SessionLayerState, baseline_headers_middleware, csrf_origin_guard, hsts_middleware,
     sliding_session_middleware,
@@ -55,12 +59,32 @@ pub fn create_router(state: AppState) -> Router {
         )
         .route("/workouts", get(workouts::list))
         .route("/workouts/new", get(workouts::new_page))
+        .route("/workouts/search", get(workouts::search))
+        .route("/workouts/calendar", get(workouts::calendar))
+        .route("/workouts/compare", get(workouts::compare))
+        .route("/workouts/import", get(text_import::page))
+        .route("/workouts/import", post(text_import::import))
+        .route("/workouts/shares", get(block_shares::index))
+        .route("/workouts/shares", post(block_shares::create))
+        .route("/workouts/shares/{id}/revoke", post(block_shares::revoke))
+        .route("/workouts/tags", get(tags::list))
+        .route("/workouts/tags", post(tags::create))
+        .route("/workouts/tags/{id}", post(tags::rename))
+        .route("/workouts/tags/{id}/delete", post(tags::delete))
         .route("/workouts", post(workouts::create))
         .route("/workouts/{id}", get(workouts::show))
         .route("/workouts/{id}/edit", get(workouts::edit_page))
         .route("/workouts/{id}", post(workouts::update))
         .route("/workouts/{id}/delete", post(workouts::delete))
         .route("/workouts/{id}/logs", post(workouts::add_log))
+        .route(
+            "/workouts/{id}/quick-entry",
+            post(workouts::quick_entry_preview),
+        )
+        .route(
+            "/workouts/{id}/quick-entry/add",
+            post(workouts::quick_entry_add),
+        )
         .route(
             "/workouts/{id}/logs/{log_id}/delete",
             post(workouts::delete_log),
@@ -70,22 +94,118 @@ pub fn create_router(state: AppState) -> Router {
             get(workouts::edit_log_page),
         )
         .route("/workouts/{id}/logs/{log_id}", post(workouts::update_log))
+        .route("/workouts/{id}/comments", post(coaching::add_comment))
+        .route(
+            "/workouts/{id}/comments/{comment_id}/delete",
+            post(coaching::delete_comment),
+        )
+        .route(
+            "/workouts/{id}/exercises/{exercise_id}/sets",
+            get(set_editor::page),
+        )
+        .route(
+            "/workouts/{id}/exercises/{exercise_id}/sets",
+            post(set_editor::add_sets),
+        )
+        .route(
+            "/workouts/{id}/exercises/{exercise_id}/sets/reorder",
+            post(set_editor::reorder),
+        )
+        .route(
+            "/workouts/{id}/exercises/{exercise_id}/sets/move",
+            post(set_editor::move_sets),
+        )
+        .route(
+            "/workouts/{id}/exercises/{exercise_id}/sets/{log_id}/shift",
+            post(set_editor::shift),
+        )
+        .route("/workouts/{id}/export", get(workouts::export))
         .route("/workouts/{id}/share", post(workouts::share_workout))
         .route("/workouts/{id}/revoke-share", post(workouts::revoke_share))
-        // Public shared workout route (no auth required)
+        // Public shared workout, block, year review and profile pages (no
+        // auth required)
         .route("/shared/{token}", get(workouts::view_shared))
+        .route("/shared/{token}/card.png", get(workouts::shared_card))
+        .route("/shared/year/{token}", get(year_review::view_shared))
+        .route("/shared/block/{token}", get(block_shares::view_shared))
+        .route("/shared/profile/{token}", get(profile::view_shared))
+        .route(
+            "/shared/{token}/exercise/{exercise_id}/chart.svg",
+            get(chart_images::shared_svg),
+        )
+        .route(
+            "/shared/{token}/exercise/{exercise_id}/chart.png",
+            get(chart_images::shared_png),
+        )
         .route("/exercises", get(exercises::list))
         .route("/exercises/new", get(exercises::new_page))
         .route("/exercises", post(exercises::create))
         .route("/exercises/{id}/edit", get(exercises::edit_page))
         .route("/exercises/{id}", post(exercises::update))
         .route("/exercises/{id}/delete", post(exercises::delete))
+        .route("/exercises/{id}/hide", post(exercises::hide))
+        .route("/exercises/{id}/unhide", post(exercises::unhide))
+        .route("/exercises/{id}/fork", post(exercises::fork))
+        .route("/exercises/{id}/archive", post(exercises::archive))
+        .route("/exercises/{id}/unarchive", post(exercises::unarchive))
+        .route("/exercises/{id}/merge", post(exercises::merge))
+        .route("/exercises/catalog", get(catalog::index))
+        .route("/exercises/catalog", post(catalog::import))
+        .route("/exercises/categories", get(categories::list))
+        .route("/exercises/categories", post(categories::create))
+        .route("/exercises/categories/{name}", post(categories::rename))
+        .route(
+            "/exercises/categories/{name}/move",
+            post(categories::move_category),
+        )
+        .route(
+            "/exercises/categories/{name}/delete",
+            post(categories::delete),
+        )
         .route("/stats", get(stats::index))
         .route("/stats/exercise/{id}", get(stats::exercise_stats))
+        .route(
+            "/stats/exercise/{id}/chart.svg",
+            get(chart_images::exercise_svg),
+        )
+        .route(
+            "/stats/exercise/{id}/chart.png",
+            get(chart_images::exercise_png),
+        )
         .route("/stats/prs", get(stats::prs_list))
+        .route("/stats/progress", get(stats::progress))
+        .route("/stats/muscles", get(stats::muscle_volume))
+        .route("/stats/tags", get(stats::tag_volume))
+        .route("/stats/year", get(year_review::index))
+        .route("/stats/year/{year}/share", post(year_review::share))
+        .route(
+            "/stats/year/{year}/revoke-share",
+            post(year_review::revoke_share),
+        )
+        .route("/stats/muscles/targets", post(stats::update_muscle_targets))
+        .route("/reports/{kind}", get(reports::show))
+        .route("/reports/{kind}/download", get(reports::download))
         .route("/settings", get(settings::index))
         .route("/settings/password", post(settings::change_password))
+        .route("/settings/training", post(settings::update_training))
         .route("/settings/logout-others", post(settings::logout_others))
+        .route("/settings/profile", get(profile::index))
+        .route("/settings/profile", post(profile::update))
+        .route("/settings/profile/share", post(profile::share))
+        .route(
+            "/settings/profile/revoke-share",
+            post(profile::revoke_share),
+        )
+        .route("/settings/coaches", get(coaching::coaches))
+        .route("/settings/coaches", post(coaching::grant))
+        .route(
+            "/settings/coaches/{coach_id}/revoke",
+            post(coaching::revoke),
+        )
+        .route("/coaching", get(coaching::athletes))
+        .route("/coaching/workouts/{id}", get(coaching::workout))
+        .route("/coaching/{athlete_id}", get(coaching::athlete))
+        .route("/coaching/{athlete_id}/program", post(coaching::program))
         .with_state(state)
         // Sliding session: validate cookie, slide expiry, re-issue Set-Cookie on touch
         .layer(from_fn_with_state(
@@ -0,0 +1,151 @@
+//! Preview card for a shared workout
+//!
+//! What a chat app shows when a `/shared/{token}` link is pasted: the date,
+//! the owner, and each exercise's top set with a PR badge where it earned
+//! one. Drawn as SVG at the 1200×630 that Open Graph and Twitter crop
+//! previews to, and rasterised through `chart_image::PngCache`.
+
+use crate::models::WorkoutLogWithExercise;
+use crate::workout_text::group_by_exercise;
+
+pub const CARD_WIDTH: u32 = 1200;
+pub const CARD_HEIGHT: u32 = 630;
+/// Exercises listed on the card; any more are counted instead.
+pub const MAX_LINES: usize = 6;
+/// Longer exercise names are cut short to leave room for the top set.
+const MAX_NAME_CHARS: usize = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardLine {
+    pub exercise_name: String,
+    /// The heaviest set, e.g. "140 kg × 5".
+    pub top_set: String,
+    /// "PR" or "PR 1M" when any of the exercise's sets is one.
+    pub badge: Option<&'static str>,
+}
+
+/// One line per exercise in the order first trained, with its heaviest set
+/// (most reps breaking a tie). Unilateral sets count their heavier side.
+pub fn card_lines(logs: &[WorkoutLogWithExercise]) -> Vec<CardLine> {
+    group_by_exercise(logs)
+        .into_iter()
+        .map(|sets| {
+            let heaviest = |log: &&WorkoutLogWithExercise| {
+                (
+                    log.weight.max(log.right_weight.unwrap_or(f64::MIN)),
+                    log.reps.max(log.right_reps.unwrap_or(i32::MIN)),
+                )
+            };
+            let top = sets
+                .iter()
+                .max_by(|a, b| {
+                    heaviest(a)
+                        .partial_cmp(&heaviest(b))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .expect("groups are never empty");
+            let badge = if sets.iter().any(|log| log.is_pr) {
+                Some("PR")
+            } else if sets.iter().any(|log| log.is_recent_pr) {
+                Some("PR 1M")
+            } else {
+                None
+            };
+            CardLine {
+                exercise_name: shorten(&top.exercise_name),
+                top_set: format!("{} kg × {}", top.weight_text(), top.reps_text()),
+                badge,
+            }
+        })
+        .collect()
+}
+
+/// The one-line description beside the card, e.g. "3 exercises, 12 sets".
+pub fn summary(logs: &[WorkoutLogWithExercise]) -> String {
+    if logs.is_empty() {
+        return "No sets recorded".to_string();
+    }
+    let plural =
+        |n: usize, one: &str, many: &str| format!("{n} {}", if n == 1 { one } else { many });
+    format!(
+        "{}, {}",
+        plural(group_by_exercise(logs).len(), "exercise", "exercises"),
+        plural(logs.len(), "set", "sets")
+    )
+}
+
+fn shorten(name: &str) -> String {
+    if name.chars().count() <= MAX_NAME_CHARS {
+        return name.to_string();
+    }
+    let mut short: String = name.chars().take(MAX_NAME_CHARS - 1).collect();
+    short.push('…');
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(exercise: &str, set_number: i32, reps: i32, weight: f64) -> WorkoutLogWithExercise {
+        WorkoutLogWithExercise {
+            id: format!("{exercise}-{set_number}"),
+            session_id: "s".to_string(),
+            exercise_id: exercise.to_lowercase(),
+            exercise_name: exercise.to_string(),
+            set_number,
+            reps,
+            weight,
+            rpe: None,
+            right_reps: None,
+            right_weight: None,
+            notes: None,
+            tempo: None,
+            rir: None,
+            is_pr: false,
+            is_recent_pr: false,
+        }
+    }
+
+    #[test]
+    fn lists_the_heaviest_set_per_exercise_with_badges() {
+        // Newest first, as the repository returns them.
+        let mut bench = log("Bench", 1, 8, 80.0);
+        bench.is_recent_pr = true;
+        let mut heavy = log("Squat", 3, 3, 150.0);
+        heavy.is_pr = true;
+        let logs = vec![
+            bench,
+            log("Squat", 4, 5, 150.0),
+            heavy,
+            log("Squat", 2, 5, 140.0),
+            log("Squat", 1, 5, 100.0),
+        ];
+
+        assert_eq!(
+            card_lines(&logs),
+            vec![
+                CardLine {
+                    exercise_name: "Squat".to_string(),
+                    top_set: "150 kg × 5".to_string(),
+                    badge: Some("PR"),
+                },
+                CardLine {
+                    exercise_name: "Bench".to_string(),
+                    top_set: "80 kg × 8".to_string(),
+                    badge: Some("PR 1M"),
+                },
+            ]
+        );
+        assert_eq!(summary(&logs), "2 exercises, 5 sets");
+        assert_eq!(summary(&[]), "No sets recorded");
+    }
+
+    #[test]
+    fn long_names_are_shortened() {
+        let name = "Single-Arm Half-Kneeling Landmine Press";
+        let lines = card_lines(&[log(name, 1, 10, 20.0)]);
+        assert_eq!(lines[0].exercise_name.chars().count(), MAX_NAME_CHARS);
+        assert!(lines[0].exercise_name.ends_with('…'));
+    }
+}
@@ -1,17 +1,23 @@
 use std::net::IpAddr;
 use std::sync::Arc;
 
+use crate::chart_image::PngCache;
 use crate::config::TrustedProxyHeader;
 use crate::rate_limit::{FailureBackoff, RateLimiter};
 use crate::repositories::{
-    ExerciseRepository, SessionRepository, UserRepository, WorkoutRepository,
+    CategoryRepository, CommentRepository, ExerciseRepository, SessionRepository, ShareRepository,
+    TagRepository, UserRepository, WorkoutRepository,
 };
 
 #[derive(Clone)]
 pub struct AppState {
     pub user_repo: UserRepository,
     pub exercise_repo: ExerciseRepository,
+    pub category_repo: CategoryRepository,
+    pub tag_repo: TagRepository,
     pub workout_repo: WorkoutRepository,
+    pub share_repo: ShareRepository,
+    pub comment_repo: CommentRepository,
     pub session_repo: SessionRepository,
     /// Throttles `POST /auth/login`, keyed by client IP — the request is
     /// anonymous, so the source address is the only identity available.
@@ -57,9 +63,16 @@ pub struct AppState {
     /// Whether the `Strict-Transport-Security` header, when enabled, also
     /// carries `includeSubDomains`.
     pub hsts_include_subdomains: bool,
+    /// `scheme://host` the site is served at, for absolute URLs such as a
+    /// shared workout's link preview. `None` falls back to the request's
+    /// `Host` header.
+    pub public_url: Option<Arc<str>>,
     /// Per-process random salt for `session_fp` in the audit log. Regenerated
     /// on every restart: events correlate within one process lifetime, not
     /// across restarts. OWASP only requires that the raw token never be
     /// logged, which a per-process salt satisfies with zero configuration.
     pub log_salt: Arc<[u8; 32]>,
+    /// Rendered preview cards and chart PNGs of shared workouts, which
+    /// anyone with the link may fetch again and again.
+    pub share_card_cache: Arc<PngCache>,
 }
@@ -0,0 +1,366 @@
+//! Plain-text workout import
+//!
+//! Turns workout logs kept in note apps into sessions. Dates start sessions,
+//! whether on their own line or as a Markdown heading, and the text after a
+//! date becomes the session's notes. Everything else is read as sets in the
+//! quick-entry notation (see `quick_entry`), one or more exercises per line
+//! separated by `/` or `;`:
+//!
+//! ```text
+//! # 2024-01-15 Leg day
+//! - Squat 3x5 140 / Bench 5x5 90
+//! - Row 80x8,8,7
+//!
+//! Jan 17, 2024
+//! Deadlift: 180x5
+//! ```
+//!
+//! Bullets and numbering are ignored. A heading without a date names the
+//! next session that has no notes of its own. Lines that are neither, and
+//! dates outside `calendar::YEARS` along with their sets, are flagged for
+//! the preview and skipped on import.
+//!
+//! One upload is capped at `MAX_TEXT_BYTES` of text, `MAX_SESSIONS`
+//! sessions and `MAX_SETS` sets; larger logs are imported in parts.
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::calendar::YEARS;
+use crate::models::Exercise;
+use crate::quick_entry::{self, LineError, QuickSet};
+
+/// Formats tried for a date, after commas and a leading weekday are removed.
+/// Day/month order is only guessed where the month is spelled out.
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d", "%d.%m.%Y", "%b %d %Y", "%B %d %Y", "%d %b %Y", "%d %B %Y",
+];
+
+/// Longest text accepted, in bytes.
+pub const MAX_TEXT_BYTES: usize = 256 * 1024;
+
+/// Most sessions one import may create.
+pub const MAX_SESSIONS: usize = 500;
+
+/// Most sets one import may create.
+pub const MAX_SETS: usize = 5000;
+
+/// One session found in the text.
+#[derive(Debug, Clone)]
+pub struct ImportedSession {
+    pub date: NaiveDate,
+    pub notes: Option<String>,
+    pub sets: Vec<QuickSet>,
+}
+
+#[derive(Debug, Default)]
+pub struct TextImport {
+    /// Sessions with at least one set, in the order they appear.
+    pub sessions: Vec<ImportedSession>,
+    pub errors: Vec<LineError>,
+}
+
+impl TextImport {
+    pub fn set_count(&self) -> usize {
+        self.sessions.iter().map(|s| s.sets.len()).sum()
+    }
+
+    /// Why this import is too big to preview or create, if it is.
+    pub fn limit_error(&self) -> Option<String> {
+        if self.sessions.len() > MAX_SESSIONS {
+            Some(format!(
+                "At most {MAX_SESSIONS} workouts can be imported at once"
+            ))
+        } else if self.set_count() > MAX_SETS {
+            Some(format!("At most {MAX_SETS} sets can be imported at once"))
+        } else {
+            None
+        }
+    }
+}
+
+/// Parse `input` against the user's `exercises`.
+pub fn parse(input: &str, exercises: &[Exercise]) -> TextImport {
+    let mut import = TextImport::default();
+    let mut session: Option<ImportedSession> = None;
+    let mut pending_title: Option<String> = None;
+    let mut current: Option<&Exercise> = None;
+
+    for (index, raw) in input.lines().enumerate() {
+        let text = strip_list_marker(raw.trim());
+        if text.is_empty() {
+            continue;
+        }
+        let heading = text.starts_with('#');
+        let text = text.trim_start_matches('#').trim();
+
+        if let Some((date, rest)) = parse_date_prefix(text) {
+            import
+                .sessions
+                .extend(session.take().filter(|s| !s.sets.is_empty()));
+            if !YEARS.contains(&date.year()) {
+                import.errors.push(LineError {
+                    line: index + 1,
+                    text: raw.trim().to_string(),
+                    message: format!(
+                        "Dates must fall in the years {} to {}",
+                        YEARS.start(),
+                        YEARS.end()
+                    ),
+                });
+                pending_title = None;
+                current = None;
+                continue;
+            }
+            let notes = (!rest.is_empty())
+                .then_some(rest)
+                .or_else(|| pending_title.take());
+            session = Some(ImportedSession {
+                date,
+                notes,
+                sets: Vec::new(),
+            });
+            current = None;
+            continue;
+        }
+        if heading {
+            match session.as_mut() {
+                Some(s) if s.sets.is_empty() && s.notes.is_none() => {
+                    s.notes = Some(text.to_string());
+                }
+                _ => pending_title = Some(text.to_string()),
+            }
+            continue;
+        }
+
+        let mut fail = |message: String| {
+            import.errors.push(LineError {
+                line: index + 1,
+                text: raw.trim().to_string(),
+                message,
+            });
+        };
+        let Some(session) = session.as_mut() else {
+            fail("Sets without a date; add a date line above them".to_string());
+            continue;
+        };
+        for segment in text
+            .split(['/', ';'])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let line = match quick_entry::parse_line(segment) {
+                Ok(line) => line,
+                Err(message) => {
+                    fail(message);
+                    break;
+                }
+            };
+            if let Some(name) = &line.name {
+                current = quick_entry::match_exercise(name, exercises);
+                if current.is_none() {
+                    fail(format!("No exercise matches \"{name}\""));
+                    break;
+                }
+            }
+            let Some(exercise) = current else {
+                fail("Start the line with an exercise name".to_string());
+                break;
+            };
+            session.sets.extend(
+                line.sets
+                    .iter()
+                    .map(|set| QuickSet::from_parsed(exercise, set)),
+            );
+        }
+    }
+    import
+        .sessions
+        .extend(session.filter(|s| !s.sets.is_empty()));
+    import
+}
+
+/// Drop a Markdown bullet or list number: `- `, `* `, `+ `, `1. `, `1) `.
+fn strip_list_marker(text: &str) -> &str {
+    if let Some(rest) = text
+        .strip_prefix("- ")
+        .or_else(|| text.strip_prefix("* "))
+        .or_else(|| text.strip_prefix("+ "))
+    {
+        return rest.trim_start();
+    }
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0
+        && digits < 3
+        && let Some(rest) = text[digits..]
+            .strip_prefix(". ")
+            .or_else(|| text[digits..].strip_prefix(") "))
+    {
+        return rest.trim_start();
+    }
+    text
+}
+
+/// Parse a date at the start of `text`, returning it with the rest of the
+/// line (stripped of a separating dash or colon).
+fn parse_date_prefix(text: &str) -> Option<(NaiveDate, String)> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let skip = usize::from(words.first().is_some_and(|w| {
+        w.trim_end_matches([',', '.'])
+            .parse::<chrono::Weekday>()
+            .is_ok()
+    }));
+    for len in (1..=3).rev() {
+        let Some(candidate) = words.get(skip..skip + len) else {
+            continue;
+        };
+        let candidate = candidate.join(" ").replace(',', "");
+        let candidate = candidate.trim_end_matches([':', '.']);
+        let Some(date) = DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(candidate, format).ok())
+        else {
+            continue;
+        };
+        let rest = words[skip + len..]
+            .join(" ")
+            .trim_start_matches(['-', '–', ':', '|'])
+            .trim()
+            .to_string();
+        return Some((date, rest));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(id: &str, name: &str) -> Exercise {
+        Exercise {
+            id: id.to_string(),
+            name: name.to_string(),
+            category: "legs".to_string(),
+            user_id: Some("user1".to_string()),
+            catalog_id: None,
+            archived_at: None,
+            unilateral: false,
+        }
+    }
+
+    fn exercises() -> Vec<Exercise> {
+        vec![
+            exercise("squat", "Squat"),
+            exercise("bench", "Bench Press"),
+            exercise("row", "Barbell Row"),
+        ]
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp, reason = "exact-value test assertion")]
+    fn parses_dated_sessions_with_headings_and_bullets() {
+        let text = "# 2024-01-15 Leg day\n\
+                    - Squat 3x5 140 / Bench 5x5 90\n\
+                    - row 80x8,8,7\n\
+                    \n\
+                    Wed, Jan 17, 2024\n\
+                    1. squat: 150x3";
+        let import = parse(text, &exercises());
+        assert!(import.errors.is_empty(), "{:?}", import.errors);
+        assert_eq!(import.sessions.len(), 2);
+
+        let first = &import.sessions[0];
+        assert_eq!(first.date, date(2024, 1, 15));
+        assert_eq!(first.notes.as_deref(), Some("Leg day"));
+        assert_eq!(first.sets.len(), 11);
+
+        let second = &import.sessions[1];
+        assert_eq!(second.date, date(2024, 1, 17));
+        assert_eq!(second.notes, None);
+        assert_eq!(second.sets[0].weight, 150.0);
+        assert_eq!(import.set_count(), 12);
+    }
+
+    #[test]
+    fn heading_without_a_date_names_the_session() {
+        let import = parse("## Push A\n15 March 2024\nbench 100x5", &exercises());
+        assert_eq!(import.sessions[0].date, date(2024, 3, 15));
+        assert_eq!(import.sessions[0].notes.as_deref(), Some("Push A"));
+
+        let import = parse("2024-03-15\n## Push A\nbench 100x5", &exercises());
+        assert_eq!(import.sessions[0].notes.as_deref(), Some("Push A"));
+    }
+
+    #[test]
+    fn flags_unparseable_lines_and_skips_empty_sessions() {
+        let text = "bench 100x5\n\
+                    2024-01-01\n\
+                    2024-01-02\n\
+                    felt great today\n\
+                    deadlift 200x5\n\
+                    squat 100x5";
+        let import = parse(text, &exercises());
+        assert_eq!(import.sessions.len(), 1);
+        assert_eq!(import.sessions[0].date, date(2024, 1, 2));
+        let lines: Vec<usize> = import.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn flags_dates_outside_the_supported_years_with_their_sets() {
+        let text = "0003-01-01\n\
+                    squat 100x5\n\
+                    2024-01-01\n\
+                    squat 110x5\n\
+                    +12345-01-01\n\
+                    squat 120x5";
+        let import = parse(text, &exercises());
+        assert_eq!(import.sessions.len(), 1);
+        assert_eq!(import.sessions[0].date, date(2024, 1, 1));
+        assert_eq!(import.sessions[0].sets.len(), 1);
+        let lines: Vec<usize> = import.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![1, 2, 5, 6]);
+        assert!(import.errors[0].message.contains("1900 to 9999"));
+    }
+
+    #[test]
+    fn date_prefix_formats() {
+        assert_eq!(
+            parse_date_prefix("2024/02/29"),
+            Some((date(2024, 2, 29), String::new()))
+        );
+        assert_eq!(
+            parse_date_prefix("29.02.2024 - Pull"),
+            Some((date(2024, 2, 29), "Pull".to_string()))
+        );
+        assert_eq!(
+            parse_date_prefix("Thursday February 29, 2024: heavy"),
+            Some((date(2024, 2, 29), "heavy".to_string()))
+        );
+        assert_eq!(parse_date_prefix("100x5"), None);
+        assert_eq!(parse_date_prefix("Squat 3x5 140"), None);
+    }
+
+    #[test]
+    fn imports_over_the_limits_are_refused() {
+        let text = (0..=MAX_SESSIONS as u64)
+            .map(|day| format!("{}\nsquat 100x5", date(2020, 1, 1) + chrono::Days::new(day)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let import = parse(&text, &exercises());
+        assert!(import.limit_error().unwrap().contains("workouts"));
+
+        let text = format!(
+            "2024-01-01\n{}",
+            "squat 100x5x20\n".repeat(MAX_SETS / 20 + 1)
+        );
+        let import = parse(&text, &exercises());
+        assert!(import.limit_error().unwrap().contains("sets"));
+
+        let import = parse("2024-01-01\nsquat 100x5x20", &exercises());
+        assert!(import.limit_error().is_none());
+    }
+}
@@ -0,0 +1,277 @@
+//! Side-by-side comparison of two workouts
+//!
+//! Exercises are matched by id, and sets by their position within the
+//! exercise (the first set against the first set). Every difference is the
+//! second workout minus the first, so "up" means the second workout did
+//! more.
+
+use crate::models::{WorkoutLogWithExercise, estimated_one_rep_max};
+
+/// One workout's side of a set row.
+#[derive(Debug, Clone)]
+pub struct SetSide {
+    pub weight_text: String,
+    pub reps_text: String,
+    /// Heavier side of a unilateral set.
+    pub weight: f64,
+    pub reps: i32,
+    /// Both sides of a unilateral set.
+    pub volume: f64,
+    /// Better side of a unilateral set.
+    pub e1rm: f64,
+}
+
+impl SetSide {
+    fn from_log(log: &WorkoutLogWithExercise) -> Self {
+        let mut weight = log.weight;
+        let mut reps = log.reps;
+        let mut volume = log.weight * f64::from(log.reps);
+        let mut e1rm = estimated_one_rep_max(log.weight, log.reps);
+        if let (Some(right_weight), Some(right_reps)) = (log.right_weight, log.right_reps) {
+            volume += right_weight * f64::from(right_reps);
+            e1rm = e1rm.max(estimated_one_rep_max(right_weight, right_reps));
+            if (right_weight, right_reps) > (weight, reps) {
+                (weight, reps) = (right_weight, right_reps);
+            }
+        }
+        Self {
+            weight_text: log.weight_text(),
+            reps_text: log.reps_text(),
+            weight,
+            reps,
+            volume,
+            e1rm,
+        }
+    }
+}
+
+/// A difference with its up/down indicator, ready for display.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Delta {
+    /// e.g. "▲ 5 kg", "▼ 2", "=", or blank when one side is missing.
+    pub text: String,
+    /// `change-up`, `change-down` or empty.
+    pub css_class: &'static str,
+}
+
+impl Delta {
+    /// `diff` rounded to `decimals`, with `unit` appended when non-empty.
+    fn new(diff: f64, decimals: usize, unit: &str) -> Self {
+        let scale = 10_f64.powi(i32::try_from(decimals).unwrap_or(0));
+        let diff = (diff * scale).round() / scale;
+        let unit = if unit.is_empty() {
+            String::new()
+        } else {
+            format!(" {unit}")
+        };
+        if diff > 0.0 {
+            Self {
+                text: format!("\u{25b2} {diff}{unit}"),
+                css_class: "change-up",
+            }
+        } else if diff < 0.0 {
+            Self {
+                text: format!("\u{25bc} {}{unit}", -diff),
+                css_class: "change-down",
+            }
+        } else {
+            Self {
+                text: "=".to_string(),
+                css_class: "",
+            }
+        }
+    }
+
+    fn between(a: Option<f64>, b: Option<f64>, decimals: usize, unit: &str) -> Self {
+        match (a, b) {
+            (Some(a), Some(b)) => Self::new(b - a, decimals, unit),
+            _ => Self::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetComparison {
+    /// 1-based position within the exercise.
+    pub position: usize,
+    pub a: Option<SetSide>,
+    pub b: Option<SetSide>,
+    pub weight: Delta,
+    pub reps: Delta,
+    pub volume: Delta,
+    pub e1rm: Delta,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExerciseComparison {
+    pub exercise_id: String,
+    pub exercise_name: String,
+    pub sets: Vec<SetComparison>,
+    pub volume_a: f64,
+    pub volume_b: f64,
+    pub volume: Delta,
+    /// Best set e1RM of each workout, compared.
+    pub best_e1rm: Delta,
+}
+
+/// The logs of one exercise, in set order.
+struct ExerciseSets<'a> {
+    id: &'a str,
+    name: &'a str,
+    sets: Vec<&'a WorkoutLogWithExercise>,
+}
+
+/// Group `logs` by exercise, exercises in the order they were started.
+fn by_exercise(logs: &[WorkoutLogWithExercise]) -> Vec<ExerciseSets<'_>> {
+    let mut groups: Vec<ExerciseSets> = Vec::new();
+    // Sessions list their newest sets first.
+    for log in logs.iter().rev() {
+        match groups.iter_mut().find(|g| g.id == log.exercise_id) {
+            Some(group) => group.sets.push(log),
+            None => groups.push(ExerciseSets {
+                id: &log.exercise_id,
+                name: &log.exercise_name,
+                sets: vec![log],
+            }),
+        }
+    }
+    for group in &mut groups {
+        group.sets.sort_by_key(|log| log.set_number);
+    }
+    groups
+}
+
+/// Line up workout `a` against workout `b`: `a`'s exercises first, in
+/// `a`'s order, then any only in `b`.
+pub fn compare_workouts(
+    a: &[WorkoutLogWithExercise],
+    b: &[WorkoutLogWithExercise],
+) -> Vec<ExerciseComparison> {
+    let a = by_exercise(a);
+    let b = by_exercise(b);
+    let mut ids: Vec<(&str, &str)> = a.iter().map(|g| (g.id, g.name)).collect();
+    for group in &b {
+        if !ids.iter().any(|(id, _)| *id == group.id) {
+            ids.push((group.id, group.name));
+        }
+    }
+
+    ids.into_iter()
+        .map(|(id, name)| {
+            let sides = |groups: &[ExerciseSets]| -> Vec<SetSide> {
+                groups
+                    .iter()
+                    .find(|g| g.id == id)
+                    .map(|g| g.sets.iter().map(|log| SetSide::from_log(log)).collect())
+                    .unwrap_or_default()
+            };
+            let (a_sets, b_sets) = (sides(&a), sides(&b));
+            let volume_a: f64 = a_sets.iter().map(|s| s.volume).sum();
+            let volume_b: f64 = b_sets.iter().map(|s| s.volume).sum();
+            let best = |sets: &[SetSide]| sets.iter().map(|s| s.e1rm).reduce(f64::max);
+            let best_e1rm = Delta::between(best(&a_sets), best(&b_sets), 1, "kg");
+
+            let rows = a_sets.len().max(b_sets.len());
+            let sets = (0..rows)
+                .map(|i| {
+                    let (a, b) = (a_sets.get(i).cloned(), b_sets.get(i).cloned());
+                    let field = |f: fn(&SetSide) -> f64| (a.as_ref().map(f), b.as_ref().map(f));
+                    let (wa, wb) = field(|s| s.weight);
+                    let (ra, rb) = field(|s| f64::from(s.reps));
+                    let (va, vb) = field(|s| s.volume);
+                    let (ea, eb) = field(|s| s.e1rm);
+                    SetComparison {
+                        position: i + 1,
+                        weight: Delta::between(wa, wb, 2, "kg"),
+                        reps: Delta::between(ra, rb, 0, ""),
+                        volume: Delta::between(va, vb, 0, "kg"),
+                        e1rm: Delta::between(ea, eb, 1, "kg"),
+                        a,
+                        b,
+                    }
+                })
+                .collect();
+
+            ExerciseComparison {
+                exercise_id: id.to_string(),
+                exercise_name: name.to_string(),
+                sets,
+                volume_a,
+                volume_b,
+                volume: Delta::new(volume_b - volume_a, 0, "kg"),
+                best_e1rm,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(exercise: &str, set_number: i32, reps: i32, weight: f64) -> WorkoutLogWithExercise {
+        WorkoutLogWithExercise {
+            id: format!("{exercise}-{set_number}"),
+            session_id: "s".to_string(),
+            exercise_id: exercise.to_string(),
+            exercise_name: exercise.to_string(),
+            set_number,
+            reps,
+            weight,
+            rpe: None,
+            right_reps: None,
+            right_weight: None,
+            notes: None,
+            tempo: None,
+            rir: None,
+            is_pr: false,
+            is_recent_pr: false,
+        }
+    }
+
+    #[test]
+    fn aligns_exercises_and_sets() {
+        // Newest first, as the repository returns them.
+        let a = vec![
+            log("bench", 2, 5, 100.0),
+            log("bench", 1, 5, 100.0),
+            log("squat", 1, 5, 140.0),
+        ];
+        let b = vec![
+            log("row", 1, 10, 60.0),
+            log("bench", 3, 3, 105.0),
+            log("bench", 2, 6, 100.0),
+            log("bench", 1, 5, 102.5),
+        ];
+        let c = compare_workouts(&a, &b);
+        let names: Vec<&str> = c.iter().map(|e| e.exercise_name.as_str()).collect();
+        assert_eq!(names, vec!["squat", "bench", "row"]);
+
+        let bench = &c[1];
+        assert_eq!(bench.sets.len(), 3);
+        assert_eq!(bench.sets[0].weight.text, "\u{25b2} 2.5 kg");
+        assert_eq!(bench.sets[0].reps.text, "=");
+        assert_eq!(bench.sets[1].reps.text, "\u{25b2} 1");
+        assert!(bench.sets[2].a.is_none());
+        assert_eq!(bench.sets[2].weight, Delta::default());
+        // 1000 kg against 512.5 + 600 + 315.
+        assert_eq!(bench.volume.text, "\u{25b2} 428 kg");
+        assert_eq!(bench.volume.css_class, "change-up");
+
+        let squat = &c[0];
+        assert!(squat.sets[0].b.is_none());
+        assert_eq!(squat.volume.text, "\u{25bc} 700 kg");
+        assert_eq!(squat.best_e1rm, Delta::default());
+    }
+
+    #[test]
+    fn unilateral_sets_count_both_sides() {
+        let mut single = log("lunge", 1, 10, 20.0);
+        single.right_reps = Some(8);
+        single.right_weight = Some(22.0);
+        let side = SetSide::from_log(&single);
+        assert!((side.volume - 376.0).abs() < 1e-9);
+        assert!((side.weight - 22.0).abs() < 1e-9);
+        assert_eq!(side.weight_text, "20 / 22");
+    }
+}
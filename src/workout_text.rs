@@ -0,0 +1,322 @@
+//! Plain-text and Markdown renderings of a workout
+//!
+//! A compact summary meant for pasting into a chat or a notes app: one block
+//! per exercise in the order it was first trained, with consecutive identical
+//! sets folded into `weight × reps × sets` (the quick-entry notation), PR
+//! markers, effort, and any tempo or notes.
+//!
+//! ```text
+//! Workout 2024-01-15
+//! Leg day
+//!
+//! Squat
+//!   140 kg × 5 × 3 @8
+//!   150 kg × 3 PR
+//! ```
+
+use std::fmt::Write;
+
+use axum::http::{HeaderMap, header};
+
+use crate::models::{WorkoutLogWithExercise, WorkoutSession};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat {
+    Plain,
+    Markdown,
+}
+
+impl TextFormat {
+    /// Parse a `?format=` value: `txt`/`text` or `md`/`markdown`.
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "txt" | "text" | "plain" => Some(Self::Plain),
+            "md" | "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Pick a text format from an `Accept` header. Anything that accepts
+    /// HTML gets HTML (`None`), so browsers and `*/*` keep the normal page;
+    /// only clients asking for text specifically get text.
+    pub fn from_accept(headers: &HeaderMap) -> Option<Self> {
+        let accept = headers.get(header::ACCEPT)?.to_str().ok()?;
+        let types: Vec<&str> = accept
+            .split(',')
+            .map(|t| t.split(';').next().unwrap_or("").trim())
+            .collect();
+        if types.contains(&"text/html") {
+            None
+        } else if types.contains(&"text/markdown") || types.contains(&"text/x-markdown") {
+            Some(Self::Markdown)
+        } else if types.contains(&"text/plain") {
+            Some(Self::Plain)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Plain => "text/plain; charset=utf-8",
+            Self::Markdown => "text/markdown; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Plain => "txt",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+/// Render `workout` with its `logs` (in any order). `byline` names the
+/// owner on shared copies.
+pub fn render(
+    workout: &WorkoutSession,
+    logs: &[WorkoutLogWithExercise],
+    byline: Option<&str>,
+    format: TextFormat,
+) -> String {
+    let md = format == TextFormat::Markdown;
+    let mut lines = Vec::new();
+
+    let mut title = format!("Workout {}", workout.date);
+    if let Some(owner) = byline {
+        title.push_str(" by ");
+        title.push_str(&text(owner, md));
+    }
+    lines.push(if md { format!("# {title}") } else { title });
+    if let Some(notes) = workout.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        if md {
+            lines.push(String::new());
+        }
+        lines.push(text(notes.trim(), md));
+    }
+
+    for group in group_by_exercise(logs) {
+        lines.push(String::new());
+        let name = text(&group[0].exercise_name, md);
+        lines.push(if md { format!("### {name}") } else { name });
+        let mut i = 0;
+        while i < group.len() {
+            let set = group[i];
+            let mut count = 1;
+            while group.get(i + count).is_some_and(|next| same_set(set, next)) {
+                count += 1;
+            }
+            let line = set_line(set, count, md);
+            lines.push(if md {
+                format!("- {line}")
+            } else {
+                format!("  {line}")
+            });
+            i += count;
+        }
+    }
+
+    if logs.is_empty() {
+        lines.push(String::new());
+        lines.push("No sets recorded.".to_string());
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Logs grouped per exercise, exercises in the order first trained and sets
+/// by set number.
+pub fn group_by_exercise(logs: &[WorkoutLogWithExercise]) -> Vec<Vec<&WorkoutLogWithExercise>> {
+    // Logs arrive newest first; walk them oldest first.
+    let mut groups: Vec<Vec<&WorkoutLogWithExercise>> = Vec::new();
+    for log in logs.iter().rev() {
+        match groups
+            .iter_mut()
+            .find(|g| g[0].exercise_id == log.exercise_id)
+        {
+            Some(group) => group.push(log),
+            None => groups.push(vec![log]),
+        }
+    }
+    for group in &mut groups {
+        group.sort_by_key(|log| log.set_number);
+    }
+    groups
+}
+
+/// Two sets fold together when nothing shown about them differs.
+fn same_set(a: &WorkoutLogWithExercise, b: &WorkoutLogWithExercise) -> bool {
+    a.weight_text() == b.weight_text()
+        && a.reps_text() == b.reps_text()
+        && a.rpe == b.rpe
+        && a.rir == b.rir
+        && a.is_pr == b.is_pr
+        && a.is_recent_pr == b.is_recent_pr
+        && a.annotation_text().is_none()
+        && b.annotation_text().is_none()
+}
+
+fn set_line(set: &WorkoutLogWithExercise, count: usize, md: bool) -> String {
+    let mut line = format!("{} kg × {}", set.weight_text(), set.reps_text());
+    if count > 1 {
+        let _ = write!(line, " × {count}");
+    }
+    match (set.rpe, set.rir) {
+        (Some(rpe), _) => {
+            let _ = write!(line, " @{rpe}");
+        }
+        (None, Some(rir)) => {
+            let _ = write!(line, " ({rir} RIR)");
+        }
+        (None, None) => {}
+    }
+    let pr = if set.is_pr {
+        Some("PR")
+    } else if set.is_recent_pr {
+        Some("PR 1M")
+    } else {
+        None
+    };
+    if let Some(pr) = pr {
+        let _ = if md {
+            write!(line, " **{pr}**")
+        } else {
+            write!(line, " {pr}")
+        };
+    }
+    if let Some(note) = set.annotation_text() {
+        line.push_str(" — ");
+        line.push_str(&text(&note, md));
+    }
+    line
+}
+
+/// User-entered text, with Markdown's inline syntax escaped when needed.
+fn text(value: &str, md: bool) -> String {
+    if !md {
+        return value.to_string();
+    }
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Utc};
+
+    fn session(notes: Option<&str>) -> WorkoutSession {
+        WorkoutSession {
+            id: "s1".to_string(),
+            user_id: "user1".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            notes: notes.map(str::to_string),
+            share_token: None,
+            share_expires_at: None,
+            share_history: false,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn log(exercise: &str, set_number: i32, weight: f64, reps: i32) -> WorkoutLogWithExercise {
+        WorkoutLogWithExercise {
+            id: format!("{exercise}-{set_number}"),
+            session_id: "s1".to_string(),
+            exercise_id: exercise.to_lowercase(),
+            exercise_name: exercise.to_string(),
+            set_number,
+            reps,
+            weight,
+            rpe: None,
+            right_reps: None,
+            right_weight: None,
+            notes: None,
+            tempo: None,
+            rir: None,
+            is_pr: false,
+            is_recent_pr: false,
+        }
+    }
+
+    /// Logs as the repository returns them: newest first.
+    fn logs() -> Vec<WorkoutLogWithExercise> {
+        let mut top = log("Squat", 4, 150.0, 3);
+        top.is_pr = true;
+        let mut bench = log("Bench", 1, 90.0, 5);
+        bench.rir = Some(2);
+        bench.tempo = Some("3-1-1-0".to_string());
+        bench.notes = Some("paused *hard*".to_string());
+        let mut squats: Vec<_> = (1..=3)
+            .map(|n| {
+                let mut set = log("Squat", n, 140.0, 5);
+                set.rpe = Some(8);
+                set
+            })
+            .collect();
+        squats.reverse();
+        let mut all = vec![bench, top];
+        all.extend(squats);
+        all
+    }
+
+    #[test]
+    fn plain_text_folds_identical_sets_in_training_order() {
+        let text = render(&session(Some("Leg day")), &logs(), None, TextFormat::Plain);
+        assert_eq!(
+            text,
+            "Workout 2024-01-15\n\
+             Leg day\n\
+             \n\
+             Squat\n  \
+             140 kg × 5 × 3 @8\n  \
+             150 kg × 3 PR\n\
+             \n\
+             Bench\n  \
+             90 kg × 5 (2 RIR) — Tempo 3-1-1-0 · paused *hard*\n"
+        );
+    }
+
+    #[test]
+    fn markdown_uses_headings_lists_and_escapes_user_text() {
+        let text = render(
+            &session(None),
+            &logs(),
+            Some("lifter_1"),
+            TextFormat::Markdown,
+        );
+        assert!(text.starts_with("# Workout 2024-01-15 by lifter\\_1\n"));
+        assert!(text.contains("### Squat\n- 140 kg × 5 × 3 @8\n- 150 kg × 3 **PR**\n"));
+        assert!(text.contains("paused \\*hard\\*"));
+    }
+
+    #[test]
+    fn empty_workout_says_so() {
+        let text = render(&session(None), &[], None, TextFormat::Plain);
+        assert_eq!(text, "Workout 2024-01-15\n\nNo sets recorded.\n");
+    }
+
+    #[test]
+    fn negotiates_only_explicit_text_requests() {
+        let accept = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT, value.parse().unwrap());
+            TextFormat::from_accept(&headers)
+        };
+        assert_eq!(accept("text/markdown"), Some(TextFormat::Markdown));
+        assert_eq!(accept("text/plain;q=0.9"), Some(TextFormat::Plain));
+        assert_eq!(accept("text/html,application/xhtml+xml,*/*;q=0.8"), None);
+        assert_eq!(accept("*/*"), None);
+        assert_eq!(TextFormat::from_accept(&HeaderMap::new()), None);
+        assert_eq!(TextFormat::from_param("MD"), Some(TextFormat::Markdown));
+        assert_eq!(TextFormat::from_param("pdf"), None);
+    }
+}
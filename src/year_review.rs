@@ -0,0 +1,215 @@
+//! Year in review: a recap of one calendar year of training
+//!
+//! Built from the same `WorkoutRepository` period aggregates as the weekly
+//! and monthly reports, over 1 January to 31 December. Streaks use the
+//! user's current weekly target, as on the stats page.
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+
+use crate::calendar::YEARS;
+use crate::consistency::longest_streak;
+use crate::models::{
+    ExercisePeriodTotals, MonthlyTotals, PeriodSummary, PrGain, WeeklySessionCount,
+};
+
+/// PR gains listed in a review.
+pub const PR_GAINS_SHOWN: usize = 10;
+
+/// First and last day of `year`, or `None` outside `calendar::YEARS`.
+pub fn year_range(year: i32) -> Option<(NaiveDate, NaiveDate)> {
+    if !YEARS.contains(&year) {
+        return None;
+    }
+    Some((
+        NaiveDate::from_ymd_opt(year, 1, 1)?,
+        NaiveDate::from_ymd_opt(year, 12, 31)?,
+    ))
+}
+
+#[derive(Debug, Clone)]
+pub struct YearReview {
+    pub year: i32,
+    pub summary: PeriodSummary,
+    /// The exercise trained in the most sessions (then sets, then volume).
+    pub most_trained: Option<ExercisePeriodTotals>,
+    /// Biggest first, up to `PR_GAINS_SHOWN`.
+    pub pr_gains: Vec<PrGain>,
+    /// Most consecutive weeks meeting `streak_target`.
+    pub longest_streak: usize,
+    pub streak_target: i64,
+    /// All twelve months, January first; untrained months are zero.
+    pub months: Vec<MonthlyTotals>,
+    /// The month with the most sessions (then volume); `None` for a year
+    /// without training.
+    pub busiest_month: Option<MonthlyTotals>,
+}
+
+/// The repository aggregates a review is built from, over the year's
+/// range.
+#[derive(Debug, Default)]
+pub struct YearReviewData {
+    pub summary: PeriodSummary,
+    pub exercises: Vec<ExercisePeriodTotals>,
+    pub pr_gains: Vec<PrGain>,
+    pub months: Vec<MonthlyTotals>,
+    /// Weekly counts up to 31 December; earlier weeks are ignored.
+    pub weeks: Vec<WeeklySessionCount>,
+    pub streak_target: i64,
+}
+
+pub fn year_review(year: i32, data: YearReviewData) -> YearReview {
+    let YearReviewData {
+        summary,
+        exercises,
+        mut pr_gains,
+        months,
+        weeks,
+        streak_target,
+    } = data;
+
+    let most_trained = exercises.into_iter().max_by(|a, b| {
+        a.sessions
+            .cmp(&b.sessions)
+            .then(a.sets.cmp(&b.sets))
+            .then(a.volume.total_cmp(&b.volume))
+    });
+    pr_gains.truncate(PR_GAINS_SHOWN);
+
+    let first_month = NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or_default();
+    let months: Vec<MonthlyTotals> = (0..12)
+        .filter_map(|i| first_month.checked_add_months(Months::new(i)))
+        .map(|month| {
+            months
+                .iter()
+                .find(|m| m.month == month)
+                .cloned()
+                .unwrap_or(MonthlyTotals {
+                    month,
+                    sessions: 0,
+                    volume: 0.0,
+                })
+        })
+        .collect();
+    let busiest_month = months
+        .iter()
+        .filter(|m| m.sessions > 0)
+        .max_by(|a, b| {
+            a.sessions
+                .cmp(&b.sessions)
+                .then(a.volume.total_cmp(&b.volume))
+        })
+        .cloned();
+
+    // A week that starts in late December of the year before still counts
+    // when it runs into this one.
+    let weeks: Vec<WeeklySessionCount> = weeks
+        .into_iter()
+        .filter(|w| (w.week_start + Duration::days(6)).year() >= year)
+        .collect();
+
+    YearReview {
+        year,
+        summary,
+        most_trained,
+        pr_gains,
+        longest_streak: longest_streak(&weeks, streak_target),
+        streak_target,
+        months,
+        busiest_month,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn totals(id: &str, sessions: i64, sets: i64, volume: f64) -> ExercisePeriodTotals {
+        ExercisePeriodTotals {
+            exercise_id: id.to_string(),
+            exercise_name: id.to_string(),
+            sessions,
+            sets,
+            volume,
+            top_weight: 100.0,
+            top_reps: 5,
+            average_rpe: None,
+        }
+    }
+
+    fn week(start: NaiveDate, sessions: i64) -> WeeklySessionCount {
+        WeeklySessionCount {
+            week_start: start,
+            sessions,
+        }
+    }
+
+    #[test]
+    fn year_range_covers_only_supported_years() {
+        assert_eq!(
+            year_range(2024),
+            Some((date(2024, 1, 1), date(2024, 12, 31)))
+        );
+        for year in [-50, 1, 1899, 10_000] {
+            assert_eq!(year_range(year), None, "{year}");
+        }
+    }
+
+    #[test]
+    fn picks_the_most_trained_exercise_and_busiest_month() {
+        let data = YearReviewData {
+            exercises: vec![
+                totals("deadlift", 20, 60, 90_000.0),
+                totals("squat", 40, 150, 80_000.0),
+                totals("bench", 40, 120, 50_000.0),
+            ],
+            months: vec![
+                MonthlyTotals {
+                    month: date(2024, 3, 1),
+                    sessions: 12,
+                    volume: 9_000.0,
+                },
+                MonthlyTotals {
+                    month: date(2024, 10, 1),
+                    sessions: 12,
+                    volume: 11_000.0,
+                },
+            ],
+            ..YearReviewData::default()
+        };
+        let review = year_review(2024, data);
+        assert_eq!(review.most_trained.unwrap().exercise_id, "squat");
+        assert_eq!(review.months.len(), 12);
+        assert_eq!(review.months[0].sessions, 0);
+        assert_eq!(review.months[2].sessions, 12);
+        assert_eq!(review.busiest_month.unwrap().month, date(2024, 10, 1));
+    }
+
+    #[test]
+    fn an_empty_year_has_no_highlights() {
+        let review = year_review(2024, YearReviewData::default());
+        assert!(review.most_trained.is_none());
+        assert!(review.busiest_month.is_none());
+        assert_eq!(review.longest_streak, 0);
+    }
+
+    #[test]
+    fn streaks_count_the_week_running_into_the_year() {
+        let data = YearReviewData {
+            weeks: vec![
+                week(date(2023, 12, 18), 3),
+                week(date(2024, 1, 1) - Duration::days(1), 3),
+                week(date(2024, 1, 7), 3),
+                week(date(2024, 1, 14), 3),
+            ],
+            streak_target: 3,
+            ..YearReviewData::default()
+        };
+        // Sunday-first weeks: 31 Dec 2023 starts the first week of 2024.
+        let review = year_review(2024, data);
+        assert_eq!(review.longest_streak, 3);
+    }
+}
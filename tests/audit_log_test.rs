@@ -80,13 +80,16 @@ impl<'a> MakeWriter<'a> for CapturingWriter {
 /// This would NOT be safe under plain `cargo test`, which runs many tests as
 /// threads inside one shared process.
 fn install_capturing_subscriber(writer: CapturingWriter) {
-    let subscriber = tracing_subscriber::fmt()
+    tracing::subscriber::set_global_default(capturing_subscriber(writer))
+        .expect("no subscriber should already be installed in this test process");
+}
+
+fn capturing_subscriber(writer: CapturingWriter) -> impl tracing::Subscriber + Send + Sync {
+    tracing_subscriber::fmt()
         .with_writer(writer)
         .with_ansi(false)
         .with_env_filter(tracing_subscriber::EnvFilter::new("liftlog::audit=info"))
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("no subscriber should already be installed in this test process");
+        .finish()
 }
 
 /// Pulls the value out of a `key="value"` (or bare `key=value`) pair in a
@@ -560,3 +563,107 @@ async fn login_backoff_climbs_per_account_and_resets_on_success() {
         "a successful login should have cleared the accumulated penalty"
     );
 }
+
+/// Every coach request is audited, reads included, with the athlete it
+/// touched. This one installs its subscriber for the current thread only
+/// (`set_default`), so it also passes when plain `cargo test` runs the file
+/// in one process: `#[tokio::test]`'s runtime is single-threaded and the
+/// events are emitted on the handler's task.
+#[tokio::test]
+async fn coach_grants_and_actions_emit_audit_events() {
+    let writer = CapturingWriter::default();
+    let _guard = tracing::subscriber::set_default(capturing_subscriber(writer.clone()));
+
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+    let athlete = common::create_test_user(&pool, "athlete", "password123", UserRole::User).await;
+    let coach = common::create_test_user(&pool, "coach", "password123", UserRole::User).await;
+    let athlete_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &athlete).await);
+    let coach_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &coach).await);
+    let workout = common::create_test_workout(
+        &pool,
+        &athlete.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        None,
+    )
+    .await;
+
+    let request = |method: &str, uri: String, cookie: &str, body: &'static str| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::COOKIE, cookie)
+            .body(Body::from(body))
+            .unwrap()
+    };
+    let send = |request| test_app.router.clone().oneshot(request);
+
+    let response = send(request(
+        "POST",
+        "/settings/coaches".to_string(),
+        &athlete_cookie,
+        "username=coach&can_comment=on",
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let log = writer.contents();
+    assert!(log.contains("coach.access"), "got: {log}");
+    assert_eq!(extract_field(&log, "change"), Some("granted"));
+    assert_eq!(extract_field(&log, "coach_id"), Some(coach.id.as_str()));
+    assert_eq!(extract_field(&log, "can_comment"), Some("true"));
+
+    let response = send(request(
+        "GET",
+        format!("/coaching/workouts/{}", workout.id),
+        &coach_cookie,
+        "",
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send(request(
+        "POST",
+        format!("/workouts/{}/comments", workout.id),
+        &coach_cookie,
+        "body=Good+session",
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let log = writer.contents();
+    let actions: Vec<&str> = log
+        .lines()
+        .filter(|line| line.contains("coach.action"))
+        .collect();
+    assert_eq!(actions.len(), 2, "got: {log}");
+    assert_eq!(extract_field(actions[0], "action"), Some("view_workout"));
+    assert_eq!(
+        extract_field(actions[0], "target"),
+        Some(workout.id.as_str())
+    );
+    assert_eq!(extract_field(actions[1], "action"), Some("comment"));
+    for line in &actions {
+        assert_eq!(extract_field(line, "athlete_id"), Some(athlete.id.as_str()));
+        assert!(
+            !line.contains("Good session"),
+            "comment text logged: {line}"
+        );
+    }
+
+    // The athlete's own comments are not coach actions.
+    let response = send(request(
+        "POST",
+        format!("/workouts/{}/comments", workout.id),
+        &athlete_cookie,
+        "body=Thanks",
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(writer.contents().matches("coach.action").count(), 2);
+}
@@ -1834,6 +1834,27 @@ async fn test_baseline_security_headers_on_the_public_share_route() {
     assert_baseline_headers(response.headers());
 }
 
+/// The public profile is the other page meant for strangers, and gets the
+/// same headers.
+#[tokio::test]
+async fn test_baseline_security_headers_on_the_public_profile_route() {
+    let pool = common::setup_test_db();
+    let app = common::create_test_app_with_session(pool).router;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/shared/profile/no-such-token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_baseline_headers(response.headers());
+}
+
 /// Companion to `test_setup_rejects_short_password` at the other bound. The
 /// maximum exists so the hash comparison has a bounded input (OWASP:
 /// "protect against denial of service attacks with very long inputs"), and
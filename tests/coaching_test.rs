@@ -0,0 +1,367 @@
+mod common;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    response::Response,
+};
+use http_body_util::BodyExt;
+use liftlog::models::UserRole;
+use liftlog::repositories::{CommentRepository, UserRepository, WorkoutRepository};
+use tower::ServiceExt;
+
+async fn get(router: &Router, cookie: &str, uri: &str) -> Response {
+    router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(uri)
+                .header(header::COOKIE, cookie)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+async fn post(router: &Router, cookie: &str, uri: &str, body: String) -> Response {
+    router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, cookie)
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+async fn body_text(response: Response) -> String {
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    String::from_utf8_lossy(&body).into_owned()
+}
+
+#[tokio::test]
+async fn test_coach_reads_only_with_a_grant_and_never_edits() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+    let router = &test_app.router;
+
+    let athlete = common::create_test_user(&pool, "athlete", "password123", UserRole::User).await;
+    let coach = common::create_test_user(&pool, "coach", "password123", UserRole::User).await;
+    let athlete_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &athlete).await);
+    let coach_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &coach).await);
+
+    let squat = common::create_test_exercise(&pool, &athlete.id, "Squat", "legs").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &athlete.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        Some("Heavy triples"),
+    )
+    .await;
+    common::create_test_log(&pool, &workout.id, &squat.id, 1, 3, 150.0, Some(9)).await;
+
+    let athlete_url = format!("/coaching/{}", athlete.id);
+    let workout_url = format!("/coaching/workouts/{}", workout.id);
+    for uri in [&athlete_url, &workout_url] {
+        let response = get(router, &coach_cookie, uri).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{uri}");
+    }
+
+    // Unknown names and the athlete themself are refused on the page.
+    let response = post(
+        router,
+        &athlete_cookie,
+        "/settings/coaches",
+        "username=nobody".to_string(),
+    )
+    .await;
+    assert!(body_text(response).await.contains("No user with that name"));
+    let response = post(
+        router,
+        &athlete_cookie,
+        "/settings/coaches",
+        "username=athlete".to_string(),
+    )
+    .await;
+    assert!(body_text(response).await.contains("cannot coach yourself"));
+
+    let response = post(
+        router,
+        &athlete_cookie,
+        "/settings/coaches",
+        "username=coach".to_string(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let html = body_text(get(router, &athlete_cookie, "/settings/coaches").await).await;
+    assert!(html.contains("coach"));
+
+    let html = body_text(get(router, &coach_cookie, "/coaching").await).await;
+    assert!(html.contains(&athlete_url));
+    let response = get(router, &coach_cookie, &athlete_url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let html = body_text(response).await;
+    assert!(html.contains("150 kg"));
+    assert!(html.contains(&workout_url));
+    assert!(!html.contains("Add to Plan"));
+    let response = get(router, &coach_cookie, &workout_url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let html = body_text(response).await;
+    assert!(html.contains("Heavy triples"));
+    assert!(html.contains("Squat"));
+    assert!(!html.contains("comment-body"));
+
+    // Read-only: the owner's pages and edits stay closed, and so do
+    // comments and planning until granted.
+    let response = get(router, &coach_cookie, &format!("/workouts/{}", workout.id)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = post(
+        router,
+        &coach_cookie,
+        &format!("/workouts/{}/logs", workout.id),
+        format!("exercise_id={}&weight=20&reps=5", squat.id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = post(
+        router,
+        &coach_cookie,
+        &format!("/workouts/{}/comments", workout.id),
+        "body=Nice".to_string(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = post(
+        router,
+        &coach_cookie,
+        &format!("{athlete_url}/program"),
+        "date=2999-01-01".to_string(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let logs = WorkoutRepository::new(pool.clone())
+        .find_logs_by_session_for_share(&workout.id)
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 1);
+
+    let response = post(
+        router,
+        &athlete_cookie,
+        &format!("/settings/coaches/{}/revoke", coach.id),
+        String::new(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    for uri in [&athlete_url, &workout_url] {
+        let response = get(router, &coach_cookie, uri).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn test_coach_comments_and_programs_when_allowed() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+    let router = &test_app.router;
+
+    let athlete = common::create_test_user(&pool, "athlete", "password123", UserRole::User).await;
+    let coach = common::create_test_user(&pool, "coach", "password123", UserRole::User).await;
+    let athlete_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &athlete).await);
+    let coach_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &coach).await);
+    UserRepository::new(pool.clone())
+        .grant_coach(&athlete.id, &coach.id, true, true)
+        .await
+        .unwrap();
+
+    let bench = common::create_test_exercise(&pool, &athlete.id, "Bench Press", "chest").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &athlete.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        None,
+    )
+    .await;
+    let log = common::create_test_log(&pool, &workout.id, &bench.id, 1, 5, 100.0, None).await;
+
+    let comments_url = format!("/workouts/{}/comments", workout.id);
+    let response = post(
+        router,
+        &coach_cookie,
+        &comments_url,
+        format!("log_id={}&body=Elbows+in", log.id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        &format!("/coaching/workouts/{}", workout.id)
+    );
+    let response = post(router, &coach_cookie, &comments_url, "body=+".to_string()).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // The athlete sees it on their own page, and can reply.
+    let html = body_text(
+        get(
+            router,
+            &athlete_cookie,
+            &format!("/workouts/{}", workout.id),
+        )
+        .await,
+    )
+    .await;
+    assert!(html.contains("Elbows in"));
+    assert!(html.contains("Bench Press set 1"));
+    let response = post(
+        router,
+        &athlete_cookie,
+        &comments_url,
+        "body=Will+do".to_string(),
+    )
+    .await;
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        &format!("/workouts/{}", workout.id)
+    );
+
+    let comments = CommentRepository::new(pool.clone())
+        .find_comments(&workout.id)
+        .await
+        .unwrap();
+    assert_eq!(comments.len(), 2);
+    // A coach can delete only their own comments; the athlete can delete
+    // any on their workout.
+    let delete_url =
+        |comment_id: &str| format!("/workouts/{}/comments/{comment_id}/delete", workout.id);
+    let (coach_comment, athlete_comment) = (&comments[0], &comments[1]);
+    let response = post(
+        router,
+        &coach_cookie,
+        &delete_url(&athlete_comment.id),
+        String::new(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let html = body_text(
+        get(
+            router,
+            &athlete_cookie,
+            &format!("/workouts/{}", workout.id),
+        )
+        .await,
+    )
+    .await;
+    assert!(html.contains(&delete_url(&coach_comment.id)));
+    let response = post(
+        router,
+        &athlete_cookie,
+        &delete_url(&coach_comment.id),
+        String::new(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let comments = CommentRepository::new(pool.clone())
+        .find_comments(&workout.id)
+        .await
+        .unwrap();
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].body, "Will do");
+
+    // Planned workouts go into the athlete's log, from their today on.
+    let program_url = format!("/coaching/{}/program", athlete.id);
+    let response = post(
+        router,
+        &coach_cookie,
+        &program_url,
+        "date=2000-01-01&notes=Too+late".to_string(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = post(
+        router,
+        &coach_cookie,
+        &program_url,
+        "date=2999-01-04&notes=Deload+week".to_string(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let sessions = WorkoutRepository::new(pool.clone())
+        .find_sessions_by_user_paginated(&athlete.id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].date.to_string(), "2999-01-04");
+    assert_eq!(sessions[0].notes.as_deref(), Some("Deload week"));
+
+    // Only sessions without sets count as planned, whatever their date.
+    let logged = common::create_test_workout(
+        &pool,
+        &athlete.id,
+        chrono::NaiveDate::from_ymd_opt(2999, 1, 5).unwrap(),
+        None,
+    )
+    .await;
+    common::create_test_log(&pool, &logged.id, &bench.id, 1, 5, 100.0, None).await;
+    let html =
+        body_text(get(router, &coach_cookie, &format!("/coaching/{}", athlete.id)).await).await;
+    assert!(html.contains(&format!("/coaching/workouts/{}", logged.id)));
+    assert_eq!(html.matches("(planned)").count(), 1);
+    assert!(
+        WorkoutRepository::new(pool.clone())
+            .find_sessions_by_user_paginated(&coach.id, 10, 0)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_coach_pages_through_an_athletes_workouts() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+    let router = &test_app.router;
+
+    let athlete = common::create_test_user(&pool, "athlete", "password123", UserRole::User).await;
+    let coach = common::create_test_user(&pool, "coach", "password123", UserRole::User).await;
+    let coach_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &coach).await);
+    UserRepository::new(pool.clone())
+        .grant_coach(&athlete.id, &coach.id, false, false)
+        .await
+        .unwrap();
+    let first = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    let mut workouts = Vec::new();
+    for day in 0..21 {
+        let date = first + chrono::Days::new(day);
+        workouts.push(common::create_test_workout(&pool, &athlete.id, date, None).await);
+    }
+
+    let athlete_url = format!("/coaching/{}", athlete.id);
+    let html = body_text(get(router, &coach_cookie, &athlete_url).await).await;
+    assert_eq!(html.matches("/coaching/workouts/").count(), 20);
+    assert!(!html.contains(&format!("/coaching/workouts/{}", workouts[0].id)));
+    assert!(!html.contains("?before="));
+    let start = html.find("?after=").expect("a link to older workouts");
+    let end = start + html[start..].find('"').unwrap();
+    let older = format!("{athlete_url}{}", &html[start..end]);
+
+    let html = body_text(get(router, &coach_cookie, &older).await).await;
+    assert_eq!(html.matches("/coaching/workouts/").count(), 1);
+    assert!(html.contains(&format!("/coaching/workouts/{}", workouts[0].id)));
+    assert!(html.contains("?before="));
+    assert!(!html.contains("?after="));
+
+    let response = get(router, &coach_cookie, &format!("{athlete_url}?after=junk")).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -73,6 +73,7 @@ pub fn create_test_app_with_password_change_limit(
         Vec::new(),
         0,
         false,
+        None,
     )
 }
 
@@ -95,6 +96,7 @@ pub fn create_test_app_with_rate_limit(
         Vec::new(),
         0,
         false,
+        None,
     )
 }
 
@@ -113,6 +115,7 @@ pub fn create_test_app_with_cookie_secure(pool: DbPool, cookie_secure: bool) ->
         Vec::new(),
         0,
         false,
+        None,
     )
 }
 
@@ -141,6 +144,7 @@ pub fn create_test_app_with_proxy_header(
         trusted_proxies,
         0,
         false,
+        None,
     )
 }
 
@@ -162,9 +166,32 @@ pub fn create_test_app_with_hsts(pool: DbPool, max_age: u64, include_subdomains:
         Vec::new(),
         max_age,
         include_subdomains,
+        None,
     )
 }
 
+/// Like [`create_test_app`], but with `LIFTLOG_PUBLIC_URL` set, so
+/// absolute links ignore the request's `Host` header.
+#[allow(dead_code)]
+pub fn create_test_app_with_public_url(pool: DbPool, public_url: &str) -> Router {
+    build_test_app(
+        pool,
+        GENEROUS_MAX_ATTEMPTS,
+        GENEROUS_WINDOW,
+        GENEROUS_MAX_ATTEMPTS,
+        GENEROUS_WINDOW,
+        NO_BACKOFF_FREE_ATTEMPTS,
+        NO_BACKOFF_BASE,
+        false,
+        liftlog::config::TrustedProxyHeader::None,
+        Vec::new(),
+        0,
+        false,
+        Some(public_url.to_string()),
+    )
+    .router
+}
+
 /// Builds an app whose per-account login backoff is active, so a test can
 /// observe the delay without every other test paying for it.
 #[allow(dead_code)]
@@ -186,6 +213,7 @@ pub fn create_test_app_with_login_backoff(
         Vec::new(),
         0,
         false,
+        None,
     )
 }
 
@@ -205,16 +233,24 @@ fn build_test_app(
     trusted_proxies: Vec<std::net::IpAddr>,
     hsts_max_age: u64,
     hsts_include_subdomains: bool,
+    public_url: Option<String>,
 ) -> TestApp {
     use liftlog::rate_limit::{FailureBackoff, RateLimiter};
-    use liftlog::repositories::{ExerciseRepository, WorkoutRepository};
+    use liftlog::repositories::{
+        CategoryRepository, CommentRepository, ExerciseRepository, ShareRepository, TagRepository,
+        WorkoutRepository,
+    };
     use liftlog::state::AppState;
     use std::sync::Arc;
 
     let app_state = AppState {
         user_repo: UserRepository::new(pool.clone()),
         exercise_repo: ExerciseRepository::new(pool.clone()),
+        category_repo: CategoryRepository::new(pool.clone()),
+        tag_repo: TagRepository::new(pool.clone()),
         workout_repo: WorkoutRepository::new(pool.clone()),
+        share_repo: ShareRepository::new(pool.clone()),
+        comment_repo: CommentRepository::new(pool.clone()),
         session_repo: SessionRepository::new(pool.clone()),
         login_rate_limiter: Arc::new(RateLimiter::new(max_attempts, window)),
         login_backoff: Arc::new(FailureBackoff::new(
@@ -237,10 +273,12 @@ fn build_test_app(
         // opts in.
         hsts_max_age,
         hsts_include_subdomains,
+        public_url: public_url.map(Into::into),
         // Fixed, deterministic salt (not random) so a test can assert a
         // specific fingerprint if it ever needs to; nothing in this test
         // suite currently relies on its exact value.
         log_salt: Arc::new([7u8; 32]),
+        share_card_cache: Arc::new(liftlog::chart_image::PngCache::new(8)),
     };
 
     let router = liftlog::routes::create_router(app_state);
@@ -337,7 +375,10 @@ pub async fn create_test_exercise(
     category: &str,
 ) -> liftlog::models::Exercise {
     let exercise_repo = liftlog::repositories::ExerciseRepository::new(pool.clone());
-    exercise_repo.create(name, category, user_id).await.unwrap()
+    exercise_repo
+        .create_with_muscles(name, category, Some(user_id), false, Vec::new())
+        .await
+        .unwrap()
 }
 
 #[allow(dead_code)]
@@ -366,7 +407,12 @@ pub async fn create_test_log(
 ) -> liftlog::models::WorkoutLog {
     let workout_repo = liftlog::repositories::WorkoutRepository::new(pool.clone());
     workout_repo
-        .create_log(session_id, exercise_id, set_number, reps, weight, rpe)
+        .create_log(
+            session_id,
+            exercise_id,
+            set_number,
+            &liftlog::models::SetValues::new(reps, weight, rpe),
+        )
         .await
         .unwrap()
 }
@@ -575,3 +575,804 @@ async fn test_delete_unreferenced_exercise_still_succeeds() {
     let found = exercise_repo.find_by_id(&exercise.id).await.unwrap();
     assert!(found.is_none());
 }
+
+// Muscle mapping
+
+#[tokio::test]
+async fn test_update_exercise_saves_muscles() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/exercises/{}", exercise.id))
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(
+                    "name=Bench%20Press&category=chest&muscle.chest=primary\
+                     &muscle.triceps=secondary&muscle.biceps=&muscle.wings=primary",
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let exercise_repo = ExerciseRepository::new(pool);
+    let muscles = exercise_repo.find_muscles(&exercise.id).await.unwrap();
+    let names: Vec<&str> = muscles.iter().map(|m| m.muscle.as_str()).collect();
+    assert_eq!(names, ["chest", "triceps"]);
+}
+
+#[tokio::test]
+async fn test_edit_exercise_page_preselects_muscles() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    {
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO exercise_muscles (exercise_id, muscle, role) VALUES (?, 'chest', 'primary')",
+            [&exercise.id],
+        )
+        .unwrap();
+    }
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri(format!("/exercises/{}/edit", exercise.id))
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains(r#"name="muscle.chest""#));
+    assert!(html.contains(r#"<option value="primary" selected>"#));
+}
+
+// Categories
+
+#[tokio::test]
+async fn test_create_exercise_unknown_category_rejected() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/exercises")
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from("name=Clean&category=olympic"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("Please choose one of your categories"));
+
+    let exercise_repo = ExerciseRepository::new(pool);
+    let exercises = exercise_repo
+        .find_available_for_user(&user.id)
+        .await
+        .unwrap();
+    assert!(exercises.is_empty());
+}
+
+#[tokio::test]
+async fn test_custom_category_can_be_created_renamed_and_used() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let post = |uri: &str, body: &'static str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post("/exercises/categories", "display_name=Olympic"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post(
+            "/exercises/categories/olympic",
+            "display_name=Olympic%20Lifts",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post("/exercises", "name=Power%20Clean&category=olympic"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/exercises")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    let heading = html
+        .find("Olympic Lifts")
+        .expect("renamed category heading");
+    let exercise = html.find("Power Clean").expect("exercise listed");
+    assert!(heading < exercise);
+}
+
+#[tokio::test]
+async fn test_delete_category_in_use_is_refused() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/exercises/categories/legs/delete")
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("This category still has exercises"));
+}
+
+#[tokio::test]
+async fn test_cannot_rename_others_category() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    common::create_test_user(&pool, "owner", "password123", UserRole::User).await;
+    let attacker = common::create_test_user(&pool, "attacker", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &attacker).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    // Both users have a "legs" category; the rename can only ever reach the
+    // caller's own row, so the owner's stays untouched.
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/exercises/categories/legs")
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from("display_name=Hacked"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let conn = pool.get().unwrap();
+    let hacked: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM exercise_categories c JOIN users u ON u.id = c.user_id
+             WHERE u.username = 'owner' AND c.display_name = 'Hacked'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(hacked, 0);
+}
+
+// Catalog
+
+#[tokio::test]
+async fn test_empty_exercise_list_offers_catalog_import() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/exercises")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("Import Entire Catalog"));
+}
+
+#[tokio::test]
+async fn test_import_selected_catalog_entries() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/exercises/catalog")
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(
+                    "entry.barbell-back-squat=1&entry.romanian-deadlift=1&entry.not-a-lift=1",
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("Imported 2 exercises"));
+
+    let exercise_repo = ExerciseRepository::new(pool);
+    let exercises = exercise_repo
+        .find_available_for_user(&user.id)
+        .await
+        .unwrap();
+    assert_eq!(exercises.len(), 2);
+    let squat = exercises
+        .iter()
+        .find(|e| e.catalog_id.as_deref() == Some("barbell-back-squat"))
+        .expect("squat imported with its catalog id");
+    assert_eq!(squat.name, "Squat");
+    assert_eq!(squat.category, "legs");
+    let muscles = exercise_repo.find_muscles(&squat.id).await.unwrap();
+    assert!(muscles.iter().any(|m| m.muscle == "quads"));
+}
+
+#[tokio::test]
+async fn test_import_all_catalog_is_idempotent() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    for _ in 0..2 {
+        let response = test_app
+            .router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/exercises/catalog")
+                    .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                    .header(header::COOKIE, &cookie_header)
+                    .body(Body::from("all=1"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let exercise_repo = ExerciseRepository::new(pool);
+    let exercises = exercise_repo
+        .find_available_for_user(&user.id)
+        .await
+        .unwrap();
+    assert_eq!(exercises.len(), liftlog::catalog::entries().len());
+}
+
+// Global exercise tests
+
+async fn post_form(
+    test_app: &common::TestApp,
+    cookie_header: &str,
+    uri: &str,
+    body: &str,
+) -> axum::response::Response {
+    test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, cookie_header)
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_admin_creates_global_exercise_visible_to_users() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let admin = common::create_test_user(&pool, "admin", "adminpass123", UserRole::Admin).await;
+    let admin_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &admin).await);
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let user_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &user).await);
+
+    let response = post_form(
+        &test_app,
+        &admin_cookie,
+        "/exercises",
+        "name=Deadlift&category=back&global=1",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let exercise_repo = ExerciseRepository::new(pool);
+    let exercises = exercise_repo
+        .find_available_for_user(&user.id)
+        .await
+        .unwrap();
+    assert_eq!(exercises.len(), 1);
+    assert!(exercises[0].is_global());
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/exercises")
+                .header(header::COOKIE, &user_cookie)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    assert!(html.contains("Deadlift"));
+    assert!(html.contains("Make Personal Copy"));
+}
+
+#[tokio::test]
+async fn test_global_flag_ignored_for_regular_users() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let user_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &user).await);
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        "/exercises",
+        "name=Deadlift&category=back&global=1",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let exercises = ExerciseRepository::new(pool)
+        .find_available_for_user(&user.id)
+        .await
+        .unwrap();
+    assert_eq!(exercises[0].user_id.as_deref(), Some(user.id.as_str()));
+}
+
+#[tokio::test]
+async fn test_users_cannot_edit_or_delete_global_exercise() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let user_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &user).await);
+    let exercise_repo = ExerciseRepository::new(pool.clone());
+    let global = exercise_repo
+        .create_with_muscles("Deadlift", "back", None, false, Vec::new())
+        .await
+        .unwrap();
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}", global.id),
+        "name=Renamed&category=back",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}/delete", global.id),
+        "",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let unchanged = exercise_repo.find_by_id(&global.id).await.unwrap().unwrap();
+    assert_eq!(unchanged.name, "Deadlift");
+}
+
+#[tokio::test]
+async fn test_admin_can_edit_global_exercise() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let admin = common::create_test_user(&pool, "admin", "adminpass123", UserRole::Admin).await;
+    let admin_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &admin).await);
+    let exercise_repo = ExerciseRepository::new(pool.clone());
+    let global = exercise_repo
+        .create_with_muscles("Deadlift", "back", None, false, Vec::new())
+        .await
+        .unwrap();
+
+    let response = post_form(
+        &test_app,
+        &admin_cookie,
+        &format!("/exercises/{}", global.id),
+        "name=Conventional+Deadlift&category=back",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let updated = exercise_repo.find_by_id(&global.id).await.unwrap().unwrap();
+    assert_eq!(updated.name, "Conventional Deadlift");
+    assert!(updated.is_global());
+}
+
+#[tokio::test]
+async fn test_hide_and_fork_global_exercise() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let user_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &user).await);
+    let exercise_repo = ExerciseRepository::new(pool.clone());
+    let global = exercise_repo
+        .create_with_muscles("Deadlift", "back", None, false, Vec::new())
+        .await
+        .unwrap();
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}/hide", global.id),
+        "",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert!(
+        exercise_repo
+            .find_available_for_user(&user.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}/unhide", global.id),
+        "",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}/fork", global.id),
+        "",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let exercises = exercise_repo
+        .find_available_for_user(&user.id)
+        .await
+        .unwrap();
+    assert_eq!(exercises.len(), 1);
+    assert_eq!(exercises[0].name, "Deadlift");
+    assert_eq!(exercises[0].user_id.as_deref(), Some(user.id.as_str()));
+    let location = response
+        .headers()
+        .get("location")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert_eq!(location, format!("/exercises/{}/edit", exercises[0].id));
+}
+
+#[tokio::test]
+async fn test_cannot_fork_personal_exercise() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let user_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &user).await);
+    let exercise = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}/fork", exercise.id),
+        "",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+// Merge and archive tests
+
+#[tokio::test]
+async fn test_merge_exercise_moves_logs_and_deletes_source() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let user_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &user).await);
+    let typo = common::create_test_exercise(&pool, &user.id, "Bench", "chest").await;
+    let bench = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2026, 1, 5).unwrap(),
+        None,
+    )
+    .await;
+    let log = common::create_test_log(&pool, &workout.id, &typo.id, 1, 5, 100.0, None).await;
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}/merge", typo.id),
+        &format!("target_id={}", bench.id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let exercise_repo = ExerciseRepository::new(pool.clone());
+    assert!(exercise_repo.find_by_id(&typo.id).await.unwrap().is_none());
+    let workout_repo = liftlog::repositories::WorkoutRepository::new(pool);
+    let moved = workout_repo.find_log_by_id(&log.id).await.unwrap().unwrap();
+    assert_eq!(moved.exercise_id, bench.id);
+}
+
+#[tokio::test]
+async fn test_cannot_merge_into_others_exercise() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let other = common::create_test_user(&pool, "other", "password123", UserRole::User).await;
+    let user_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &user).await);
+    let mine = common::create_test_exercise(&pool, &user.id, "Bench", "chest").await;
+    let theirs = common::create_test_exercise(&pool, &other.id, "Bench Press", "chest").await;
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}/merge", mine.id),
+        &format!("target_id={}", theirs.id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let exercise_repo = ExerciseRepository::new(pool);
+    assert!(exercise_repo.find_by_id(&mine.id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_cannot_merge_into_archived_exercise() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let user_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &user).await);
+    let typo = common::create_test_exercise(&pool, &user.id, "Bench", "chest").await;
+    let bench = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let exercise_repo = ExerciseRepository::new(pool.clone());
+    exercise_repo.set_archived(&bench.id, true).await.unwrap();
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}/merge", typo.id),
+        &format!("target_id={}", bench.id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(exercise_repo.find_by_id(&typo.id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_shared_exercise_cannot_merge_into_personal() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let admin = common::create_test_user(&pool, "admin", "adminpass123", UserRole::Admin).await;
+    let admin_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &admin).await);
+    let exercise_repo = ExerciseRepository::new(pool.clone());
+    let global = exercise_repo
+        .create_with_muscles("Deadlift", "back", None, false, Vec::new())
+        .await
+        .unwrap();
+    let personal = common::create_test_exercise(&pool, &admin.id, "Deadlift", "back").await;
+
+    let response = post_form(
+        &test_app,
+        &admin_cookie,
+        &format!("/exercises/{}/merge", global.id),
+        &format!("target_id={}", personal.id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(
+        exercise_repo
+            .find_by_id(&global.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn test_archived_exercise_hidden_from_workout_picker() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let user_cookie =
+        common::extract_cookie_header(&common::create_session_cookie(&pool, &user).await);
+    let exercise = common::create_test_exercise(&pool, &user.id, "Good Morning", "legs").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2026, 1, 5).unwrap(),
+        None,
+    )
+    .await;
+    common::create_test_log(&pool, &workout.id, &exercise.id, 1, 5, 60.0, None).await;
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}/archive", exercise.id),
+        "",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/workouts/{}", workout.id))
+                .header(header::COOKIE, &user_cookie)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    assert!(!html.contains(&format!("<option value=\"{}\"", exercise.id)));
+
+    // History stays reachable.
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/stats/exercise/{}", exercise.id))
+                .header(header::COOKIE, &user_cookie)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    assert!(html.contains("Archived"));
+
+    let response = post_form(
+        &test_app,
+        &user_cookie,
+        &format!("/exercises/{}/unarchive", exercise.id),
+        "",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let exercise_repo = ExerciseRepository::new(pool);
+    assert_eq!(
+        exercise_repo
+            .find_available_for_user(&user.id)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
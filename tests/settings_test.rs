@@ -865,3 +865,79 @@ async fn test_change_password_rejects_reusing_the_current_password() {
         "other sessions must survive a refused change"
     );
 }
+
+#[tokio::test]
+async fn test_timezone_and_week_start_drive_today_and_the_calendar() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let post = |body: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/settings/training")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let get = |uri: &str| {
+        Request::builder()
+            .uri(uri)
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post(
+            "weekly_session_target=3&timezone=Mars%2FOlympus&week_start=Mon",
+        ))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains("Unknown timezone: Mars/Olympus"));
+
+    // UTC+14: always a day ahead of most of the world.
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post(
+            "weekly_session_target=3&timezone=Pacific%2FKiritimati&week_start=Sun",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("value=\"Pacific/Kiritimati\""));
+    assert!(html.contains("<option value=\"Sun\" selected>Sunday</option>"));
+
+    let today = chrono::Utc::now()
+        .with_timezone(&chrono_tz::Pacific::Kiritimati)
+        .date_naive();
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/workouts/new"))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains(&format!("value=\"{today}\"")));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/workouts/calendar?month=2024-02"))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    let first_heading = html.find("<th>").map(|i| &html[i..i + 12]);
+    assert_eq!(first_heading, Some("<th>Sun</th>"));
+}
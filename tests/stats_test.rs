@@ -372,6 +372,7 @@ async fn test_exercise_stats_chart_renders_with_two_or_more_sessions() {
 
     let response = test_app
         .router
+        .clone()
         .oneshot(
             Request::builder()
                 .uri(format!("/stats/exercise/{}", exercise.id))
@@ -387,29 +388,41 @@ async fn test_exercise_stats_chart_renders_with_two_or_more_sessions() {
     let body = response.into_body().collect().await.unwrap().to_bytes();
     let body_str = String::from_utf8_lossy(&body);
 
-    // Server-rendered SVG line is present
+    // Server-rendered SVG line is present, over all time by default, with
+    // a dot and hover text per session.
     assert!(body_str.contains("<polyline"), "polyline missing");
-    assert!(body_str.contains("id=\"chart-line\""));
-
-    // JSON-embedded dataset is present and parseable
-    let start = body_str
-        .find("id=\"exercise-chart-data\">")
-        .expect("chart-data script tag missing");
-    let after_open = &body_str[start + "id=\"exercise-chart-data\">".len()..];
-    let end = after_open
-        .find("</script>")
-        .expect("chart-data script close tag");
-    let json_text = &after_open[..end];
-    let parsed: serde_json::Value =
-        serde_json::from_str(json_text).expect("chart data JSON should parse");
-    let arr = parsed.as_array().expect("chart data should be an array");
-    assert_eq!(arr.len(), 3);
-    let first = &arr[0];
-    assert!(first.get("top_weight").is_some());
-    assert!(first.get("top_reps").is_some());
-    assert!(first.get("volume").is_some());
-    assert!(first.get("e1rm").is_some());
-    assert!(first.get("date").is_some());
+    assert!(body_str.contains("class=\"chart-line\""));
+    assert_eq!(body_str.matches("<title>Bench Press 2024-01-").count(), 3);
+    assert!(body_str.contains("<title>Bench Press 2024-01-14: 110 kg</title>"));
+    assert!(body_str.contains("Last session, 2024-01-14: top set 110 kg &times; 5"));
+    assert!(!body_str.contains("exercise-chart-data"));
+
+    // Tabs switch the metric and range by link; a range with nothing in it
+    // says so, and unknown options are refused.
+    let get = |uri: String| {
+        test_app.router.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+    let response = get(format!("/stats/exercise/{}?metric=volume", exercise.id))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body_str = String::from_utf8_lossy(&body);
+    assert!(body_str.contains("<title>Bench Press 2024-01-14: 550 kg</title>"));
+    let response = get(format!("/stats/exercise/{}?range=3m", exercise.id))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains("Nothing logged in this range"));
+    let response = get(format!("/stats/exercise/{}?metric=speed", exercise.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -562,3 +575,523 @@ async fn test_exercise_stats_rejects_exercise_owned_by_another_user() {
         "the other user's exercise name must not be disclosed, body=\n{body_str}"
     );
 }
+
+// Muscle volume
+
+#[tokio::test]
+async fn test_muscle_volume_requires_auth() {
+    let pool = common::setup_test_db();
+    let app = common::create_test_app(pool);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/stats/muscles")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+}
+
+#[tokio::test]
+async fn test_muscle_volume_shows_hard_sets_for_mapped_exercises() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    {
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO exercise_muscles (exercise_id, muscle, role) VALUES (?, 'chest', 'primary')",
+            [&exercise.id],
+        )
+        .unwrap();
+    }
+    let today = chrono::Utc::now().date_naive();
+    let workout = common::create_test_workout(&pool, &user.id, today, None).await;
+    for set in 1..=3 {
+        common::create_test_log(&pool, &workout.id, &exercise.id, set, 8, 80.0, Some(8)).await;
+    }
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/stats/muscles")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains(r#"<td class="volume-under" title="1920 kg volume">3</td>"#));
+}
+
+#[tokio::test]
+async fn test_update_muscle_targets() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/stats/muscles/targets")
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from("min.chest=2&max.chest=6"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/stats/muscles/targets")
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from("min.chest=8&max.chest=6"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/stats/muscles")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains(r#"name="min.chest" value="2""#));
+    assert!(html.contains(r#"name="max.chest" value="6""#));
+}
+
+#[tokio::test]
+async fn test_weekly_target_drives_streaks_on_dashboard_and_stats() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let today = chrono::Utc::now().date_naive();
+    for days_ago in [0, 7, 14] {
+        let date = today - chrono::Duration::days(days_ago);
+        let workout = common::create_test_workout(&pool, &user.id, date, None).await;
+        common::create_test_log(&pool, &workout.id, &exercise.id, 1, 5, 100.0, None).await;
+    }
+
+    let post_target = |value: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/settings/training")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::from(format!(
+                "weekly_session_target={value}&timezone=UTC&week_start=Mon"
+            )))
+            .unwrap()
+    };
+    let get = |uri: &str| {
+        Request::builder()
+            .uri(uri)
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post_target("99"))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains("Weekly target must be"));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post_target("1"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains("value=\"1\""));
+
+    let response = test_app.router.clone().oneshot(get("/")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains(
+        "<div class=\"stat-value\">3</div>\n        <div class=\"stat-label\">Week Streak</div>"
+    ));
+    assert!(html.contains("100%"));
+    assert!(html.contains("1/1"));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/stats"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("Week Streak"));
+    assert!(html.contains(&format!(
+        "Week of {}: 1",
+        today
+            - chrono::Duration::days(i64::from(
+                chrono::Datelike::weekday(&today).num_days_from_monday()
+            ))
+    )));
+}
+
+#[tokio::test]
+async fn test_weekly_and_monthly_reports_compare_with_the_previous_period() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let squat = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let date = |d| chrono::NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+    let before = common::create_test_workout(&pool, &user.id, date(9), None).await;
+    common::create_test_log(&pool, &before.id, &squat.id, 1, 5, 100.0, Some(8)).await;
+    let workout = common::create_test_workout(&pool, &user.id, date(17), None).await;
+    common::create_test_log(&pool, &workout.id, &squat.id, 1, 5, 110.0, Some(9)).await;
+    common::create_test_log(&pool, &workout.id, &squat.id, 2, 5, 100.0, None).await;
+
+    let get = |uri: &str| {
+        Request::builder()
+            .uri(uri)
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/reports/week?week=2024-01-19"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("Week of 15 Jan 2024"));
+    assert!(html.contains("href=\"/reports/week?week=2024-01-08\""));
+    assert!(html.contains("href=\"/reports/week?week=2024-01-22\""));
+    // 1050 kg against 500 kg the week before.
+    assert!(html.contains("<td>1050</td>"));
+    assert!(html.contains("+110%"));
+    assert!(html.contains("110 kg &times; 5"));
+    assert!(html.contains("100 kg</td>"));
+    assert!(html.contains(&format!("href=\"/workouts/{}\"", workout.id)));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/reports/month/download?month=2024-01"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+        "attachment; filename=\"liftlog-month-2024-01.html\""
+    );
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("January 2024"));
+    assert!(html.contains("<td>1550</td>"));
+    assert!(html.contains("First time"));
+    assert!(!html.contains("href=\"/workouts/"));
+    assert!(!html.contains("nav-bar"));
+
+    for uri in [
+        "/reports/month?month=2024-13",
+        "/reports/month?month=-262143-01",
+        "/reports/week?week=%2B262142-12-31",
+        "/reports/week?week=-262143-01-01",
+    ] {
+        let response = test_app.router.clone().oneshot(get(uri)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/reports/year"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_year_review_sums_the_year() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let squat = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let bench = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    for (day, exercise, weight) in [
+        (date(2022, 12, 5), &bench, 80.0),
+        (date(2023, 3, 6), &squat, 100.0),
+        (date(2023, 3, 8), &squat, 105.0),
+        (date(2023, 3, 10), &bench, 90.0),
+        (date(2023, 9, 4), &squat, 120.0),
+    ] {
+        let workout = common::create_test_workout(&pool, &user.id, day, None).await;
+        common::create_test_log(&pool, &workout.id, &exercise.id, 1, 10, weight, None).await;
+    }
+
+    let get = |uri: &str| {
+        Request::builder()
+            .uri(uri)
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/stats/year?year=2023"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("2023 in Review"));
+    assert!(html.contains("<div class=\"stat-value\">4</div>"));
+    assert!(html.contains("<div class=\"stat-value\">4150</div>"));
+    assert!(html.contains("<div class=\"stat-value\">March</div>"));
+    assert!(html.contains(&format!(
+        "<a href=\"/stats/exercise/{}\">Squat</a>: 3 workouts",
+        squat.id
+    )));
+    assert!(html.contains("+20 kg"));
+    assert!(html.contains("+10 kg"));
+    assert!(html.contains("href=\"/stats/year?year=2024\""));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/stats/year?year=9999"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Nor are years outside 1900-9999, to view or to share.
+    for year in ["-50", "1"] {
+        let response = test_app
+            .router
+            .clone()
+            .oneshot(get(&format!("/stats/year?year={year}")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{year}");
+        let response = test_app
+            .router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/stats/year/{year}/share"))
+                    .header(header::COOKIE, &cookie_header)
+                    .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                    .body(Body::from("expires_in_days="))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{year}");
+    }
+}
+
+#[tokio::test]
+async fn test_progress_chart_overlays_exercises_from_query_parameters() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let other = common::create_test_user(&pool, "other", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let squat = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let bench = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let theirs = common::create_test_exercise(&pool, &other.id, "Their Lift", "legs").await;
+    let date = |m, d| chrono::NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+    for (day, exercise, reps, weight) in [
+        (date(1, 8), &squat, 5, 100.0),
+        (date(1, 15), &squat, 5, 110.0),
+        (date(1, 22), &squat, 8, 90.0),
+        (date(1, 15), &bench, 5, 80.0),
+    ] {
+        let workout = common::create_test_workout(&pool, &user.id, day, None).await;
+        common::create_test_log(&pool, &workout.id, &exercise.id, 1, reps, weight, None).await;
+    }
+
+    let get = |uri: &str| {
+        Request::builder()
+            .uri(uri)
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::empty())
+            .unwrap()
+    };
+    let page = |uri: String| {
+        let router = test_app.router.clone();
+        let request = get(&uri);
+        async move {
+            let response = router.oneshot(request).await.unwrap();
+            let status = response.status();
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            (status, String::from_utf8_lossy(&body).into_owned())
+        }
+    };
+
+    let (status, html) = page("/stats/progress".to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(html.contains("Tick one or more exercises"));
+
+    let (status, html) = page(format!(
+        "/stats/progress?ex.{}=on&ex.{}=on&metric=top_set&from=2024-01-01&to=2024-01-31",
+        squat.id, bench.id
+    ))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(html.matches("class=\"progress-series\"").count(), 2);
+    assert!(html.contains("<title>Squat 2024-01-15: 110 kg</title>"));
+    assert!(html.contains("<title>Bench Press 2024-01-15: 80 kg</title>"));
+    assert!(html.contains("latest 90 kg"));
+
+    // Best set of 6-12 reps only counts the 8-rep session; smoothing
+    // averages the top sets.
+    let (_, html) = page(format!(
+        "/stats/progress?ex.{}=on&metric=rep_range&reps=mid&from=2024-01-01",
+        squat.id
+    ))
+    .await;
+    assert_eq!(html.matches("<circle").count(), 1);
+    let (_, html) = page(format!(
+        "/stats/progress?ex.{}=on&smooth=3&from=2024-01-01",
+        squat.id
+    ))
+    .await;
+    assert!(html.contains("3-session moving average"));
+
+    let (status, _) = page("/stats/progress?metric=speed".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = page(format!("/stats/progress?ex.{}=on", theirs.id)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_exercise_chart_downloads_as_svg_and_png() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let other = common::create_test_user(&pool, "other", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let squat = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let theirs = common::create_test_exercise(&pool, &other.id, "Their Lift", "legs").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+    common::create_test_log(&pool, &workout.id, &squat.id, 1, 5, 110.0, None).await;
+
+    let get = |uri: String| {
+        test_app.router.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+
+    let response = get(format!(
+        "/stats/exercise/{}/chart.svg?from=2024-01-01&width=800&height=300",
+        squat.id
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/svg+xml");
+    assert_eq!(
+        response.headers()[header::CONTENT_DISPOSITION],
+        "inline; filename=\"squat-top_set.svg\""
+    );
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let svg = String::from_utf8_lossy(&body);
+    assert!(svg.contains("width=\"800\" height=\"328\""));
+    assert!(svg.contains("<title>Squat 2024-01-15: 110 kg</title>"));
+
+    let response = get(format!(
+        "/stats/exercise/{}/chart.png?from=2024-01-01",
+        squat.id
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(&body[1..4], b"PNG");
+
+    let response = get(format!("/stats/exercise/{}/chart.png?width=5000", squat.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = get(format!("/stats/exercise/{}/chart.svg", theirs.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
@@ -6,7 +6,7 @@ use axum::{
 };
 use http_body_util::BodyExt;
 use liftlog::models::UserRole;
-use liftlog::repositories::WorkoutRepository;
+use liftlog::repositories::{ShareRepository, WorkoutRepository};
 use tower::ServiceExt;
 
 #[tokio::test]
@@ -78,7 +78,7 @@ async fn test_view_shared_workout_public() {
 
     let workout_repo = WorkoutRepository::new(pool.clone());
     let share_token = workout_repo
-        .set_share_token(&workout.id, &user.id, None)
+        .set_share_token(&workout.id, &user.id, None, false)
         .await
         .unwrap();
 
@@ -141,7 +141,7 @@ async fn test_revoke_share_success() {
 
     let workout_repo = WorkoutRepository::new(pool.clone());
     let share_token = workout_repo
-        .set_share_token(&workout.id, &user.id, None)
+        .set_share_token(&workout.id, &user.id, None, false)
         .await
         .unwrap();
 
@@ -197,7 +197,7 @@ async fn test_reshare_after_revoke_generates_new_token() {
     let workout_repo = WorkoutRepository::new(pool.clone());
 
     let token1 = workout_repo
-        .set_share_token(&workout.id, &user.id, None)
+        .set_share_token(&workout.id, &user.id, None, false)
         .await
         .unwrap();
 
@@ -207,7 +207,7 @@ async fn test_reshare_after_revoke_generates_new_token() {
         .unwrap();
 
     let token2 = workout_repo
-        .set_share_token(&workout.id, &user.id, None)
+        .set_share_token(&workout.id, &user.id, None, false)
         .await
         .unwrap();
 
@@ -357,7 +357,7 @@ async fn test_cannot_revoke_others_share() {
     .await;
     let workout_repo = WorkoutRepository::new(pool.clone());
     let share_token = workout_repo
-        .set_share_token(&workout.id, &user2.id, None)
+        .set_share_token(&workout.id, &user2.id, None, false)
         .await
         .unwrap();
 
@@ -445,7 +445,7 @@ async fn test_show_workout_displays_share_link_and_revoke() {
 
     let workout_repo = WorkoutRepository::new(pool.clone());
     let share_token = workout_repo
-        .set_share_token(&workout.id, &user.id, None)
+        .set_share_token(&workout.id, &user.id, None, false)
         .await
         .unwrap();
 
@@ -620,7 +620,12 @@ async fn test_expired_share_token_returns_404() {
 
     let workout_repo = WorkoutRepository::new(pool.clone());
     let share_token = workout_repo
-        .set_share_token(&workout.id, &user.id, Some(chrono::Duration::days(7)))
+        .set_share_token(
+            &workout.id,
+            &user.id,
+            Some(chrono::Duration::days(7)),
+            false,
+        )
         .await
         .unwrap();
 
@@ -675,7 +680,12 @@ async fn test_revoke_share_clears_expiry_too() {
 
     let workout_repo = WorkoutRepository::new(pool.clone());
     workout_repo
-        .set_share_token(&workout.id, &user.id, Some(chrono::Duration::days(7)))
+        .set_share_token(
+            &workout.id,
+            &user.id,
+            Some(chrono::Duration::days(7)),
+            false,
+        )
         .await
         .unwrap();
 
@@ -782,11 +792,16 @@ async fn test_cleanup_expired_share_tokens_nulls_them() {
     .await;
 
     workout_repo
-        .set_share_token(&expired.id, &user.id, Some(chrono::Duration::days(7)))
+        .set_share_token(
+            &expired.id,
+            &user.id,
+            Some(chrono::Duration::days(7)),
+            false,
+        )
         .await
         .unwrap();
     workout_repo
-        .set_share_token(&never_expires.id, &user.id, None)
+        .set_share_token(&never_expires.id, &user.id, None, false)
         .await
         .unwrap();
     workout_repo
@@ -794,6 +809,7 @@ async fn test_cleanup_expired_share_tokens_nulls_them() {
             &future_expiry.id,
             &user.id,
             Some(chrono::Duration::days(30)),
+            false,
         )
         .await
         .unwrap();
@@ -834,3 +850,773 @@ async fn test_cleanup_expired_share_tokens_nulls_them() {
     assert!(future_row.share_token.is_some());
     assert!(future_row.share_expires_at.is_some());
 }
+
+#[tokio::test]
+async fn test_view_shared_workout_negotiates_text_variants() {
+    let pool = common::setup_test_db();
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let exercise = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        Some("Push day"),
+    )
+    .await;
+    for set in 1..=3 {
+        common::create_test_log(&pool, &workout.id, &exercise.id, set, 5, 100.0, Some(8)).await;
+    }
+    let share_token = WorkoutRepository::new(pool.clone())
+        .set_share_token(&workout.id, &user.id, None, false)
+        .await
+        .unwrap();
+    let app = common::create_test_app(pool.clone());
+
+    let fetch = |accept: &'static str, query: &'static str| {
+        let app = app.clone();
+        let uri = format!("/shared/{share_token}{query}");
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .uri(uri)
+                        .header(header::ACCEPT, accept)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let status = response.status();
+            let content_type = response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string();
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            (
+                status,
+                content_type,
+                String::from_utf8(body.to_vec()).unwrap(),
+            )
+        }
+    };
+
+    let (status, content_type, body) = fetch("text/markdown", "").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(content_type.starts_with("text/markdown"));
+    assert!(body.starts_with("# Workout 2024-01-15 by testuser\n\nPush day\n"));
+    assert!(body.contains("### Bench Press\n- 100 kg × 5 × 3 @8\n"));
+
+    let (_, content_type, body) = fetch("text/html", "?format=txt").await;
+    assert!(content_type.starts_with("text/plain"));
+    assert!(body.contains("Bench Press\n  100 kg × 5 × 3 @8\n"));
+
+    let (_, content_type, _) = fetch("text/html,*/*;q=0.8", "").await;
+    assert!(content_type.starts_with("text/html"));
+
+    let (status, _, _) = fetch("text/html", "?format=pdf").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_year_review_share_link_is_public_until_revoked() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let post = |uri: &str, body: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let get_public = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post("/stats/year/2023/share", "expires_in_days=400"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post("/stats/year/2023/share", "expires_in_days=7"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        "/stats/year?year=2023"
+    );
+
+    let share = ShareRepository::new(pool.clone())
+        .find_year_share(&user.id, 2023)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(share.expires_at.is_some());
+    let url = format!("/shared/year/{}", share.token);
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get_public(&url))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("2023 in Review"));
+    assert!(html.contains("Shared by"));
+    assert!(html.contains("testuser"));
+    assert!(!html.contains("Revoke Share"));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post("/stats/year/2023/revoke-share", ""))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get_public(&url))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// A workout shared with its progress links to charts of its exercises,
+/// which stop at the workout, cover at most a preset range and are always
+/// the default size; other exercises and revoked links are not found.
+#[tokio::test]
+async fn test_shared_workout_chart_images() {
+    let pool = common::setup_test_db();
+    let app = common::create_test_app(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let bench = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let squat = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let date = |m, d| chrono::NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+    let workout = common::create_test_workout(&pool, &user.id, date(1, 15), None).await;
+    common::create_test_log(&pool, &workout.id, &bench.id, 1, 5, 100.0, None).await;
+    let later = common::create_test_workout(&pool, &user.id, date(2, 15), None).await;
+    common::create_test_log(&pool, &later.id, &bench.id, 1, 5, 120.0, None).await;
+    common::create_test_log(&pool, &later.id, &squat.id, 1, 5, 140.0, None).await;
+
+    let workout_repo = WorkoutRepository::new(pool.clone());
+    let token = workout_repo
+        .set_share_token(&workout.id, &user.id, None, true)
+        .await
+        .unwrap();
+    let get = |uri: String| {
+        app.clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+    };
+
+    let response = get(format!("/shared/{token}")).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains(&format!("/shared/{token}/exercise/{}/chart.png", bench.id)));
+    assert!(html.contains("later sessions are never shown"));
+
+    let response = get(format!("/shared/{token}/exercise/{}/chart.svg", bench.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let svg = String::from_utf8_lossy(&body);
+    assert!(svg.contains("<title>Bench Press 2024-01-15: 100 kg</title>"));
+    assert!(!svg.contains("120 kg"));
+
+    // Only preset ranges ending on the workout: all time and custom dates
+    // would show sessions that were never shared.
+    for query in [
+        "range=all",
+        "from=2023-01-01",
+        "to=2024-12-31",
+        "range=3m&from=2024-01-01",
+    ] {
+        let response = get(format!(
+            "/shared/{token}/exercise/{}/chart.svg?{query}",
+            bench.id
+        ))
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{query}");
+    }
+    let response = get(format!(
+        "/shared/{token}/exercise/{}/chart.svg?range=3m",
+        bench.id
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Asking for the largest size still gets the default one.
+    let response = get(format!(
+        "/shared/{token}/exercise/{}/chart.png?width=2400&height=1600",
+        bench.id
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    let png = response.into_body().collect().await.unwrap().to_bytes();
+    // IHDR width and height, big-endian: 600 wide, 260 plus the title band.
+    assert_eq!(&png[16..24], &[0, 0, 2, 88, 0, 0, 1, 32]);
+
+    let response = get(format!("/shared/{token}/exercise/{}/chart.svg", squat.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    workout_repo
+        .revoke_share_token(&workout.id, &user.id)
+        .await
+        .unwrap();
+    let response = get(format!("/shared/{token}/exercise/{}/chart.svg", bench.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Charts show the owner's other sessions, so a workout shared without its
+/// progress links to none and serves none; the share form's checkbox opts in.
+#[tokio::test]
+async fn test_shared_workout_charts_need_the_history_opt_in() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+    let bench = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let date = |m, d| chrono::NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+    let earlier = common::create_test_workout(&pool, &user.id, date(1, 1), None).await;
+    common::create_test_log(&pool, &earlier.id, &bench.id, 1, 5, 90.0, None).await;
+    let workout = common::create_test_workout(&pool, &user.id, date(1, 15), None).await;
+    common::create_test_log(&pool, &workout.id, &bench.id, 1, 5, 100.0, None).await;
+
+    let share = |body: &'static str| {
+        test_app.router.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/workouts/{}/share", workout.id))
+                .header(header::COOKIE, &cookie_header)
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+    let get = |uri: String| {
+        test_app
+            .router
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+    };
+    let workout_repo = WorkoutRepository::new(pool.clone());
+
+    let response = share("expires_in_days=").await.unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let token = workout_repo
+        .find_session_by_id(&workout.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .share_token
+        .unwrap();
+    let response = get(format!("/shared/{token}")).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(!html.contains("chart.svg"));
+    assert!(!html.contains("90 kg"));
+    for ext in ["svg", "png"] {
+        let response = get(format!("/shared/{token}/exercise/{}/chart.{ext}", bench.id))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{ext}");
+    }
+
+    workout_repo
+        .revoke_share_token(&workout.id, &user.id)
+        .await
+        .unwrap();
+    let response = share("expires_in_days=&include_history=on").await.unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let token = workout_repo
+        .find_session_by_id(&workout.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .share_token
+        .unwrap();
+    let response = get(format!("/shared/{token}/exercise/{}/chart.svg", bench.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains("90 kg"));
+}
+
+/// The shared page carries link-preview tags pointing at a card image,
+/// which stops working when the link expires or is revoked.
+#[tokio::test]
+async fn test_shared_workout_preview_card() {
+    let pool = common::setup_test_db();
+    let app = common::create_test_app(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let squat = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+    common::create_test_log(&pool, &workout.id, &squat.id, 1, 5, 140.0, None).await;
+    common::create_test_log(&pool, &workout.id, &squat.id, 2, 3, 150.0, None).await;
+
+    let workout_repo = WorkoutRepository::new(pool.clone());
+    let token = workout_repo
+        .set_share_token(
+            &workout.id,
+            &user.id,
+            Some(chrono::Duration::days(7)),
+            false,
+        )
+        .await
+        .unwrap();
+    let get = |uri: String| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .header(header::HOST, "lifts.example")
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+
+    let response = get(format!("/shared/{token}")).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains(&format!(
+        "<meta property=\"og:image\" content=\"http://lifts.example/shared/{token}/card.png\">"
+    )));
+    assert!(html.contains("<meta name=\"twitter:card\" content=\"summary_large_image\">"));
+    assert!(html.contains("content=\"1 exercise, 2 sets\""));
+
+    let response = get(format!("/shared/{token}/card.png")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(&body[1..4], b"PNG");
+    assert_eq!(&body[16..24], &[0, 0, 4, 176, 0, 0, 2, 118]);
+
+    {
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "UPDATE workout_sessions SET share_expires_at = datetime('now', '-1 hour') WHERE id = ?",
+            [&workout.id],
+        )
+        .unwrap();
+    }
+    let response = get(format!("/shared/{token}/card.png")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let token = workout_repo
+        .set_share_token(&workout.id, &user.id, None, false)
+        .await
+        .unwrap();
+    workout_repo
+        .revoke_share_token(&workout.id, &user.id)
+        .await
+        .unwrap();
+    let response = get(format!("/shared/{token}/card.png")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// With a public URL configured, preview links use it rather than the
+/// `Host` header the client sent.
+#[tokio::test]
+async fn test_shared_workout_preview_uses_the_public_url() {
+    let pool = common::setup_test_db();
+    let app = common::create_test_app_with_public_url(pool.clone(), "https://lifts.example");
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+    let token = WorkoutRepository::new(pool.clone())
+        .set_share_token(&workout.id, &user.id, None, false)
+        .await
+        .unwrap();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/shared/{token}"))
+                .header(header::HOST, "attacker.example")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains(&format!(
+        "<meta property=\"og:image\" content=\"https://lifts.example/shared/{token}/card.png\">"
+    )));
+    assert!(!html.contains("attacker.example"));
+}
+
+/// A public profile shows only the sections and PRs the owner picked, only
+/// workouts that are shared themselves, and goes away with its link.
+#[tokio::test]
+async fn test_public_profile_shows_only_chosen_sections() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let squat = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let bench = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let date = |d| chrono::NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+    let shared = common::create_test_workout(&pool, &user.id, date(8), None).await;
+    common::create_test_log(&pool, &shared.id, &squat.id, 1, 5, 140.0, None).await;
+    let private = common::create_test_workout(&pool, &user.id, date(15), None).await;
+    common::create_test_log(&pool, &private.id, &bench.id, 1, 5, 100.0, None).await;
+    let workout_repo = WorkoutRepository::new(pool.clone());
+    let workout_token = workout_repo
+        .set_share_token(&shared.id, &user.id, None, false)
+        .await
+        .unwrap();
+
+    let post = |uri: &str, body: String| {
+        test_app.router.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+    let get_public = |uri: String| {
+        test_app
+            .router
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+    };
+
+    let response = post("/settings/profile/share", "expires_in_days=30".to_string())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let profile = ShareRepository::new(pool.clone())
+        .get_public_profile(&user.id)
+        .await
+        .unwrap();
+    let url = format!("/shared/profile/{}", profile.token.unwrap());
+
+    // Shared, but nothing turned on yet.
+    let response = get_public(url.clone()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("Nothing shared here yet"));
+    assert!(!html.contains("Squat"));
+
+    let response = post(
+        "/settings/profile",
+        format!("show_prs=on&show_sessions=on&ex.{}=on", squat.id),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let response = get_public(url.clone()).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("Squat"));
+    assert!(html.contains("140 kg"));
+    assert!(!html.contains("Bench Press"));
+    assert!(html.contains(&format!("/shared/{workout_token}")));
+    assert!(!html.contains("2024-01-15"));
+    assert!(!html.contains("Week Streak"));
+
+    // Only exercises with a PR can be picked.
+    let deadlift = common::create_test_exercise(&pool, &user.id, "Deadlift", "back").await;
+    let response = post(
+        "/settings/profile",
+        format!("show_prs=on&ex.{}=on", deadlift.id),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = post("/settings/profile", "show_consistency=on".to_string())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let response = get_public(url.clone()).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("Week Streak"));
+    assert!(!html.contains("Squat"));
+    assert!(!html.contains(&format!("/shared/{workout_token}")));
+
+    let response = post("/settings/profile/revoke-share", String::new())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let response = get_public(url).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// A tag can cover any number of workouts; the public page lists the first
+/// ones and says so, while the totals cover them all.
+#[tokio::test]
+async fn test_block_share_lists_at_most_a_page_of_workouts() {
+    let pool = common::setup_test_db();
+    let app = common::create_test_app(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let tag = liftlog::repositories::TagRepository::new(pool.clone())
+        .create(&user.id, "base")
+        .await
+        .unwrap();
+    let first = chrono::NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+    let count = liftlog::models::MAX_BLOCK_SESSIONS + 1;
+    let mut last = None;
+    for day in 0..count {
+        let date = first + chrono::Days::new(u64::try_from(day).unwrap() * 7);
+        let workout = common::create_test_workout(&pool, &user.id, date, None).await;
+        pool.get()
+            .unwrap()
+            .execute(
+                "INSERT INTO workout_session_tags (session_id, tag_id) VALUES (?, ?)",
+                [&workout.id, &tag.id],
+            )
+            .unwrap();
+        last = Some(date);
+    }
+    let scope = liftlog::models::BlockScope::parse("", "", &tag.id).unwrap();
+    let token = ShareRepository::new(pool.clone())
+        .create_block_share(&user.id, &scope, None)
+        .await
+        .unwrap();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/shared/block/{token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains(&format!(
+        "Showing the first {} of {count} workouts",
+        liftlog::models::MAX_BLOCK_SESSIONS
+    )));
+    assert!(html.contains(&first.to_string()));
+    assert!(!html.contains(&last.unwrap().to_string()));
+}
+
+#[tokio::test]
+async fn test_block_share_lists_only_included_workouts() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let squat = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let bench = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let date = |d| chrono::NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+    let week1 = common::create_test_workout(&pool, &user.id, date(4), Some("Heavy day")).await;
+    common::create_test_log(&pool, &week1.id, &squat.id, 1, 5, 140.0, Some(8)).await;
+    common::create_test_log(&pool, &week1.id, &squat.id, 2, 5, 140.0, Some(9)).await;
+    let week2 = common::create_test_workout(&pool, &user.id, date(11), None).await;
+    common::create_test_log(&pool, &week2.id, &bench.id, 1, 5, 100.0, None).await;
+    let later = common::create_test_workout(&pool, &user.id, date(25), None).await;
+    common::create_test_log(&pool, &later.id, &bench.id, 1, 3, 110.0, None).await;
+    let tag = liftlog::repositories::TagRepository::new(pool.clone())
+        .create(&user.id, "peak")
+        .await
+        .unwrap();
+    {
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO workout_session_tags (session_id, tag_id) VALUES (?, ?)",
+            [&later.id, &tag.id],
+        )
+        .unwrap();
+    }
+
+    let post = |uri: String, body: String| {
+        test_app.router.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+    let get_public = |uri: String| {
+        test_app
+            .router
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+    };
+    let share_repo = ShareRepository::new(pool.clone());
+
+    // A date alone is not a block.
+    let response = post(
+        "/workouts/shares".to_string(),
+        "from=2024-03-01".to_string(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains("Pick a tag, or both ends"));
+    assert!(
+        share_repo
+            .find_block_shares(&user.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // Nor is a decade of workouts.
+    let response = post(
+        "/workouts/shares".to_string(),
+        "from=2014-03-01&to=2024-03-14".to_string(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains("at most a year"));
+
+    let response = post(
+        "/workouts/shares".to_string(),
+        "from=2024-03-01&to=2024-03-14&expires_in_days=30".to_string(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let response = post("/workouts/shares".to_string(), format!("tag_id={}", tag.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let shares = share_repo.find_block_shares(&user.id).await.unwrap();
+    assert_eq!(shares.len(), 2);
+    let range = shares.iter().find(|s| s.scope.tag_id.is_none()).unwrap();
+    let tagged = shares.iter().find(|s| s.scope.tag_id.is_some()).unwrap();
+    assert!(range.expires_at.is_some());
+    assert_eq!(tagged.label(), "#peak");
+
+    let response = get_public(format!("/shared/block/{}", range.token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("2024-03-04"));
+    assert!(html.contains("Heavy day"));
+    assert!(html.contains("2024-03-11"));
+    assert!(!html.contains("2024-03-25"));
+    assert!(html.contains("Squat"));
+    assert!(html.contains("Bench Press"));
+    // 2 x 5 x 140 + 5 x 100 = 1900 kg over three sets.
+    assert!(html.contains("1900"));
+    assert!(html.contains("8.5"));
+
+    let response = get_public(format!("/shared/block/{}", tagged.token))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("2024-03-25"));
+    assert!(!html.contains("2024-03-04"));
+    assert!(!html.contains("Squat"));
+
+    // Someone else's tag cannot be shared.
+    let other = common::create_test_user(&pool, "other", "password123", UserRole::User).await;
+    let other_tag = liftlog::repositories::TagRepository::new(pool.clone())
+        .create(&other.id, "theirs")
+        .await
+        .unwrap();
+    let response = post(
+        "/workouts/shares".to_string(),
+        format!("tag_id={}", other_tag.id),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Revoked links are gone; expired ones stop working and get swept.
+    let response = post(
+        format!("/workouts/shares/{}/revoke", tagged.id),
+        String::new(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let response = get_public(format!("/shared/block/{}", tagged.token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    {
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "UPDATE block_shares SET expires_at = datetime('now', '-1 hour') WHERE id = ?",
+            [&range.id],
+        )
+        .unwrap();
+    }
+    let response = get_public(format!("/shared/block/{}", range.token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(share_repo.cleanup_expired_share_tokens().await.unwrap(), 1);
+    let remaining: i64 = pool
+        .get()
+        .unwrap()
+        .query_row("SELECT COUNT(*) FROM block_shares", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(remaining, 0);
+}
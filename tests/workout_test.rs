@@ -5,7 +5,7 @@ use axum::{
     http::{Request, StatusCode, header},
 };
 use http_body_util::BodyExt;
-use liftlog::models::{UserRole, recent_pr_window_start};
+use liftlog::models::{UserPreferences, UserRole};
 use liftlog::repositories::WorkoutRepository;
 use tower::ServiceExt;
 
@@ -606,7 +606,11 @@ async fn test_add_log_success() {
 
     let workout_repo = WorkoutRepository::new(pool);
     let logs = workout_repo
-        .find_logs_by_session_with_pr(&workout.id, &user.id, recent_pr_window_start())
+        .find_logs_by_session_with_pr(
+            &workout.id,
+            &user.id,
+            UserPreferences::default().pr_window_start(),
+        )
         .await
         .unwrap();
     assert_eq!(logs.len(), 1);
@@ -660,7 +664,11 @@ async fn test_add_log_rejects_exercise_owned_by_another_user() {
     // The write must not have happened at all, not merely been reported as denied.
     let workout_repo = WorkoutRepository::new(pool);
     let logs = workout_repo
-        .find_logs_by_session_with_pr(&workout.id, &attacker.id, recent_pr_window_start())
+        .find_logs_by_session_with_pr(
+            &workout.id,
+            &attacker.id,
+            UserPreferences::default().pr_window_start(),
+        )
         .await
         .unwrap();
     assert!(
@@ -710,7 +718,11 @@ async fn test_add_log_accepts_fractional_weight() {
 
     let workout_repo = WorkoutRepository::new(pool);
     let logs = workout_repo
-        .find_logs_by_session_with_pr(&workout.id, &user.id, recent_pr_window_start())
+        .find_logs_by_session_with_pr(
+            &workout.id,
+            &user.id,
+            UserPreferences::default().pr_window_start(),
+        )
         .await
         .unwrap();
     assert_eq!(logs.len(), 1);
@@ -793,7 +805,11 @@ async fn test_delete_log_success() {
 
     let workout_repo = WorkoutRepository::new(pool);
     let logs = workout_repo
-        .find_logs_by_session_with_pr(&workout.id, &user.id, recent_pr_window_start())
+        .find_logs_by_session_with_pr(
+            &workout.id,
+            &user.id,
+            UserPreferences::default().pr_window_start(),
+        )
         .await
         .unwrap();
     assert_eq!(logs.len(), 0);
@@ -1014,7 +1030,7 @@ async fn test_update_log_requires_ownership() {
 // Pagination tests
 
 #[tokio::test]
-async fn test_workouts_list_pagination_page_2() {
+async fn test_workouts_list_pages_by_cursor() {
     let pool = common::setup_test_db();
     let test_app = common::create_test_app_with_session(pool.clone());
 
@@ -1037,24 +1053,1099 @@ async fn test_workouts_list_pagination_page_2() {
         .await;
     }
 
+    let get = |uri: String| {
+        Request::builder()
+            .uri(uri)
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::empty())
+            .unwrap()
+    };
+    let next_link = |body: &str| {
+        let start = body.find("href=\"/workouts?after=").unwrap() + "href=\"".len();
+        let end = start + body[start..].find('"').unwrap();
+        body[start..end].replace("&amp;", "&")
+    };
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/workouts".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body_str = String::from_utf8_lossy(&body);
+    assert!(body_str.contains("2024-01-15"));
+    assert!(!body_str.contains("2024-01-05"));
+    assert!(!body_str.contains("&larr; Previous"));
+
+    let response = test_app
+        .router
+        .oneshot(get(next_link(&body_str)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body_str = String::from_utf8_lossy(&body);
+
+    // Page 2 has the older workouts (1-5, since ordered by date DESC) and
+    // links back but not forward.
+    assert!(body_str.contains("2024-01-01") && body_str.contains("2024-01-05"));
+    assert!(!body_str.contains("2024-01-06"));
+    assert!(body_str.contains("&larr; Previous"));
+    assert!(!body_str.contains("Next &rarr;"));
+}
+
+#[tokio::test]
+async fn test_workouts_list_filters_from_query_string() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let squat = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let bench = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let date = |d| chrono::NaiveDate::from_ymd_opt(2024, 2, d).unwrap();
+    let heavy = common::create_test_workout(&pool, &user.id, date(1), Some("Heavy legs")).await;
+    let light =
+        common::create_test_workout(&pool, &user.id, date(8), Some("Light legs, deload")).await;
+    let push = common::create_test_workout(&pool, &user.id, date(9), Some("Push day")).await;
+    common::create_test_log(&pool, &heavy.id, &squat.id, 1, 5, 160.0, None).await;
+    common::create_test_log(&pool, &light.id, &squat.id, 1, 5, 100.0, None).await;
+    common::create_test_log(&pool, &push.id, &bench.id, 1, 5, 90.0, None).await;
+
+    let list = |query: String| {
+        let router = test_app.router.clone();
+        let cookie_header = cookie_header.clone();
+        async move {
+            let response = router
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/workouts?{query}"))
+                        .header(header::COOKIE, &cookie_header)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            (status, String::from_utf8_lossy(&body).into_owned())
+        }
+    };
+
+    let (status, body) = list(format!("exercise={}&from=2024-02-02", squat.id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("Light legs, deload"));
+    assert!(!body.contains("Heavy legs"));
+    assert!(!body.contains("Push day"));
+    assert!(body.contains("1 matching workout"));
+
+    let (_, body) = list("q=DELO".to_string()).await;
+    assert!(body.contains("Light legs, deload"));
+    assert!(!body.contains("Push day"));
+
+    let (_, body) = list("sort=volume&to=2024-02-08".to_string()).await;
+    let heavy_at = body.find("Heavy legs").unwrap();
+    let light_at = body.find("Light legs").unwrap();
+    assert!(heavy_at < light_at, "most volume comes first");
+
+    let (status, _) = list("from=2024-02-09&to=2024-02-01".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = list("sort=random".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_add_log_records_both_sides_for_unilateral_exercise() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let exercise = liftlog::repositories::ExerciseRepository::new(pool.clone())
+        .create_with_muscles("One-Arm Row", "back", Some(&user.id), true, Vec::new())
+        .await
+        .unwrap();
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/workouts/{}/logs", workout.id))
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(format!(
+                    "exercise_id={}&reps=10&weight=30&rpe=&right_reps=8&right_weight=",
+                    exercise.id
+                )))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let workout_repo = WorkoutRepository::new(pool);
+    let logs = workout_repo
+        .find_logs_by_session_with_pr(
+            &workout.id,
+            &user.id,
+            UserPreferences::default().pr_window_start(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(logs[0].right_reps, Some(8));
+    // A blank right-side weight repeats the left.
+    assert_eq!(logs[0].right_weight, Some(30.0));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/stats/exercise/{}", exercise.id))
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    assert!(html.contains("Side Balance"));
+    assert!(html.contains("right side trails by 20.0%"));
+    assert!(html.contains("10 / 8"));
+}
+
+#[tokio::test]
+async fn test_add_log_ignores_right_side_for_bilateral_exercise() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/workouts/{}/logs", workout.id))
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(format!(
+                    "exercise_id={}&reps=10&weight=100&right_reps=8&right_weight=90",
+                    exercise.id
+                )))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let workout_repo = WorkoutRepository::new(pool);
+    let logs = workout_repo
+        .find_logs_by_session_with_pr(
+            &workout.id,
+            &user.id,
+            UserPreferences::default().pr_window_start(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(logs[0].right_reps, None);
+    assert_eq!(logs[0].right_weight, None);
+}
+
+#[tokio::test]
+async fn test_add_log_stores_annotations_and_search_finds_them() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/workouts/{}/logs", workout.id))
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(format!(
+                    "exercise_id={}&reps=5&weight=100&rpe=&rir=2&tempo=31x0&notes=paused+on+chest",
+                    exercise.id
+                )))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let logs = WorkoutRepository::new(pool.clone())
+        .find_logs_by_session_with_pr(
+            &workout.id,
+            &user.id,
+            UserPreferences::default().pr_window_start(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(logs[0].rir, Some(2));
+    assert_eq!(logs[0].tempo.as_deref(), Some("3-1-X-0"));
+    assert_eq!(logs[0].notes.as_deref(), Some("paused on chest"));
+
     let response = test_app
         .router
+        .clone()
         .oneshot(
             Request::builder()
-                .uri("/workouts?page=2")
+                .uri(format!("/workouts/{}", workout.id))
                 .header(header::COOKIE, &cookie_header)
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("Tempo 3-1-X-0 · paused on chest"));
+    assert!(body.contains("2 RIR"));
 
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/workouts/search?q=paused")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("paused on chest"));
+    assert!(body.contains(&format!("/workouts/{}", workout.id)));
+}
+
+#[tokio::test]
+async fn test_update_log_rejects_malformed_tempo() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+    let log = common::create_test_log(&pool, &workout.id, &exercise.id, 1, 5, 100.0, None).await;
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/workouts/{}/logs/{}", workout.id, log.id))
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from("reps=5&weight=100&tempo=slow"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
 
+#[tokio::test]
+async fn test_quick_entry_previews_then_adds_sets() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    common::create_test_exercise(&pool, &user.id, "Back Squat", "legs").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+    let text = "text=bench+100x5x3+%408%0Asquat+140x5%2C5%2C4";
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/workouts/{}/quick-entry", workout.id))
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(text))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
     let body = response.into_body().collect().await.unwrap().to_bytes();
-    let body_str = String::from_utf8_lossy(&body);
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("Add 6 Sets"));
+    assert!(body.contains("Back Squat"));
 
-    // Page 2 should have the older workouts (workouts 1-5 since ordered by date DESC)
-    // First page has workouts 15-6
-    assert!(body_str.contains("2024-01-01") || body_str.contains("2024-01-05"));
+    let workout_repo = WorkoutRepository::new(pool.clone());
+    let logs = workout_repo
+        .find_logs_by_session_for_share(&workout.id)
+        .await
+        .unwrap();
+    assert!(logs.is_empty(), "previewing must not log anything");
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/workouts/{}/quick-entry/add", workout.id))
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(text))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let logs = workout_repo
+        .find_logs_by_session_for_share(&workout.id)
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 6);
+    assert_eq!(
+        logs.iter()
+            .filter(|l| l.exercise_name == "Bench Press" && l.rpe == Some(8))
+            .count(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_quick_entry_add_logs_nothing_when_a_line_fails() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/workouts/{}/quick-entry/add", workout.id))
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from("text=bench+100x5%0Adeadlift+200x1"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("No exercise matches"));
+
+    let logs = WorkoutRepository::new(pool)
+        .find_logs_by_session_for_share(&workout.id)
+        .await
+        .unwrap();
+    assert!(logs.is_empty());
+}
+
+#[tokio::test]
+async fn test_text_import_previews_then_creates_sessions() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let text = "text=%23+2024-01-15+Leg+day%0A-+Squat+3x5+140+%2F+Bench+5x5+90%0Afelt+good%0A%0AJan+17%2C+2024%0Asquat+150x3";
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/workouts/import")
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(text))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("2 workouts, 9 sets"));
+    assert!(body.contains("Line 3"));
+
+    let workout_repo = WorkoutRepository::new(pool.clone());
+    assert_eq!(
+        workout_repo.count_sessions_by_user(&user.id).await.unwrap(),
+        0
+    );
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/workouts/import")
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::from(format!("{text}&confirm=1")))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let sessions = workout_repo
+        .find_sessions_by_user_paginated(&user.id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(sessions.len(), 2);
+    let leg_day = sessions
+        .iter()
+        .find(|s| s.date == chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .unwrap();
+    assert_eq!(leg_day.notes.as_deref(), Some("Leg day"));
+    let logs = workout_repo
+        .find_logs_by_session_for_share(&leg_day.id)
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 8);
+}
+
+#[tokio::test]
+async fn test_text_import_rejects_oversized_uploads() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+    common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+
+    let too_long = format!("text={}", "a".repeat(300 * 1024));
+    let too_many_sets = format!("text=2024-01-01%0A{}", "squat+100x5x20%0A".repeat(300));
+    for body in [too_long, too_many_sets] {
+        let response = test_app
+            .router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/workouts/import")
+                    .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                    .header(header::COOKIE, &cookie_header)
+                    .body(Body::from(format!("{body}&confirm=1")))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let workout_repo = WorkoutRepository::new(pool.clone());
+    assert_eq!(
+        workout_repo.count_sessions_by_user(&user.id).await.unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_export_workout_as_markdown_download() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+    common::create_test_log(&pool, &workout.id, &exercise.id, 1, 5, 100.0, None).await;
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/workouts/{}/export?format=md", workout.id))
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+        "attachment; filename=\"workout-2024-01-15.md\""
+    );
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    // The only set of the exercise is its PR.
+    assert!(body.contains("- 100 kg × 5 **PR**"));
+
+    let other = common::create_test_user(&pool, "other", "password123", UserRole::User).await;
+    let other_cookie = common::create_session_cookie(&pool, &other).await;
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri(format!("/workouts/{}/export", workout.id))
+                .header(header::COOKIE, common::extract_cookie_header(&other_cookie))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_set_editor_adds_shifts_and_moves_sets() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let bench = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let incline = common::create_test_exercise(&pool, &user.id, "Incline Press", "chest").await;
+    let workout = common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        None,
+    )
+    .await;
+    let editor = format!("/workouts/{}/exercises/{}/sets", workout.id, bench.id);
+    let post = |uri: String, body: String| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post(
+            editor.clone(),
+            "count=3&weight=100&reps=5&rpe=8".to_string(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let workout_repo = WorkoutRepository::new(pool.clone());
+    let logs = workout_repo
+        .find_logs_for_exercise(&workout.id, &bench.id)
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 3);
+    assert!(logs.iter().all(|l| l.reps == 5 && l.rpe == Some(8)));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post(
+            format!("{editor}/{}/shift", logs[2].id),
+            "direction=up".to_string(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let order: Vec<String> = workout_repo
+        .find_logs_for_exercise(&workout.id, &bench.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|l| l.id)
+        .collect();
+    assert_eq!(
+        order,
+        vec![logs[0].id.clone(), logs[2].id.clone(), logs[1].id.clone()]
+    );
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(post(
+            format!("{editor}/move"),
+            format!(
+                "target_session_id={}&target_exercise_id={}&log.{}=1",
+                workout.id, incline.id, logs[0].id
+            ),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        &format!("/workouts/{}/exercises/{}/sets", workout.id, incline.id)
+    );
+
+    let remaining = workout_repo
+        .find_logs_for_exercise(&workout.id, &bench.id)
+        .await
+        .unwrap();
+    assert_eq!(
+        remaining
+            .iter()
+            .map(|l| (l.id.clone(), l.set_number))
+            .collect::<Vec<_>>(),
+        vec![(logs[2].id.clone(), 1), (logs[1].id.clone(), 2)]
+    );
+
+    let response = test_app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri(&editor)
+                .header(header::COOKIE, &cookie_header)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains(&format!("log.{}", logs[1].id)));
+    assert!(body.contains("Incline Press"));
+}
+
+#[tokio::test]
+async fn test_workout_tags_from_create_edit_and_list_filter() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+    let tag_repo = liftlog::repositories::TagRepository::new(pool.clone());
+    let push = tag_repo.create(&user.id, "Push").await.unwrap();
+
+    let request = |method: &str, uri: String, body: String| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/workouts".to_string(),
+            format!(
+                "date=2024-01-15&notes=Bench&tag.{}=on&new_tags=Heavy%2C+push",
+                push.id
+            ),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let location = response.headers()["location"].to_str().unwrap().to_string();
+    let workout_id = location.trim_start_matches("/workouts/").to_string();
+
+    let names = |tags: Vec<liftlog::models::WorkoutTag>| -> Vec<String> {
+        tags.into_iter().map(|t| t.name).collect()
+    };
+    assert_eq!(
+        names(tag_repo.find_for_session(&workout_id).await.unwrap()),
+        vec!["Heavy", "Push"]
+    );
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(request("GET", location.clone(), String::new()))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8_lossy(&body);
+    assert!(body.contains("href=\"/workouts?tag=Heavy\" class=\"tag-chip\""));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(request(
+            "POST",
+            location.clone(),
+            "date=2024-01-15&notes=Bench&new_tags=deload".to_string(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(
+        names(tag_repo.find_for_session(&workout_id).await.unwrap()),
+        vec!["deload"]
+    );
+
+    common::create_test_workout(
+        &pool,
+        &user.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 16).unwrap(),
+        Some("Untagged"),
+    )
+    .await;
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(request(
+            "GET",
+            "/workouts?tag=DELOAD".to_string(),
+            String::new(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8_lossy(&body);
+    assert!(body.contains("2024-01-15"));
+    assert!(!body.contains("Untagged"));
+
+    let response = test_app
+        .router
+        .oneshot(request(
+            "POST",
+            "/workouts".to_string(),
+            format!("date=2024-01-17&new_tags={}", "x".repeat(31)),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_tag_management_and_volume_by_tag() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+    let tag_repo = liftlog::repositories::TagRepository::new(pool.clone());
+
+    let request = |method: &str, uri: &str, body: &str| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(request("POST", "/workouts/tags", "name=Legs"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(request("POST", "/workouts/tags", "name=legs"))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains("already exists"));
+
+    let legs = tag_repo.find_by_user(&user.id).await.unwrap().remove(0);
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(request(
+            "POST",
+            &format!("/workouts/tags/{}", legs.id),
+            "name=Leg+Day",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let today = chrono::Utc::now().date_naive();
+    let workout = common::create_test_workout(&pool, &user.id, today, None).await;
+    common::create_test_log(&pool, &workout.id, &exercise.id, 1, 5, 100.0, None).await;
+    WorkoutRepository::new(pool.clone())
+        .update_session_with_tags(
+            &workout.id,
+            &user.id,
+            today,
+            None,
+            vec![legs.id.clone()],
+            Vec::new(),
+        )
+        .await
+        .unwrap();
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(request("GET", "/stats/tags?months=3", ""))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8_lossy(&body);
+    assert!(body.contains("Leg Day"));
+    assert!(body.contains("500 kg"));
+
+    let response = test_app
+        .router
+        .oneshot(request(
+            "POST",
+            &format!("/workouts/tags/{}/delete", legs.id),
+            "",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert!(
+        tag_repo
+            .find_for_session(&workout.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_workout_calendar_and_stats_heatmap_link_sessions() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let get = |uri: &str| {
+        Request::builder()
+            .uri(uri)
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Squat", "legs").await;
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let single = common::create_test_workout(&pool, &user.id, date(2024, 3, 5), None).await;
+    common::create_test_log(&pool, &single.id, &exercise.id, 1, 5, 100.0, None).await;
+    for _ in 0..2 {
+        let workout = common::create_test_workout(&pool, &user.id, date(2024, 3, 12), None).await;
+        common::create_test_log(&pool, &workout.id, &exercise.id, 1, 5, 60.0, None).await;
+    }
+    let planned = common::create_test_workout(&pool, &user.id, date(2099, 1, 7), None).await;
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/workouts/calendar?month=2024-03"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("March 2024"));
+    assert!(html.contains(&format!("href=\"/workouts/{}\"", single.id)));
+    assert!(html.contains("href=\"/workouts?from=2024-03-12&#38;to=2024-03-12\""));
+    assert!(html.contains("heat-4"));
+    assert!(html.contains("month=2024-04"));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/workouts/calendar?month=2099-01"))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("heat-planned"));
+    assert!(html.contains(&format!("href=\"/workouts/{}\"", planned.id)));
+
+    for month in ["2024-13", "%2B262142-12", "-262143-01"] {
+        let response = test_app
+            .router
+            .clone()
+            .oneshot(get(&format!("/workouts/calendar?month={month}")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{month}");
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    let recent = common::create_test_workout(&pool, &user.id, today, None).await;
+    common::create_test_log(&pool, &recent.id, &exercise.id, 1, 5, 100.0, None).await;
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get("/stats"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("Days trained in the last year: 1."));
+    assert!(html.contains(&format!("<a href=\"/workouts/{}\"><rect", recent.id)));
+}
+
+#[tokio::test]
+async fn test_compare_workouts_side_by_side() {
+    let pool = common::setup_test_db();
+    let test_app = common::create_test_app_with_session(pool.clone());
+
+    let user = common::create_test_user(&pool, "testuser", "password123", UserRole::User).await;
+    let other = common::create_test_user(&pool, "other", "password123", UserRole::User).await;
+    let session_cookie = common::create_session_cookie(&pool, &user).await;
+    let cookie_header = common::extract_cookie_header(&session_cookie);
+
+    let get = |uri: &str| {
+        Request::builder()
+            .uri(uri)
+            .header(header::COOKIE, &cookie_header)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let exercise = common::create_test_exercise(&pool, &user.id, "Bench Press", "chest").await;
+    let date = |d| chrono::NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+    let first = common::create_test_workout(&pool, &user.id, date(5), None).await;
+    common::create_test_log(&pool, &first.id, &exercise.id, 1, 5, 100.0, None).await;
+    let second = common::create_test_workout(&pool, &user.id, date(8), None).await;
+    common::create_test_log(&pool, &second.id, &exercise.id, 1, 5, 105.0, None).await;
+    common::create_test_log(&pool, &second.id, &exercise.id, 2, 5, 95.0, None).await;
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get(&format!("/workouts/{}", second.id)))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    let compare_href = format!("/workouts/compare?a={}&b={}", first.id, second.id);
+    assert!(html.contains(&compare_href));
+    assert!(html.contains("Compare with 2024-03-05"));
+
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get(&format!(
+            "/workouts/compare?a={}&b={}",
+            first.id, second.id
+        )))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("2024-03-05 vs 2024-03-08"));
+    assert!(html.contains("<td class=\"change-up\">\u{25b2} 5 kg</td>"));
+    // 500 kg against 525 + 475.
+    assert!(html.contains("\u{25b2} 500 kg"));
+
+    let theirs = common::create_test_workout(&pool, &other.id, date(8), None).await;
+    let response = test_app
+        .router
+        .clone()
+        .oneshot(get(&format!(
+            "/workouts/compare?a={}&b={}",
+            first.id, theirs.id
+        )))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }